@@ -0,0 +1,26 @@
+use std::{env, path::Path, process::Command};
+
+/// Sets `CLGRADER_VERSION` to the version of the crate followed by the git commit it was
+/// built from, e.g. `0.1.0 (1a2b3c4)`, or to the version alone outside of a repository.
+fn main() {
+    let version = env::var("CARGO_PKG_VERSION").unwrap();
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty());
+    match commit {
+        Some(commit) => println!("cargo:rustc-env=CLGRADER_VERSION={version} ({commit})"),
+        None => println!("cargo:rustc-env=CLGRADER_VERSION={version}"),
+    }
+    // rebuilt on a new commit, but not on every build when there is no repository
+    for path in ["../.git/HEAD", "../.git/refs/heads"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
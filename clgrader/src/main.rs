@@ -1,3 +1,637 @@
-fn main() {
-    println!("Hello, world!, 10 - 4 = {}", cli_grader::add(3, 3));
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cli_grader::{
+    BatchResult, CombinedGradingResult, GlobalConfig, Grader, GradingConfig, Initialized,
+    LogContext, LoggingMode, Manifest, ReportOutput, ReportSection, Reporter, ScoreReporter,
+    TextReporter, write_explanation, write_test_tree,
+};
+use env_logger::fmt::default_kv_format;
+use log::LevelFilter;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// Exit code when the grading runs, but the score does not reach the pass threshold.
+const GRADING_FAILURE_CODE: u8 = 1;
+/// Exit code when the grading could not run, e.g. invalid configuration or IO errors.
+/// It is the same one used by clap for invalid arguments.
+const ERROR_CODE: u8 = 2;
+/// Exit code when the grading is interrupted by SIGINT (Ctrl-C), following the shell
+/// convention of 128 plus the number of the signal.
+const INTERRUPTED_CODE: u8 = 130;
+
+/// Set by the first SIGINT, so the grading stops and the partial results are reported.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum ReportFormat {
+    /// The report described by the `report` section of the configuration.
+    Text,
+    /// Only the final score, e.g. `7 / 10 (70.0%)`, written to stdout as a single line.
+    Score,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum ColorChoice {
+    /// Colors when the report is written to a terminal and `NO_COLOR` is not set.
+    Auto,
+    /// Colors whenever the report is written to stdout, even if it is not a terminal.
+    Always,
+    /// Never colors.
+    Never,
+}
+
+/// Grades CLI programs according to a configuration file.
+///
+/// Without a subcommand, the arguments are the ones of `run`, e.g.
+/// `clgrader config.json program`.
+#[derive(Parser, Debug)]
+#[command(
+    version = env!("CLGRADER_VERSION"),
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Grades the programs. It is the default when no subcommand is given.
+    Run(RunArgs),
+    /// Validates the configuration without running anything. The programs are not needed.
+    Check(ConfigArgs),
+    /// Prints the JSON Schema of the configuration format, e.g. to validate the
+    /// configuration files in an editor.
+    Schema,
+    /// Describes what each test checks, without running anything. The programs are not
+    /// needed.
+    Explain(ConfigArgs),
+    /// Lists the sections and their tests, with the number of assertions and the weights,
+    /// without running anything. The programs are not needed.
+    ListTests(ConfigArgs),
+}
+
+#[derive(Args, Debug)]
+struct ConfigArgs {
+    /// Path to the configuration file. It is parsed as TOML if it has the `.toml`
+    /// extension, and as JSON otherwise. Use `-` to read a JSON configuration from stdin.
+    config: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Path to the configuration file. It is parsed as TOML if it has the `.toml`
+    /// extension, and as JSON otherwise. Use `-` to read a JSON configuration from stdin.
+    // optional for clap, so it is not required with a subcommand, but `required` when
+    // grading
+    #[arg(required = true)]
+    config: Option<PathBuf>,
+    /// Paths to the programs that will be graded, in the same order they are declared in
+    /// the `input_programs` of the configuration.
+    programs: Vec<PathBuf>,
+    /// Paths to the programs graded by every configuration, e.g. one per problem of an
+    /// assignment. Every positional argument is a configuration then, e.g.
+    /// `clgrader a.json b.json --programs program`. The reports are shown one after the
+    /// other, followed by the combined score, which is the sum of their scores.
+    #[arg(long = "programs", num_args = 1.., value_name = "PROGRAMS")]
+    shared_programs: Vec<PathBuf>,
+    /// Path to a manifest of submissions, each one with the paths of its programs by
+    /// program name, to grade them one after the other, e.g. `clgrader config.json
+    /// --manifest submissions.json`. It is parsed as CSV if it has the `.csv` extension,
+    /// and as JSON otherwise. The reports are shown one after the other, followed by the
+    /// number of submissions that reach the pass threshold.
+    #[arg(long, conflicts_with_all = ["programs", "shared_programs"])]
+    manifest: Option<PathBuf>,
+    /// Seed exposed to the programs through the `GRADER_SEED` environment variable. It is
+    /// random if omitted, and always shown in the report so the run can be reproduced.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Shows the diagnostics of every failure. It overrides `report.is_verbose`.
+    #[arg(short, long, conflicts_with = "quiet")]
+    verbose: bool,
+    /// Shows only the final summary. It overrides `report.is_verbose`.
+    #[arg(short, long)]
+    quiet: bool,
+    /// Runs only the assertions with this tag.
+    #[arg(long)]
+    tag: Option<String>,
+    /// Shows the score of each tag in the final summary.
+    #[arg(long)]
+    group_by_tag: bool,
+    /// Shows together the failed assertions of a unit test that failed the same way. It
+    /// overrides `report.group_failures`.
+    #[arg(long)]
+    group_failures: bool,
+    /// Shows how long each section and each assertion took to run.
+    #[arg(long)]
+    durations: bool,
+    /// Minimum percentage of the maximum score for the grading to succeed. Otherwise, the
+    /// process exits with code 1.
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pass_threshold: u32,
+    /// Path of the report file. It overrides `report.path` and is ignored when the report
+    /// is written to stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Format of the report. With `score`, the stdout has nothing but the final score, so
+    /// it can be captured by a script, and `report.output` and `--output` are ignored.
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+    /// Whether the final score is colored according to `report.score_colors`. Report
+    /// files are never colored.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Grades even if fewer programs than expected are given. The missing ones are the
+    /// last declared ones, and every test that uses them fails as not submitted.
+    #[arg(long)]
+    allow_missing_programs: bool,
+    /// Maximum number of unit tests of a section that run at the same time.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    max_parallelism: u16,
+    /// Runs the sections and their unit tests in an order shuffled with this seed, to find
+    /// tests that depend on each other. The report keeps the declaration order.
+    #[arg(long)]
+    shuffle_seed: Option<u64>,
+    /// Maximum duration of the whole run, in seconds. Once it is exceeded, the remaining
+    /// tests fail as not run and the partial results are reported.
+    #[arg(long, value_name = "SECONDS")]
+    deadline: Option<u64>,
+    /// Fails instead of warning when the same file is given for different programs.
+    #[arg(long)]
+    reject_reused_paths: bool,
+    /// Warns instead of failing when a program is not executable, e.g. because the global
+    /// setup builds it. Its tests fail if it is still not executable when they run.
+    #[arg(long)]
+    allow_non_executable_programs: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // the programs get the SIGINT as well, as they are in the same process group, so the
+    // running assertion usually ends right away. A second one exits without waiting.
+    let handler = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            process::exit(INTERRUPTED_CODE.into());
+        }
+        eprintln!("interrupted: finishing the running tests, press Ctrl-C again to exit now");
+    });
+    if let Err(err) = handler {
+        eprintln!("warning: Ctrl-C will not report the partial results: {err}");
+    }
+    let result = match cli.command.unwrap_or(CliCommand::Run(cli.run)) {
+        CliCommand::Run(args) => run(args),
+        CliCommand::Check(args) => check(&args.config),
+        CliCommand::Schema => print_schema(),
+        CliCommand::Explain(args) => explain(&args.config),
+        CliCommand::ListTests(args) => list_tests(&args.config),
+    };
+    match result {
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(ERROR_CODE)
+        }
+        Ok(_) if INTERRUPTED.load(Ordering::Relaxed) => ExitCode::from(INTERRUPTED_CODE),
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(GRADING_FAILURE_CODE),
+    }
+}
+
+fn print_schema() -> Result<bool, String> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &GlobalConfig::json_schema())
+        .map_err(io::Error::from)
+        .and_then(|()| writeln!(stdout))
+        .map_err(|err| format!("could not write the schema: {err}"))?;
+    Ok(true)
+}
+
+/// Validates the configuration, including what is only checked when building the tests,
+/// and lists the programs that it expects.
+fn check(config_path: &Path) -> Result<bool, String> {
+    let config = load_config(config_path)?;
+    warn_about_config(&config);
+    config.build_placeholder_grading_config()?;
+    let expected_program_names = config.expected_program_names();
+    let mut message = format!(
+        "valid configuration '{}', which expects {} program(s):",
+        config_path.display(),
+        expected_program_names.len()
+    );
+    for name in expected_program_names {
+        message.push_str(&format!(" <{name}>"));
+    }
+    println!("{message}");
+    Ok(true)
+}
+
+fn explain(config_path: &Path) -> Result<bool, String> {
+    let config = load_config(config_path)?;
+    let grading_config = config.build_placeholder_grading_config()?;
+    write_explanation(&mut io::stdout().lock(), &grading_config)
+        .map_err(|err| format!("could not write the explanation: {err}"))?;
+    Ok(true)
+}
+
+fn list_tests(config_path: &Path) -> Result<bool, String> {
+    let config = load_config(config_path)?;
+    let grading_config = config.build_placeholder_grading_config()?;
+    write_test_tree(&mut io::stdout().lock(), &grading_config)
+        .map_err(|err| format!("could not write the tests: {err}"))?;
+    Ok(true)
+}
+
+/// Grades the programs, returning whether the final score reaches the pass threshold.
+fn run(cli: RunArgs) -> Result<bool, String> {
+    let Some(ref config_path) = cli.config else {
+        unreachable!("clap requires the configuration to grade");
+    };
+    if !cli.shared_programs.is_empty() {
+        // every positional argument is a configuration then
+        let config_paths: Vec<&Path> = std::iter::once(config_path.as_path())
+            .chain(cli.programs.iter().map(PathBuf::as_path))
+            .collect();
+        return run_several(&config_paths, &cli.shared_programs, &cli);
+    }
+    if let Some(ref manifest_path) = cli.manifest {
+        return run_manifest(config_path, manifest_path, &cli);
+    }
+    let (config, grading_config) = prepare(config_path, &cli.programs, &cli)?;
+
+    // the command line takes precedence over the configuration
+    let mut reporter: Box<dyn Reporter> = match config.get_report().get_output() {
+        _ if cli.format == ReportFormat::Score => Box::new(ScoreReporter::new(io::stdout().lock())),
+        ReportOutput::Stdout => Box::new(new_text_reporter(
+            io::stdout().lock(),
+            config.get_report(),
+            &cli,
+            true,
+        )),
+        ReportOutput::Txt => {
+            let path = cli
+                .output
+                .as_deref()
+                .unwrap_or(config.get_report().get_path());
+            let file = create_report_file(path)?;
+            Box::new(new_text_reporter(file, config.get_report(), &cli, false))
+        }
+    };
+    let result = Grader::new(&grading_config)
+        .with_interruption_flag(&INTERRUPTED)
+        .run_with_reporter(reporter.as_mut())
+        .map_err(|err| err.to_string())?;
+    Ok(result.meets_threshold(cli.pass_threshold))
+}
+
+/// Grades the same programs with every configuration, one after the other, returning
+/// whether the combined score reaches the pass threshold.
+///
+/// The reports are written one after the other to the stdout, or to `--output`, each one
+/// under the path of its configuration, and followed by the combined score. The
+/// `report.output` and `report.path` of the configurations are ignored.
+fn run_several(
+    config_paths: &[&Path],
+    programs: &[PathBuf],
+    cli: &RunArgs,
+) -> Result<bool, String> {
+    let writes_to_stdout = cli.output.is_none() || cli.format != ReportFormat::Text;
+    let mut writer: Box<dyn Write> = match cli.output {
+        Some(ref path) if !writes_to_stdout => Box::new(create_report_file(path)?),
+        _ => Box::new(io::stdout().lock()),
+    };
+    let mut combined_result = CombinedGradingResult::new();
+    for config_path in config_paths {
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            break;
+        }
+        let (config, grading_config) = prepare(config_path, programs, cli)?;
+        let grader = Grader::new(&grading_config).with_interruption_flag(&INTERRUPTED);
+        let result = if cli.format == ReportFormat::Score {
+            grader.run()
+        } else {
+            writeln!(writer, "== {} ==", config_path.display())
+                .map_err(|err| format!("could not write the report: {err}"))?;
+            grader.run_with_reporter(&mut new_text_reporter(
+                &mut writer,
+                config.get_report(),
+                cli,
+                writes_to_stdout,
+            ))
+        }
+        .map_err(|err| format!("'{}': {err}", config_path.display()))?;
+        combined_result.add_result(config_path.display().to_string(), result);
+    }
+    let written = if cli.format == ReportFormat::Score {
+        writeln!(writer, "{}", combined_result.score_line())
+    } else {
+        writeln!(writer, "Combined score: {}", combined_result.score_line())
+    };
+    written
+        .and_then(|()| writer.flush())
+        .map_err(|err| format!("could not write the report: {err}"))?;
+    Ok(combined_result.meets_threshold(cli.pass_threshold))
+}
+
+/// Grades the submissions of the manifest with the configuration, one after the other,
+/// returning whether the score of every one reaches the pass threshold.
+///
+/// Every submission is checked against the configuration before grading any of them. The
+/// reports are written one after the other to the stdout, or to `--output`, each one under
+/// the id of its submission, and followed by the number of submissions that reach the pass
+/// threshold. With `--format score`, there is a line per submission with its id and score.
+fn run_manifest(config_path: &Path, manifest_path: &Path, cli: &RunArgs) -> Result<bool, String> {
+    // read only once, as the stdin can not be read again
+    let content = read_config(config_path)?;
+    let config = parse_config(&content, config_path)?;
+    warn_about_config(&config);
+    let manifest = Manifest::read_from_file(manifest_path)
+        .map_err(|err| format!("invalid manifest '{}': {err}", manifest_path.display()))?;
+    let expected_program_names = config.expected_program_names();
+    for entry in manifest.entries() {
+        entry
+            .check_programs(&expected_program_names, cli.allow_missing_programs)
+            .map_err(|err| format!("submission '{}': {err}", entry.id()))?;
+    }
+
+    let writes_to_stdout = cli.output.is_none() || cli.format != ReportFormat::Text;
+    let mut writer: Box<dyn Write> = match cli.output {
+        Some(ref path) if !writes_to_stdout => Box::new(create_report_file(path)?),
+        _ => Box::new(io::stdout().lock()),
+    };
+    let mut batch_result = BatchResult::new();
+    for entry in manifest.entries() {
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            break;
+        }
+        // the configuration is consumed by its initialization, so it is parsed again
+        let (config, grading_config) = initialize(
+            parse_config(&content, config_path)?,
+            &entry.program_name_to_path(),
+            cli,
+        )
+        .map_err(|err| format!("submission '{}': {err}", entry.id()))?;
+        let grader = Grader::new(&grading_config).with_interruption_flag(&INTERRUPTED);
+        let result = if cli.format == ReportFormat::Score {
+            grader.run()
+        } else {
+            writeln!(writer, "== {} ==", entry.id())
+                .map_err(|err| format!("could not write the report: {err}"))?;
+            grader.run_with_reporter(&mut new_text_reporter(
+                &mut writer,
+                config.get_report(),
+                cli,
+                writes_to_stdout,
+            ))
+        }
+        .map_err(|err| format!("submission '{}': {err}", entry.id()))?;
+        if cli.format == ReportFormat::Score {
+            writeln!(writer, "{}: {}", entry.id(), result.score_line())
+                .map_err(|err| format!("could not write the report: {err}"))?;
+        }
+        batch_result.add_result(entry.id().to_string(), result);
+    }
+    if cli.format == ReportFormat::Text {
+        writeln!(
+            writer,
+            "Submissions reaching the pass threshold: {}/{}",
+            batch_result.count_meeting_threshold(cli.pass_threshold),
+            batch_result.results().len()
+        )
+        .map_err(|err| format!("could not write the report: {err}"))?;
+    }
+    writer
+        .flush()
+        .map_err(|err| format!("could not write the report: {err}"))?;
+    Ok(batch_result.meets_threshold(cli.pass_threshold))
+}
+
+/// Loads the configuration, matches the programs with it and builds the tests to run,
+/// according to the options of the command line.
+fn prepare(
+    config_path: &Path,
+    programs: &[PathBuf],
+    cli: &RunArgs,
+) -> Result<(GlobalConfig<Initialized>, GradingConfig), String> {
+    let config = load_config(config_path)?;
+    warn_about_config(&config);
+
+    let expected_program_names = config.expected_program_names();
+    let is_missing_allowed =
+        cli.allow_missing_programs && programs.len() < expected_program_names.len();
+    if expected_program_names.len() != programs.len() && !is_missing_allowed {
+        return Err(wrong_number_of_programs_message(
+            &expected_program_names,
+            programs.len(),
+        ));
+    }
+    // the programs are matched by position with their standard names
+    let program_names: Vec<String> = (1..=programs.len())
+        .map(|n| format!("program{n}"))
+        .collect();
+    let program_name_to_path: Vec<(&str, PathBuf)> = program_names
+        .iter()
+        .map(String::as_str)
+        .zip(programs.iter().cloned())
+        .collect();
+    initialize(config, &program_name_to_path, cli)
+}
+
+/// Initializes the configuration with the paths of the programs, by program name, and
+/// builds the tests to run, according to the options of the command line.
+fn initialize(
+    config: GlobalConfig,
+    program_name_to_path: &[(&str, PathBuf)],
+    cli: &RunArgs,
+) -> Result<(GlobalConfig<Initialized>, GradingConfig), String> {
+    let config = config
+        .with_reject_reused_paths(cli.reject_reused_paths)
+        .with_allow_non_executable_programs(cli.allow_non_executable_programs);
+    let config = if cli.allow_missing_programs {
+        config.initialize_allowing_missing_programs(program_name_to_path)
+    } else {
+        config.initialize(program_name_to_path)
+    }
+    .map_err(|err| err.1.to_string())?;
+    if !config.missing_programs().is_empty() {
+        eprintln!(
+            "warning: program(s) not submitted: {}",
+            config.missing_programs().join(", ")
+        );
+    }
+    if !config.non_executable_programs().is_empty() {
+        eprintln!(
+            "warning: program(s) not executable: {}",
+            config.non_executable_programs().join(", ")
+        );
+    }
+    for (program, other_program) in config.reused_paths() {
+        eprintln!("warning: {program} and {other_program} were given the same file");
+    }
+    let mut grading_config = config.build_grading_config()?;
+    if let Some(seed) = cli.seed {
+        grading_config.set_seed(seed);
+    }
+    grading_config.set_max_parallelism(cli.max_parallelism.into());
+    if let Some(shuffle_seed) = cli.shuffle_seed {
+        grading_config.set_shuffle_seed(shuffle_seed);
+    }
+    if let Some(deadline) = cli.deadline {
+        grading_config.set_deadline(Duration::from_secs(deadline));
+    }
+    if let Some(ref tag) = cli.tag {
+        grading_config
+            .retain_tagged(tag)
+            .map_err(|_| format!("no assertion is tagged with '{tag}'"))?;
+    }
+    Ok((config, grading_config))
+}
+
+/// Creates the text reporter, where `writes_to_stdout` tells whether `writer` is the
+/// stdout, which is the only one that may be colored.
+///
+/// The command line takes precedence over the `report` section of the configuration.
+fn new_text_reporter<W: Write>(
+    writer: W,
+    report: &ReportSection,
+    cli: &RunArgs,
+    writes_to_stdout: bool,
+) -> TextReporter<W> {
+    let is_verbose = cli.verbose || (!cli.quiet && report.is_verbose());
+    let mut reporter = TextReporter::new(writer, is_verbose);
+    if cli.quiet {
+        reporter = reporter.with_summary_only();
+    }
+    if cli.group_by_tag {
+        reporter = reporter.with_tag_summary();
+    }
+    if cli.durations {
+        reporter = reporter.with_durations();
+    }
+    if report.has_header() {
+        reporter = reporter.with_header();
+    }
+    if cli.group_failures || report.groups_failures() {
+        reporter = reporter.with_grouped_failures();
+    }
+    if writes_to_stdout && uses_color(cli.color) {
+        reporter = reporter.with_score_colors(*report.get_score_colors());
+    }
+    reporter
+}
+
+/// Whether the stdout is colored, following the `NO_COLOR` convention in `auto`.
+fn uses_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Auto => {
+            io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        }
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
+}
+
+/// Creates the report file, along with any missing parent directory.
+fn create_report_file(path: &Path) -> Result<File, String> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("could not create '{}': {err}", parent.display()))?;
+    }
+    File::create(path).map_err(|err| format!("could not create '{}': {err}", path.display()))
+}
+
+/// Reads and parses the configuration, and initializes the logger with its logging mode.
+fn load_config(config_path: &Path) -> Result<GlobalConfig, String> {
+    parse_config(&read_config(config_path)?, config_path)
+}
+
+/// Parses the configuration read from `config_path`, and initializes the logger with its
+/// logging mode.
+fn parse_config(content: &str, config_path: &Path) -> Result<GlobalConfig, String> {
+    let config = if config_path.extension().is_some_and(|ext| ext == "toml") {
+        GlobalConfig::from_toml(content).map_err(|err| err.to_string())
+    } else {
+        GlobalConfig::from_json(content).map_err(|err| err.to_string())
+    }
+    .map_err(|err| format!("invalid configuration '{}': {err}", config_path.display()))?
+    // the relative paths of the configuration are resolved against its own directory
+    .with_base_dir(config_path.parent().unwrap_or(Path::new("")));
+    init_logger(config.get_logging_mode());
+    Ok(config)
+}
+
+fn warn_about_config(config: &GlobalConfig) {
+    if !config.reserved_aliases().is_empty() {
+        eprintln!(
+            "warning: alias(es) that look like a standard program name: {}",
+            config.reserved_aliases().join(", ")
+        );
+    }
+    if !config.unused_programs().is_empty() {
+        eprintln!(
+            "warning: declared program(s) not run by any test: {}",
+            config.unused_programs().join(", ")
+        );
+    }
+}
+
+fn read_config(path: &Path) -> Result<String, String> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|err| format!("could not read the configuration from stdin: {err}"))?;
+        content
+    } else {
+        fs::read_to_string(path)
+            .map_err(|err| format!("could not read '{}': {err}", path.display()))?
+    };
+    if content.trim().is_empty() {
+        return Err(format!("empty configuration '{}'", path.display()));
+    }
+    Ok(content)
+}
+
+fn init_logger(logging_mode: &LoggingMode) {
+    let level = match logging_mode {
+        LoggingMode::Silent => LevelFilter::Off,
+        LoggingMode::Normal => LevelFilter::Warn,
+        LoggingMode::Verbose => LevelFilter::Debug,
+    };
+    // the stdout is reserved to the report, which may be parsed, e.g. with `--format score`.
+    // With several configurations, the logging mode of the first one is kept.
+    let _ = env_logger::Builder::new()
+        .filter_level(level)
+        .target(env_logger::Target::Stderr)
+        // e.g. `section=basics unit_test=echo assertion=empty`, to tell apart the records
+        // of the unit tests that run in parallel
+        .format_key_values(|buf, fields| {
+            default_kv_format(buf, fields)?;
+            default_kv_format(buf, &LogContext::current())
+        })
+        .try_init();
+}
+
+/// Lists every program expected by the configuration, so the user knows exactly what
+/// must be provided.
+fn wrong_number_of_programs_message(expected_program_names: &[String], given: usize) -> String {
+    let mut message = format!(
+        "the configuration expects {} program(s), but {given} was(were) given\n\nusage: clgrader <CONFIG>",
+        expected_program_names.len()
+    );
+    for name in expected_program_names {
+        message.push_str(&format!(" <{name}>"));
+    }
+    message
 }
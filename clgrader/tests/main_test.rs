@@ -1,10 +1,1466 @@
 use assert_cmd::Command;
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
 
 const EXECUTABLE_NAME: &str = "clgrader";
+const GRADING_FAILURE_CODE: i32 = 1;
+const ERROR_CODE: i32 = 2;
+
+const ECHO_CONFIG: &str = r#"
+        {
+          "title": "echo",
+          "sections": [
+            {
+              "title": "section 1",
+              "unit_tests": {
+                "tests": [
+                  {
+                    "title": "echo args",
+                    "detailed_tests": [
+                      { "name": "hello", "args": "hello", "stdout": "hello\n" },
+                      { "name": "bye", "args": "hello", "stdout": "bye\n" }
+                    ]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#;
+const ECHO_REPORT: &str = "section 1: 1/2\n\
+                           \x20 echo args (program1): 1/2\n\
+                           \x20   ✅ hello\n\
+                           \x20   ❌ bye\n\
+                           Final score: 1 / 2 (50.0%)\n\
+                           Seed: 42\n";
+
+/// Writes an executable shell script that echoes its arguments.
+fn write_echo_program(dir: &Path) -> std::path::PathBuf {
+    let path = dir.join("echo.sh");
+    fs::write(&path, "#!/bin/sh\necho \"$@\"\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn should_grade_program() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_grade_a_program_given_relative_to_the_cwd_or_to_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    write_echo_program(dir.path());
+
+    for resolve in ["cwd", "relative"] {
+        let config = ECHO_CONFIG.replacen(
+            r#""title": "echo","#,
+            &format!(
+                r#""title": "echo", "input": {{ "input_programs": [{{ "alias": "echo", "resolve": "{resolve}" }}] }},"#
+            ),
+            1,
+        );
+        fs::write(dir.path().join("config.json"), config).unwrap();
+
+        let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+        cmd.current_dir(dir.path())
+            .args(["config.json", "echo.sh", "--seed", "42"])
+            .assert()
+            .code(GRADING_FAILURE_CODE)
+            .stdout(ECHO_REPORT);
+    }
+}
+
+#[test]
+fn should_grade_a_program_given_relative_to_the_cwd_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("config.json"), ECHO_CONFIG).unwrap();
+    write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.current_dir(dir.path())
+        .args(["config.json", "./echo.sh", "--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_show_the_line_of_an_invalid_table_file_even_when_silent() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"{
+          "title": "echo",
+          "logging_mode": "silent",
+          "sections": [
+            { "unit_tests": { "tests": [{ "table_file": "tests.csv" }] } }
+          ]
+        }"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("tests.csv"), "args,stdout\na,a\nb,b,c\n").unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg(&config).arg(&program).assert().code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(
+        stderr.contains(&format!(
+            "{}:3: inconsistent test case size",
+            dir.path().join("tests.csv").display()
+        )),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn should_grade_program_with_the_run_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("run")
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_override_config_verbosity_from_cli() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let quiet_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "report": { "is_verbose": false },"#,
+        1,
+    );
+    fs::write(&config, quiet_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--verbose"])
+        .assert()
+        .code(GRADING_FAILURE_CODE);
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("      stdout:\n        expected: \"bye\\n\"\n"));
+}
+
+#[test]
+fn should_print_only_summary_when_quiet() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let verbose_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "report": { "is_verbose": true },"#,
+        1,
+    );
+    fs::write(&config, verbose_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "-q"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("Final score: 1 / 2 (50.0%)\nSeed: 42\n");
+}
+
+#[test]
+fn should_color_the_final_score_only_when_asked_outside_a_terminal() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let colored_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "report": { "score_colors": { "yellow": 50 } },"#,
+        1,
+    );
+    fs::write(&config, colored_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    Command::cargo_bin(EXECUTABLE_NAME)
+        .unwrap()
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "-q", "--color", "always"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("\x1b[33mFinal score: 1 / 2 (50.0%)\x1b[0m\nSeed: 42\n");
+    // the stdout of the test is not a terminal
+    Command::cargo_bin(EXECUTABLE_NAME)
+        .unwrap()
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "-q"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("Final score: 1 / 2 (50.0%)\nSeed: 42\n");
+}
+
+#[test]
+fn should_print_only_the_score_with_the_score_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    // the logs of the verbose mode must not reach the stdout
+    let verbose_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "logging_mode": "verbose", "report": { "is_verbose": true },"#,
+        1,
+    );
+    fs::write(&config, verbose_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--format", "score"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("1 / 2 (50.0%)\n");
+}
+
+#[test]
+fn should_tag_the_logs_with_the_assertion_they_belong_to() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let verbose_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "logging_mode": "verbose","#,
+        1,
+    );
+    fs::write(&config, verbose_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--max-parallelism", "2"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for name in ["hello", "bye"] {
+        assert!(
+            stderr
+                .lines()
+                .any(|line| line.contains("Executing assertion")
+                    && line.ends_with(&format!(
+                        " section=section 1 unit_test=echo args assertion={name}"
+                    ))),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn should_group_the_failures_when_configured() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let grouped_config = ECHO_CONFIG
+        .replacen(
+            r#""title": "echo","#,
+            r#""title": "echo", "report": { "group_failures": true },"#,
+            1,
+        )
+        .replacen(
+            r#"{ "name": "hello", "args": "hello", "stdout": "hello\n" }"#,
+            r#"{ "name": "hi", "args": "hello", "stdout": "hi\n" }"#,
+            1,
+        );
+    fs::write(&config, grouped_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(
+            "section 1: 0/2\n\
+             \x20 echo args (program1): 0/2\n\
+             \x20   ❌ this failure occurred in 2 assertions: hi, bye\n\
+             Final score: 0 / 2 (0.0%)\n\
+             Seed: 42\n",
+        );
+}
+
+#[test]
+fn should_show_the_durations_when_asked() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--durations"])
+        .assert()
+        .code(GRADING_FAILURE_CODE);
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("section 1: 1/2 ("), "{stdout}");
+    assert!(lines[2].starts_with("    ✅ hello ("), "{stdout}");
+    assert!(lines[3].starts_with("    ❌ bye ("), "{stdout}");
+    assert_eq!(lines[4..], ["Final score: 1 / 2 (50.0%)", "Seed: 42"]);
+}
+
+#[test]
+fn should_run_only_tagged_assertions() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let tagged_config = ECHO_CONFIG.replacen(
+        r#""stdout": "hello\n" }"#,
+        r#""stdout": "hello\n", "tags": ["basic"] }"#,
+        1,
+    );
+    fs::write(&config, tagged_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "-q", "--tag", "basic", "--group-by-tag"])
+        .assert()
+        .success()
+        .stdout("Tags:\n  basic: 1/1\nFinal score: 1 / 1 (100.0%)\nSeed: 42\n");
+}
+
+#[test]
+fn should_fail_when_tag_matches_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--tag", "missing"])
+        .assert()
+        .code(ERROR_CODE)
+        .stderr("error: no assertion is tagged with 'missing'\n");
+}
+
+#[test]
+fn should_succeed_when_score_meets_pass_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--pass-threshold", "50"])
+        .assert()
+        .success()
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_report_the_same_when_running_in_parallel() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--max-parallelism", "4"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_not_accept_zero_parallelism() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.args(["config.json", "--max-parallelism", "0"])
+        .assert()
+        .code(ERROR_CODE);
+}
+
+#[test]
+fn should_fail_the_tests_not_run_before_the_deadline() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--deadline", "0"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(
+            "section 1: 0/2\n\
+             \x20 echo args (program1): 0/2\n\
+             \x20   ❌ hello\n\
+             \x20     program not run: deadline exceeded\n\
+             \x20   ❌ bye\n\
+             \x20     program not run: deadline exceeded\n\
+             Deadline exceeded: some tests were not run\n\
+             Final score: 0 / 2 (0.0%)\n\
+             Seed: 42\n",
+        );
+}
+
+#[test]
+fn should_not_accept_pass_threshold_above_100() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.args(["config.json", "--pass-threshold", "101"])
+        .assert()
+        .code(ERROR_CODE);
+}
+
+#[test]
+fn should_explain_config_without_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("explain").arg(&config).assert().success().stdout(
+        "echo\n\
+             Mode: Weighted\n\
+             section 1 (weight: 1)\n\
+             \x20 echo args (program1)\n\
+             \x20   hello (weight: 1)\n\
+             \x20     args: [\"hello\"]\n\
+             \x20     stdout must be: \"hello\\n\"\n\
+             \x20   bye (weight: 1)\n\
+             \x20     args: [\"hello\"]\n\
+             \x20     stdout must be: \"bye\\n\"\n",
+    );
+}
+
+#[test]
+fn should_list_the_tests_without_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("list-tests")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(
+            "echo\n\
+         section 1 (weight 1)\n\
+         \x20 UnitTest \"echo args\" [program1] — 2 assertion(s)\n\
+         Maximum score: 2\n",
+        );
+}
+
+#[test]
+fn should_print_the_schema_without_a_config() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg("schema").assert().success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.starts_with("{\n"));
+    assert!(stdout.contains("\"title\": \"GlobalConfig\""));
+}
+
+#[test]
+fn should_not_accept_a_config_with_the_schema_subcommand() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.args(["schema", "config.json"])
+        .assert()
+        .code(ERROR_CODE);
+}
+
+#[test]
+fn should_check_config_without_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("check")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(format!(
+            "valid configuration '{}', which expects 1 program(s): <program1/p1>\n",
+            config.display()
+        ));
+}
+
+#[test]
+fn should_fail_to_check_an_invalid_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, r#"{ "title": "echo", "sections": [] }"#).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("check").arg(&config).assert().code(ERROR_CODE);
+}
+
+#[test]
+fn should_not_accept_verbose_and_quiet_together() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.args(["config.json", "-v", "-q"]).assert().failure();
+}
+
+#[test]
+fn should_grade_program_with_unusual_path() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program_dir = dir
+        .path()
+        .join(OsStr::from_bytes(b"student's files \xff (a&b) $HOME"));
+    fs::create_dir(&program_dir).unwrap();
+    let program = write_echo_program(&program_dir);
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_pass_base_args_before_test_args() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "base args",
+          "input": { "input_programs": [{ "alias": "echo", "base_args": ["base"] }] },
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "args": "hello", "stdout": "base hello\n" }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "-q"])
+        .assert()
+        .success()
+        .stdout("Final score: 1 / 1 (100.0%)\nSeed: 42\n");
+}
+
+#[test]
+fn should_grade_program_with_toml_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(
+        &config,
+        r#"
+        title = "echo"
+
+        [[sections]]
+        title = "section 1"
+
+        [[sections.unit_tests.tests]]
+        title = "echo args"
+        table = [
+          ["name",  "args",  "stdout"],
+          ["hello", "hello", "hello\n"],
+          ["bye",   "hello", "bye\n"],
+        ]
+        "#,
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_read_config_from_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("-")
+        .arg(&program)
+        .args(["--seed", "42"])
+        .write_stdin(ECHO_CONFIG)
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+}
+
+#[test]
+fn should_expose_seed_to_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "seed",
+          "sections": [
+            {
+              "title": "section 1",
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "name": "seed", "stdout": "7\n" }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = dir.path().join("seed.sh");
+    fs::write(&program, "#!/bin/sh\necho \"$GRADER_SEED\"\n").unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "7"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("Final score: 1 / 1 (100.0%)\nSeed: 7\n"));
+}
+
+#[test]
+fn should_load_env_file_relative_to_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    let config = config_dir.join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "env",
+          "sections": [
+            {
+              "title": "section 1",
+              "unit_tests": {
+                "env": [["GREETING", "hi"]],
+                "env_file": "test.env",
+                "tests": [{ "detailed_tests": [{ "name": "env", "stdout": "hi bob\n" }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("test.env"),
+        "# variables of the tests\nGREETING=hello\nexport NAME=\"bob\"\n",
+    )
+    .unwrap();
+    let program = dir.path().join("env.sh");
+    fs::write(&program, "#!/bin/sh\necho \"$GREETING $NAME\"\n").unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config).arg(&program).assert().success();
+}
+
+#[test]
+fn should_inherit_the_default_env_unless_a_section_overrides_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "env",
+          "defaults": { "env": [["GREETING", "hi"]] },
+          "sections": [
+            {
+              "title": "inherited",
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "name": "env", "stdout": "hi\n" }] }]
+              }
+            },
+            {
+              "title": "cleared",
+              "unit_tests": {
+                "env": [],
+                "tests": [{ "detailed_tests": [{ "name": "env", "stdout": "\n" }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = dir.path().join("env.sh");
+    fs::write(&program, "#!/bin/sh\necho \"$GREETING\"\n").unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .env_remove("GREETING")
+        .assert()
+        .success();
+}
+
+#[test]
+fn should_fail_with_missing_env_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "env",
+          "sections": [
+            {
+              "unit_tests": {
+                "env_file": "missing.env",
+                "tests": [{ "detailed_tests": [{ "name": "env", "stdout": "" }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg(&config).arg(&program).assert().code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("could not read env_file"));
+}
+
+#[test]
+fn should_fail_with_empty_config_from_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg("-")
+        .arg(&program)
+        .write_stdin("  \n")
+        .assert()
+        .code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert_eq!(stderr, "error: empty configuration '-'\n");
+}
 
 #[test]
-fn should_return_successfully() {
+fn should_list_expected_programs_when_their_number_is_wrong() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "two programs",
+          "input": { "input_programs": ["exe", { "alias": "java" }] },
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg(&config).arg(&program).assert().code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("expects 2 program(s), but 1 was(were) given"));
+    assert!(stderr.contains("usage: clgrader <CONFIG> <program1/p1> <program2/p2/java>"));
+}
+
+#[test]
+fn should_fail_the_tests_of_missing_programs_when_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "two programs",
+          "input": { "input_programs": ["exe", "exe"] },
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  { "title": "first", "detailed_tests": [{ "args": "a", "stdout": "a\n" }] },
+                  {
+                    "title": "second",
+                    "program_name": "p2",
+                    "detailed_tests": [{ "args": "b", "stdout": "b\n" }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
     let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
 
-    cmd.assert().success().stdout("Hello, world!, 10 - 4 = 6\n");
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--allow-missing-programs"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(
+            "Section 1: 1/2\n\
+             \x20 first (program1): 1/1\n\
+             \x20   ✅ Assertion 1\n\
+             \x20 second (program2): 0/1\n\
+             \x20   ❌ Assertion 1\n\
+             \x20     program not submitted\n\
+             Final score: 1 / 2 (50.0%)\n\
+             Seed: 42\n",
+        );
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.starts_with("warning: program(s) not submitted: program2\n"));
+}
+
+const TWO_ECHO_PROGRAMS_CONFIG: &str = r#"
+{
+  "title": "two programs",
+  "input": { "input_programs": ["exe", "exe"] },
+  "sections": [
+    {
+      "unit_tests": {
+        "tests": [
+          { "title": "first", "detailed_tests": [{ "args": "a", "stdout": "a\n" }] },
+          {
+            "title": "second",
+            "program_name": "p2",
+            "detailed_tests": [{ "args": "b", "stdout": "b\n" }]
+          }
+        ]
+      }
+    }
+  ]
+}"#;
+
+#[test]
+fn should_warn_when_the_same_file_is_given_for_different_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, TWO_ECHO_PROGRAMS_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("warning: program1 and program2 were given the same file\n"));
+}
+
+#[test]
+fn should_reject_the_same_file_for_different_programs_when_asked() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, TWO_ECHO_PROGRAMS_CONFIG).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .arg(&program)
+        .arg("--reject-reused-paths")
+        .assert()
+        .code(ERROR_CODE)
+        .stderr("error: the same path was given for different programs\n");
+}
+
+#[test]
+fn should_fail_up_front_when_the_program_is_not_executable() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = dir.path().join("echo.py");
+    fs::write(&program, "print('hello')\n").unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg(&program)
+        .assert()
+        .code(ERROR_CODE)
+        .stdout("")
+        .stderr("error: program not executable\n");
+}
+
+#[test]
+fn should_only_warn_about_non_executable_programs_when_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    let program = dir.path().join("echo.py");
+    fs::write(&program, "print('hello')\n").unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--allow-non-executable-programs"])
+        .assert()
+        .code(GRADING_FAILURE_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("warning: program(s) not executable: program1\n"));
+}
+
+#[test]
+fn should_warn_about_aliases_that_look_like_standard_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        ECHO_CONFIG.replacen(
+            r#""title": "echo","#,
+            r#""title": "echo", "input": { "input_programs": [{ "alias": "program2" }] },"#,
+            1,
+        ),
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout(ECHO_REPORT);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(
+        stderr.contains("warning: alias(es) that look like a standard program name: program2\n")
+    );
+}
+
+#[test]
+fn should_warn_about_programs_not_run_by_any_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        ECHO_CONFIG.replacen(
+            r#""title": "echo","#,
+            r#""title": "echo", "input": { "input_programs": ["exe", "exe"] },"#,
+            1,
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg("check").arg(&config).assert().success();
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("warning: declared program(s) not run by any test: program2\n"));
+}
+
+/// Writes the echo configuration and another one whose only test passes.
+fn write_two_configs(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let config_a = dir.join("a.json");
+    fs::write(&config_a, ECHO_CONFIG).unwrap();
+    let config_b = dir.join("b.json");
+    fs::write(
+        &config_b,
+        ECHO_CONFIG
+            .replacen(r#""title": "section 1""#, r#""title": "section 2""#, 1)
+            .replacen(
+                r#",
+                      { "name": "bye", "args": "hello", "stdout": "bye\n" }"#,
+                "",
+                1,
+            ),
+    )
+    .unwrap();
+    (config_a, config_b)
+}
+
+#[test]
+fn should_grade_the_programs_with_several_configs() {
+    let dir = tempfile::tempdir().unwrap();
+    let (config_a, config_b) = write_two_configs(dir.path());
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config_a)
+        .arg(&config_b)
+        .arg("--programs")
+        .arg(&program)
+        .args(["--seed", "42", "--pass-threshold", "60"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "== {} ==\n\
+             {ECHO_REPORT}\
+             == {} ==\n\
+             section 2: 1/1\n\
+             \x20 echo args (program1): 1/1\n\
+             \x20   ✅ hello\n\
+             Final score: 1 / 1 (100.0%)\n\
+             Seed: 42\n\
+             Combined score: 2 / 3 (66.7%)\n",
+            config_a.display(),
+            config_b.display()
+        ));
+}
+
+#[test]
+fn should_print_only_the_combined_score_of_several_configs() {
+    let dir = tempfile::tempdir().unwrap();
+    let (config_a, config_b) = write_two_configs(dir.path());
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config_a)
+        .arg(&config_b)
+        .arg("--programs")
+        .arg(&program)
+        .args(["--format", "score"])
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("2 / 3 (66.7%)\n");
+}
+
+#[test]
+fn should_name_the_config_that_can_not_grade_the_programs() {
+    let dir = tempfile::tempdir().unwrap();
+    let (config_a, _) = write_two_configs(dir.path());
+    let missing_config = dir.path().join("missing.json");
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config_a)
+        .arg(&missing_config)
+        .arg("--programs")
+        .arg(&program)
+        .assert()
+        .code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains(&format!("could not read '{}'", missing_config.display())));
+}
+
+/// Writes the echo program and a program that prints nothing, graded 1/2 and 0/2 by the
+/// `ECHO_CONFIG`.
+fn write_two_submissions(dir: &Path) {
+    write_echo_program(dir);
+    let path = dir.join("silent.sh");
+    fs::write(&path, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn should_grade_every_submission_of_a_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    write_two_submissions(dir.path());
+    let manifest = dir.path().join("submissions.json");
+    fs::write(
+        &manifest,
+        r#"[
+          { "id": "alice", "programs": { "program1": "echo.sh" } },
+          { "id": "bob", "programs": { "p1": "silent.sh" } }
+        ]"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg("--manifest")
+        .arg(&manifest)
+        .args(["--seed", "42", "--pass-threshold", "50"])
+        .assert()
+        .code(GRADING_FAILURE_CODE);
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(
+        stdout.starts_with(&format!("== alice ==\n{ECHO_REPORT}== bob ==\n")),
+        "{stdout}"
+    );
+    assert!(
+        stdout.ends_with(
+            "Final score: 0 / 2 (0.0%)\nSeed: 42\nSubmissions reaching the pass threshold: 1/2\n"
+        ),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn should_print_only_the_score_of_each_submission_of_a_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    write_two_submissions(dir.path());
+    let manifest = dir.path().join("submissions.csv");
+    fs::write(&manifest, "id,program1\nalice,echo.sh\nbob,silent.sh\n").unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg(&config)
+        .arg("--manifest")
+        .arg(&manifest)
+        .args(["--format", "score", "--pass-threshold", "0"])
+        .assert()
+        .success()
+        .stdout("alice: 1 / 2 (50.0%)\nbob: 0 / 2 (0.0%)\n");
+}
+
+#[test]
+fn should_grade_a_manifest_with_the_config_read_from_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    write_two_submissions(dir.path());
+    let manifest = dir.path().join("submissions.csv");
+    fs::write(&manifest, "id,program1\nalice,echo.sh\nbob,silent.sh\n").unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("-")
+        .arg("--manifest")
+        .arg(&manifest)
+        .args(["--format", "score", "--pass-threshold", "0"])
+        .write_stdin(ECHO_CONFIG)
+        .assert()
+        .success()
+        .stdout("alice: 1 / 2 (50.0%)\nbob: 0 / 2 (0.0%)\n");
+}
+
+#[test]
+fn should_warn_only_once_about_unused_programs_when_grading_a_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        ECHO_CONFIG.replacen(
+            r#""title": "echo","#,
+            r#""title": "echo", "logging_mode": "verbose", "input": { "input_programs": ["exe", "exe"] },"#,
+            1,
+        ),
+    )
+    .unwrap();
+    write_two_submissions(dir.path());
+    let manifest = dir.path().join("submissions.csv");
+    fs::write(
+        &manifest,
+        "id,program1,program2\nalice,echo.sh,echo.sh\nbob,silent.sh,echo.sh\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg("--manifest")
+        .arg(&manifest)
+        .args(["--format", "score", "--pass-threshold", "0"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert_eq!(stderr.matches("not run by any test").count(), 1, "{stderr}");
+}
+
+#[test]
+fn should_check_every_submission_of_a_manifest_before_grading() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(&config, ECHO_CONFIG).unwrap();
+    write_two_submissions(dir.path());
+    let manifest = dir.path().join("submissions.csv");
+    fs::write(
+        &manifest,
+        "id,program1,program2\nalice,echo.sh,\nbob,silent.sh,echo.sh\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg("--manifest")
+        .arg(&manifest)
+        .assert()
+        .code(ERROR_CODE)
+        .stdout("");
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(
+        stderr.contains("submission 'bob': the submission supplies a program that is not expected"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn should_start_the_report_with_a_header_when_configured() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        ECHO_CONFIG.replacen(
+            r#""title": "echo","#,
+            r#""title": "echo", "author": "teacher", "report": { "header": true },"#,
+            1,
+        ),
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42"])
+        .assert()
+        .code(GRADING_FAILURE_CODE);
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let (header, report) = stdout.split_at(stdout.find("section 1").unwrap());
+    assert!(header.starts_with("Title: echo\nAuthor: teacher\nDate: "));
+    assert!(header.ends_with(" UTC\n"));
+    assert_eq!(report, ECHO_REPORT);
+}
+
+#[test]
+fn should_fail_with_missing_config() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.arg("____missing_config.json").assert().code(ERROR_CODE);
+}
+
+#[test]
+fn should_write_txt_report_to_output_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let txt_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "report": { "output": "txt", "path": "ignored.txt" },"#,
+        1,
+    );
+    fs::write(&config, txt_config).unwrap();
+    let program = write_echo_program(dir.path());
+    let report = dir.path().join("reports").join("nested").join("grade.txt");
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    cmd.current_dir(dir.path())
+        .arg(&config)
+        .arg(&program)
+        .args(["--seed", "42", "--output"])
+        .arg(&report)
+        .assert()
+        .code(GRADING_FAILURE_CODE)
+        .stdout("");
+    assert_eq!(fs::read_to_string(&report).unwrap(), ECHO_REPORT);
+    assert!(!dir.path().join("ignored.txt").exists());
+}
+
+#[test]
+fn should_fail_when_report_can_not_be_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    let txt_config = ECHO_CONFIG.replacen(
+        r#""title": "echo","#,
+        r#""title": "echo", "report": { "output": "txt" },"#,
+        1,
+    );
+    fs::write(&config, txt_config).unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd
+        .arg(&config)
+        .arg(&program)
+        .arg("--output")
+        .arg(config.join("report.txt"))
+        .assert()
+        .code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stderr.starts_with("error: could not create"));
+}
+
+#[test]
+fn should_show_the_path_of_the_invalid_field_in_the_configuration() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        ECHO_CONFIG.replacen(r#""args": "hello", "#, r#""args": 1, "#, 1),
+    )
+    .unwrap();
+    let program = write_echo_program(dir.path());
+
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg(&config).arg(&program).assert().code(ERROR_CODE);
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(
+        stderr.contains("sections[0].unit_tests.tests[0].detailed_tests[0].args: "),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn should_report_partial_results_when_interrupted() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        r#"
+        {
+          "title": "sleep",
+          "sections": [
+            {
+              "title": "section 1",
+              "unit_tests": {
+                "tests": [{
+                  "title": "sleep",
+                  "detailed_tests": [
+                    { "name": "first", "args": "1", "status": 0 },
+                    { "name": "second", "args": "1", "status": 0 }
+                  ]
+                }]
+              }
+            }
+          ]
+        }"#,
+    )
+    .unwrap();
+
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin(EXECUTABLE_NAME))
+        .arg(&config)
+        .arg("/bin/sleep")
+        .args(["--seed", "1"])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    // only the grader is interrupted, so the running assertion finishes normally
+    let killed = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(130));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "section 1: 1/1\n\
+         \x20 sleep (program1): 1/1\n\
+         \x20   ✅ first\n\
+         Interrupted: the results are incomplete\n\
+         Final score: 1 / 1 (100.0%)\n\
+         Seed: 1\n"
+    );
+}
+
+#[test]
+fn should_print_the_version_of_the_crate() {
+    let mut cmd = Command::cargo_bin(EXECUTABLE_NAME).unwrap();
+
+    let output = cmd.arg("--version").assert().success().get_output().clone();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with(&format!("clgrader {}", env!("CARGO_PKG_VERSION"))),
+        "{stdout}"
+    );
 }
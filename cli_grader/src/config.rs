@@ -1,25 +1,82 @@
 use crate::{
     GradingConfig, LoggingMode,
     config::{
-        grading_section::GradingSection, input_section::InputSection,
-        report_section::ReportSection, test_section::TestSection,
+        defaults_section::DefaultsSection, grading_section::GradingSection,
+        input_section::InputSection, report_section::ReportSection, test_section::TestSection,
     },
-    input::ExecutableArtifact,
+    input::{ExecutableArtifact, ProgramType},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, marker, path::PathBuf};
+use shlex::Shlex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, marker,
+    path::PathBuf,
+};
 
+mod defaults_section;
 mod grading_section;
 mod input_section;
-mod report_section;
+pub(crate) mod manifest;
+pub(crate) mod report_section;
 mod test_section;
 
 const DEFAULT_MAIN_PROGRAM_NAME: &str = "program1";
 const DEFAULT_PREFIX_PROGRAM_NAME1: &str = "program";
 const DEFAULT_PREFIX_PROGRAM_NAME2: &str = "p";
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// Shell used to run the setup and teardown commands that need shell semantics.
+const SHELL: &str = "sh";
+
+/// Process a slice of raw string commands (String) into a vector of commands in
+/// the format: (Command, Vec<Arg>).
+///
+/// # Example
+/// Transforms the raw string command: `"cmd1 arg1 arg2 arg3"` into
+/// ("cmd1", ["arg1", "arg2", "arg3"])
+fn process_raw_string_commands(
+    commands: &[String],
+) -> Result<Vec<(String, Vec<String>)>, &'static str> {
+    let mut processed_commands = vec![];
+    for command in commands {
+        let mut lex = Shlex::new(command.as_str());
+        let command_name = match lex.next() {
+            Some(c) => c,
+            None => return Err("missing command"),
+        };
+        let mut processed_command = (command_name, vec![]);
+        for arg in lex.by_ref() {
+            processed_command.1.push(arg);
+        }
+        if lex.had_error {
+            return Err("invalid args string");
+        }
+        processed_commands.push(processed_command);
+    }
+    Ok(processed_commands)
+}
+
+/// Process a slice of raw string commands into commands that run them through `SHELL`,
+/// so pipes, redirects, and the other shell features are available.
+///
+/// # Example
+/// Transforms the raw string command: `"cat a.txt | wc -l > n.txt"` into
+/// ("sh", ["-c", "cat a.txt | wc -l > n.txt"])
+fn process_shell_commands(commands: &[String]) -> Result<Vec<(String, Vec<String>)>, &'static str> {
+    let mut processed_commands = vec![];
+    for command in commands {
+        if command.trim().is_empty() {
+            return Err("missing command");
+        }
+        processed_commands.push((SHELL.to_string(), vec!["-c".to_string(), command.clone()]));
+    }
+    Ok(processed_commands)
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
+#[schemars(rename = "GlobalConfig")]
 struct GlobalConfigUnchecked {
     title: String,
     author: Option<String>,
@@ -31,29 +88,73 @@ struct GlobalConfigUnchecked {
     report: ReportSection,
     #[serde(default)]
     input: InputSection,
+    #[serde(default)]
+    global_setup: Vec<String>,
+    #[serde(default)]
+    global_teardown: Vec<String>,
+    #[serde(default)]
+    defaults: DefaultsSection,
     sections: Vec<TestSection>,
 }
 
+/// State of a `GlobalConfig` that was only parsed and validated.
 #[derive(Debug, PartialEq)]
-struct NotInitialized;
+pub struct NotInitialized;
 
+/// State of a `GlobalConfig` that already knows the executables it will grade.
 #[derive(Debug, PartialEq)]
-struct Initialized;
+pub struct Initialized;
 
+/// The configuration written by the instructor.
+///
+/// It is first deserialized as `GlobalConfig<NotInitialized>` and, once the paths of the
+/// programs are known, it must be initialized in order to build the `GradingConfig`.
 #[derive(Serialize, Debug, PartialEq)]
-struct GlobalConfig<State = NotInitialized> {
+pub struct GlobalConfig<State = NotInitialized> {
     title: String,
     author: Option<String>,
     logging_mode: LoggingMode,
     grading: GradingSection,
     report: ReportSection,
     input: InputSection,
+    /// Commands executed only once, before the first section is graded.
+    global_setup: Vec<String>,
+    /// Commands executed only once, after the last section is graded.
+    global_teardown: Vec<String>,
+    /// Values inherited by the unit tests of the sections that do not define their own.
+    defaults: DefaultsSection,
     sections: Vec<TestSection>,
     // aux
     /// In order to initialize this field, it is necessary to run `initialize` at least
     /// once.
     #[serde(skip)]
     executables_by_name: Option<HashMap<String, ExecutableArtifact>>,
+    /// Standard names of the declared programs that were not provided when initializing
+    /// with `initialize_allowing_missing_programs`.
+    #[serde(skip)]
+    missing_programs: Vec<String>,
+    /// Whether giving the same file to different programs is an error when initializing,
+    /// instead of only a warning.
+    #[serde(skip)]
+    reject_reused_paths: bool,
+    /// Standard names of the declared programs that are not run by any test, see
+    /// `InputSection::rejects_unused_programs`.
+    #[serde(skip)]
+    unused_programs: Vec<String>,
+    /// Pairs of standard names of the programs that were given the same file.
+    #[serde(skip)]
+    reused_paths: Vec<(String, String)>,
+    /// Whether a program that is not executable is only a warning when initializing,
+    /// instead of an error, e.g. because the global setup builds it.
+    #[serde(skip)]
+    allow_non_executable_programs: bool,
+    /// Standard names of the programs that were given a file that is not executable.
+    #[serde(skip)]
+    non_executable_programs: Vec<String>,
+    /// Directory against which the relative paths of the configuration, e.g. `env_file`,
+    /// are resolved. By default, it is the current directory.
+    #[serde(skip)]
+    base_dir: PathBuf,
     #[serde(skip)]
     _state: marker::PhantomData<State>,
 }
@@ -68,7 +169,94 @@ impl<'de> Deserialize<'de> for GlobalConfig<NotInitialized> {
     }
 }
 
+impl<State> GlobalConfig<State> {
+    pub fn get_logging_mode(&self) -> &LoggingMode {
+        &self.logging_mode
+    }
+
+    pub fn get_report(&self) -> &ReportSection {
+        &self.report
+    }
+
+    /// Aliases of the programs that look like a standard name (`program<n>` or `p<n>`)
+    /// without being one, see `InputSection::get_reserved_aliases`.
+    pub fn reserved_aliases(&self) -> &[String] {
+        self.input.get_reserved_aliases()
+    }
+
+    /// Standard names (`program<n>`) of the declared programs that are not run by any
+    /// test, neither explicitly nor as the default program.
+    pub fn unused_programs(&self) -> &[String] {
+        &self.unused_programs
+    }
+
+    fn build_grading_config_with(
+        &self,
+        executables_by_name: &HashMap<String, ExecutableArtifact>,
+    ) -> Result<GradingConfig, String> {
+        let mut c = GradingConfig::new(
+            self.title.clone(),
+            self.author.clone(),
+            self.grading.get_grading_mode(),
+        );
+        c.set_global_setup(process_raw_string_commands(&self.global_setup)?);
+        c.set_global_teardown(process_raw_string_commands(&self.global_teardown)?);
+        c.set_global_env(
+            self.defaults.global_env(),
+            self.defaults.inherits_parent_env(),
+        );
+
+        for (i, t) in self.sections.iter().enumerate() {
+            if t.is_skipped() {
+                c.add_skipped_section(t.get_name(i + 1));
+            } else {
+                c.add_grading_section(t.build_grading_section(
+                    i + 1,
+                    executables_by_name,
+                    self.input.get_default_program(),
+                    &self.defaults,
+                    &self.base_dir,
+                    self.grading.get_comparison_policy(),
+                )?);
+            }
+        }
+        if c.max_reachable_score().is_none() {
+            return Err("the sum of the weights is too large".into());
+        }
+
+        Ok(c)
+    }
+}
+
+/// Wraps errors that are not related to any field, like a syntax error.
+fn without_path<E>(err: E) -> serde_path_to_error::Error<E> {
+    serde_path_to_error::Error::new(serde_path_to_error::Track::new().path(), err)
+}
+
 impl GlobalConfig<NotInitialized> {
+    /// JSON Schema of the configuration format, so editors can validate and autocomplete
+    /// the configuration files.
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(GlobalConfigUnchecked).to_value()
+    }
+
+    /// The errors are prefixed with the path of the invalid field, e.g.
+    /// `sections[2].unit_tests.tests[0]: <message>`.
+    pub fn from_json(config: &str) -> Result<Self, serde_path_to_error::Error<serde_json::Error>> {
+        let mut deserializer = serde_json::Deserializer::from_str(config);
+        let result = serde_path_to_error::deserialize(&mut deserializer)?;
+        // same as `serde_json::from_str`, which rejects trailing characters
+        deserializer.end().map_err(without_path)?;
+        Ok(result)
+    }
+
+    /// Same as `from_json`, but for TOML.
+    pub fn from_toml(config: &str) -> Result<Self, serde_path_to_error::Error<toml::de::Error>> {
+        let deserializer = toml::Deserializer::parse(config).map_err(without_path)?;
+        serde_path_to_error::deserialize(deserializer)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn build(
         title: String,
         author: Option<String>,
@@ -76,25 +264,83 @@ impl GlobalConfig<NotInitialized> {
         grading: GradingSection,
         report: ReportSection,
         input: InputSection,
+        global_setup: Vec<String>,
+        global_teardown: Vec<String>,
+        defaults: DefaultsSection,
         sections: Vec<TestSection>,
     ) -> Result<Self, &'static str> {
         if sections.is_empty() {
             return Err("at least one test section is expected");
         }
+        process_raw_string_commands(&global_setup)?;
+        process_raw_string_commands(&global_teardown)?;
+        defaults.check_commands(&global_setup)?;
+        defaults.check_commands(&global_teardown)?;
+        if defaults
+            .get_setup()
+            .iter()
+            .chain(defaults.get_teardown())
+            .any(|command| command.trim().is_empty())
+        {
+            return Err("missing command");
+        }
+        if [defaults.get_locale(), defaults.get_timezone()]
+            .into_iter()
+            .flatten()
+            .any(|value| value.trim().is_empty())
+        {
+            return Err("locale and timezone can not be empty");
+        }
 
-        for s in &sections {
+        // The names are checked as they are reported, so an explicit title also collides
+        // with the default one of another position, e.g. `Section 2`.
+        let mut section_titles = HashSet::with_capacity(sections.len());
+        // whether each declared program is run by some test
+        let mut referenced = vec![false; input.input_programs_size()];
+        for (i, s) in sections.iter().enumerate() {
+            if !section_titles.insert(s.get_name(i + 1)) {
+                return Err("duplicated section title");
+            }
             match s.get_tests() {
                 test_section::Tests::UnitTests(unit_tests) => {
-                    for t in unit_tests.get_tests() {
-                        if let Some(name) = t.get_program_name()
-                            && !input.contains_program_name(name)
-                        {
+                    let mut unit_test_titles = HashSet::with_capacity(unit_tests.get_tests().len());
+                    for (j, t) in unit_tests.get_tests().iter().enumerate() {
+                        let name = t.get_program_name().unwrap_or(input.get_default_program());
+                        if !input.contains_program_name(name) {
+                            return Err("program name out of scope");
+                        }
+                        referenced[input.get_program_index_unchecked(name)] = true;
+                        if !unit_test_titles.insert(t.get_name(j + 1)) {
+                            return Err("duplicated unit test title");
+                        }
+                    }
+                }
+                test_section::Tests::PerformanceTests(performance_tests) => {
+                    let mut performance_test_titles =
+                        HashSet::with_capacity(performance_tests.get_tests().len());
+                    for (j, t) in performance_tests.get_tests().iter().enumerate() {
+                        let name = t.get_program_name().unwrap_or(input.get_default_program());
+                        if !input.contains_program_name(name) {
                             return Err("program name out of scope");
                         }
+                        referenced[input.get_program_index_unchecked(name)] = true;
+                        if !performance_test_titles.insert(t.get_name(j + 1)) {
+                            return Err("duplicated performance test title");
+                        }
                     }
                 }
             }
         }
+        let unused_programs: Vec<String> = referenced
+            .iter()
+            .enumerate()
+            .filter(|(_, referenced)| !**referenced)
+            .map(|(index, _)| format!("{DEFAULT_PREFIX_PROGRAM_NAME1}{}", index + 1))
+            .collect();
+        // otherwise, it is up to the caller to warn about them, see `unused_programs`
+        if !unused_programs.is_empty() && input.rejects_unused_programs() {
+            return Err("declared program not run by any test");
+        }
 
         Ok(Self {
             title,
@@ -103,29 +349,135 @@ impl GlobalConfig<NotInitialized> {
             grading,
             report,
             input,
+            global_setup,
+            global_teardown,
+            defaults,
             sections,
             executables_by_name: None,
+            missing_programs: vec![],
+            reject_reused_paths: false,
+            allow_non_executable_programs: false,
+            non_executable_programs: vec![],
+            reused_paths: vec![],
+            unused_programs,
+            base_dir: PathBuf::new(),
             _state: marker::PhantomData,
         })
     }
 
+    /// The names that may be used to refer to each one of the programs expected by this
+    /// configuration, in the order they were declared. Each entry has the format
+    /// `program<n>/p<n>[/<alias>]`.
+    ///
+    /// Exactly one path must be provided for each entry when initializing it.
+    pub fn expected_program_names(&self) -> Vec<String> {
+        (0..self.input.input_programs_size())
+            .map(|i| {
+                let n = i + 1;
+                let mut names =
+                    format!("{DEFAULT_PREFIX_PROGRAM_NAME1}{n}/{DEFAULT_PREFIX_PROGRAM_NAME2}{n}");
+                if let Some(alias) = self.input.get_alias_unchecked(i) {
+                    names.push('/');
+                    names.push_str(alias);
+                }
+                names
+            })
+            .collect()
+    }
+
+    /// Builds a `GradingConfig` in which each program is only a placeholder with its
+    /// standard name as path, so the configuration can be inspected (e.g. explained)
+    /// without the real programs. It is not meant to be run.
+    pub fn build_placeholder_grading_config(&self) -> Result<GradingConfig, String> {
+        let executables_by_name = self
+            .input
+            .get_program_name_by_index()
+            .iter()
+            .map(|(program_name, index)| {
+                let standard_name = format!("{DEFAULT_PREFIX_PROGRAM_NAME1}{}", index + 1);
+                let executable = ExecutableArtifact::CompiledProgram {
+                    name: standard_name.clone(),
+                    path: standard_name.into(),
+                    base_args: self.input.get_base_args_unchecked(program_name).to_vec(),
+                };
+                (program_name.clone(), executable)
+            })
+            .collect();
+        self.build_grading_config_with(&executables_by_name)
+    }
+
+    /// Resolves the relative paths of the configuration, e.g. `env_file`, against
+    /// `base_dir`, which usually is the directory of the configuration file.
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = base_dir.into();
+        self
+    }
+
+    /// Makes `initialize` fail when the same file is given to different programs, which is
+    /// almost always a mistake. By default, it is only reported by `reused_paths`.
+    pub fn with_reject_reused_paths(mut self, reject_reused_paths: bool) -> Self {
+        self.reject_reused_paths = reject_reused_paths;
+        self
+    }
+
+    /// Makes a program that is not executable, e.g. a source file or a missing path, only a
+    /// warning when initializing, instead of an error. Its tests fail like any program that
+    /// can not be launched, unless something makes it executable before, e.g. the global
+    /// setup. It is reported by `non_executable_programs`.
+    pub fn with_allow_non_executable_programs(
+        mut self,
+        allow_non_executable_programs: bool,
+    ) -> Self {
+        self.allow_non_executable_programs = allow_non_executable_programs;
+        self
+    }
+
     /// It is necessary to initialize the `GlobalConfig` before using it further. It is
     /// necessary to provide additional information about the executables, which is made
     /// as the argument `program_name_to_path`, an array of tuples, mapping each program
     /// name with its path provided by the user.
-    fn initialize(
+    pub fn initialize(
         self,
         program_name_to_path: &[(&str, PathBuf)],
     ) -> Result<GlobalConfig<Initialized>, Box<(GlobalConfig<NotInitialized>, &'static str)>> {
-        if self.input.input_programs_size() != program_name_to_path.len() {
+        self.initialize_with(program_name_to_path, false)
+    }
+
+    /// Same as `initialize`, but the declared programs without a path are allowed. They
+    /// are recorded as missing and every test that uses them fails with a "program not
+    /// submitted" diagnostic, instead of preventing the others from being graded.
+    pub fn initialize_allowing_missing_programs(
+        self,
+        program_name_to_path: &[(&str, PathBuf)],
+    ) -> Result<GlobalConfig<Initialized>, Box<(GlobalConfig<NotInitialized>, &'static str)>> {
+        self.initialize_with(program_name_to_path, true)
+    }
+
+    fn initialize_with(
+        self,
+        program_name_to_path: &[(&str, PathBuf)],
+        allow_missing_programs: bool,
+    ) -> Result<GlobalConfig<Initialized>, Box<(GlobalConfig<NotInitialized>, &'static str)>> {
+        let input_programs_size = self.input.input_programs_size();
+        if allow_missing_programs && input_programs_size < program_name_to_path.len() {
+            return Err(Box::new((
+                self,
+                "there are more program names in user's program_name_to_path map than in config",
+            )));
+        }
+        if !allow_missing_programs && input_programs_size != program_name_to_path.len() {
             return Err(Box::new((
                 self,
                 "there is a different number of program names between config and user's program_name_to_path map",
             )));
         }
-        let mut index_mapped = vec![false; program_name_to_path.len()];
+        let mut index_mapped = vec![false; input_programs_size];
 
-        let mut executables_by_index = HashMap::with_capacity(program_name_to_path.len());
+        let mut executables_by_index = HashMap::with_capacity(input_programs_size);
+        let mut index_by_canonical_path: HashMap<PathBuf, usize> =
+            HashMap::with_capacity(input_programs_size);
+        let mut reused_paths = vec![];
+        let mut non_executable_programs = vec![];
         for (program_name, path) in program_name_to_path {
             if !self.input.contains_program_name(program_name) {
                 return Err(Box::new((self, "user program name not found in namespace")));
@@ -137,18 +489,82 @@ impl GlobalConfig<NotInitialized> {
             index_mapped[program_index] = true;
 
             let program_type = self.input.get_program_type_unchecked(program_name);
+            let resolution = self
+                .input
+                .get_path_mode_unchecked(program_name)
+                .resolution(&self.base_dir);
 
             let executable_artifact = match ExecutableArtifact::build(
                 program_name.to_string(),
                 path.clone(),
                 program_type.into(),
+                resolution,
             ) {
                 Ok(e) => e,
+                Err(_) if self.allow_non_executable_programs => {
+                    let name = format!("{DEFAULT_PREFIX_PROGRAM_NAME1}{}", program_index + 1);
+                    log::warn!("{name} is not executable: '{}'", path.display());
+                    non_executable_programs.push(name);
+                    match ProgramType::from(program_type) {
+                        ProgramType::Compiled => ExecutableArtifact::CompiledProgram {
+                            name: program_name.to_string(),
+                            path: resolution.resolve(path).unwrap_or_else(|_| path.clone()),
+                            base_args: vec![],
+                        },
+                    }
+                }
                 Err(err) => return Err(Box::new((self, err))),
-            };
+            }
+            .with_base_args(self.input.get_base_args_unchecked(program_name).to_vec());
             executables_by_index.insert(program_index, executable_artifact);
+
+            // different spellings of the same path, e.g. `./a` and `a`, are the same file
+            let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            if let Some(&other_index) = index_by_canonical_path.get(&canonical_path) {
+                let pair = (
+                    format!(
+                        "{DEFAULT_PREFIX_PROGRAM_NAME1}{}",
+                        other_index.min(program_index) + 1
+                    ),
+                    format!(
+                        "{DEFAULT_PREFIX_PROGRAM_NAME1}{}",
+                        other_index.max(program_index) + 1
+                    ),
+                );
+                if self.reject_reused_paths {
+                    return Err(Box::new((
+                        self,
+                        "the same path was given for different programs",
+                    )));
+                }
+                log::warn!(
+                    "{} and {} were given the same path '{}'",
+                    pair.0,
+                    pair.1,
+                    path.display()
+                );
+                reused_paths.push(pair);
+            } else {
+                index_by_canonical_path.insert(canonical_path, program_index);
+            }
+        }
+        // the map is not ordered, so the pairs are sorted to be reported consistently
+        reused_paths.sort();
+        non_executable_programs.sort();
+        let mut missing_programs = vec![];
+        for (index, _) in index_mapped
+            .iter()
+            .enumerate()
+            .filter(|(_, mapped)| !**mapped)
+        {
+            let name = format!("{DEFAULT_PREFIX_PROGRAM_NAME1}{}", index + 1);
+            executables_by_index.insert(
+                index,
+                ExecutableArtifact::NotSubmitted { name: name.clone() },
+            );
+            missing_programs.push(name);
         }
-        let mut executables_by_name = HashMap::with_capacity(program_name_to_path.len() * 2);
+        let mut executables_by_name = HashMap::with_capacity(input_programs_size * 2);
         for (program_name, index) in self.input.get_program_name_by_index() {
             let executable_artifact = &executables_by_index[index];
             executables_by_name.insert(program_name.clone(), executable_artifact.clone());
@@ -161,31 +577,47 @@ impl GlobalConfig<NotInitialized> {
             grading: self.grading,
             report: self.report,
             input: self.input,
+            global_setup: self.global_setup,
+            global_teardown: self.global_teardown,
+            defaults: self.defaults,
             sections: self.sections,
             executables_by_name: Some(executables_by_name),
+            missing_programs,
+            reject_reused_paths: self.reject_reused_paths,
+            allow_non_executable_programs: self.allow_non_executable_programs,
+            non_executable_programs,
+            reused_paths,
+            unused_programs: self.unused_programs,
+            base_dir: self.base_dir,
             _state: marker::PhantomData,
         })
     }
 }
 
 impl GlobalConfig<Initialized> {
-    fn build_grading_config(&self) -> Result<GradingConfig, &'static str> {
-        let mut c = GradingConfig::new(
-            self.title.clone(),
-            self.author.clone(),
-            self.grading.get_grading_mode(),
-        );
+    /// Standard names (`program<n>`) of the declared programs that were not provided.
+    pub fn missing_programs(&self) -> &[String] {
+        &self.missing_programs
+    }
+
+    /// Standard names (`program<n>`) of the programs that were given a file that is not
+    /// executable, see `with_allow_non_executable_programs`.
+    pub fn non_executable_programs(&self) -> &[String] {
+        &self.non_executable_programs
+    }
+
+    /// Pairs of standard names (`program<n>`) of the programs that were given the same
+    /// file, e.g. `("program1", "program3")`.
+    pub fn reused_paths(&self) -> &[(String, String)] {
+        &self.reused_paths
+    }
 
+    pub fn build_grading_config(&self) -> Result<GradingConfig, String> {
         let executables_by_name = self
             .executables_by_name
-            .clone()
+            .as_ref()
             .ok_or("executables per name map not initialized")?;
-
-        for (i, t) in self.sections.iter().enumerate() {
-            c.add_grading_section(t.build_grading_section(i + 1, &executables_by_name)?);
-        }
-
-        Ok(c)
+        self.build_grading_config_with(executables_by_name)
     }
 }
 
@@ -200,6 +632,9 @@ impl TryFrom<GlobalConfigUnchecked> for GlobalConfig<NotInitialized> {
             grading,
             report,
             input,
+            global_setup,
+            global_teardown,
+            defaults,
             sections,
         } = value;
 
@@ -210,13 +645,16 @@ impl TryFrom<GlobalConfigUnchecked> for GlobalConfig<NotInitialized> {
             grading,
             report,
             input,
+            global_setup,
+            global_teardown,
+            defaults,
             sections,
         )
     }
 }
 
 #[cfg(test)]
-mod test_macros {
+pub(crate) mod test_macros {
     /// From a deserialized item, test if it serializes correctly and then deserializes in
     /// sequence, maintaining the same information.
     macro_rules! test_serialize_and_deserialize {
@@ -278,7 +716,7 @@ mod tests {
 
     mod test_configuration {
         use super::*;
-        use crate::grader::score::GradingMode;
+        use crate::grader::{grading_tests::unit_test::assertion::StdoutMatch, score::GradingMode};
         use crate::{
             config::{
                 test_macros::{
@@ -302,17 +740,30 @@ mod tests {
                 grading: GradingSection::new(GradingMode::Weighted,),
                 report: ReportSection::new(false, ReportOutput::Txt),
                 input: InputSection::default(),
+                global_setup: vec!["cmd1 a b".to_string()],
+                global_teardown: vec![],
+                defaults: DefaultsSection::default(),
                 sections: vec![
                     TestSection::build(
                         Some("Section 1".to_string()),
                         Some(12),
+                        false,
+                        false,
                         Some(
                             UnitTests::build(
+                                Some(vec![]),
+                                None,
+                                Some(false),
                                 vec![],
-                                false,
+                                None,
+                                None,
                                 vec![("file 1".to_string(), "content 1".to_string())],
-                                vec![],
-                                vec![],
+                                Some(vec![]),
+                                Some(vec![]),
+                                false,
+                                false,
+                                false,
+                                1,
                                 vec![
                                     UnitTest::build(
                                         None,
@@ -336,6 +787,7 @@ mod tests {
                                             )
                                             .unwrap()
                                         ),
+                                        None,
                                         vec![
                                             DetailedTest::build(
                                                 Some("test2".to_string()),
@@ -348,16 +800,28 @@ mod tests {
                                             )
                                             .unwrap()
                                         ],
+                                        None,
+                                        HashMap::new(),
+                                        None,
+                                        false,
                                     )
                                     .unwrap()
                                 ],
                             )
                             .unwrap()
                         ),
+                        None,
                     )
                     .unwrap()
                 ],
                 executables_by_name: None,
+                missing_programs: vec![],
+                reject_reused_paths: false,
+                allow_non_executable_programs: false,
+                non_executable_programs: vec![],
+                reused_paths: vec![],
+                unused_programs: vec![],
+                base_dir: PathBuf::new(),
             },
             GlobalConfig
         );
@@ -533,6 +997,21 @@ mod tests {
         }"#,
             GlobalConfig
         );
+        test_invalid_deserialization!(
+            should_panic_with_performance_test_program_name_out_of_scope,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "performance_tests": {
+                "tests": [{ "program_name": "p2", "max_duration_ms": 100 }]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
         test_invalid_deserialization!(
             should_panic_with_program_name_out_of_scope,
             r#"
@@ -654,69 +1133,28 @@ mod tests {
             GlobalConfig
         );
 
-        // valid
-        test_valid_deserialization!(
-            should_accept_basic,
+        test_invalid_deserialization!(
+            should_panic_with_duplicated_section_title,
             r#"
         {
           "title": "Configuration ABC",
-          "author": "Author ABC",
-          "logging_mode": "silent",
-          "grading": {
-            "mode": "weighted"
-          },
-          "report": {
-            "is_verbose": true,
-            "output": "txt"
-          },
-          "input": {
-            "input_programs": [
-              "exe",
-              {
-                "alias": "programY",
-                "program_type":"exe"
-              },
-              {
-                "alias": "programZ",
-                "program_type":"exe"
-              }
-            ]
-          },
           "sections": [
             {
-              "title": "Section 1",
-              "weight": 12,
+              "title": "Section A",
               "unit_tests": {
-                "env": [],
-                "setup": [],
-                "teardown": [],
                 "tests": [
                   {
-                    "title": null,
-                    "program_name": "p1",
-                    "table": [
-                      ["args",                "name",  "stdout"],
-                      ["arg1 arg2 arg3",      "test1", "expected1"],
-                      ["a1 a2 ",              "test2", "expected2"],
-                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
-                      ["",                    "test4", "expected4"]
-                    ],
-                    "detailed_tests": [
-                      {
-                        "name": "test2",
-                        "args": "a1 a2 a3 a4",
-                        "stdout": null,
-                        "stderr": null,
-                        "status": 23,
-                        "weight": 2
-                      },
-                      {
-                        "name": "testABC",
-                        "args": "a1",
-                        "status": -1,
-                        "weight": 3
-                      }
-                    ]
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            },
+            {
+              "title": "Section A",
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a2", "status": 0 }]
                   }
                 ]
               }
@@ -725,64 +1163,311 @@ mod tests {
         }"#,
             GlobalConfig
         );
-        test_valid_deserialization!(
-            should_accept_programs_with_aliases,
+        test_invalid_deserialization!(
+            should_panic_with_duplicated_unit_test_title,
             r#"
         {
           "title": "Configuration ABC",
-          "author": "Author ABC",
-          "logging_mode": "silent",
-          "grading": {
-            "mode": "weighted"
-          },
-          "report": {
-            "is_verbose": true,
-            "output": "txt"
-          },
-          "input": {
-            "input_programs": [
-              "exe",
-              {
-                "alias": "programY",
-                "program_type":"exe"
-              },
-              {
-                "alias": "programZ",
-                "program_type":"exe"
-              }
-            ]
-          },
           "sections": [
             {
-              "title": "Section 1",
-              "weight": 12,
+              "title": "Section A",
               "unit_tests": {
-                "env": [],
-                "setup": [],
-                "teardown": [],
                 "tests": [
                   {
-                    "title": null,
-                    "program_name": "p1",
-                    "table": [
-                      ["args",                "name",  "stdout"],
-                      ["arg1 arg2 arg3",      "test1", "expected1"],
-                      ["a1 a2 ",              "test2", "expected2"],
-                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
-                      ["",                    "test4", "expected4"]
-                    ],
-                    "detailed_tests": [
-                      {
-                        "name": "test2",
-                        "args": "a1 a2 a3 a4",
-                        "stdout": null,
-                        "stderr": null,
-                        "status": 23,
-                        "weight": 2
-                      },
-                      {
-                        "name": "testABC",
-                        "args": "a1",
+                    "title": "Unit Test A",
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  },
+                  {
+                    "title": "Unit Test A",
+                    "detailed_tests": [{ "args": "a2", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_a_section_title_equal_to_a_default_name,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "title": "Section 2",
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "args": "a1", "status": 0 }] }]
+              }
+            },
+            {
+              "unit_tests": {
+                "tests": [{ "detailed_tests": [{ "args": "a2", "status": 0 }] }]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_invalid_deserialization!(
+            should_panic_with_a_unit_test_title_equal_to_a_default_name,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "title": "Unit Test 2",
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  },
+                  { "detailed_tests": [{ "args": "a2", "status": 0 }] }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_invalid_deserialization!(
+            should_panic_with_a_performance_test_title_equal_to_a_default_name,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "performance_tests": {
+                "tests": [
+                  { "title": "Performance Test 2", "max_duration_ms": 100 },
+                  { "max_duration_ms": 100 }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_empty_global_setup_command,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "global_setup": ["curl -o data.csv http://example.com", ""],
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_empty_default_locale,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "defaults": { "locale": "" },
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_empty_default_setup_command,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "defaults": { "setup": ["  "] },
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        // valid
+        test_valid_deserialization!(
+            should_accept_defaults,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "defaults": {
+            "env": [["k1", "v1"]],
+            "inherit_parent_env": false,
+            "setup": ["cmd1 a"],
+            "teardown": ["cmd2 b"]
+          },
+          "sections": [
+            {
+              "unit_tests": {
+                "env_passthrough": ["HOME"],
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_valid_deserialization!(
+            should_accept_basic,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "author": "Author ABC",
+          "logging_mode": "silent",
+          "grading": {
+            "mode": "weighted"
+          },
+          "report": {
+            "is_verbose": true,
+            "output": "txt"
+          },
+          "input": {
+            "input_programs": [
+              "exe",
+              {
+                "alias": "programY",
+                "program_type":"exe"
+              },
+              {
+                "alias": "programZ",
+                "program_type":"exe"
+              }
+            ]
+          },
+          "sections": [
+            {
+              "title": "Section 1",
+              "weight": 12,
+              "unit_tests": {
+                "env": [],
+                "setup": [],
+                "teardown": [],
+                "tests": [
+                  {
+                    "title": null,
+                    "program_name": "p1",
+                    "table": [
+                      ["args",                "name",  "stdout"],
+                      ["arg1 arg2 arg3",      "test1", "expected1"],
+                      ["a1 a2 ",              "test2", "expected2"],
+                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
+                      ["",                    "test4", "expected4"]
+                    ],
+                    "detailed_tests": [
+                      {
+                        "name": "test2",
+                        "args": "a1 a2 a3 a4",
+                        "stdout": null,
+                        "stderr": null,
+                        "status": 23,
+                        "weight": 2
+                      },
+                      {
+                        "name": "testABC",
+                        "args": "a1",
+                        "status": -1,
+                        "weight": 3
+                      }
+                    ]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_valid_deserialization!(
+            should_accept_programs_with_aliases,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "author": "Author ABC",
+          "logging_mode": "silent",
+          "grading": {
+            "mode": "weighted"
+          },
+          "report": {
+            "is_verbose": true,
+            "output": "txt"
+          },
+          "input": {
+            "input_programs": [
+              "exe",
+              {
+                "alias": "programY",
+                "program_type":"exe"
+              },
+              {
+                "alias": "programZ",
+                "program_type":"exe"
+              }
+            ]
+          },
+          "sections": [
+            {
+              "title": "Section 1",
+              "weight": 12,
+              "unit_tests": {
+                "env": [],
+                "setup": [],
+                "teardown": [],
+                "tests": [
+                  {
+                    "title": null,
+                    "program_name": "p1",
+                    "table": [
+                      ["args",                "name",  "stdout"],
+                      ["arg1 arg2 arg3",      "test1", "expected1"],
+                      ["a1 a2 ",              "test2", "expected2"],
+                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
+                      ["",                    "test4", "expected4"]
+                    ],
+                    "detailed_tests": [
+                      {
+                        "name": "test2",
+                        "args": "a1 a2 a3 a4",
+                        "stdout": null,
+                        "stderr": null,
+                        "status": 23,
+                        "weight": 2
+                      },
+                      {
+                        "name": "testABC",
+                        "args": "a1",
                         "status": -1,
                         "weight": 3
                       }
@@ -827,24 +1512,136 @@ mod tests {
                     ]
                   },
                   {
-                    "program_name": "program3",
-                    "table": [
-                      ["args",                "name",  "stdout"],
-                      ["arg1 arg2 arg3",      "test1", "expected1"],
-                      ["a1 a2 ",              "test2", "expected2"],
-                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
-                      ["",                    "test4", "expected4"]
-                    ]
-                  },
+                    "program_name": "program3",
+                    "table": [
+                      ["args",                "name",  "stdout"],
+                      ["arg1 arg2 arg3",      "test1", "expected1"],
+                      ["a1 a2 ",              "test2", "expected2"],
+                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
+                      ["",                    "test4", "expected4"]
+                    ]
+                  },
+                  {
+                    "program_name": "programZ",
+                    "table": [
+                      ["args",                "name",  "stdout"],
+                      ["arg1 arg2 arg3",      "test1", "expected1"],
+                      ["a1 a2 ",              "test2", "expected2"],
+                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
+                      ["",                    "test4", "expected4"]
+                    ]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_valid_deserialization!(
+            should_accept_with_mandatory_fields_only,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "title": "Section 1",
+              "weight": 12,
+              "unit_tests": {
+                "env": [],
+                "setup": [],
+                "teardown": [],
+                "tests": [
+                  {
+                    "title": null,
+                    "program_name": "p1",
+                    "table": [
+                      ["args",                "name",  "stdout"],
+                      ["arg1 arg2 arg3",      "test1", "expected1"],
+                      ["a1 a2 ",              "test2", "expected2"],
+                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
+                      ["",                    "test4", "expected4"]
+                    ],
+                    "detailed_tests": [
+                      {
+                        "name": "test2",
+                        "args": "a1 a2 a3 a4",
+                        "stdout": null,
+                        "stderr": null,
+                        "status": 23,
+                        "weight": 2
+                      },
+                      {
+                        "name": "testABC",
+                        "args": "a1",
+                        "status": -1,
+                        "weight": 3
+                      }
+                    ]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+
+        test_valid_deserialization!(
+            should_accept_sections_and_unit_tests_without_titles,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  },
+                  {
+                    "detailed_tests": [{ "args": "a2", "status": 0 }]
+                  }
+                ]
+              }
+            },
+            {
+              "unit_tests": {
+                "tests": [
+                  {
+                    "detailed_tests": [{ "args": "a3", "status": 0 }]
+                  }
+                ]
+              }
+            }
+          ]
+        }"#,
+            GlobalConfig
+        );
+        test_valid_deserialization!(
+            should_accept_same_unit_test_title_in_different_sections,
+            r#"
+        {
+          "title": "Configuration ABC",
+          "sections": [
+            {
+              "title": "Section A",
+              "unit_tests": {
+                "tests": [
+                  {
+                    "title": "Unit Test A",
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                  }
+                ]
+              }
+            },
+            {
+              "title": "Section B",
+              "unit_tests": {
+                "tests": [
                   {
-                    "program_name": "programZ",
-                    "table": [
-                      ["args",                "name",  "stdout"],
-                      ["arg1 arg2 arg3",      "test1", "expected1"],
-                      ["a1 a2 ",              "test2", "expected2"],
-                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
-                      ["",                    "test4", "expected4"]
-                    ]
+                    "title": "Unit Test A",
+                    "detailed_tests": [{ "args": "a2", "status": 0 }]
                   }
                 ]
               }
@@ -853,46 +1650,20 @@ mod tests {
         }"#,
             GlobalConfig
         );
+
         test_valid_deserialization!(
-            should_accept_with_mandatory_fields_only,
+            should_accept_global_setup_and_teardown,
             r#"
         {
           "title": "Configuration ABC",
+          "global_setup": ["curl -o data.csv \"http://example.com/a b\""],
+          "global_teardown": ["rm data.csv"],
           "sections": [
             {
-              "title": "Section 1",
-              "weight": 12,
               "unit_tests": {
-                "env": [],
-                "setup": [],
-                "teardown": [],
                 "tests": [
                   {
-                    "title": null,
-                    "program_name": "p1",
-                    "table": [
-                      ["args",                "name",  "stdout"],
-                      ["arg1 arg2 arg3",      "test1", "expected1"],
-                      ["a1 a2 ",              "test2", "expected2"],
-                      ["arg1 arg2 arg3 arg4", "test3", "expected3"],
-                      ["",                    "test4", "expected4"]
-                    ],
-                    "detailed_tests": [
-                      {
-                        "name": "test2",
-                        "args": "a1 a2 a3 a4",
-                        "stdout": null,
-                        "stderr": null,
-                        "status": 23,
-                        "weight": 2
-                      },
-                      {
-                        "name": "testABC",
-                        "args": "a1",
-                        "status": -1,
-                        "weight": 3
-                      }
-                    ]
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
                   }
                 ]
               }
@@ -902,9 +1673,96 @@ mod tests {
             GlobalConfig
         );
 
+        /// Configuration with three programs in which only `program2` is run explicitly,
+        /// with the extra `input` fields of `input_fields`.
+        fn config_with_three_programs(input_fields: &str) -> String {
+            format!(
+                r#"{{
+                  "title": "configuration 1",
+                  "input": {{ "input_programs": ["exe", "exe", "exe"]{input_fields} }},
+                  "sections": [
+                    {{
+                      "unit_tests": {{
+                        "tests": [
+                          {{ "detailed_tests": [{{ "status": 0 }}] }},
+                          {{ "program_name": "p2", "detailed_tests": [{{ "status": 0 }}] }}
+                        ]
+                      }}
+                    }}
+                  ]
+                }}"#
+            )
+        }
+
+        #[test]
+        fn should_list_the_programs_not_run_by_any_test() {
+            let c = GlobalConfig::from_json(&config_with_three_programs("")).unwrap();
+
+            assert_eq!(c.unused_programs(), ["program3".to_string()]);
+        }
+
+        #[test]
+        fn should_reject_the_programs_not_run_by_any_test_when_asked() {
+            let err = GlobalConfig::from_json(&config_with_three_programs(
+                r#", "reject_unused_programs": true"#,
+            ))
+            .unwrap_err();
+
+            assert!(
+                err.to_string()
+                    .contains("declared program not run by any test")
+            );
+        }
+
+        #[test]
+        fn should_count_the_default_program_as_run_by_tests_without_program_name() {
+            let c = GlobalConfig::from_json(&config_with_three_programs(
+                r#", "default_program": "program3""#,
+            ))
+            .unwrap();
+
+            assert_eq!(c.unused_programs(), ["program1".to_string()]);
+        }
+
         mod test_initialize {
             use super::*;
-            use crate::config::input_section::{InputType, ProgramSpecification};
+            use crate::config::input_section::{InputType, PathMode, ProgramSpecification};
+
+            #[test]
+            fn should_list_expected_program_names_with_aliases() {
+                let c = GlobalConfig::build(
+                    "test 1".to_string(),
+                    None,
+                    LoggingMode::Verbose,
+                    GradingSection::new(GradingMode::Weighted),
+                    ReportSection::new(false, ReportOutput::Txt),
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::Complete {
+                                alias: "java".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
+                    .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
+                    vec![TestSection::new_dummy(1)],
+                )
+                .unwrap();
+
+                assert_eq!(
+                    c.expected_program_names(),
+                    vec!["program1/p1".to_string(), "program2/p2/java".to_string()]
+                );
+            }
 
             #[test]
             #[should_panic]
@@ -915,12 +1773,20 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(false, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -944,12 +1810,20 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(false, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -971,12 +1845,20 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(false, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -999,12 +1881,20 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(false, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -1026,23 +1916,37 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(true, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::Complete {
-                            alias: "java".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::Complete {
-                            alias: "rust".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                        ProgramSpecification::Complete {
-                            alias: "python".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::Complete {
+                                alias: "java".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::Complete {
+                                alias: "rust".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                            ProgramSpecification::Complete {
+                                alias: "python".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -1066,23 +1970,37 @@ mod tests {
                     LoggingMode::Verbose,
                     GradingSection::new(GradingMode::Weighted),
                     ReportSection::new(false, ReportOutput::Txt),
-                    InputSection::build(vec![
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::Complete {
-                            alias: "java".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                        ProgramSpecification::OnlyType(InputType::CompiledProgram),
-                        ProgramSpecification::Complete {
-                            alias: "rust".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                        ProgramSpecification::Complete {
-                            alias: "python".to_string(),
-                            program_type: InputType::CompiledProgram,
-                        },
-                    ])
+                    InputSection::build(
+                        vec![
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::Complete {
+                                alias: "java".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                            ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ProgramSpecification::Complete {
+                                alias: "rust".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                            ProgramSpecification::Complete {
+                                alias: "python".to_string(),
+                                program_type: InputType::CompiledProgram,
+                                base_args: vec![],
+                                resolve: PathMode::Auto,
+                            },
+                        ],
+                        None,
+                        false,
+                        false,
+                    )
                     .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
                     vec![TestSection::new_dummy(1)],
                 )
                 .unwrap();
@@ -1105,6 +2023,211 @@ mod tests {
                 ])
                 .unwrap();
             }
+            #[test]
+            fn should_resolve_the_programs_with_their_path_mode() {
+                let config_with_path_mode = |resolve| {
+                    GlobalConfig::build(
+                        "test 1".to_string(),
+                        None,
+                        LoggingMode::Verbose,
+                        GradingSection::new(GradingMode::Weighted),
+                        ReportSection::new(false, ReportOutput::Txt),
+                        InputSection::build(
+                            vec![
+                                ProgramSpecification::Complete {
+                                    alias: "java".to_string(),
+                                    program_type: InputType::CompiledProgram,
+                                    base_args: vec![],
+                                    resolve,
+                                },
+                                ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                                ProgramSpecification::OnlyType(InputType::CompiledProgram),
+                            ],
+                            None,
+                            false,
+                            false,
+                        )
+                        .unwrap(),
+                        vec![],
+                        vec![],
+                        DefaultsSection::default(),
+                        vec![TestSection::new_dummy(1)],
+                    )
+                    .unwrap()
+                };
+                use crate::utils;
+                // only the path mode of `program1` is not the default one
+                let programs = |program1: PathBuf| {
+                    [
+                        ("program1", program1),
+                        ("program2", utils::create_dummy_executable()),
+                        ("program3", utils::create_dummy_executable()),
+                    ]
+                };
+                let path = utils::create_dummy_executable();
+                let (base_dir, file_name) = (path.parent().unwrap(), path.file_name().unwrap());
+
+                config_with_path_mode(PathMode::Relative)
+                    .with_base_dir(base_dir)
+                    .initialize(&programs(file_name.into()))
+                    .unwrap();
+                let err = config_with_path_mode(PathMode::Cwd)
+                    .with_base_dir(base_dir)
+                    .initialize(&programs(file_name.into()))
+                    .unwrap_err();
+                assert_eq!(err.1, "program not executable");
+                config_with_path_mode(PathMode::Path)
+                    .initialize(&programs("sh".into()))
+                    .unwrap();
+                let err = config_with_path_mode(PathMode::Path)
+                    .initialize(&programs(path.clone()))
+                    .unwrap_err();
+                assert_eq!(err.1, "program looked up on PATH must be a bare name");
+            }
+
+            fn config_with_programs(size: usize) -> GlobalConfig {
+                GlobalConfig::build(
+                    "test 1".to_string(),
+                    None,
+                    LoggingMode::Verbose,
+                    GradingSection::new(GradingMode::Weighted),
+                    ReportSection::new(false, ReportOutput::Txt),
+                    InputSection::build(
+                        (0..size)
+                            .map(|_| ProgramSpecification::OnlyType(InputType::CompiledProgram))
+                            .collect(),
+                        None,
+                        false,
+                        false,
+                    )
+                    .unwrap(),
+                    vec![],
+                    vec![],
+                    DefaultsSection::default(),
+                    vec![TestSection::new_dummy(1)],
+                )
+                .unwrap()
+            }
+
+            #[test]
+            fn should_record_missing_programs_when_allowed() {
+                let c = config_with_programs(3)
+                    .initialize_allowing_missing_programs(&[(
+                        "program2",
+                        crate::utils::create_dummy_executable(),
+                    )])
+                    .unwrap();
+
+                assert_eq!(
+                    c.missing_programs(),
+                    ["program1".to_string(), "program3".to_string()]
+                );
+                let executables_by_name = c.executables_by_name.as_ref().unwrap();
+                assert_eq!(
+                    executables_by_name["p3"],
+                    ExecutableArtifact::NotSubmitted {
+                        name: "program3".to_string()
+                    }
+                );
+                assert!(executables_by_name["p2"].new_cmd().is_some());
+                assert!(c.build_grading_config().is_ok());
+            }
+
+            #[test]
+            fn should_not_accept_non_executable_programs_by_default() {
+                let err = config_with_programs(2)
+                    .initialize(&[
+                        ("program1", crate::utils::create_dummy_executable()),
+                        ("program2", PathBuf::from("not/a/program")),
+                    ])
+                    .unwrap_err();
+                assert_eq!(err.1, "program not executable");
+            }
+
+            #[test]
+            fn should_record_non_executable_programs_when_allowed() {
+                let c = config_with_programs(2)
+                    .with_allow_non_executable_programs(true)
+                    .initialize(&[
+                        ("program1", crate::utils::create_dummy_executable()),
+                        ("program2", PathBuf::from("not/a/program")),
+                    ])
+                    .unwrap();
+
+                assert_eq!(c.non_executable_programs(), ["program2".to_string()]);
+                assert_eq!(
+                    c.executables_by_name.as_ref().unwrap()["p2"],
+                    ExecutableArtifact::CompiledProgram {
+                        name: "program2".to_string(),
+                        path: std::path::absolute("not/a/program").unwrap(),
+                        base_args: vec![],
+                    }
+                );
+            }
+
+            #[test]
+            fn should_not_accept_missing_programs_by_default() {
+                let err = config_with_programs(2)
+                    .initialize(&[("program1", crate::utils::create_dummy_executable())])
+                    .unwrap_err();
+                assert_eq!(
+                    err.1,
+                    "there is a different number of program names between config and user's program_name_to_path map"
+                );
+            }
+
+            #[test]
+            fn should_not_accept_more_programs_than_declared_when_missing_is_allowed() {
+                let err = config_with_programs(2)
+                    .initialize_allowing_missing_programs(&[
+                        ("program1", crate::utils::create_dummy_executable()),
+                        ("program2", crate::utils::create_dummy_executable()),
+                        ("program3", crate::utils::create_dummy_executable()),
+                    ])
+                    .unwrap_err();
+                assert_eq!(
+                    err.1,
+                    "there are more program names in user's program_name_to_path map than in config"
+                );
+            }
+
+            #[test]
+            fn should_warn_about_the_same_path_for_different_programs() {
+                let path = crate::utils::create_dummy_executable();
+                let c = config_with_programs(3)
+                    .initialize(&[
+                        ("program1", path.clone()),
+                        ("program2", crate::utils::create_dummy_executable()),
+                        ("program3", path),
+                    ])
+                    .unwrap();
+                assert_eq!(
+                    c.reused_paths(),
+                    [("program1".to_string(), "program3".to_string())]
+                );
+                assert!(c.build_grading_config().is_ok());
+            }
+
+            #[test]
+            fn should_not_report_different_paths() {
+                let c = config_with_programs(2)
+                    .initialize(&[
+                        ("program1", crate::utils::create_dummy_executable()),
+                        ("program2", crate::utils::create_dummy_executable()),
+                    ])
+                    .unwrap();
+                assert!(c.reused_paths().is_empty());
+            }
+
+            #[test]
+            fn should_reject_the_same_path_for_different_programs_when_asked() {
+                let path = crate::utils::create_dummy_executable();
+                let err = config_with_programs(2)
+                    .with_reject_reused_paths(true)
+                    .initialize(&[("program1", path.clone()), ("program2", path)])
+                    .unwrap_err();
+                assert_eq!(err.1, "the same path was given for different programs");
+            }
         }
         mod test_build_grader_config {
             use super::*;
@@ -1124,36 +2247,401 @@ mod tests {
                     grading: GradingSection::new(GradingMode::Absolute),
                     report: ReportSection::new(false, ReportOutput::Txt),
                     input: InputSection::default(),
+                    global_setup: vec!["cmd1 a \"b c\"".to_string()],
+                    global_teardown: vec!["cmd2".to_string()],
+                    defaults: DefaultsSection::default(),
                     sections: vec![
                         TestSection::new_dummy(1),
                         TestSection::new_dummy(2),
                         TestSection::new_dummy(1),
                     ],
                     executables_by_name: Some(executables_by_name.clone()),
+                    missing_programs: vec![],
+                    reject_reused_paths: false,
+                    allow_non_executable_programs: false,
+                    non_executable_programs: vec![],
+                    reused_paths: vec![],
+                    unused_programs: vec![],
+                    base_dir: PathBuf::new(),
                     _state: marker::PhantomData::<Initialized>,
                 };
 
                 let mut expected =
                     GradingConfig::new("test 1".to_string(), None, GradingMode::Absolute);
+                expected.set_global_setup(vec![(
+                    "cmd1".to_string(),
+                    vec!["a".to_string(), "b c".to_string()],
+                )]);
+                expected.set_global_teardown(vec![("cmd2".to_string(), vec![])]);
 
                 expected.add_grading_section(
                     TestSection::new_dummy(1)
-                        .build_grading_section(1, &executables_by_name)
+                        .build_grading_section(
+                            1,
+                            &executables_by_name,
+                            DEFAULT_MAIN_PROGRAM_NAME,
+                            &DefaultsSection::default(),
+                            &PathBuf::new(),
+                            StdoutMatch::Exact,
+                        )
                         .unwrap(),
                 );
                 expected.add_grading_section(
                     TestSection::new_dummy(2)
-                        .build_grading_section(2, &executables_by_name)
+                        .build_grading_section(
+                            2,
+                            &executables_by_name,
+                            DEFAULT_MAIN_PROGRAM_NAME,
+                            &DefaultsSection::default(),
+                            &PathBuf::new(),
+                            StdoutMatch::Exact,
+                        )
                         .unwrap(),
                 );
                 expected.add_grading_section(
                     TestSection::new_dummy(1)
-                        .build_grading_section(1, &executables_by_name)
+                        .build_grading_section(
+                            1,
+                            &executables_by_name,
+                            DEFAULT_MAIN_PROGRAM_NAME,
+                            &DefaultsSection::default(),
+                            &PathBuf::new(),
+                            StdoutMatch::Exact,
+                        )
                         .unwrap(),
                 );
 
                 assert_eq!(c.build_grading_config().unwrap(), expected);
             }
+
+            #[test]
+            fn should_fail_when_the_sum_of_weights_overflows() {
+                let config_with_weight = |section_weight: u32| {
+                    GlobalConfig::from_json(&format!(
+                        r#"
+                        {{
+                          "title": "weights",
+                          "sections": [
+                            {{
+                              "weight": {section_weight},
+                              "unit_tests": {{
+                                "tests": [{{
+                                  "detailed_tests": [
+                                    {{ "status": 0, "weight": 1 }},
+                                    {{ "status": 0, "weight": 1 }}
+                                  ]
+                                }}]
+                              }}
+                            }}
+                          ]
+                        }}"#
+                    ))
+                    .unwrap()
+                    .initialize(&[("program1", crate::utils::create_dummy_executable())])
+                    .unwrap()
+                };
+
+                assert!(
+                    config_with_weight(u32::MAX / 2)
+                        .build_grading_config()
+                        .is_ok()
+                );
+                assert_eq!(
+                    config_with_weight(u32::MAX / 2 + 1).build_grading_config(),
+                    Err("the sum of the weights is too large".to_string())
+                );
+            }
+
+            #[test]
+            fn should_not_build_skipped_items() {
+                let c = GlobalConfig::from_json(
+                    r#"
+                    {
+                      "title": "skip",
+                      "sections": [
+                        {
+                          "unit_tests": {
+                            "tests": [
+                              {
+                                "detailed_tests": [
+                                  { "name": "a", "status": 0 },
+                                  { "name": "b", "status": 0, "skip": true },
+                                  { "status": 0, "skip": true }
+                                ]
+                              },
+                              {
+                                "skip": true,
+                                "detailed_tests": [{ "args": "'unclosed", "status": 0 }]
+                              }
+                            ]
+                          }
+                        },
+                        {
+                          "title": "skipped section",
+                          "skip": true,
+                          "unit_tests": {
+                            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+                          }
+                        }
+                      ]
+                    }"#,
+                )
+                .unwrap()
+                .initialize(&[("program1", crate::utils::create_dummy_executable())])
+                .unwrap();
+
+                let grading_config = c.build_grading_config().unwrap();
+
+                assert_eq!(grading_config.skipped_sections(), ["skipped section"]);
+                assert_eq!(grading_config.grading_sections().len(), 1);
+                let crate::grader::grading_tests::GradingTests::UnitTests(unit_tests) =
+                    grading_config.grading_sections()[0].tests()
+                else {
+                    panic!("expected unit tests");
+                };
+                assert_eq!(unit_tests.skipped_unit_tests(), ["Unit Test 2"]);
+                assert_eq!(unit_tests.unit_tests().len(), 1);
+                let unit_test = &unit_tests.unit_tests()[0];
+                assert_eq!(unit_test.size(), 1);
+                assert_eq!(unit_test.skipped_assertions(), ["b", "Assertion 3"]);
+                assert_eq!(grading_config.max_score(), Some(1));
+            }
+        }
+    }
+
+    mod test_toml {
+        use super::*;
+        use crate::{
+            config::input_section::ProgramSpecification, grader::score::GradingMode,
+            report::ReportOutput,
+        };
+
+        #[test]
+        fn should_match_equivalent_json_config() {
+            let json = r#"
+            {
+              "title": "Configuration ABC",
+              "author": "Author ABC",
+              "logging_mode": "silent",
+              "grading": { "mode": "absolute" },
+              "report": { "is_verbose": true, "output": "txt" },
+              "input": {
+                "input_programs": ["exe", { "alias": "programY", "program_type": "exe" }]
+              },
+              "global_setup": ["mkdir data"],
+              "sections": [
+                {
+                  "title": "Section 1",
+                  "weight": 12,
+                  "unit_tests": {
+                    "env": [["k1", "v1"]],
+                    "files": [["file.txt", "content"]],
+                    "setup": ["touch a"],
+                    "tests": [
+                      {
+                        "program_name": "programY",
+                        "table": [
+                          ["args",           "name",  "stdout",    "status"],
+                          ["arg1 arg2 arg3", "test1", "expected1", 0],
+                          ["\"a b\"",        "test2", "expected2", 1]
+                        ],
+                        "detailed_tests": [
+                          { "name": "test3", "args": "a1", "status": -1, "weight": 3 }
+                        ]
+                      }
+                    ]
+                  }
+                }
+              ]
+            }"#;
+            let toml = r#"
+            title = "Configuration ABC"
+            author = "Author ABC"
+            logging_mode = "silent"
+            global_setup = ["mkdir data"]
+
+            [grading]
+            mode = "absolute"
+
+            [report]
+            is_verbose = true
+            output = "txt"
+
+            [input]
+            input_programs = ["exe", { alias = "programY", program_type = "exe" }]
+
+            [[sections]]
+            title = "Section 1"
+            weight = 12
+
+            [sections.unit_tests]
+            env = [["k1", "v1"]]
+            files = [["file.txt", "content"]]
+            setup = ["touch a"]
+
+            [[sections.unit_tests.tests]]
+            program_name = "programY"
+            table = [
+              ["args",           "name",  "stdout",    "status"],
+              ["arg1 arg2 arg3", "test1", "expected1", 0],
+              ['"a b"',          "test2", "expected2", 1],
+            ]
+            detailed_tests = [
+              { name = "test3", args = "a1", status = -1, weight = 3 },
+            ]
+            "#;
+
+            assert_eq!(
+                GlobalConfig::from_toml(toml).unwrap(),
+                GlobalConfig::from_json(json).unwrap()
+            );
+        }
+
+        #[test]
+        fn should_round_trip_through_toml() {
+            let config = GlobalConfig::build(
+                "test 1".to_string(),
+                Some("author".to_string()),
+                LoggingMode::Verbose,
+                GradingSection::new(GradingMode::Weighted),
+                ReportSection::new(false, ReportOutput::Txt),
+                InputSection::build(
+                    vec![
+                        ProgramSpecification::default(),
+                        ProgramSpecification::default(),
+                    ],
+                    None,
+                    false,
+                    false,
+                )
+                .unwrap(),
+                vec!["cmd1 a b".to_string()],
+                vec![],
+                DefaultsSection::default(),
+                vec![TestSection::new_dummy(1), TestSection::new_dummy(2)],
+            )
+            .unwrap();
+
+            let serialized = toml::to_string(&config).unwrap();
+
+            assert_eq!(GlobalConfig::from_toml(&serialized).unwrap(), config);
+        }
+
+        #[test]
+        fn should_reject_invalid_table_in_toml() {
+            let toml = r#"
+            title = "Configuration ABC"
+
+            [[sections]]
+            [sections.unit_tests]
+            [[sections.unit_tests.tests]]
+            table = [
+              ["args", "name"],
+              ["arg1", "test1"],
+            ]
+            "#;
+
+            assert_eq!(
+                GlobalConfig::from_toml(toml)
+                    .unwrap_err()
+                    .path()
+                    .to_string(),
+                "sections[0].unit_tests.tests[0].table"
+            );
+        }
+
+        #[test]
+        fn should_show_the_path_of_the_invalid_field() {
+            let json = r#"
+            {
+              "title": "Configuration ABC",
+              "sections": [
+                { "unit_tests": { "tests": [{ "detailed_tests": [{ "status": 0 }] }] } },
+                {
+                  "unit_tests": {
+                    "tests": [
+                      { "table": [["args", "name"], ["arg1", "test1"]] }
+                    ]
+                  }
+                }
+              ]
+            }"#;
+
+            let err = GlobalConfig::from_json(json).unwrap_err();
+
+            assert_eq!(
+                err.path().to_string(),
+                "sections[1].unit_tests.tests[0].table"
+            );
+            assert!(
+                err.to_string()
+                    .starts_with("sections[1].unit_tests.tests[0].table: ")
+            );
+        }
+
+        #[test]
+        fn should_reject_trailing_characters_in_json() {
+            let json = r#"
+            {
+              "title": "Configuration ABC",
+              "sections": [
+                { "unit_tests": { "tests": [{ "detailed_tests": [{ "status": 0 }] }] } }
+              ]
+            }"#;
+            assert!(GlobalConfig::from_json(json).is_ok());
+
+            let err = GlobalConfig::from_json(&format!("{json} }}")).unwrap_err();
+
+            assert_eq!(err.path().to_string(), ".");
+            assert!(err.inner().is_syntax());
+        }
+    }
+
+    mod test_json_schema {
+        use super::*;
+
+        #[test]
+        fn should_describe_the_top_level_fields() {
+            let schema = GlobalConfig::json_schema();
+
+            assert_eq!(schema["title"], "GlobalConfig");
+            assert_eq!(schema["additionalProperties"], false);
+            assert_eq!(schema["required"], serde_json::json!(["title", "sections"]));
+        }
+
+        #[test]
+        fn should_list_the_values_of_the_enums() {
+            let schema = GlobalConfig::json_schema();
+
+            assert_eq!(
+                schema["$defs"]["LoggingMode"]["enum"],
+                serde_json::json!(["silent", "normal", "verbose"])
+            );
+            assert_eq!(
+                schema["$defs"]["ReportOutput"]["enum"],
+                serde_json::json!(["txt", "stdout"])
+            );
+            let grading_modes: Vec<_> = schema["$defs"]["GradingMode"]["oneOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|mode| mode["const"].as_str().unwrap())
+                .collect();
+            assert_eq!(grading_modes, ["absolute", "weighted"]);
+        }
+
+        #[test]
+        fn should_describe_the_table_as_a_header_followed_by_the_tests() {
+            let schema = GlobalConfig::json_schema();
+            let table = &schema["$defs"]["Table"];
+
+            assert_eq!(table["type"], "array");
+            assert_eq!(table["minItems"], 2);
+            assert_eq!(
+                table["prefixItems"][0]["items"]["$ref"],
+                "#/$defs/TableHeaderType"
+            );
+            assert_eq!(table["items"]["items"]["$ref"], "#/$defs/TableCellContent");
         }
     }
 }
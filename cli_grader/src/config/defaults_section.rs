@@ -0,0 +1,242 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use shlex::Shlex;
+use std::path::Path;
+
+/// Values inherited by the `unit_tests` of every section that does not define its own.
+/// A section overrides a value as a whole, e.g. an empty `env` clears the default one.
+///
+/// The `env`, `inherit_parent_env`, `locale` and `timezone` also apply to the
+/// `global_setup` and `global_teardown`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct DefaultsSection {
+    env: Vec<(String, String)>,
+    /// Defaults to `true`.
+    inherit_parent_env: Option<bool>,
+    /// Locale set as `LC_ALL` and `LANG`, see the `locale` of the `unit_tests`.
+    locale: Option<String>,
+    /// Timezone set as `TZ`, see the `timezone` of the `unit_tests`.
+    timezone: Option<String>,
+    setup: Vec<String>,
+    teardown: Vec<String>,
+    /// Names of the only commands that the setups and teardowns may run, e.g.
+    /// `["mkdir", "cp"]`. Every command is allowed when it is omitted. Unlike the other
+    /// values, it applies to every section and can not be overridden.
+    allowed_commands: Option<Vec<String>>,
+    /// Names of the commands that the setups and teardowns may not run, e.g. `["curl"]`.
+    /// It can not be overridden either.
+    denied_commands: Vec<String>,
+}
+
+impl DefaultsSection {
+    pub fn new(
+        env: Vec<(String, String)>,
+        inherit_parent_env: bool,
+        setup: Vec<String>,
+        teardown: Vec<String>,
+    ) -> Self {
+        Self {
+            env,
+            inherit_parent_env: Some(inherit_parent_env),
+            locale: None,
+            timezone: None,
+            setup,
+            teardown,
+            allowed_commands: None,
+            denied_commands: vec![],
+        }
+    }
+
+    pub fn with_locale(mut self, locale: String) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: String) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    pub fn with_allowed_commands(mut self, allowed_commands: Vec<String>) -> Self {
+        self.allowed_commands = Some(allowed_commands);
+        self
+    }
+
+    pub fn with_denied_commands(mut self, denied_commands: Vec<String>) -> Self {
+        self.denied_commands = denied_commands;
+        self
+    }
+
+    pub fn get_env(&self) -> &[(String, String)] {
+        &self.env
+    }
+
+    pub fn inherits_parent_env(&self) -> bool {
+        self.inherit_parent_env.unwrap_or(true)
+    }
+
+    pub fn get_locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    pub fn get_timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    /// Environment of the global setup and teardown: `env` followed by the variables of
+    /// `locale` and `timezone`, which take precedence over it.
+    pub fn global_env(&self) -> Vec<(String, String)> {
+        let mut env = self.env.clone();
+        if let Some(ref locale) = self.locale {
+            env.push(("LC_ALL".to_string(), locale.clone()));
+            env.push(("LANG".to_string(), locale.clone()));
+        }
+        if let Some(ref timezone) = self.timezone {
+            env.push(("TZ".to_string(), timezone.clone()));
+        }
+        env
+    }
+
+    pub fn get_setup(&self) -> &[String] {
+        &self.setup
+    }
+
+    pub fn get_teardown(&self) -> &[String] {
+        &self.teardown
+    }
+
+    pub fn get_allowed_commands(&self) -> Option<&[String]> {
+        self.allowed_commands.as_deref()
+    }
+
+    pub fn get_denied_commands(&self) -> &[String] {
+        &self.denied_commands
+    }
+
+    /// Checks the name of the command run by each of the setup or teardown `commands`
+    /// against `allowed_commands` and `denied_commands`. A command given by its path is
+    /// checked by its file name, e.g. `/bin/rm` as `rm`, and a shell command by its first
+    /// word.
+    ///
+    /// It is only a guardrail: the programs and the shell commands can still run anything.
+    pub fn check_commands(&self, commands: &[String]) -> Result<(), &'static str> {
+        for command in commands {
+            let Some(name) = Shlex::new(command).next() else {
+                continue;
+            };
+            let name = Path::new(&name)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(&name);
+            if self.denied_commands.iter().any(|denied| denied == name) {
+                log::error!("the setup or teardown runs the denied command '{name}'");
+                return Err("setup or teardown runs a denied command");
+            }
+            if let Some(ref allowed_commands) = self.allowed_commands
+                && !allowed_commands.iter().any(|allowed| allowed == name)
+            {
+                log::error!(
+                    "the setup or teardown runs the command '{name}', which is not allowed"
+                );
+                return Err("setup or teardown runs a command that is not allowed");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_macros::{
+        test_invalid_deserialization, test_serialize_and_deserialize, test_valid_deserialization,
+    };
+
+    // serialization
+    test_serialize_and_deserialize!(
+        should_serialize_deserialize_full,
+        DefaultsSection::new(
+            vec![("k1".to_string(), "v1".to_string())],
+            false,
+            vec!["cmd1 a".to_string()],
+            vec!["cmd2 b".to_string()],
+        )
+        .with_locale("C.UTF-8".to_string())
+        .with_timezone("UTC".to_string())
+        .with_allowed_commands(vec!["cmd1".to_string(), "cmd2".to_string()])
+        .with_denied_commands(vec!["curl".to_string()]),
+        DefaultsSection
+    );
+
+    // invalid deserialization
+    test_invalid_deserialization!(
+        should_panic_with_unknown_field,
+        r#"{"files": [["a.txt", "a"]]}"#,
+        DefaultsSection
+    );
+
+    // valid deserialization
+    test_valid_deserialization!(should_accept_empty, r#"{}"#, DefaultsSection);
+
+    #[test]
+    fn should_inherit_parent_env_by_default() {
+        assert!(DefaultsSection::default().inherits_parent_env());
+    }
+
+    #[test]
+    fn should_give_precedence_to_the_locale_and_timezone_in_the_global_env() {
+        let defaults = DefaultsSection::new(
+            vec![("LANG".to_string(), "pt_BR.UTF-8".to_string())],
+            true,
+            vec![],
+            vec![],
+        )
+        .with_locale("C.UTF-8".to_string())
+        .with_timezone("UTC".to_string());
+
+        assert_eq!(
+            defaults.global_env(),
+            [
+                ("LANG".to_string(), "pt_BR.UTF-8".to_string()),
+                ("LC_ALL".to_string(), "C.UTF-8".to_string()),
+                ("LANG".to_string(), "C.UTF-8".to_string()),
+                ("TZ".to_string(), "UTC".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_allow_every_command_by_default() {
+        assert_eq!(
+            DefaultsSection::default().check_commands(&["rm -rf out".to_string()]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_reject_denied_commands_by_their_name() {
+        let defaults = DefaultsSection::default().with_denied_commands(vec!["curl".to_string()]);
+
+        assert_eq!(defaults.check_commands(&["mkdir out".to_string()]), Ok(()));
+        assert_eq!(
+            defaults.check_commands(&["/usr/bin/curl -O http://example.com".to_string()]),
+            Err("setup or teardown runs a denied command")
+        );
+    }
+
+    #[test]
+    fn should_reject_commands_that_are_not_allowed() {
+        let defaults = DefaultsSection::default()
+            .with_allowed_commands(vec!["mkdir".to_string(), "cp".to_string()]);
+
+        assert_eq!(
+            defaults.check_commands(&["mkdir out".to_string(), "cp a b".to_string()]),
+            Ok(())
+        );
+        assert_eq!(
+            defaults.check_commands(&["mkdir out".to_string(), "rm a".to_string()]),
+            Err("setup or teardown runs a command that is not allowed")
+        );
+    }
+}
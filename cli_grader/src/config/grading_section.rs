@@ -1,16 +1,31 @@
-use crate::grader::score::GradingMode;
+use crate::grader::{grading_tests::unit_test::assertion::StdoutMatch, score::GradingMode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq)]
 pub struct GradingSection {
     mode: GradingMode,
+    /// How the stdout of every assertion is compared when its test does not set its own
+    /// `stdout_match`. It defaults to `"exact"`.
+    #[serde(default)]
+    comparison_policy: StdoutMatch,
 }
 
 impl GradingSection {
     pub fn new(mode: GradingMode) -> Self {
-        Self { mode }
+        Self {
+            mode,
+            comparison_policy: StdoutMatch::Exact,
+        }
+    }
+    pub fn with_comparison_policy(mut self, comparison_policy: StdoutMatch) -> Self {
+        self.comparison_policy = comparison_policy;
+        self
     }
     pub fn get_grading_mode(&self) -> GradingMode {
         self.mode
     }
+    pub fn get_comparison_policy(&self) -> StdoutMatch {
+        self.comparison_policy
+    }
 }
@@ -1,11 +1,14 @@
 use crate::{
-    config::{DEFAULT_PREFIX_PROGRAM_NAME1, DEFAULT_PREFIX_PROGRAM_NAME2},
-    input::ProgramType,
+    config::{
+        DEFAULT_MAIN_PROGRAM_NAME, DEFAULT_PREFIX_PROGRAM_NAME1, DEFAULT_PREFIX_PROGRAM_NAME2,
+    },
+    input::{PathResolution, ProgramType},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq, Clone, Copy)]
 pub enum InputType {
     #[default]
     #[serde(rename = "exe")]
@@ -20,7 +23,35 @@ impl From<InputType> for ProgramType {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// How the path given for a program is resolved, see `PathResolution`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PathMode {
+    /// A bare name, e.g. `cat`, is looked up on `PATH`, and any other path is relative to
+    /// the current directory.
+    #[default]
+    Auto,
+    /// The program is a bare name looked up on `PATH`.
+    Path,
+    /// The path is relative to the directory of the configuration file.
+    Relative,
+    /// The path is relative to the current directory, even if it is a bare name.
+    Cwd,
+}
+
+impl PathMode {
+    /// Resolution of this mode, where `Relative` paths are relative to `base_dir`.
+    pub fn resolution(self, base_dir: &Path) -> PathResolution<'_> {
+        match self {
+            PathMode::Auto => PathResolution::Auto,
+            PathMode::Path => PathResolution::Path,
+            PathMode::Relative => PathResolution::RelativeTo(base_dir),
+            PathMode::Cwd => PathResolution::Cwd,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum ProgramSpecification {
     OnlyType(InputType),
@@ -46,6 +77,13 @@ pub enum ProgramSpecification {
         alias: String,
         #[serde(default)]
         program_type: InputType,
+        /// Arguments passed to the program before the ones of each test, e.g. `["-O"]`.
+        #[serde(default)]
+        base_args: Vec<String>,
+        /// How the path given for the program is resolved, e.g. `path` for a program that
+        /// must be found on `PATH` even if its name looks like a file.
+        #[serde(default)]
+        resolve: PathMode,
     },
 }
 
@@ -53,10 +91,21 @@ impl ProgramSpecification {
     fn get_program_type(&self) -> InputType {
         match self {
             ProgramSpecification::OnlyType(input_type) => *input_type,
-            ProgramSpecification::Complete {
-                alias: _,
-                program_type,
-            } => *program_type,
+            ProgramSpecification::Complete { program_type, .. } => *program_type,
+        }
+    }
+
+    fn get_path_mode(&self) -> PathMode {
+        match self {
+            ProgramSpecification::OnlyType(_) => PathMode::default(),
+            ProgramSpecification::Complete { resolve, .. } => *resolve,
+        }
+    }
+
+    fn get_base_args(&self) -> &[String] {
+        match self {
+            ProgramSpecification::OnlyType(_) => &[],
+            ProgramSpecification::Complete { base_args, .. } => base_args,
         }
     }
 }
@@ -67,13 +116,18 @@ impl Default for ProgramSpecification {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct InputSectionUnchecked {
     input_programs: Option<Vec<ProgramSpecification>>,
+    default_program: Option<String>,
+    #[serde(default)]
+    reject_reserved_aliases: bool,
+    #[serde(default)]
+    reject_unused_programs: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(try_from = "InputSectionUnchecked")]
 pub struct InputSection {
     /// This vector will define all the programs that will be available in the scope of the
@@ -82,16 +136,54 @@ pub struct InputSection {
     /// # Default
     /// Defaults to only one program with the standard name: "program1" and one additional  "p1"
     input_programs: Vec<ProgramSpecification>,
+    /// Program run by the tests without a `program_name`, e.g. an alias that is more
+    /// meaningful than the standard name in a single program assignment.
+    ///
+    /// # Default
+    /// Defaults to "program1".
+    default_program: Option<String>,
+    /// Whether an alias with the pattern of a standard name (`program<n>` or `p<n>`) that
+    /// is not one of the standard names of the declared programs, e.g. `program5` with
+    /// three programs, is an error instead of only a warning.
+    ///
+    /// # Default
+    /// Defaults to `false`.
+    #[serde(default)]
+    reject_reserved_aliases: bool,
+    /// Whether a declared program that is not run by any test, e.g. `program3` with only
+    /// `program1` and `program2` referenced, is an error instead of only a warning.
+    ///
+    /// # Default
+    /// Defaults to `false`.
+    #[serde(default)]
+    reject_unused_programs: bool,
 
     // aux
     /// This field maps each possible program name to its relative index in
     /// `input_programs`.
     #[serde(skip)]
     program_name_by_index: HashMap<String, usize>,
+    /// User defined aliases with the pattern of a standard name, see
+    /// `reject_reserved_aliases`.
+    #[serde(skip)]
+    reserved_aliases: Vec<String>,
+}
+
+/// Whether `alias` looks like a standard name, i.e. `program<n>` or `p<n>`.
+fn has_reserved_pattern(alias: &str) -> bool {
+    [DEFAULT_PREFIX_PROGRAM_NAME1, DEFAULT_PREFIX_PROGRAM_NAME2]
+        .iter()
+        .filter_map(|prefix| alias.strip_prefix(prefix))
+        .any(|n| !n.is_empty() && n.bytes().all(|digit| digit.is_ascii_digit()))
 }
 
 impl InputSection {
-    pub fn build(input_programs: Vec<ProgramSpecification>) -> Result<Self, &'static str> {
+    pub fn build(
+        input_programs: Vec<ProgramSpecification>,
+        default_program: Option<String>,
+        reject_reserved_aliases: bool,
+        reject_unused_programs: bool,
+    ) -> Result<Self, &'static str> {
         if input_programs.is_empty() {
             return Err("input_program array may not be empty");
         }
@@ -106,22 +198,40 @@ impl InputSection {
         }
 
         // Then, add aliases user defined aliases
+        let mut reserved_aliases = vec![];
         for (i, input_program) in input_programs.iter().enumerate().take(len) {
-            if let ProgramSpecification::Complete {
-                alias,
-                program_type: _,
-            } = input_program
-            {
+            if let ProgramSpecification::Complete { alias, .. } = input_program {
                 if program_name_to_index.contains_key(alias) {
                     return Err("duplicated alias (<alias>)");
                 }
+                // it is not a standard name of these programs, but it looks like one
+                if has_reserved_pattern(alias) {
+                    if reject_reserved_aliases {
+                        return Err("alias with the pattern of a standard program name");
+                    }
+                    log::warn!(
+                        "the alias '{alias}' looks like a standard program name, but it refers to program{}",
+                        i + 1
+                    );
+                    reserved_aliases.push(alias.clone());
+                }
                 program_name_to_index.insert(alias.clone(), i);
             }
         }
+        if default_program
+            .as_ref()
+            .is_some_and(|name| !program_name_to_index.contains_key(name))
+        {
+            return Err("default program out of scope");
+        }
 
         Ok(InputSection {
             input_programs,
+            default_program,
+            reject_reserved_aliases,
+            reject_unused_programs,
             program_name_by_index: program_name_to_index,
+            reserved_aliases,
         })
     }
 
@@ -139,18 +249,53 @@ impl InputSection {
         self.input_programs[i].get_program_type()
     }
 
+    /// Name of the program run by the tests without a `program_name`.
+    pub fn get_default_program(&self) -> &str {
+        self.default_program
+            .as_deref()
+            .unwrap_or(DEFAULT_MAIN_PROGRAM_NAME)
+    }
+
     pub fn input_programs_size(&self) -> usize {
         self.input_programs.len()
     }
 
+    pub fn get_base_args_unchecked(&self, program_name: &str) -> &[String] {
+        let i = self.program_name_by_index[program_name];
+        self.input_programs[i].get_base_args()
+    }
+
+    pub fn get_path_mode_unchecked(&self, program_name: &str) -> PathMode {
+        let i = self.program_name_by_index[program_name];
+        self.input_programs[i].get_path_mode()
+    }
+
+    /// The user defined alias of the program at `index`, if any.
+    pub fn get_alias_unchecked(&self, index: usize) -> Option<&str> {
+        match &self.input_programs[index] {
+            ProgramSpecification::OnlyType(_) => None,
+            ProgramSpecification::Complete { alias, .. } => Some(alias),
+        }
+    }
+
     pub fn get_program_name_by_index(&self) -> &HashMap<String, usize> {
         &self.program_name_by_index
     }
+
+    /// User defined aliases that look like a standard name without being one of the
+    /// standard names of the declared programs, e.g. `program5` with three programs.
+    pub fn get_reserved_aliases(&self) -> &[String] {
+        &self.reserved_aliases
+    }
+
+    pub fn rejects_unused_programs(&self) -> bool {
+        self.reject_unused_programs
+    }
 }
 
 impl Default for InputSection {
     fn default() -> Self {
-        Self::build(vec![ProgramSpecification::default()]).expect(
+        Self::build(vec![ProgramSpecification::default()], None, false, false).expect(
             "it is expected that an input section with one default program specification works",
         )
     }
@@ -160,11 +305,18 @@ impl TryFrom<InputSectionUnchecked> for InputSection {
     type Error = &'static str;
 
     fn try_from(value: InputSectionUnchecked) -> Result<Self, Self::Error> {
-        let InputSectionUnchecked { input_programs } = value;
-        match input_programs {
-            Some(input_programs) => InputSection::build(input_programs),
-            None => Ok(InputSection::default()),
-        }
+        let InputSectionUnchecked {
+            input_programs,
+            default_program,
+            reject_reserved_aliases,
+            reject_unused_programs,
+        } = value;
+        InputSection::build(
+            input_programs.unwrap_or_else(|| vec![ProgramSpecification::default()]),
+            default_program,
+            reject_reserved_aliases,
+            reject_unused_programs,
+        )
     }
 }
 
@@ -188,7 +340,9 @@ mod tests {
             should_serialize_deserialize_with_complete_spec,
             ProgramSpecification::Complete {
                 alias: "program ABC".to_string(),
-                program_type: InputType::CompiledProgram
+                program_type: InputType::CompiledProgram,
+                base_args: vec![],
+                resolve: PathMode::Auto,
             },
             ProgramSpecification
         );
@@ -246,6 +400,15 @@ mod tests {
             }"#,
             ProgramSpecification
         );
+        test_invalid_deserialization!(
+            should_panic_with_unknown_path_mode,
+            r#"
+            {
+                "alias":"name1",
+                "resolve":"home"
+            }"#,
+            ProgramSpecification
+        );
 
         // valid deserialization
         test_valid_deserialization!(
@@ -262,6 +425,24 @@ mod tests {
             }"#,
             ProgramSpecification
         );
+        test_valid_deserialization!(
+            should_accept_complete_type_with_path_mode,
+            r#"
+            {
+                "alias":"cat",
+                "resolve":"path"
+            }"#,
+            ProgramSpecification
+        );
+        test_valid_deserialization!(
+            should_accept_complete_type_with_base_args,
+            r#"
+            {
+                "alias":"python",
+                "base_args":["-O", "solution.py"]
+            }"#,
+            ProgramSpecification
+        );
         test_valid_deserialization!(
             should_accept_complete_type_without_program_type,
             r#"
@@ -287,10 +468,15 @@ mod tests {
                     ProgramSpecification::OnlyType(InputType::CompiledProgram),
                     ProgramSpecification::Complete {
                         alias: "hello".to_string(),
-                        program_type: InputType::CompiledProgram
+                        program_type: InputType::CompiledProgram,
+                        base_args: vec![],
+                        resolve: PathMode::Auto,
                     },
                     ProgramSpecification::OnlyType(InputType::CompiledProgram),
                 ],
+                default_program: Some("hello".to_string()),
+                reject_reserved_aliases: true,
+                reject_unused_programs: true,
                 program_name_by_index: HashMap::from_iter([
                     // p1
                     (format!("{DEFAULT_PREFIX_PROGRAM_NAME1}1"), 0),
@@ -302,7 +488,8 @@ mod tests {
                     // p3
                     (format!("{DEFAULT_PREFIX_PROGRAM_NAME1}3"), 2),
                     (format!("{DEFAULT_PREFIX_PROGRAM_NAME2}3"), 2),
-                ])
+                ]),
+                reserved_aliases: vec![],
             },
             InputSection
         );
@@ -389,6 +576,26 @@ mod tests {
             InputSection
         );
 
+        test_invalid_deserialization!(
+            should_panic_with_reserved_alias_when_rejected,
+            r#"
+        {
+            "input_programs": ["exe", {"alias":"program5"}],
+            "reject_reserved_aliases": true
+        }"#,
+            InputSection
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_default_program_out_of_scope,
+            r#"
+        {
+            "input_programs": ["exe"],
+            "default_program": "program2"
+        }"#,
+            InputSection
+        );
+
         // valid
         test_valid_deserialization!(should_accept_empty, r#"{}"#, InputSection);
         test_valid_deserialization!(
@@ -399,5 +606,28 @@ mod tests {
         }"#,
             InputSection
         );
+
+        #[test]
+        fn should_warn_about_aliases_with_the_pattern_of_standard_names() {
+            let input: InputSection = serde_json::from_str(
+                r#"{ "input_programs": [{"alias": "program5"}, {"alias": "p0"}, {"alias": "p3x"}, {"alias": "prog"}] }"#,
+            )
+            .unwrap();
+
+            assert_eq!(input.get_reserved_aliases(), ["program5", "p0"]);
+            assert_eq!(input.get_program_index_unchecked("program5"), 0);
+            assert!(InputSection::default().get_reserved_aliases().is_empty());
+        }
+
+        #[test]
+        fn should_default_to_the_first_program() {
+            assert_eq!(InputSection::default().get_default_program(), "program1");
+
+            let input: InputSection = serde_json::from_str(
+                r#"{ "input_programs": ["exe", {"alias": "solver"}], "default_program": "solver" }"#,
+            )
+            .unwrap();
+            assert_eq!(input.get_default_program(), "solver");
+        }
     }
 }
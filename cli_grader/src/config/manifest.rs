@@ -0,0 +1,255 @@
+//! Manifest of the submissions to grade in batch, each one with the paths of its programs.
+
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Submission of a manifest: its identifier, e.g. the name of the student, and the path of
+/// each of its programs, by program name, e.g. `program1`, `p2` or an alias.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEntry {
+    id: String,
+    programs: BTreeMap<String, PathBuf>,
+}
+
+impl ManifestEntry {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Program names and their paths, as expected by `GlobalConfig::initialize`.
+    pub fn program_name_to_path(&self) -> Vec<(&str, PathBuf)> {
+        self.programs
+            .iter()
+            .map(|(name, path)| (name.as_str(), path.clone()))
+            .collect()
+    }
+
+    /// Checks that the entry supplies exactly the programs of `expected_program_names`, as
+    /// returned by `GlobalConfig::expected_program_names`, i.e. one path per declared
+    /// program under any of its names. Unless `allow_missing_programs`, none of them may be
+    /// left out.
+    pub fn check_programs(
+        &self,
+        expected_program_names: &[String],
+        allow_missing_programs: bool,
+    ) -> Result<(), &'static str> {
+        let mut supplied = HashSet::new();
+        for name in self.programs.keys() {
+            let index = expected_program_names
+                .iter()
+                .position(|names| names.split('/').any(|n| n == name))
+                .ok_or("the submission supplies a program that is not expected")?;
+            if !supplied.insert(index) {
+                return Err("the submission supplies the same program twice");
+            }
+        }
+        if !allow_missing_programs && supplied.len() != expected_program_names.len() {
+            return Err("the submission does not supply every expected program");
+        }
+        Ok(())
+    }
+}
+
+/// Submissions to grade with the same configuration, in the order they are declared.
+///
+/// In JSON, it is a list of entries, e.g.
+/// `[{ "id": "alice", "programs": { "program1": "alice/main" } }]`. In CSV, the header
+/// is `id` followed by the program names, and each following line is a submission, e.g.
+/// `alice,alice/main`. An empty cell leaves the program out.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn build(entries: Vec<ManifestEntry>) -> Result<Self, &'static str> {
+        if entries.is_empty() {
+            return Err("the manifest must have at least one submission");
+        }
+        let mut ids = HashSet::new();
+        for entry in &entries {
+            if entry.id.trim().is_empty() {
+                return Err("the id of a submission can not be empty");
+            }
+            if !ids.insert(entry.id.as_str()) {
+                return Err("the id of a submission must be unique");
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, &'static str> {
+        let entries = serde_json::from_str(content).map_err(|err| {
+            log::debug!("error: {err:?}");
+            "invalid JSON manifest"
+        })?;
+        Self::build(entries)
+    }
+
+    pub fn from_csv(content: &str) -> Result<Self, &'static str> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(content.as_bytes());
+        let mut records = reader.records().map(|record| {
+            record.map_err(|err| {
+                log::debug!("error: {err:?}");
+                "invalid CSV manifest"
+            })
+        });
+        let header = records.next().ok_or("the manifest must have a header")??;
+        let mut columns = header.iter().map(str::trim);
+        if columns.next() != Some("id") {
+            return Err("the first column of the manifest must be the id");
+        }
+        let program_names: Vec<&str> = columns.collect();
+
+        let mut entries = vec![];
+        for record in records {
+            let record = record?;
+            let mut cells = record.iter();
+            let id = cells.next().unwrap_or_default().trim().to_string();
+            let programs = program_names
+                .iter()
+                .zip(cells)
+                .filter(|(_, path)| !path.trim().is_empty())
+                .map(|(name, path)| (name.to_string(), PathBuf::from(path.trim())))
+                .collect();
+            entries.push(ManifestEntry { id, programs });
+        }
+        Self::build(entries)
+    }
+
+    /// Reads a manifest from a CSV file if its extension is `csv`, or from a JSON one
+    /// otherwise. The relative paths of the programs are resolved against the directory of
+    /// the manifest, so it can be written next to the submissions.
+    ///
+    /// The errors are also logged with the path of the manifest.
+    pub fn read_from_file(path: &Path) -> Result<Self, &'static str> {
+        let fail = |err: &'static str| {
+            log::error!("{}: {err}", path.display());
+            err
+        };
+        let content = fs::read_to_string(path).map_err(|err| {
+            log::debug!("error: {err:?}");
+            fail("could not read the manifest")
+        })?;
+        let mut manifest = if path.extension() == Some(OsStr::new("csv")) {
+            Self::from_csv(&content)
+        } else {
+            Self::from_json(&content)
+        }
+        .map_err(fail)?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        for entry in &mut manifest.entries {
+            for program_path in entry.programs.values_mut() {
+                *program_path = base_dir.join(&*program_path);
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, programs: &[(&str, &str)]) -> ManifestEntry {
+        ManifestEntry {
+            id: id.to_string(),
+            programs: programs
+                .iter()
+                .map(|(name, path)| (name.to_string(), PathBuf::from(path)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn should_read_the_same_manifest_from_json_and_csv() {
+        let json = r#"[
+          { "id": "alice", "programs": { "program1": "alice/main", "helper": "alice/helper" } },
+          { "id": "bob", "programs": { "program1": "bob/main" } }
+        ]"#;
+        let csv = "id,program1,helper\nalice,alice/main,alice/helper\nbob,bob/main,\n";
+
+        let expected = Manifest::build(vec![
+            entry(
+                "alice",
+                &[("program1", "alice/main"), ("helper", "alice/helper")],
+            ),
+            entry("bob", &[("program1", "bob/main")]),
+        ])
+        .unwrap();
+        assert_eq!(Manifest::from_json(json), Ok(expected.clone()));
+        assert_eq!(Manifest::from_csv(csv), Ok(expected));
+    }
+
+    #[test]
+    fn should_reject_invalid_manifests() {
+        assert_eq!(
+            Manifest::from_json("[]"),
+            Err("the manifest must have at least one submission")
+        );
+        assert_eq!(
+            Manifest::from_csv("id,program1\na,a/main\na,b/main\n"),
+            Err("the id of a submission must be unique")
+        );
+        assert_eq!(
+            Manifest::from_csv("program1,id\na/main,a\n"),
+            Err("the first column of the manifest must be the id")
+        );
+        assert_eq!(
+            Manifest::from_json(r#"[{ "id": "a", "paths": {} }]"#),
+            Err("invalid JSON manifest")
+        );
+    }
+
+    #[test]
+    fn should_resolve_the_paths_against_the_directory_of_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("submissions.csv");
+        fs::write(&path, "id,program1\nalice,alice/main\n").unwrap();
+
+        let manifest = Manifest::read_from_file(&path).unwrap();
+
+        assert_eq!(
+            manifest.entries()[0].program_name_to_path(),
+            vec![("program1", dir.path().join("alice/main"))]
+        );
+    }
+
+    #[test]
+    fn should_check_that_every_expected_program_is_supplied_once() {
+        let expected = vec!["program1/p1".to_string(), "program2/p2/helper".to_string()];
+
+        assert_eq!(
+            entry("a", &[("p1", "main"), ("helper", "helper")]).check_programs(&expected, false),
+            Ok(())
+        );
+        assert_eq!(
+            entry("a", &[("program1", "main")]).check_programs(&expected, false),
+            Err("the submission does not supply every expected program")
+        );
+        assert_eq!(
+            entry("a", &[("program1", "main")]).check_programs(&expected, true),
+            Ok(())
+        );
+        assert_eq!(
+            entry("a", &[("program1", "main"), ("p1", "main")]).check_programs(&expected, false),
+            Err("the submission supplies the same program twice")
+        );
+        assert_eq!(
+            entry("a", &[("program3", "main")]).check_programs(&expected, true),
+            Err("the submission supplies a program that is not expected")
+        );
+    }
+}
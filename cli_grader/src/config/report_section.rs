@@ -1,16 +1,177 @@
-use crate::report::ReportOutput;
+use crate::{grader::score::Score, report::ReportOutput};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+/// Path of the report file when the output is not `Stdout` and no path is configured.
+pub const DEFAULT_REPORT_PATH: &str = "report.txt";
+/// Percentage of the maximum score from which the final score is shown in green.
+pub const DEFAULT_GREEN_THRESHOLD: f64 = 90.0;
+/// Percentage of the maximum score from which the final score is shown in yellow.
+pub const DEFAULT_YELLOW_THRESHOLD: f64 = 60.0;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq)]
 #[serde(deny_unknown_fields, default)]
 pub struct ReportSection {
     is_verbose: bool,
     output: ReportOutput,
+    /// Where the report file is written. It is ignored when the output is `Stdout`.
+    path: Option<PathBuf>,
+    /// Whether the report starts with the title, the author and when the run happened.
+    header: bool,
+    /// Whether the failed assertions of a unit test that failed the same way are shown
+    /// together, e.g. when the program does not run at all.
+    group_failures: bool,
+    /// Grade bands of the final score when the report is colored, see `ScoreColors`.
+    score_colors: ScoreColors,
 }
 
 impl ReportSection {
     pub fn new(is_verbose: bool, output: ReportOutput) -> Self {
-        Self { is_verbose, output }
+        Self {
+            is_verbose,
+            output,
+            path: None,
+            header: false,
+            group_failures: false,
+            score_colors: ScoreColors::default(),
+        }
+    }
+
+    pub fn with_score_colors(mut self, score_colors: ScoreColors) -> Self {
+        self.score_colors = score_colors;
+        self
+    }
+
+    pub fn with_header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+
+    pub fn with_grouped_failures(mut self) -> Self {
+        self.group_failures = true;
+        self
+    }
+
+    pub fn with_path(mut self, path: PathBuf) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    pub fn is_verbose(&self) -> bool {
+        self.is_verbose
+    }
+
+    pub fn has_header(&self) -> bool {
+        self.header
+    }
+
+    pub fn groups_failures(&self) -> bool {
+        self.group_failures
+    }
+
+    pub fn get_output(&self) -> &ReportOutput {
+        &self.output
+    }
+
+    /// Path of the report file, relative to the current directory unless it is absolute.
+    pub fn get_path(&self) -> &Path {
+        self.path
+            .as_deref()
+            .unwrap_or(Path::new(DEFAULT_REPORT_PATH))
+    }
+
+    pub fn get_score_colors(&self) -> &ScoreColors {
+        &self.score_colors
+    }
+}
+
+/// Color of the final score line, according to the band its percentage falls in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScoreBand {
+    Green,
+    Yellow,
+    Red,
+}
+
+/// Percentages of the maximum score from which the final score is shown in green and in
+/// yellow. Below `yellow`, it is shown in red. They are only used when the report is
+/// colored, i.e. written to a terminal with the colors enabled.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
+#[serde(try_from = "ScoreColorsUnchecked")]
+pub struct ScoreColors {
+    green: f64,
+    yellow: f64,
+}
+
+impl Default for ScoreColors {
+    fn default() -> Self {
+        Self {
+            green: DEFAULT_GREEN_THRESHOLD,
+            yellow: DEFAULT_YELLOW_THRESHOLD,
+        }
+    }
+}
+
+impl ScoreColors {
+    pub fn build(green: f64, yellow: f64) -> Result<Self, &'static str> {
+        if !(0.0..=100.0).contains(&green) || !(0.0..=100.0).contains(&yellow) {
+            return Err("the thresholds of the score colors must be between 0 and 100");
+        }
+        if yellow > green {
+            return Err("the yellow threshold of the score colors can not be above the green one");
+        }
+        Ok(Self { green, yellow })
+    }
+
+    pub fn green(&self) -> f64 {
+        self.green
+    }
+
+    pub fn yellow(&self) -> f64 {
+        self.yellow
+    }
+
+    /// Band of `score`. An absolute score is green when it passes and red otherwise, and a
+    /// weighted score without any weight has no band.
+    pub fn band_of(&self, score: &Score) -> Option<ScoreBand> {
+        let percentage = match *score {
+            Score::Absolute(true) => return Some(ScoreBand::Green),
+            Score::Absolute(false) => return Some(ScoreBand::Red),
+            Score::Weighted { .. } => score.percentage()?,
+        };
+        Some(if percentage >= self.green {
+            ScoreBand::Green
+        } else if percentage >= self.yellow {
+            ScoreBand::Yellow
+        } else {
+            ScoreBand::Red
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct ScoreColorsUnchecked {
+    #[serde(default = "default_green_threshold")]
+    green: f64,
+    #[serde(default = "default_yellow_threshold")]
+    yellow: f64,
+}
+
+fn default_green_threshold() -> f64 {
+    DEFAULT_GREEN_THRESHOLD
+}
+
+fn default_yellow_threshold() -> f64 {
+    DEFAULT_YELLOW_THRESHOLD
+}
+
+impl TryFrom<ScoreColorsUnchecked> for ScoreColors {
+    type Error = &'static str;
+
+    fn try_from(value: ScoreColorsUnchecked) -> Result<Self, Self::Error> {
+        ScoreColors::build(value.green, value.yellow)
     }
 }
 
@@ -26,7 +187,26 @@ mod tests {
         should_serialize_deserialize_with_txt,
         ReportSection {
             is_verbose: true,
-            output: ReportOutput::Txt
+            output: ReportOutput::Txt,
+            path: None,
+            header: false,
+            group_failures: false,
+            score_colors: ScoreColors::default(),
+        },
+        ReportSection
+    );
+    test_serialize_and_deserialize!(
+        should_serialize_deserialize_with_txt_and_path,
+        ReportSection {
+            is_verbose: true,
+            output: ReportOutput::Txt,
+            path: Some("reports/grade.txt".into()),
+            header: true,
+            group_failures: true,
+            score_colors: ScoreColors {
+                green: 80.0,
+                yellow: 50.5,
+            },
         },
         ReportSection
     );
@@ -34,7 +214,11 @@ mod tests {
         should_serialize_deserialize_with_stdout,
         ReportSection {
             is_verbose: true,
-            output: ReportOutput::Stdout
+            output: ReportOutput::Stdout,
+            path: None,
+            header: false,
+            group_failures: false,
+            score_colors: ScoreColors::default(),
         },
         ReportSection
     );
@@ -77,6 +261,48 @@ mod tests {
         }"#,
         ReportSection
     );
+    test_invalid_deserialization!(
+        should_panic_with_path_as_number,
+        r#"
+        {
+            "output": "txt",
+            "path": 123
+        }"#,
+        ReportSection
+    );
+    test_invalid_deserialization!(
+        should_panic_with_header_as_str,
+        r#"
+        {
+            "header": "true"
+        }"#,
+        ReportSection
+    );
+
+    test_invalid_deserialization!(
+        should_panic_with_yellow_above_green,
+        r#"
+        {
+            "score_colors": { "green": 50, "yellow": 70 }
+        }"#,
+        ReportSection
+    );
+    test_invalid_deserialization!(
+        should_panic_with_threshold_above_100,
+        r#"
+        {
+            "score_colors": { "green": 120 }
+        }"#,
+        ReportSection
+    );
+    test_invalid_deserialization!(
+        should_panic_with_unknown_score_color,
+        r#"
+        {
+            "score_colors": { "blue": 10 }
+        }"#,
+        ReportSection
+    );
 
     // valid deserialization
     test_valid_deserialization!(should_accept_empty_object, r#"{}"#, ReportSection);
@@ -97,4 +323,75 @@ mod tests {
         }"#,
         ReportSection
     );
+    test_valid_deserialization!(
+        should_accept_with_path,
+        r#"
+        {
+            "output": "txt",
+            "path": "reports/grade.txt"
+        }"#,
+        ReportSection
+    );
+    test_valid_deserialization!(
+        should_accept_with_header,
+        r#"
+        {
+            "output": "stdout",
+            "header": true
+        }"#,
+        ReportSection
+    );
+
+    test_valid_deserialization!(
+        should_accept_with_score_colors,
+        r#"
+        {
+            "score_colors": { "green": 75.5, "yellow": 40 }
+        }"#,
+        ReportSection
+    );
+    test_valid_deserialization!(
+        should_accept_with_only_one_score_color,
+        r#"
+        {
+            "score_colors": { "yellow": 70 }
+        }"#,
+        ReportSection
+    );
+
+    #[test]
+    fn should_band_the_score_by_percentage() {
+        let colors = ScoreColors::default();
+        let weighted = |current| Score::Weighted { current, max: 10 };
+        assert_eq!(colors.band_of(&weighted(10)), Some(ScoreBand::Green));
+        assert_eq!(colors.band_of(&weighted(9)), Some(ScoreBand::Green));
+        assert_eq!(colors.band_of(&weighted(8)), Some(ScoreBand::Yellow));
+        assert_eq!(colors.band_of(&weighted(6)), Some(ScoreBand::Yellow));
+        assert_eq!(colors.band_of(&weighted(5)), Some(ScoreBand::Red));
+        assert_eq!(
+            colors.band_of(&Score::Absolute(true)),
+            Some(ScoreBand::Green)
+        );
+        assert_eq!(
+            colors.band_of(&Score::Absolute(false)),
+            Some(ScoreBand::Red)
+        );
+        assert_eq!(
+            colors.band_of(&Score::Weighted { current: 0, max: 0 }),
+            None
+        );
+
+        let colors = ScoreColors::build(50.0, 20.0).unwrap();
+        assert_eq!(colors.band_of(&weighted(5)), Some(ScoreBand::Green));
+        assert_eq!(colors.band_of(&weighted(2)), Some(ScoreBand::Yellow));
+    }
+
+    #[test]
+    fn should_default_to_report_txt_path() {
+        let report = ReportSection::new(false, ReportOutput::Txt);
+        assert_eq!(report.get_path(), Path::new(DEFAULT_REPORT_PATH));
+
+        let report = report.with_path("out/grade.txt".into());
+        assert_eq!(report.get_path(), Path::new("out/grade.txt"));
+    }
 }
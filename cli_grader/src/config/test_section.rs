@@ -1,53 +1,82 @@
 use crate::{
-    config::test_section::unit_tests::UnitTests,
-    grader::{GradingTestSection, grading_tests::GradingTests},
+    config::{
+        defaults_section::DefaultsSection,
+        test_section::{performance_tests::PerformanceTests, unit_tests::UnitTests},
+    },
+    grader::{
+        GradingTestSection,
+        grading_tests::{GradingTests, unit_test::assertion::StdoutMatch},
+    },
     input::ExecutableArtifact,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 /*
-mod performance_tests;
 mod integration_tests;
  */
 
+pub(crate) mod performance_tests;
 pub(crate) mod unit_tests;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct TestSectionUnchecked {
     title: Option<String>,
     weight: Option<u32>,
+    #[serde(default)]
+    extra_credit: bool,
+    #[serde(default)]
+    skip: bool,
     unit_tests: Option<UnitTests>,
+    performance_tests: Option<PerformanceTests>,
     // integration_tests: IntegrationTests,
-    // performance_tests: PerformanceTests,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 pub enum Tests {
     UnitTests(UnitTests),
+    PerformanceTests(PerformanceTests),
     // IntegrationTests(IntegrationTests),
-    // PerformanceTests(PerformanceTests),
 }
 
 impl Tests {
     fn build_grading_tests(
         &self,
         executables_by_name: &HashMap<String, ExecutableArtifact>,
-    ) -> Result<GradingTests, &'static str> {
+        default_program: &str,
+        defaults: &DefaultsSection,
+        base_dir: &Path,
+        comparison_policy: StdoutMatch,
+    ) -> Result<GradingTests, String> {
         match self {
             Tests::UnitTests(unit_tests) => Ok(GradingTests::UnitTests(
-                unit_tests.build_grading_unit_tests(executables_by_name)?,
+                unit_tests.build_grading_unit_tests(
+                    executables_by_name,
+                    default_program,
+                    defaults,
+                    base_dir,
+                    comparison_policy,
+                )?,
+            )),
+            Tests::PerformanceTests(performance_tests) => Ok(GradingTests::PerformanceTests(
+                performance_tests
+                    .build_grading_performance_tests(executables_by_name, default_program)?,
             )),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(try_from = "TestSectionUnchecked", into = "TestSectionUnchecked")]
 pub struct TestSection {
     title: Option<String>,
     weight: Option<u32>,
+    /// Bonus section, whose score does not count towards the maximum.
+    extra_credit: bool,
+    /// Disabled section: it is not executed nor scored, only reported as skipped.
+    skip: bool,
     tests: Tests,
 }
 
@@ -56,6 +85,8 @@ impl From<TestSection> for TestSectionUnchecked {
         let TestSection {
             title,
             weight,
+            extra_credit,
+            skip,
             tests,
         } = val;
 
@@ -63,7 +94,18 @@ impl From<TestSection> for TestSectionUnchecked {
             Tests::UnitTests(unit_tests) => TestSectionUnchecked {
                 title,
                 weight,
+                extra_credit,
+                skip,
                 unit_tests: Some(unit_tests),
+                performance_tests: None,
+            },
+            Tests::PerformanceTests(performance_tests) => TestSectionUnchecked {
+                title,
+                weight,
+                extra_credit,
+                skip,
+                unit_tests: None,
+                performance_tests: Some(performance_tests),
             },
         }
     }
@@ -73,34 +115,67 @@ impl TestSection {
     pub fn build(
         title: Option<String>,
         weight: Option<u32>,
+        extra_credit: bool,
+        skip: bool,
         unit_tests: Option<UnitTests>,
+        performance_tests: Option<PerformanceTests>,
     ) -> Result<Self, &'static str> {
-        if unit_tests.is_none() {
-            return Err("at least one type of test is expected in the TestSection");
-        }
+        // exactly one type of test is expected
+        let tests = match (unit_tests, performance_tests) {
+            (Some(unit_tests), None) => Tests::UnitTests(unit_tests),
+            (None, Some(performance_tests)) => Tests::PerformanceTests(performance_tests),
+            (None, None) => {
+                return Err("at least one type of test is expected in the TestSection");
+            }
+            _ => return Err("only one type of test is allowed in the TestSection"),
+        };
 
         Ok(Self {
             title,
             weight,
-            tests: Tests::UnitTests(unit_tests.expect("unit_tests is not none at this point")),
+            extra_credit,
+            skip,
+            tests,
         })
     }
 
+    pub fn get_title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
     pub fn get_tests(&self) -> &Tests {
         &self.tests
     }
 
+    pub fn is_skipped(&self) -> bool {
+        self.skip
+    }
+
+    /// Name of the `n`-th section, which is its title or `Section <n>` if it has none.
+    pub fn get_name(&self, n: usize) -> String {
+        self.title.clone().unwrap_or(format!("Section {n}"))
+    }
+
     pub fn build_grading_section(
         &self,
         n: usize,
         executables_by_name: &HashMap<String, ExecutableArtifact>,
-    ) -> Result<GradingTestSection, &'static str> {
-        let tests = self.tests.build_grading_tests(executables_by_name)?;
-        Ok(GradingTestSection::new(
-            self.title.clone().unwrap_or(format!("Section {n}")),
-            self.weight.unwrap_or(1),
-            tests,
-        ))
+        default_program: &str,
+        defaults: &DefaultsSection,
+        base_dir: &Path,
+        comparison_policy: StdoutMatch,
+    ) -> Result<GradingTestSection, String> {
+        let tests = self.tests.build_grading_tests(
+            executables_by_name,
+            default_program,
+            defaults,
+            base_dir,
+            comparison_policy,
+        )?;
+        Ok(
+            GradingTestSection::new(self.get_name(n), self.weight.unwrap_or(1), tests)
+                .with_extra_credit(self.extra_credit),
+        )
     }
 
     #[cfg(test)]
@@ -108,6 +183,8 @@ impl TestSection {
         Self {
             title: Some(format!("Section {n}")),
             weight: Some(1),
+            extra_credit: false,
+            skip: false,
             tests: Tests::UnitTests(UnitTests::new_dummy()),
         }
     }
@@ -120,10 +197,20 @@ impl TryFrom<TestSectionUnchecked> for TestSection {
         let TestSectionUnchecked {
             title,
             weight,
+            extra_credit,
+            skip,
             unit_tests,
+            performance_tests,
         } = value;
 
-        TestSection::build(title, weight, unit_tests)
+        TestSection::build(
+            title,
+            weight,
+            extra_credit,
+            skip,
+            unit_tests,
+            performance_tests,
+        )
     }
 }
 #[cfg(test)]
@@ -143,6 +230,30 @@ mod tests {
             TestSection {
                 title: Some("section 1".to_string()),
                 weight: None,
+                extra_credit: false,
+                skip: false,
+                tests: Tests::UnitTests(UnitTests::new_dummy())
+            },
+            TestSection
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_performance_tests,
+            TestSection {
+                title: Some("section 1".to_string()),
+                weight: Some(2),
+                extra_credit: false,
+                skip: false,
+                tests: Tests::PerformanceTests(PerformanceTests::new_dummy())
+            },
+            TestSection
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_extra_credit,
+            TestSection {
+                title: Some("bonus".to_string()),
+                weight: Some(2),
+                extra_credit: true,
+                skip: false,
                 tests: Tests::UnitTests(UnitTests::new_dummy())
             },
             TestSection
@@ -251,7 +362,39 @@ mod tests {
             TestSection
         );
 
+        test_invalid_deserialization!(
+            should_panic_with_more_than_one_type_of_test,
+            r#"
+        {
+            "title":"sec 1",
+            "unit_tests":{
+                "tests": [
+                    {
+                        "detailed_tests":[{ "args":"a1", "status":0 }]
+                    }
+                ]
+            },
+            "performance_tests":{
+                "tests": [{ "max_duration_ms": 100 }]
+            }
+        }
+        "#,
+            TestSection
+        );
+
         // valid deserialization
+        test_valid_deserialization!(
+            should_accept_performance_tests_section,
+            r#"
+        {
+            "title":"sec 1",
+            "performance_tests":{
+                "tests": [{ "args":"1000000", "max_duration_ms": 100, "min_duration_ms": 1 }]
+            }
+        }
+        "#,
+            TestSection
+        );
         test_valid_deserialization!(
             should_accept_valid_section,
             r#"
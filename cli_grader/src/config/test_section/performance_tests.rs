@@ -0,0 +1,412 @@
+use crate::{
+    grader::grading_tests::performance_test::{
+        PerformanceTest as GradingPerformanceTest, PerformanceTests as GradingPerformanceTests,
+    },
+    input::ExecutableArtifact,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use shlex::Shlex;
+use std::{collections::HashMap, time::Duration};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct PerformanceTestUnchecked {
+    title: Option<String>,
+    program_name: Option<String>,
+    // input
+    args: Option<String>,
+    stdin: Option<String>,
+    // expect
+    max_duration_ms: u64,
+    min_duration_ms: Option<u64>,
+    repeat: Option<usize>,
+    warmup: Option<usize>,
+    kill_grace_ms: Option<u64>,
+    // grading
+    weight: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(try_from = "PerformanceTestUnchecked")]
+pub struct PerformanceTest {
+    title: Option<String>,
+    /// The same as `UnitTest::program_name`, including its default.
+    program_name: Option<String>,
+    // input
+    args: Option<String>,
+    stdin: Option<String>,
+    // expect
+    /// The program must finish in less than this amount of milliseconds.
+    max_duration_ms: u64,
+    /// If defined, the program must take at least this amount of milliseconds. It is
+    /// useful to catch programs that do not do anything.
+    min_duration_ms: Option<u64>,
+    /// Number of times the program is executed, reporting the statistics of the
+    /// durations. The mean duration is the one compared against the limits and only the
+    /// status of the first execution is checked. It defaults to 1.
+    repeat: Option<usize>,
+    /// Number of times the program is executed before the measured executions, e.g. so
+    /// the first one does not pay for the cold caches. They are not graded. It defaults to
+    /// 0.
+    warmup: Option<usize>,
+    /// A program still running at `max_duration_ms` is sent SIGTERM and, if it did not
+    /// exit after this amount of milliseconds, SIGKILL. It defaults to 0, which kills it
+    /// right away. Outside of Unix, the program is always killed right away.
+    kill_grace_ms: Option<u64>,
+    // grading
+    weight: Option<u32>,
+}
+
+impl PerformanceTest {
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        title: Option<String>,
+        program_name: Option<String>,
+        args: Option<String>,
+        stdin: Option<String>,
+        max_duration_ms: u64,
+        min_duration_ms: Option<u64>,
+        repeat: Option<usize>,
+        warmup: Option<usize>,
+        kill_grace_ms: Option<u64>,
+        weight: Option<u32>,
+    ) -> Result<Self, &'static str> {
+        if max_duration_ms == 0 {
+            return Err("max_duration_ms must be greater than 0");
+        }
+        if min_duration_ms.is_some_and(|min| min >= max_duration_ms) {
+            return Err("min_duration_ms must be lower than max_duration_ms");
+        }
+        if repeat == Some(0) {
+            return Err("repeat must be greater than 0");
+        }
+        Ok(Self {
+            title,
+            program_name,
+            args,
+            stdin,
+            max_duration_ms,
+            min_duration_ms,
+            repeat,
+            warmup,
+            kill_grace_ms,
+            weight,
+        })
+    }
+
+    pub fn get_title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// The `title`, or `Performance Test <n>` for the `n`-th performance test.
+    pub fn get_name(&self, n: usize) -> String {
+        self.title
+            .clone()
+            .unwrap_or(format!("Performance Test {n}"))
+    }
+
+    pub fn get_program_name(&self) -> Option<&str> {
+        self.program_name.as_deref()
+    }
+
+    /// The performance test runs `default_program` when it has no `program_name`.
+    fn build_grading_performance_test(
+        &self,
+        n: usize,
+        executables_by_name: &HashMap<String, ExecutableArtifact>,
+        default_program: &str,
+    ) -> Result<GradingPerformanceTest, &'static str> {
+        let executable = executables_by_name
+            .get(self.program_name.as_deref().unwrap_or(default_program))
+            .ok_or("executable not found")?;
+
+        let mut args = vec![];
+        if let Some(args_string) = &self.args {
+            let mut lex = Shlex::new(args_string.as_str());
+            for arg in lex.by_ref() {
+                args.push(arg);
+            }
+            if lex.had_error {
+                return Err("invalid args string");
+            }
+        }
+
+        GradingPerformanceTest::build(
+            self.get_name(n),
+            executable.clone(),
+            args,
+            self.stdin.clone(),
+            Duration::from_millis(self.max_duration_ms),
+            self.min_duration_ms.map(Duration::from_millis),
+            self.weight.unwrap_or(1),
+        )?
+        .with_repeat(self.repeat.unwrap_or(1))
+        .map(|test| {
+            test.with_warmup(self.warmup.unwrap_or(0))
+                .with_kill_grace(Duration::from_millis(self.kill_grace_ms.unwrap_or(0)))
+        })
+    }
+
+    #[cfg(test)]
+    fn new_dummy(n: u32) -> Self {
+        Self {
+            title: Some(format!("test {n}")),
+            program_name: Some(format!("program{n}")),
+            args: Some("arg1 arg2".to_string()),
+            stdin: None,
+            max_duration_ms: 1000,
+            min_duration_ms: Some(10),
+            repeat: Some(3),
+            warmup: Some(1),
+            kill_grace_ms: Some(50),
+            weight: Some(n),
+        }
+    }
+}
+
+impl TryFrom<PerformanceTestUnchecked> for PerformanceTest {
+    type Error = &'static str;
+
+    fn try_from(value: PerformanceTestUnchecked) -> Result<Self, Self::Error> {
+        let PerformanceTestUnchecked {
+            title,
+            program_name,
+            args,
+            stdin,
+            max_duration_ms,
+            min_duration_ms,
+            repeat,
+            warmup,
+            kill_grace_ms,
+            weight,
+        } = value;
+
+        PerformanceTest::build(
+            title,
+            program_name,
+            args,
+            stdin,
+            max_duration_ms,
+            min_duration_ms,
+            repeat,
+            warmup,
+            kill_grace_ms,
+            weight,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct PerformanceTestsUnchecked {
+    tests: Vec<PerformanceTest>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(try_from = "PerformanceTestsUnchecked")]
+pub struct PerformanceTests {
+    tests: Vec<PerformanceTest>,
+}
+
+impl PerformanceTests {
+    pub fn build(tests: Vec<PerformanceTest>) -> Result<Self, &'static str> {
+        if tests.is_empty() {
+            return Err("must contain at least one test");
+        }
+        Ok(Self { tests })
+    }
+
+    pub fn get_tests(&self) -> &[PerformanceTest] {
+        &self.tests
+    }
+
+    pub fn build_grading_performance_tests(
+        &self,
+        executables_by_name: &HashMap<String, ExecutableArtifact>,
+        default_program: &str,
+    ) -> Result<GradingPerformanceTests, &'static str> {
+        let mut performance_tests = Vec::with_capacity(self.tests.len());
+        for (i, t) in self.tests.iter().enumerate() {
+            performance_tests.push(t.build_grading_performance_test(
+                i + 1,
+                executables_by_name,
+                default_program,
+            )?);
+        }
+        Ok(GradingPerformanceTests::new(performance_tests))
+    }
+
+    #[cfg(test)]
+    pub fn new_dummy() -> Self {
+        Self {
+            tests: vec![PerformanceTest::new_dummy(1), PerformanceTest::new_dummy(2)],
+        }
+    }
+}
+
+impl TryFrom<PerformanceTestsUnchecked> for PerformanceTests {
+    type Error = &'static str;
+
+    fn try_from(value: PerformanceTestsUnchecked) -> Result<Self, Self::Error> {
+        let PerformanceTestsUnchecked { tests } = value;
+        PerformanceTests::build(tests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_MAIN_PROGRAM_NAME;
+
+    mod test_performance_test {
+        use super::*;
+        use crate::config::test_macros::{
+            test_invalid_deserialization, test_serialize_and_deserialize,
+            test_valid_deserialization,
+        };
+
+        // serialization
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_full,
+            PerformanceTest::new_dummy(1),
+            PerformanceTest
+        );
+
+        // invalid deserialization
+        test_invalid_deserialization!(should_panic_with_empty_object, r#"{}"#, PerformanceTest);
+        test_invalid_deserialization!(
+            should_panic_with_zero_max_duration,
+            r#"
+        {
+            "max_duration_ms": 0
+        }"#,
+            PerformanceTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_min_duration_not_lower_than_max_duration,
+            r#"
+        {
+            "max_duration_ms": 100,
+            "min_duration_ms": 100
+        }"#,
+            PerformanceTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_zero_repeat,
+            r#"
+        {
+            "max_duration_ms": 100,
+            "repeat": 0
+        }"#,
+            PerformanceTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_extra_field,
+            r#"
+        {
+            "max_duration_ms": 100,
+            "stdout": "hello"
+        }"#,
+            PerformanceTest
+        );
+
+        // valid deserialization
+        test_valid_deserialization!(
+            should_accept_only_max_duration,
+            r#"
+        {
+            "max_duration_ms": 100
+        }"#,
+            PerformanceTest
+        );
+        test_valid_deserialization!(
+            should_accept_full,
+            r#"
+        {
+            "title": "sort 1M numbers",
+            "program_name": "p1",
+            "args": "--input \"big file.txt\"",
+            "stdin": "3 2 1",
+            "max_duration_ms": 2000,
+            "min_duration_ms": 5,
+            "repeat": 10,
+            "warmup": 2,
+            "kill_grace_ms": 500,
+            "weight": 3
+        }"#,
+            PerformanceTest
+        );
+    }
+
+    mod test_performance_tests {
+        use super::*;
+        use crate::config::test_macros::{
+            test_invalid_deserialization, test_serialize_and_deserialize,
+            test_valid_deserialization,
+        };
+
+        // serialization
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_full,
+            PerformanceTests::new_dummy(),
+            PerformanceTests
+        );
+
+        // invalid deserialization
+        test_invalid_deserialization!(
+            should_panic_without_tests,
+            r#"
+        {
+            "tests": []
+        }"#,
+            PerformanceTests
+        );
+
+        // valid deserialization
+        test_valid_deserialization!(
+            should_accept_tests,
+            r#"
+        {
+            "tests": [{ "max_duration_ms": 100 }, { "max_duration_ms": 200 }]
+        }"#,
+            PerformanceTests
+        );
+
+        #[test]
+        fn should_build_grading_performance_tests() {
+            let executable = ExecutableArtifact::new_dummy(1);
+            let executables_by_name =
+                HashMap::from([(DEFAULT_MAIN_PROGRAM_NAME.to_string(), executable.clone())]);
+            let tests: PerformanceTests = serde_json::from_str(
+                r#"{ "tests": [{ "args": "a \"b c\"", "max_duration_ms": 100, "repeat": 5, "warmup": 2, "kill_grace_ms": 300 }] }"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                tests
+                    .build_grading_performance_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME
+                    )
+                    .unwrap(),
+                GradingPerformanceTests::new(vec![
+                    GradingPerformanceTest::build(
+                        "Performance Test 1".to_string(),
+                        executable,
+                        vec!["a".to_string(), "b c".to_string()],
+                        None,
+                        Duration::from_millis(100),
+                        None,
+                        1,
+                    )
+                    .unwrap()
+                    .with_repeat(5)
+                    .unwrap()
+                    .with_warmup(2)
+                    .with_kill_grace(Duration::from_millis(300))
+                ])
+            );
+        }
+    }
+}
@@ -1,23 +1,41 @@
 use crate::{
-    config::DEFAULT_MAIN_PROGRAM_NAME,
+    config::{
+        defaults_section::DefaultsSection, process_raw_string_commands, process_shell_commands,
+    },
     grader::grading_tests::unit_test::{
         UnitTest as GradingUnitTest, UnitTests as GradingUnitTests,
-        assertion::Assertion as UnitTestAssertion,
+        assertion::{
+            Assertion as UnitTestAssertion, CaptureMode, ExpectedStatus, StdoutMatch, is_inner_path,
+        },
+        extraction::StdoutExtract as GradingStdoutExtract,
+        interaction::{
+            InteractionScript as GradingInteractionScript,
+            InteractionStep as GradingInteractionStep,
+        },
+        regex_checks::{GroupCheck, StdoutRegexChecks as GradingStdoutRegexChecks},
     },
     input::ExecutableArtifact,
 };
+use base64::{Engine, prelude::BASE64_STANDARD};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{
     Deserialize, Serialize,
-    de::{self, Visitor},
+    de::{self, IntoDeserializer, Visitor},
     ser::SerializeSeq,
 };
 use shlex::Shlex;
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
-    iter, panic,
+    ffi::OsStr,
+    fs, iter,
+    path::Path,
+    time::Duration,
 };
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+const DEFAULT_INTERACTION_STEP_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum TableHeaderType {
     Name,
@@ -30,23 +48,29 @@ pub enum TableHeaderType {
     Status,
     // grading
     Weight,
+    /// Comma-separated list of tags, e.g. `"basic, edge-cases"`.
+    Tags,
+    /// Feedback shown when the assertion fails.
+    Hint,
 }
 impl TableHeaderType {
     /// Whether the `content` is compatible with its current table column type.
     ///
     /// # Compatibility
-    /// - Args, Stdout, Stderr, Name: String
-    /// - Status, Weight: Int
+    /// - Args, Stdout, Stderr, Name, Tags, Hint: String
+    /// - Status: Int that fits in an `i32`
+    /// - Weight: Int that fits in an `u32`
     fn is_compatible_with(&self, content: &TableCellContent) -> bool {
         match self {
             TableHeaderType::Args
             | TableHeaderType::Stdin
             | TableHeaderType::Stdout
             | TableHeaderType::Stderr
-            | TableHeaderType::Name => matches!(content, TableCellContent::String(_)),
-            TableHeaderType::Status | TableHeaderType::Weight => {
-                matches!(content, TableCellContent::Int(_))
-            }
+            | TableHeaderType::Name
+            | TableHeaderType::Tags
+            | TableHeaderType::Hint => content.extract_string().is_ok(),
+            TableHeaderType::Status => content.extract_i32().is_ok(),
+            TableHeaderType::Weight => content.extract_u32().is_ok(),
         }
     }
 
@@ -66,31 +90,181 @@ impl TableHeaderType {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum TableCellContent {
     Int(i64),
     String(String),
 }
 impl TableCellContent {
-    fn extract_string(&self) -> String {
+    fn extract_string(&self) -> Result<String, &'static str> {
         match self {
-            TableCellContent::String(s) => s.clone(),
-            _ => panic!("expected string"),
+            TableCellContent::String(s) => Ok(s.clone()),
+            _ => Err("expected a string in the table cell"),
         }
     }
-    fn extract_u32(&self) -> u32 {
+    fn extract_u32(&self) -> Result<u32, &'static str> {
         match self {
-            TableCellContent::Int(i) => *i as u32,
-            _ => panic!("expected u32"),
+            TableCellContent::Int(i) => u32::try_from(*i)
+                .map_err(|_| "expected a non-negative 32-bit integer in the table cell"),
+            _ => Err("expected an integer in the table cell"),
         }
     }
-    fn extract_i32(&self) -> i32 {
+    fn extract_i32(&self) -> Result<i32, &'static str> {
         match self {
-            TableCellContent::Int(i) => *i as i32,
-            _ => panic!("expected i32"),
+            TableCellContent::Int(i) => {
+                i32::try_from(*i).map_err(|_| "expected a 32-bit integer in the table cell")
+            }
+            _ => Err("expected an integer in the table cell"),
+        }
+    }
+}
+
+/// Replaces each `{name}` in `arg` with the file of the section called `name`, or whose name
+/// without extension is `name`, e.g. `{input}` with `input.txt`. Only names made of
+/// letters, digits, `_`, `-` and `.` are placeholders, so any other text between braces is
+/// kept as it is.
+fn expand_file_placeholders(arg: &str, file_names: &[&str]) -> Result<String, &'static str> {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after_brace = &rest[start + 1..];
+        let placeholder = after_brace
+            .find('}')
+            .map(|end| &after_brace[..end])
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            });
+        let Some(placeholder) = placeholder else {
+            expanded.push('{');
+            rest = after_brace;
+            continue;
+        };
+        let file_name = match file_names.iter().find(|name| **name == placeholder) {
+            Some(name) => name,
+            None => {
+                let mut by_stem = file_names
+                    .iter()
+                    .filter(|name| Path::new(name).file_stem() == Some(OsStr::new(placeholder)));
+                match (by_stem.next(), by_stem.next()) {
+                    (Some(name), None) => name,
+                    (Some(_), Some(_)) => return Err("ambiguous file placeholder in args"),
+                    (None, _) => return Err("unknown file placeholder in args"),
+                }
+            }
+        };
+        expanded.push_str(file_name);
+        rest = &after_brace[placeholder.len() + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Replaces each `{{name}}` in `text` with the value of the variable `name`, ignoring the
+/// whitespace around the name. A `\{{` is kept as a literal `{{`.
+///
+/// Templating is only active when there are variables: without them, the text is kept as
+/// it is, even `{{typo}}` or `\{{`, so the unit tests that do not use `vars` can have
+/// literal braces, e.g. in the expected output of a template engine.
+fn expand_vars(text: &str, vars: &HashMap<String, String>) -> Result<String, &'static str> {
+    if vars.is_empty() {
+        return Ok(text.to_string());
+    }
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            expanded.push_str(&rest[..start - 1]);
+            expanded.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
         }
+        expanded.push_str(&rest[..start]);
+        let after_braces = &rest[start + 2..];
+        let end = after_braces
+            .find("}}")
+            .ok_or("unterminated variable placeholder")?;
+        let value = vars
+            .get(after_braces[..end].trim())
+            .ok_or("undefined variable in placeholder")?;
+        expanded.push_str(value);
+        rest = &after_braces[end + 2..];
     }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Splits a comma-separated list of tags, ignoring the blank ones.
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Decodes a hexadecimal string, e.g. `00ff0A`, ignoring the whitespace between the bytes.
+fn decode_hex(encoded: &str) -> Result<Vec<u8>, &'static str> {
+    let digits: Vec<u8> = encoded
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("stdin_hex must have an even number of digits");
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let digit = |b: u8| char::from(b).to_digit(16);
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(high), Some(low)) => Ok((high * 16 + low) as u8),
+                _ => Err("invalid hexadecimal digit in stdin_hex"),
+            }
+        })
+        .collect()
+}
+
+/// Parses the content of a dotenv-style file, with one `KEY=VALUE` per line. Blank lines
+/// and lines starting with `#` are ignored, the keys may be preceded by `export` and the
+/// values may be enclosed in single or double quotes, which are removed.
+fn parse_env_file(content: &str) -> Result<Vec<(Key, Value)>, &'static str> {
+    let mut env = vec![];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or("env_file has a line without '='")?;
+        let key = key.trim();
+        let is_valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_key {
+            return Err("invalid environment variable name in env_file");
+        }
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|quote| {
+                value
+                    .strip_prefix(*quote)
+                    .and_then(|value| value.strip_suffix(*quote))
+            })
+            .unwrap_or(value);
+        env.push((key.to_string(), value.to_string()));
+    }
+    Ok(env)
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -140,9 +314,17 @@ impl Table {
     fn build_grading_assertions(
         &self,
         mut n: usize,
+        default_weight: u32,
+        file_names: &[&str],
+        vars: &HashMap<String, String>,
+        comparison_policy: StdoutMatch,
     ) -> Result<Vec<UnitTestAssertion>, &'static str> {
         let mut assertions = vec![];
         for t in &self.tests {
+            // a table built without `Table::build` may have rows of any size
+            if t.len() != self.header.len() {
+                return Err("inconsistent test case size");
+            }
             let mut name = format!("Assertion {n}");
             n += 1;
             let mut args = vec![];
@@ -150,31 +332,49 @@ impl Table {
             let mut stdout: Option<String> = None;
             let mut stderr: Option<String> = None;
             let mut status: Option<i32> = None;
-            let mut weight: u32 = 1;
+            let mut weight = default_weight;
+            let mut tags = vec![];
+            let mut hint: Option<String> = None;
             for (i, h) in self.header.iter().enumerate() {
                 match h {
-                    TableHeaderType::Name => name = t[i].extract_string(),
-                    TableHeaderType::Weight => weight = t[i].extract_u32(),
+                    TableHeaderType::Tags => tags = split_tags(&t[i].extract_string()?),
+                    TableHeaderType::Hint => hint = Some(t[i].extract_string()?),
+                    TableHeaderType::Name => name = t[i].extract_string()?,
+                    TableHeaderType::Weight => weight = t[i].extract_u32()?,
                     TableHeaderType::Args => {
-                        let args_string = t[i].extract_string();
+                        let args_string = expand_vars(&t[i].extract_string()?, vars)?;
                         let mut lex = Shlex::new(args_string.as_str());
                         for arg in lex.by_ref() {
-                            args.push(arg);
+                            args.push(expand_file_placeholders(&arg, file_names)?);
                         }
                         if lex.had_error {
                             return Err("invalid args string");
                         }
                     }
-                    TableHeaderType::Stdin => stdin = Some(t[i].extract_string()),
-                    TableHeaderType::Stdout => stdout = Some(t[i].extract_string()),
-                    TableHeaderType::Stderr => stderr = Some(t[i].extract_string()),
-                    TableHeaderType::Status => status = Some(t[i].extract_i32()),
+                    TableHeaderType::Stdin => {
+                        stdin = Some(expand_vars(&t[i].extract_string()?, vars)?)
+                    }
+                    TableHeaderType::Stdout => {
+                        stdout = Some(expand_vars(&t[i].extract_string()?, vars)?)
+                    }
+                    TableHeaderType::Stderr => {
+                        stderr = Some(expand_vars(&t[i].extract_string()?, vars)?)
+                    }
+                    TableHeaderType::Status => status = Some(t[i].extract_i32()?),
                 }
             }
+            let stdout_match = match stdout {
+                Some(_) => comparison_policy,
+                None => StdoutMatch::Exact,
+            };
             if let Ok(assertion) =
                 UnitTestAssertion::build(name, args, stdin, stdout, stderr, status, weight)
             {
-                assertions.push(assertion);
+                let assertion = assertion.with_tags(tags).with_stdout_match(stdout_match);
+                assertions.push(match hint {
+                    Some(hint) => assertion.with_hint(hint),
+                    None => assertion,
+                });
                 continue;
             }
             return Err("could not build assertion properly");
@@ -182,6 +382,80 @@ impl Table {
         Ok(assertions)
     }
 
+    /// Reads a table from a CSV file, or from a TSV one if its extension is `tsv`. The
+    /// first line is the header, with the same column types as an inline table, and each
+    /// following line is a test. Quoted cells may span several lines, e.g. an stdout that
+    /// ends with a newline.
+    ///
+    /// The table is validated as an inline one. The errors start with the file and the
+    /// line where they happened, e.g. `tests.csv:3: invalid CSV in table_file`.
+    fn read_from_file(path: &Path) -> Result<Self, String> {
+        let fail = |line: u64, err: &str| format!("{}:{line}: {err}", path.display());
+        let delimiter = if path.extension() == Some(OsStr::new("tsv")) {
+            b'\t'
+        } else {
+            b','
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .map_err(|err| {
+                log::debug!("error: {err:?}");
+                format!("{}: could not read table_file", path.display())
+            })?;
+        let mut records = reader.records().map(|record| {
+            record.map_err(|err| {
+                let line = err.position().map_or(0, |position| position.line());
+                log::debug!("error: {err:?}");
+                fail(line, "invalid CSV in table_file")
+            })
+        });
+
+        let header_record = records
+            .next()
+            .ok_or_else(|| fail(1, "table_file must have a header"))??;
+        let header = header_record
+            .iter()
+            .map(|column| {
+                TableHeaderType::deserialize(column.trim().into_deserializer())
+                    .map_err(|_: de::value::Error| fail(1, "unknown column type in table_file"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut tests = vec![];
+        for record in records {
+            let record = record?;
+            let line = record.position().map_or(0, |position| position.line());
+            if record.len() != header.len() {
+                return Err(fail(line, "inconsistent test case size"));
+            }
+            let test = iter::zip(&header, &record)
+                .map(|(column_type, cell)| {
+                    let content = match column_type {
+                        TableHeaderType::Status | TableHeaderType::Weight => cell
+                            .trim()
+                            .parse()
+                            .map(TableCellContent::Int)
+                            .map_err(|_| fail(line, "expected an integer in the table cell"))?,
+                        _ => TableCellContent::String(cell.to_string()),
+                    };
+                    if !column_type.is_compatible_with(&content) {
+                        return Err(fail(line, "inconsistent type from table test content cell"));
+                    }
+                    Ok(content)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            tests.push(test);
+        }
+        if tests.is_empty() {
+            return Err(fail(1, "table_file must have at least one test"));
+        }
+        // only the header may be invalid at this point
+        Table::build(header, tests).map_err(|err| fail(1, err))
+    }
+
     #[cfg(test)]
     fn new_dummy() -> Self {
         Self {
@@ -228,6 +502,32 @@ impl Serialize for Table {
     }
 }
 
+/// Described as it is serialized: an array whose first element is the header and the
+/// others are the tests, with at least one of them.
+impl JsonSchema for Table {
+    fn schema_name() -> Cow<'static, str> {
+        "Table".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A header followed by the tests, e.g. [[\"args\", \"stdout\"], [\"a b\", \"a b\\n\"]].",
+            "type": "array",
+            "prefixItems": [{
+                "type": "array",
+                "items": generator.subschema_for::<TableHeaderType>(),
+                "minItems": 1,
+                "uniqueItems": true,
+            }],
+            "items": {
+                "type": "array",
+                "items": generator.subschema_for::<TableCellContent>(),
+            },
+            "minItems": 2,
+        })
+    }
+}
+
 struct TableVisitor;
 
 impl<'de> Visitor<'de> for TableVisitor {
@@ -267,35 +567,367 @@ impl<'de> Deserialize<'de> for Table {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InteractionStep {
+    /// Text that must appear in the stdout before `send` is written.
+    wait_for: String,
+    /// Text written to the stdin. A trailing newline is usually needed.
+    send: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct InteractionScriptUnchecked {
+    step_timeout_ms: Option<u64>,
+    kill_grace_ms: Option<u64>,
+    steps: Vec<InteractionStep>,
+}
+
+/// Conversation with a program that prompts for its input, e.g.:
+/// `{ "steps": [{ "wait_for": "name: ", "send": "Ana\n" }] }`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(try_from = "InteractionScriptUnchecked")]
+pub struct InteractionScript {
+    /// Maximum amount of milliseconds to wait for each prompt. Default: 1000.
+    step_timeout_ms: Option<u64>,
+    /// When a prompt is not seen in time, the program is sent SIGTERM and, if it did not
+    /// exit after this amount of milliseconds, SIGKILL. Default: 0, which kills it right
+    /// away. Outside of Unix, the program is always killed right away.
+    kill_grace_ms: Option<u64>,
+    steps: Vec<InteractionStep>,
+}
+
+impl InteractionScript {
+    pub fn build(
+        step_timeout_ms: Option<u64>,
+        kill_grace_ms: Option<u64>,
+        steps: Vec<InteractionStep>,
+    ) -> Result<Self, &'static str> {
+        if step_timeout_ms == Some(0) {
+            return Err("step_timeout_ms must be greater than 0");
+        }
+        if steps.is_empty() {
+            return Err("an interaction must have at least one step");
+        }
+        Ok(Self {
+            step_timeout_ms,
+            kill_grace_ms,
+            steps,
+        })
+    }
+
+    fn build_grading_interaction_script(&self) -> Result<GradingInteractionScript, &'static str> {
+        GradingInteractionScript::build(
+            self.steps
+                .iter()
+                .map(|step| GradingInteractionStep::new(step.wait_for.clone(), step.send.clone()))
+                .collect(),
+            Duration::from_millis(
+                self.step_timeout_ms
+                    .unwrap_or(DEFAULT_INTERACTION_STEP_TIMEOUT_MS),
+            ),
+        )
+        .map(|script| {
+            script.with_kill_grace(Duration::from_millis(self.kill_grace_ms.unwrap_or(0)))
+        })
+    }
+
+    #[cfg(test)]
+    fn new_dummy(n: u32) -> Self {
+        Self {
+            step_timeout_ms: Some(100 * n as u64),
+            kill_grace_ms: Some(10 * n as u64),
+            steps: vec![InteractionStep {
+                wait_for: format!("prompt {n}: "),
+                send: format!("answer {n}\n"),
+            }],
+        }
+    }
+}
+
+impl TryFrom<InteractionScriptUnchecked> for InteractionScript {
+    type Error = &'static str;
+
+    fn try_from(value: InteractionScriptUnchecked) -> Result<Self, Self::Error> {
+        let InteractionScriptUnchecked {
+            step_timeout_ms,
+            kill_grace_ms,
+            steps,
+        } = value;
+        InteractionScript::build(step_timeout_ms, kill_grace_ms, steps)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct StdoutExtractUnchecked {
+    pattern: String,
+    min: f64,
+}
+
+/// Number captured from the stdout by the first capture group of `pattern` that must be at
+/// least `min`, e.g.: `{ "pattern": "accuracy: (\\S+)", "min": 0.9 }`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(try_from = "StdoutExtractUnchecked")]
+pub struct StdoutExtract {
+    pattern: String,
+    min: f64,
+}
+
+impl StdoutExtract {
+    pub fn build(pattern: String, min: f64) -> Result<Self, &'static str> {
+        // validated as early as possible, although it is compiled again when building
+        GradingStdoutExtract::build(&pattern, min)?;
+        Ok(Self { pattern, min })
+    }
+
+    fn build_grading_stdout_extract(&self) -> Result<GradingStdoutExtract, &'static str> {
+        GradingStdoutExtract::build(&self.pattern, self.min)
+    }
+}
+
+impl TryFrom<StdoutExtractUnchecked> for StdoutExtract {
+    type Error = &'static str;
+
+    fn try_from(value: StdoutExtractUnchecked) -> Result<Self, Self::Error> {
+        let StdoutExtractUnchecked { pattern, min } = value;
+        StdoutExtract::build(pattern, min)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct StdoutRegexChecksUnchecked {
+    pattern: String,
+    checks: Vec<GroupCheck>,
+}
+
+/// Pattern that the stdout must match, and relations between the groups of its first
+/// match, each one referred to by its name or index. The values are compared as numbers
+/// when both are numbers, and as text otherwise, e.g.:
+/// `{ "pattern": "(?<a>\\d+) \\+ (?<b>\\d+) = (?<sum>\\d+)", "checks": [{ "left": "a", "op": "le", "right": "sum" }] }`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(try_from = "StdoutRegexChecksUnchecked")]
+pub struct StdoutRegexChecks {
+    pattern: String,
+    checks: Vec<GroupCheck>,
+}
+
+impl StdoutRegexChecks {
+    pub fn build(pattern: String, checks: Vec<GroupCheck>) -> Result<Self, &'static str> {
+        // validated as early as possible, although it is compiled again when building
+        GradingStdoutRegexChecks::build(&pattern, checks.clone())?;
+        Ok(Self { pattern, checks })
+    }
+
+    fn build_grading_stdout_regex_checks(&self) -> Result<GradingStdoutRegexChecks, &'static str> {
+        GradingStdoutRegexChecks::build(&self.pattern, self.checks.clone())
+    }
+}
+
+impl TryFrom<StdoutRegexChecksUnchecked> for StdoutRegexChecks {
+    type Error = &'static str;
+
+    fn try_from(value: StdoutRegexChecksUnchecked) -> Result<Self, Self::Error> {
+        let StdoutRegexChecksUnchecked { pattern, checks } = value;
+        StdoutRegexChecks::build(pattern, checks)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct DetailedTestUnchecked {
     name: Option<String>,
     // input
     args: Option<String>,
     stdin: Option<String>,
+    stdin_base64: Option<String>,
+    stdin_hex: Option<String>,
+    cwd: Option<String>,
+    #[serde(default)]
+    setup: Vec<Command>,
+    #[serde(default)]
+    teardown: Vec<Command>,
     // expect
     stdout: Option<String>,
+    #[serde(default)]
+    stdout_any_of: Vec<String>,
     stderr: Option<String>,
-    status: Option<i32>,
+    status: Option<ExpectedStatus>,
+    stdout_not_contains: Option<String>,
+    stderr_not_contains: Option<String>,
+    stdout_line_count: Option<usize>,
+    stdout_exact_bytes: Option<usize>,
+    stdout_max_bytes: Option<usize>,
+    stdout_non_empty: Option<bool>,
+    stderr_empty: Option<bool>,
+    #[serde(default)]
+    stdout_equals_stderr: bool,
+    stdout_extract: Option<StdoutExtract>,
+    stdout_regex_checks: Option<StdoutRegexChecks>,
+    interaction: Option<InteractionScript>,
+    #[serde(default)]
+    strip_ansi: bool,
+    normalize_with: Option<Command>,
+    #[serde(default)]
+    capture: CaptureMode,
+    stdout_match: Option<StdoutMatch>,
     // grading
+    #[serde(default)]
+    tags: Vec<String>,
+    hint: Option<String>,
     weight: Option<u32>,
+    #[serde(default)]
+    skip: bool,
+}
+
+/// Args strings with which the detailed tests of a unit test are run, either listed, e.g.
+/// `["a.txt", "b.txt --strict"]`, or read from a file with one of them per line, e.g.
+/// `{"file": "inputs.txt"}`, where the blank lines are ignored. The file is relative to
+/// the directory of the configuration and read when the tests are built.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum ArgsMatrix {
+    Entries(Vec<String>),
+    File { file: String },
+}
+
+impl ArgsMatrix {
+    fn check(&self) -> Result<(), &'static str> {
+        match self {
+            ArgsMatrix::Entries(entries) => check_args_matrix_entries(entries),
+            ArgsMatrix::File { file } if file.trim().is_empty() => {
+                Err("missing args_matrix file path")
+            }
+            ArgsMatrix::File { .. } => Ok(()),
+        }
+    }
+
+    fn entries(&self, base_dir: &Path) -> Result<Vec<String>, &'static str> {
+        let file = match self {
+            ArgsMatrix::Entries(entries) => return Ok(entries.clone()),
+            ArgsMatrix::File { file } => file,
+        };
+        let path = base_dir.join(file);
+        let content = fs::read_to_string(&path).map_err(|err| {
+            log::error!(
+                "error while reading the args_matrix file: {}",
+                path.display()
+            );
+            log::debug!("error: {err:?}");
+            "could not read args_matrix file"
+        })?;
+        let entries: Vec<String> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        check_args_matrix_entries(&entries).inspect_err(|err| {
+            log::error!("{}: {err}", path.display());
+        })?;
+        Ok(entries)
+    }
+}
+
+/// The entries name the assertions, so they must be unique.
+fn check_args_matrix_entries(entries: &[String]) -> Result<(), &'static str> {
+    if entries.is_empty() {
+        return Err("args_matrix must have at least one entry");
+    }
+    let mut seen = HashSet::with_capacity(entries.len());
+    if !entries.iter().all(|entry| seen.insert(entry)) {
+        return Err("duplicated args_matrix entry");
+    }
+    Ok(())
 }
 
 // Reference: https://users.rust-lang.org/t/struct-members-validation-on-serde-json-deserialize/123201/16
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(try_from = "DetailedTestUnchecked")]
 pub struct DetailedTest {
     name: Option<String>,
     // input
     args: Option<String>,
     stdin: Option<String>,
+    /// Raw bytes written to the stdin, encoded in base64. It allows inputs that are not
+    /// valid UTF-8 or that hold control characters.
+    stdin_base64: Option<String>,
+    /// Raw bytes written to the stdin, encoded in hexadecimal, e.g. `00ff0a`.
+    stdin_hex: Option<String>,
+    /// Subdirectory of the temporary directory where the program is launched, e.g.
+    /// `src`. It must exist once the files are created and the setup is executed.
+    cwd: Option<String>,
+    /// Commands executed in the temporary directory after the `setup` of the unit tests,
+    /// e.g. to create a fixture that only this test needs. If one of them fails, this
+    /// test fails without running the program, but the others are not affected.
+    setup: Vec<Command>,
+    /// Commands executed in the temporary directory after the program, before the
+    /// `teardown` of the unit tests. Their failures are only logged.
+    teardown: Vec<Command>,
     // expect
     stdout: Option<String>,
+    /// The test passes if the stdout is exactly one of these outputs. It is checked
+    /// independently of `stdout`.
+    stdout_any_of: Vec<String>,
     stderr: Option<String>,
-    status: Option<i32>,
+    /// Exit code, or `"zero"` or `"nonzero"` to only require a success or any failure.
+    status: Option<ExpectedStatus>,
+    /// The test fails if this substring appears in the stdout.
+    stdout_not_contains: Option<String>,
+    /// The test fails if this substring appears in the stderr.
+    stderr_not_contains: Option<String>,
+    /// Number of lines that the stdout must have, regardless of their content.
+    stdout_line_count: Option<usize>,
+    /// Number of bytes that the stdout must have, regardless of their content.
+    stdout_exact_bytes: Option<usize>,
+    /// Maximum number of bytes that the stdout may have, e.g. for a compressor.
+    stdout_max_bytes: Option<usize>,
+    /// Whether the stdout must have some content (`true`) or be empty (`false`).
+    stdout_non_empty: Option<bool>,
+    /// Whether the stderr must be empty (`true`), e.g. for a valid input, or have some
+    /// content (`false`), e.g. when the program must complain about an invalid input.
+    stderr_empty: Option<bool>,
+    /// Whether the stdout and the stderr must be equal, e.g. for a program that echoes its
+    /// messages to both. The report shows both of them when they differ.
+    stdout_equals_stderr: bool,
+    /// Number reported by the program in its stdout, e.g. its accuracy, that must reach a
+    /// minimum.
+    stdout_extract: Option<StdoutExtract>,
+    /// Pattern that the stdout must match, with checks between its groups, e.g. that two
+    /// printed totals are equal.
+    stdout_regex_checks: Option<StdoutRegexChecks>,
+    /// Answers the prompts of the program. It can not be used together with `stdin`,
+    /// `stdin_base64` nor `stdin_hex`.
+    interaction: Option<InteractionScript>,
+    /// Whether the ANSI escape codes, e.g. the colors of a program that assumes a
+    /// terminal, are ignored when comparing the stdout and the stderr.
+    strip_ansi: bool,
+    /// Command, e.g. `sort` or `jq -S .`, through which both the stdout and the expected
+    /// `stdout` are piped before comparing them. It runs in the directory of the program,
+    /// with the same `env` and `inherit_parent_env`, and a failure of it only fails this
+    /// assertion, whose execution status becomes `FailureInNormalization`.
+    normalize_with: Option<Command>,
+    /// Outputs that may be captured: `"auto"` (the default) captures the ones that the
+    /// expectations need, while `"none"`, `"stdout_only"` and `"stderr_only"` discard the
+    /// others, e.g. the large stdout of a program only graded by its status.
+    capture: CaptureMode,
+    /// How the stdout is compared against `stdout` and `stdout_any_of`: `"exact"`,
+    /// `"trim_line_ends"`, which ignores the whitespace at the end of every line,
+    /// `"trim_end"`, which ignores the whitespace at the end of the output, or
+    /// `"collapse_whitespace"`, which only compares the words. It defaults to the
+    /// `comparison_policy` of the grading section. The report still shows the stdout as it
+    /// was printed.
+    stdout_match: Option<StdoutMatch>,
     // grading
+    /// Labels used to group and filter the assertions (e.g. "edge-cases").
+    tags: Vec<String>,
+    /// Feedback shown in the report only when the assertion fails, e.g. "remember to
+    /// handle empty input". It does not affect the score.
+    hint: Option<String>,
     weight: Option<u32>,
+    /// Disabled test: it is not executed nor scored, only reported as skipped.
+    skip: bool,
 }
 
 impl DetailedTest {
@@ -311,50 +943,322 @@ impl DetailedTest {
         // grading
         weight: Option<u32>,
     ) -> Result<Self, &'static str> {
-        if stdout.is_none() && stderr.is_none() && status.is_none() {
-            return Err("at least one of {stdout, stderr, status} must be non-null");
-        }
-        Ok(Self {
+        Self {
             name,
             args,
             stdin,
+            stdin_base64: None,
+            stdin_hex: None,
+            cwd: None,
+            setup: vec![],
+            teardown: vec![],
             stdout,
+            stdout_any_of: vec![],
             stderr,
-            status,
+            status: status.map(ExpectedStatus::Exact),
+            stdout_not_contains: None,
+            stderr_not_contains: None,
+            stdout_line_count: None,
+            stdout_exact_bytes: None,
+            stdout_max_bytes: None,
+            stdout_non_empty: None,
+            stderr_empty: None,
+            stdout_equals_stderr: false,
+            stdout_extract: None,
+            stdout_regex_checks: None,
+            interaction: None,
+            strip_ansi: false,
+            normalize_with: None,
+            capture: CaptureMode::Auto,
+            stdout_match: None,
+            tags: vec![],
+            hint: None,
             weight,
-        })
+            skip: false,
+        }
+        .check()
+    }
+
+    /// Validates that there is at least one expectation, that the stdin is given at most
+    /// once and in a valid encoding and that the working directory does not leave the
+    /// temporary directory.
+    fn check(self) -> Result<Self, &'static str> {
+        if self.stdout.is_none()
+            && self.stdout_any_of.is_empty()
+            && self.stderr.is_none()
+            && self.status.is_none()
+            && self.stdout_not_contains.is_none()
+            && self.stderr_not_contains.is_none()
+            && self.stdout_line_count.is_none()
+            && self.stdout_exact_bytes.is_none()
+            && self.stdout_max_bytes.is_none()
+            && self.stdout_non_empty.is_none()
+            && self.stderr_empty.is_none()
+            && !self.stdout_equals_stderr
+            && self.stdout_extract.is_none()
+            && self.stdout_regex_checks.is_none()
+            && self.interaction.is_none()
+        {
+            return Err(
+                "at least one of {stdout, stdout_any_of, stderr, status, stdout_not_contains, stderr_not_contains, stdout_line_count, stdout_exact_bytes, stdout_max_bytes, stdout_non_empty, stderr_empty, stdout_equals_stderr, stdout_extract, stdout_regex_checks, interaction} must be non-null",
+            );
+        }
+        let stdin_count = [&self.stdin, &self.stdin_base64, &self.stdin_hex]
+            .iter()
+            .filter(|stdin| stdin.is_some())
+            .count();
+        if stdin_count > 1 {
+            return Err("only one of stdin, stdin_base64 and stdin_hex can be used");
+        }
+        if stdin_count > 0 && self.interaction.is_some() {
+            return Err("stdin and interaction can not be used together");
+        }
+        self.stdin_bytes()?;
+        if self
+            .cwd
+            .as_ref()
+            .is_some_and(|cwd| !is_inner_path(Path::new(cwd)))
+        {
+            return Err("cwd must be a relative path inside the temporary directory");
+        }
+        if self.normalize_with.is_some() && self.stdout.is_none() {
+            return Err("normalize_with requires stdout");
+        }
+        if self
+            .stdout_match
+            .is_some_and(|stdout_match| stdout_match != StdoutMatch::Exact)
+            && self.stdout.is_none()
+            && self.stdout_any_of.is_empty()
+        {
+            return Err("stdout_match requires stdout or stdout_any_of");
+        }
+        let expects_stdout = self.stdout.is_some()
+            || !self.stdout_any_of.is_empty()
+            || self.stdout_not_contains.is_some()
+            || self.stdout_line_count.is_some()
+            || self.stdout_exact_bytes.is_some()
+            || self.stdout_max_bytes.is_some()
+            || self.stdout_non_empty.is_some()
+            || self.stdout_extract.is_some()
+            || self.stdout_regex_checks.is_some()
+            || self.interaction.is_some()
+            || self.stdout_equals_stderr;
+        if expects_stdout && !self.capture.captures_stdout() {
+            return Err("the stdout has expectations, but capture discards it");
+        }
+        let expects_stderr = self.stderr.is_some()
+            || self.stderr_not_contains.is_some()
+            || self.stderr_empty.is_some()
+            || self.stdout_equals_stderr;
+        if expects_stderr && !self.capture.captures_stderr() {
+            return Err("the stderr has expectations, but capture discards it");
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err("tags must not be blank");
+        }
+        if self
+            .hint
+            .as_ref()
+            .is_some_and(|hint| hint.trim().is_empty())
+        {
+            return Err("hint must not be blank");
+        }
+        Ok(self)
+    }
+
+    #[cfg(test)]
+    fn with_stdout_not_contains(mut self, forbidden: String) -> Self {
+        self.stdout_not_contains = Some(forbidden);
+        self
+    }
+
+    /// Bytes written to the stdin, decoded from whichever of `stdin`, `stdin_base64` and
+    /// `stdin_hex` is given.
+    fn stdin_bytes(&self) -> Result<Option<Vec<u8>>, &'static str> {
+        if let Some(ref stdin) = self.stdin {
+            return Ok(Some(stdin.clone().into_bytes()));
+        }
+        if let Some(ref encoded) = self.stdin_base64 {
+            return BASE64_STANDARD
+                .decode(encoded.trim())
+                .map(Some)
+                .map_err(|_| "invalid base64 in stdin_base64");
+        }
+        if let Some(ref encoded) = self.stdin_hex {
+            return decode_hex(encoded).map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Name of the `n`-th assertion, which is its name or `Assertion <n>` if it has none.
+    fn get_name(&self, n: usize) -> String {
+        self.name.clone().unwrap_or(format!("Assertion {n}"))
+    }
+
+    /// Copy of the `n`-th test run with `entry` of the args matrix after its own args,
+    /// named after both of them, e.g. `Assertion 1 [a.txt]`.
+    fn with_matrix_args(&self, n: usize, entry: &str) -> Self {
+        Self {
+            name: Some(format!("{} [{entry}]", self.get_name(n))),
+            args: Some(match self.args {
+                Some(ref args) => format!("{args} {entry}"),
+                None => entry.to_string(),
+            }),
+            ..self.clone()
+        }
     }
 
-    fn build_grading_assertion(&self, n: usize) -> Result<UnitTestAssertion, &'static str> {
+    /// The `{{name}}` placeholders of the args, the stdin, the stdout and the stderr are
+    /// replaced with the values of `vars`, see `expand_vars`. Then, the `{name}`
+    /// placeholders of the args are replaced with the names of `file_names`, see
+    /// `expand_file_placeholders`. The `setup` and `teardown` are run through the shell
+    /// when `use_shell`, like the ones of the unit tests.
+    fn build_grading_assertion(
+        &self,
+        n: usize,
+        default_weight: u32,
+        file_names: &[&str],
+        vars: &HashMap<String, String>,
+        use_shell: bool,
+        comparison_policy: StdoutMatch,
+    ) -> Result<UnitTestAssertion, &'static str> {
         let DetailedTest {
-            name,
+            name: _,
             args: args_string,
             stdin,
+            stdin_base64: _,
+            stdin_hex: _,
+            cwd,
+            setup,
+            teardown,
             stdout,
+            stdout_any_of,
             stderr,
             status,
+            stdout_not_contains,
+            stderr_not_contains,
+            stdout_line_count,
+            stdout_exact_bytes,
+            stdout_max_bytes,
+            stdout_non_empty,
+            stderr_empty,
+            stdout_equals_stderr,
+            stdout_extract,
+            stdout_regex_checks,
+            interaction,
+            strip_ansi,
+            normalize_with,
+            capture,
+            stdout_match,
+            tags,
+            hint,
             weight,
+            skip: _,
         } = self;
         let mut args = vec![];
 
         if let Some(args_string) = args_string {
+            let args_string = expand_vars(args_string, vars)?;
             let mut lex = Shlex::new(args_string.as_str());
             for arg in lex.by_ref() {
-                args.push(arg);
+                args.push(expand_file_placeholders(&arg, file_names)?);
             }
             if lex.had_error {
                 return Err("invalid args string");
             }
         }
-        UnitTestAssertion::build(
-            name.clone().unwrap_or(format!("Assertion {n}")),
+        let mut assertion = UnitTestAssertion::new(
+            self.get_name(n),
             args,
-            stdin.clone(),
-            stdout.clone(),
-            stderr.clone(),
-            *status,
-            weight.unwrap_or(1),
-        )
+            None,
+            weight.unwrap_or(default_weight),
+        );
+        let stdin = match stdin {
+            Some(stdin) => Some(expand_vars(stdin, vars)?.into_bytes()),
+            None => self.stdin_bytes()?,
+        };
+        if let Some(stdin) = stdin {
+            assertion = assertion.with_stdin_bytes(stdin);
+        }
+        if let Some(cwd) = cwd {
+            assertion = assertion.with_cwd(cwd.into());
+        }
+        let process_commands = if use_shell {
+            process_shell_commands
+        } else {
+            process_raw_string_commands
+        };
+        if !setup.is_empty() {
+            assertion = assertion.with_setup(process_commands(setup)?);
+        }
+        if !teardown.is_empty() {
+            assertion = assertion.with_teardown(process_commands(teardown)?);
+        }
+        if let Some(normalizer) = normalize_with {
+            let mut normalizer = process_commands(std::slice::from_ref(normalizer))?;
+            assertion = assertion.with_normalize_with(normalizer.remove(0));
+        }
+        if let Some(stdout) = stdout {
+            assertion = assertion.with_stdout(expand_vars(stdout, vars)?);
+        }
+        if !stdout_any_of.is_empty() {
+            assertion = assertion.with_stdout_any_of(stdout_any_of.clone());
+        }
+        if let Some(stderr) = stderr {
+            assertion = assertion.with_stderr(expand_vars(stderr, vars)?);
+        }
+        if let Some(status) = status {
+            assertion = assertion.with_expected_status(*status);
+        }
+        if let Some(forbidden) = stdout_not_contains {
+            assertion = assertion.with_stdout_not_contains(forbidden.clone());
+        }
+        if let Some(forbidden) = stderr_not_contains {
+            assertion = assertion.with_stderr_not_contains(forbidden.clone());
+        }
+        if let Some(line_count) = stdout_line_count {
+            assertion = assertion.with_stdout_line_count(*line_count);
+        }
+        if let Some(bytes) = stdout_exact_bytes {
+            assertion = assertion.with_stdout_exact_bytes(*bytes);
+        }
+        if let Some(max_bytes) = stdout_max_bytes {
+            assertion = assertion.with_stdout_max_bytes(*max_bytes);
+        }
+        if let Some(non_empty) = stdout_non_empty {
+            assertion = assertion.with_stdout_non_empty(*non_empty);
+        }
+        if let Some(empty) = stderr_empty {
+            assertion = assertion.with_stderr_empty(*empty);
+        }
+        if *stdout_equals_stderr {
+            assertion = assertion.with_stdout_equals_stderr(true);
+        }
+        if let Some(extract) = stdout_extract {
+            assertion = assertion.with_stdout_extract(extract.build_grading_stdout_extract()?);
+        }
+        if let Some(regex_checks) = stdout_regex_checks {
+            assertion = assertion
+                .with_stdout_regex_checks(regex_checks.build_grading_stdout_regex_checks()?);
+        }
+        if let Some(interaction) = interaction {
+            assertion = assertion.with_interaction(interaction.build_grading_interaction_script()?);
+        }
+        if let Some(hint) = hint {
+            assertion = assertion.with_hint(hint.clone());
+        }
+        // the policy only applies to the assertions that expect an stdout
+        let stdout_match = match stdout_match {
+            Some(stdout_match) => *stdout_match,
+            None if stdout.is_some() || !stdout_any_of.is_empty() => comparison_policy,
+            None => StdoutMatch::Exact,
+        };
+        assertion
+            .with_strip_ansi(*strip_ansi)
+            .with_capture(*capture)
+            .with_stdout_match(stdout_match)
+            .with_tags(tags.clone())
+            .check()
     }
 
     #[cfg(test)]
@@ -363,10 +1267,34 @@ impl DetailedTest {
             name: Some(format!("test {n}")),
             args: Some("arg1 arg2 arg3".to_string()),
             stdin: Some(format!("in {n}")),
+            stdin_base64: None,
+            stdin_hex: None,
+            cwd: None,
+            setup: vec![],
+            teardown: vec![],
             stdout: Some(format!("out {n}")),
+            stdout_any_of: vec![],
             stderr: Some(format!("err {n}")),
-            status: Some(0),
+            status: Some(ExpectedStatus::Exact(0)),
+            stdout_not_contains: Some(format!("forbidden out {n}")),
+            stderr_not_contains: None,
+            stdout_line_count: None,
+            stdout_exact_bytes: None,
+            stdout_max_bytes: None,
+            stdout_non_empty: None,
+            stderr_empty: None,
+            stdout_equals_stderr: false,
+            stdout_extract: None,
+            stdout_regex_checks: None,
+            interaction: None,
+            strip_ansi: false,
+            normalize_with: None,
+            capture: CaptureMode::Auto,
+            stdout_match: None,
+            tags: vec![],
+            hint: None,
             weight: Some(n),
+            skip: false,
         }
     }
 }
@@ -379,27 +1307,91 @@ impl TryFrom<DetailedTestUnchecked> for DetailedTest {
             name,
             args,
             stdin,
+            stdin_base64,
+            stdin_hex,
+            cwd,
+            setup,
+            teardown,
             stdout,
+            stdout_any_of,
             stderr,
             status,
+            stdout_not_contains,
+            stderr_not_contains,
+            stdout_line_count,
+            stdout_exact_bytes,
+            stdout_max_bytes,
+            stdout_non_empty,
+            stderr_empty,
+            stdout_equals_stderr,
+            stdout_extract,
+            stdout_regex_checks,
+            interaction,
+            strip_ansi,
+            normalize_with,
+            capture,
+            stdout_match,
+            tags,
+            hint,
             weight,
+            skip,
         } = value;
 
-        DetailedTest::build(name, args, stdin, stdout, stderr, status, weight)
+        DetailedTest {
+            name,
+            args,
+            stdin,
+            stdin_base64,
+            stdin_hex,
+            cwd,
+            setup,
+            teardown,
+            stdout,
+            stdout_any_of,
+            stderr,
+            status,
+            stdout_not_contains,
+            stderr_not_contains,
+            stdout_line_count,
+            stdout_exact_bytes,
+            stdout_max_bytes,
+            stdout_non_empty,
+            stderr_empty,
+            stdout_equals_stderr,
+            stdout_extract,
+            stdout_regex_checks,
+            interaction,
+            strip_ansi,
+            normalize_with,
+            capture,
+            stdout_match,
+            tags,
+            hint,
+            weight,
+            skip,
+        }
+        .check()
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct UnitTestUnchecked {
     title: Option<String>,
     program_name: Option<String>,
     table: Option<Table>,
+    table_file: Option<String>,
     #[serde(default)]
     detailed_tests: Vec<DetailedTest>,
+    args_matrix: Option<ArgsMatrix>,
+    #[serde(default)]
+    vars: HashMap<String, String>,
+    weight: Option<u32>,
+    #[serde(default)]
+    skip: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(try_from = "UnitTestUnchecked")]
 pub struct UnitTest {
     title: Option<String>,
@@ -409,68 +1401,182 @@ pub struct UnitTest {
     /// not defined in the input scope.
     ///
     /// # Caveats
-    /// - If no program name is specified (None), it will logically default to the main
-    ///   program (`program1` or `p1`).
+    /// - If no program name is specified (None), it will logically default to the
+    ///   `default_program` of the input section, which is the main program (`program1` or
+    ///   `p1`) unless configured otherwise.
     program_name: Option<String>,
     table: Option<Table>,
+    /// CSV file, or TSV if its extension is `tsv`, with the tests of the table, relative to
+    /// the directory of the configuration. Its first line is the header, e.g.
+    /// `name,args,stdout`. It can not be used together with `table`.
+    table_file: Option<String>,
     detailed_tests: Vec<DetailedTest>,
+    /// Args with which each detailed test is run, one assertion per entry, e.g. to check
+    /// that the program exits with 0 for every input file. See `ArgsMatrix`.
+    args_matrix: Option<ArgsMatrix>,
+    /// Values of the `{{name}}` placeholders in the args, the stdin, the stdout and the
+    /// stderr of the tests, e.g. `{"n": "42"}` turns `"{{n}}\n"` into `"42\n"`. Undefined
+    /// variables are an error and `\{{` is a literal `{{`. Without variables, nothing is
+    /// replaced.
+    vars: HashMap<String, String>,
+    /// Score of the unit test, split between its assertions in proportion to their
+    /// weights, e.g. a weight of 10 with assertions weighing 1 and 4 gives them 2 and 8
+    /// points. The score reached is rounded down, so the whole weight is only reached when
+    /// every assertion passes. Without it, the unit test is worth the sum of the weights
+    /// of its assertions.
+    weight: Option<u32>,
+    /// Disabled unit test: it is not executed nor scored, only reported as skipped.
+    skip: bool,
 }
 
 impl UnitTest {
+    #[allow(clippy::too_many_arguments)]
     pub fn build(
         title: Option<String>,
         program_name: Option<String>,
         table: Option<Table>,
+        table_file: Option<String>,
         detailed_tests: Vec<DetailedTest>,
+        args_matrix: Option<ArgsMatrix>,
+        vars: HashMap<String, String>,
+        weight: Option<u32>,
+        skip: bool,
     ) -> Result<Self, &'static str> {
-        if table.is_none() && detailed_tests.is_empty() {
+        if table.is_none() && table_file.is_none() && detailed_tests.is_empty() {
             return Err("each UnitTest must have at least one table test or detailed test");
         }
+        if table.is_some() && table_file.is_some() {
+            return Err("table and table_file can not be used together");
+        }
+        if table_file
+            .as_ref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            return Err("missing table_file path");
+        }
+        if vars.keys().any(|name| {
+            name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }) {
+            return Err("invalid variable name in vars");
+        }
+        if weight == Some(0) {
+            return Err("unit test weight must be positive");
+        }
+        if let Some(ref args_matrix) = args_matrix {
+            if detailed_tests.is_empty() {
+                return Err("args_matrix needs detailed tests");
+            }
+            args_matrix.check()?;
+        }
         Ok(Self {
             title,
             program_name,
             table,
+            table_file,
             detailed_tests,
+            args_matrix,
+            vars,
+            weight,
+            skip,
         })
     }
 
+    pub fn get_title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
     pub fn get_program_name(&self) -> Option<&str> {
         self.program_name.as_deref()
     }
 
+    pub fn is_skipped(&self) -> bool {
+        self.skip
+    }
+
+    /// Name of the `n`-th unit test, which is its title or `Unit Test <n>` if it has none.
+    pub fn get_name(&self, n: usize) -> String {
+        self.title.clone().unwrap_or(format!("Unit Test {n}"))
+    }
+
+    /// The unit test runs `default_program` when it has no `program_name`. Its
+    /// `table_file` and the file of its `args_matrix` are read relative to `base_dir`.
+    #[allow(clippy::too_many_arguments)]
     fn build_grading_unit_test(
         &self,
         n: usize,
+        default_weight: u32,
         executables_by_name: &HashMap<String, ExecutableArtifact>,
-    ) -> Result<GradingUnitTest, &'static str> {
+        default_program: &str,
+        file_names: &[&str],
+        base_dir: &Path,
+        use_shell: bool,
+        comparison_policy: StdoutMatch,
+    ) -> Result<GradingUnitTest, String> {
         // try to get the executable
         let executable = executables_by_name
-            .get(
-                self.program_name
-                    .as_ref()
-                    .unwrap_or(&DEFAULT_MAIN_PROGRAM_NAME.to_string()),
-            )
+            .get(self.program_name.as_deref().unwrap_or(default_program))
             .ok_or("executable not found")?;
 
-        let mut unit_test = GradingUnitTest::new(
-            self.title
-                .as_ref()
-                .unwrap_or(&format!("Unit Test {n}"))
-                .clone(),
-            executable.clone(),
-        );
+        let mut unit_test = GradingUnitTest::new(self.get_name(n), executable.clone());
+        if let Some(weight) = self.weight {
+            unit_test = unit_test.with_weight(weight);
+        }
 
         // add assertions
         // table
-        if let Some(table) = &self.table {
-            unit_test.add_assertions(table.build_grading_assertions(1)?);
+        let table_from_file = match self.table_file {
+            Some(ref table_file) => Some(Table::read_from_file(&base_dir.join(table_file))?),
+            None => None,
+        };
+        if let Some(table) = self.table.as_ref().or(table_from_file.as_ref()) {
+            unit_test.add_assertions(table.build_grading_assertions(
+                1,
+                default_weight,
+                file_names,
+                &self.vars,
+                comparison_policy,
+            )?);
         }
 
         // detailed tests
-        let mut n = unit_test.size() + 1;
-        for d in &self.detailed_tests {
-            unit_test.add_assertion(d.build_grading_assertion(n)?);
-            n += 1;
+        let args_matrix = match self.args_matrix {
+            Some(ref args_matrix) => Some(args_matrix.entries(base_dir)?),
+            None => None,
+        };
+        for (n, d) in (unit_test.size() + 1..).zip(&self.detailed_tests) {
+            if d.skip {
+                unit_test.add_skipped_assertion(d.get_name(n));
+            } else if let Some(ref entries) = args_matrix {
+                for entry in entries {
+                    unit_test.add_assertion(d.with_matrix_args(n, entry).build_grading_assertion(
+                        n,
+                        default_weight,
+                        file_names,
+                        &self.vars,
+                        use_shell,
+                        comparison_policy,
+                    )?);
+                }
+            } else {
+                unit_test.add_assertion(d.build_grading_assertion(
+                    n,
+                    default_weight,
+                    file_names,
+                    &self.vars,
+                    use_shell,
+                    comparison_policy,
+                )?);
+            }
+        }
+        if self.weight.is_some()
+            && unit_test
+                .assertions()
+                .iter()
+                .all(|assertion| assertion.weight() == 0)
+        {
+            return Err(
+                "the weight of a unit test can not be split between weightless assertions".into(),
+            );
         }
         Ok(unit_test)
     }
@@ -481,7 +1587,12 @@ impl UnitTest {
             title: Some(format!("test {n}")),
             program_name: Some(format!("program{n}")),
             table: Some(Table::new_dummy()),
+            table_file: None,
             detailed_tests: vec![],
+            args_matrix: None,
+            vars: HashMap::new(),
+            weight: None,
+            skip: false,
         }
     }
 }
@@ -494,10 +1605,25 @@ impl TryFrom<UnitTestUnchecked> for UnitTest {
             title,
             program_name,
             table,
+            table_file,
             detailed_tests,
+            args_matrix,
+            vars,
+            weight,
+            skip,
         } = value;
 
-        UnitTest::build(title, program_name, table, detailed_tests)
+        UnitTest::build(
+            title,
+            program_name,
+            table,
+            table_file,
+            detailed_tests,
+            args_matrix,
+            vars,
+            weight,
+            skip,
+        )
     }
 }
 
@@ -506,51 +1632,134 @@ type Value = String;
 type Command = String;
 type FileContent = String;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct UnitTestsUnchecked {
-    #[serde(default)]
-    env: Vec<(Key, Value)>,
+    env: Option<Vec<(Key, Value)>>,
+    env_file: Option<String>,
     #[serde(default)]
     inherit_parent_env: Option<bool>,
     #[serde(default)]
+    env_passthrough: Vec<Key>,
+    locale: Option<String>,
+    timezone: Option<String>,
+    #[serde(default)]
     files: Vec<(String, FileContent)>,
+    setup: Option<Vec<Command>>,
+    teardown: Option<Vec<Command>>,
+    use_shell: Option<bool>,
     #[serde(default)]
-    setup: Vec<Command>,
+    stateful: bool,
     #[serde(default)]
-    teardown: Vec<Command>,
+    informational_stderr: bool,
+    default_weight: Option<u32>,
     tests: Vec<UnitTest>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(try_from = "UnitTestsUnchecked")]
 pub struct UnitTests {
-    env: Vec<(Key, Value)>,
-    inherit_parent_env: bool,
+    /// `env`, `inherit_parent_env`, `locale`, `timezone`, `setup` and `teardown` are taken
+    /// from the `defaults` of the configuration when they are `None`.
+    env: Option<Vec<(Key, Value)>>,
+    /// Dotenv-style file (`KEY=VALUE` per line) with more environment variables, relative
+    /// to the directory of the configuration. `env` wins when both define a variable.
+    env_file: Option<String>,
+    inherit_parent_env: Option<bool>,
+    /// Environment variables copied from the parent process when `inherit_parent_env` is
+    /// `false`, e.g. `HOME`. It gives a minimal and reproducible environment. `PATH` is
+    /// kept anyway, unless `env` replaces it, so the programs can still be found.
+    env_passthrough: Vec<Key>,
+    /// Locale, e.g. `C.UTF-8`, set as `LC_ALL` and `LANG` for the programs, the `setup`
+    /// and the `teardown`, so the formatting of dates and numbers does not depend on the
+    /// machine. It takes precedence over `env`. By default, it is left unset.
+    locale: Option<String>,
+    /// Timezone, e.g. `UTC`, set as `TZ` like `locale`.
+    timezone: Option<String>,
     files: Vec<(String, FileContent)>,
-    setup: Vec<Command>,
-    teardown: Vec<Command>,
+    setup: Option<Vec<Command>>,
+    teardown: Option<Vec<Command>>,
+    /// Whether `setup` and `teardown` are run through `sh -c` instead of being split
+    /// and executed directly.
+    ///
+    /// # Security
+    /// The commands get the full power of the shell (expansions, substitutions, etc.),
+    /// so configurations from untrusted sources must not enable it.
+    use_shell: bool,
+    /// Whether the tests share a single working directory, so the later ones can use what
+    /// the earlier ones created, e.g. build, then run, then clean. `files` and `setup` are
+    /// then prepared once before the first test and `teardown` runs once after the last
+    /// one. The tests run one at a time in order, so `max_parallelism` and the shuffling
+    /// do not apply to them.
+    stateful: bool,
+    /// Whether the stderr of the programs is only captured to be shown in the report, e.g.
+    /// for warnings that are not graded. The `stderr`, `stderr_not_contains` and
+    /// `stderr_empty` of the tests are then ignored.
+    informational_stderr: bool,
+    /// Weight of the tests that do not define their own.
+    default_weight: u32,
     tests: Vec<UnitTest>,
 }
 
 impl UnitTests {
+    #[allow(clippy::too_many_arguments)]
     pub fn build(
-        env: Vec<(Key, Value)>,
-        inherit_parent_env: bool,
+        env: Option<Vec<(Key, Value)>>,
+        env_file: Option<String>,
+        inherit_parent_env: Option<bool>,
+        env_passthrough: Vec<Key>,
+        locale: Option<String>,
+        timezone: Option<String>,
         files: Vec<(String, FileContent)>,
-        setup: Vec<Command>,
-        teardown: Vec<Command>,
+        setup: Option<Vec<Command>>,
+        teardown: Option<Vec<Command>>,
+        use_shell: bool,
+        stateful: bool,
+        informational_stderr: bool,
+        default_weight: u32,
         tests: Vec<UnitTest>,
     ) -> Result<Self, &'static str> {
         if tests.is_empty() {
             return Err("must contain at least one test");
         }
+        if !env_passthrough.is_empty() && inherit_parent_env == Some(true) {
+            return Err("env_passthrough requires inherit_parent_env to be false");
+        }
+        if env_passthrough
+            .iter()
+            .any(|name| name.trim().is_empty() || name.contains('='))
+        {
+            return Err("invalid environment variable name in env_passthrough");
+        }
+        if env_file.as_ref().is_some_and(|path| path.trim().is_empty()) {
+            return Err("missing env_file path");
+        }
+        if locale
+            .as_ref()
+            .is_some_and(|locale| locale.trim().is_empty())
+        {
+            return Err("locale can not be empty");
+        }
+        if timezone
+            .as_ref()
+            .is_some_and(|timezone| timezone.trim().is_empty())
+        {
+            return Err("timezone can not be empty");
+        }
         Ok(Self {
             env,
+            env_file,
             inherit_parent_env,
+            env_passthrough,
+            locale,
+            timezone,
             files,
             setup,
             teardown,
+            use_shell,
+            stateful,
+            informational_stderr,
+            default_weight,
             tests,
         })
     }
@@ -558,62 +1767,112 @@ impl UnitTests {
         &self.tests
     }
 
+    /// Environment variables of `env_file`, resolved relative to `base_dir`, followed by
+    /// the ones of `env` (or the default ones), which take precedence over the former.
+    fn build_env(
+        &self,
+        defaults: &DefaultsSection,
+        base_dir: &Path,
+    ) -> Result<Vec<(Key, Value)>, &'static str> {
+        let inline_env = self.env.as_deref().unwrap_or(defaults.get_env());
+        let Some(ref env_file) = self.env_file else {
+            return Ok(inline_env.to_vec());
+        };
+        let content =
+            fs::read_to_string(base_dir.join(env_file)).map_err(|_| "could not read env_file")?;
+        let mut env: Vec<(Key, Value)> = parse_env_file(&content)?
+            .into_iter()
+            .filter(|(key, _)| !inline_env.iter().any(|(inline_key, _)| inline_key == key))
+            .collect();
+        env.extend(inline_env.iter().cloned());
+        Ok(env)
+    }
+
     pub fn build_grading_unit_tests(
         &self,
         executables_by_name: &HashMap<String, ExecutableArtifact>,
-    ) -> Result<GradingUnitTests, &'static str> {
-        let mut unit_tests = vec![];
-
-        /// Process a slice of raw string commands (String) into a vector of commands in
-        /// the format: (Command, Vec<Arg>).
-        ///
-        /// # Example
-        /// Transforms the raw string command: `"cmd1 arg1 arg2 arg3"` into
-        /// ("cmd1", ["arg1", "arg2", "arg3"])
-        fn process_raw_string_commands(
-            commands: &[String],
-        ) -> Result<Vec<(String, Vec<String>)>, &'static str> {
-            let mut processed_commands = vec![];
-            for command in commands {
-                let mut lex = Shlex::new(command.as_str());
-                let command_name = match lex.next() {
-                    Some(c) => c,
-                    None => return Err("missing command"),
-                };
-                let mut processed_command = (command_name, vec![]);
-                for arg in lex.by_ref() {
-                    processed_command.1.push(arg);
-                }
-                if lex.had_error {
-                    return Err("invalid args string");
-                }
-                processed_commands.push(processed_command);
-            }
-            Ok(processed_commands)
+        default_program: &str,
+        defaults: &DefaultsSection,
+        base_dir: &Path,
+        comparison_policy: StdoutMatch,
+    ) -> Result<GradingUnitTests, String> {
+        let inherit_parent_env = self
+            .inherit_parent_env
+            .unwrap_or(defaults.inherits_parent_env());
+        if !self.env_passthrough.is_empty() && inherit_parent_env {
+            return Err("env_passthrough requires inherit_parent_env to be false".into());
         }
+        let mut unit_tests = vec![];
+        let mut skipped_unit_tests = vec![];
+        let file_names: Vec<&str> = self.files.iter().map(|(name, _)| name.as_str()).collect();
 
         // add unit tests
         for (i, t) in self.tests.iter().enumerate() {
-            unit_tests.push(t.build_grading_unit_test(i + 1, executables_by_name)?);
+            if t.skip {
+                skipped_unit_tests.push(t.get_name(i + 1));
+                continue;
+            }
+            for d in &t.detailed_tests {
+                defaults.check_commands(&d.setup)?;
+                defaults.check_commands(&d.teardown)?;
+            }
+            unit_tests.push(t.build_grading_unit_test(
+                i + 1,
+                self.default_weight,
+                executables_by_name,
+                default_program,
+                &file_names,
+                base_dir,
+                self.use_shell,
+                comparison_policy,
+            )?);
         }
-        Ok(GradingUnitTests::new(
-            self.env.clone(),
-            self.inherit_parent_env,
+        let setup = self.setup.as_deref().unwrap_or(defaults.get_setup());
+        let teardown = self.teardown.as_deref().unwrap_or(defaults.get_teardown());
+        defaults.check_commands(setup)?;
+        defaults.check_commands(teardown)?;
+        let process_commands = if self.use_shell {
+            process_shell_commands
+        } else {
+            process_raw_string_commands
+        };
+        let mut grading_unit_tests = GradingUnitTests::new(
+            self.build_env(defaults, base_dir)?,
+            inherit_parent_env,
             self.files.clone(),
-            process_raw_string_commands(&self.setup)?,
-            process_raw_string_commands(&self.teardown)?,
+            process_commands(setup)?,
+            process_commands(teardown)?,
             unit_tests,
-        ))
+        )
+        .with_env_passthrough(self.env_passthrough.clone())
+        .with_stateful(self.stateful)
+        .with_informational_stderr(self.informational_stderr)
+        .with_skipped_unit_tests(skipped_unit_tests);
+        if let Some(locale) = self.locale.as_deref().or(defaults.get_locale()) {
+            grading_unit_tests = grading_unit_tests.with_locale(locale.to_string());
+        }
+        if let Some(timezone) = self.timezone.as_deref().or(defaults.get_timezone()) {
+            grading_unit_tests = grading_unit_tests.with_timezone(timezone.to_string());
+        }
+        Ok(grading_unit_tests)
     }
 
     #[cfg(test)]
     pub fn new_dummy() -> Self {
         Self {
-            env: vec![("k1".to_string(), "v1".to_string())],
-            inherit_parent_env: true,
+            env: Some(vec![("k1".to_string(), "v1".to_string())]),
+            env_file: None,
+            inherit_parent_env: Some(true),
+            env_passthrough: vec![],
+            locale: None,
+            timezone: None,
             files: vec![("file1.txt".to_string(), "hello\nworld".to_string())],
-            setup: vec!["s1".to_string(), "s2".to_string()],
-            teardown: vec![],
+            setup: Some(vec!["s1".to_string(), "s2".to_string()]),
+            teardown: Some(vec![]),
+            use_shell: false,
+            stateful: false,
+            informational_stderr: false,
+            default_weight: 1,
             tests: vec![UnitTest::new_dummy(1), UnitTest::new_dummy(2)],
         }
     }
@@ -623,24 +1882,42 @@ impl TryFrom<UnitTestsUnchecked> for UnitTests {
     type Error = &'static str;
 
     /// # Default
-    /// - `inherit_parent_env`: defaults to `true` if a `None` is received from the
+    /// - `use_shell`: defaults to `false` if a `None` is received from the
+    ///   `UnitTestUnchecked`.
+    /// - `default_weight`: defaults to `1` if a `None` is received from the
     ///   `UnitTestUnchecked`.
     fn try_from(value: UnitTestsUnchecked) -> Result<Self, Self::Error> {
         let UnitTestsUnchecked {
             env,
+            env_file,
             inherit_parent_env,
+            env_passthrough,
+            locale,
+            timezone,
             files,
             setup,
             teardown,
+            use_shell,
+            stateful,
+            informational_stderr,
+            default_weight,
             tests,
         } = value;
 
         UnitTests::build(
             env,
-            inherit_parent_env.unwrap_or(true),
+            env_file,
+            inherit_parent_env,
+            env_passthrough,
+            locale,
+            timezone,
             files,
             setup,
             teardown,
+            use_shell.unwrap_or(false),
+            stateful,
+            informational_stderr,
+            default_weight.unwrap_or(1),
             tests,
         )
     }
@@ -649,6 +1926,124 @@ impl TryFrom<UnitTestsUnchecked> for UnitTests {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DEFAULT_MAIN_PROGRAM_NAME;
+    mod test_expand_file_placeholders {
+        use super::*;
+
+        const FILE_NAMES: [&str; 3] = ["input.txt", "data.csv", "data.json"];
+
+        #[test]
+        fn should_expand_full_names_and_names_without_extension() {
+            assert_eq!(
+                expand_file_placeholders("{input}", &FILE_NAMES),
+                Ok("input.txt".to_string())
+            );
+            assert_eq!(
+                expand_file_placeholders("--in={input.txt},{data.csv}", &FILE_NAMES),
+                Ok("--in=input.txt,data.csv".to_string())
+            );
+        }
+
+        #[test]
+        fn should_keep_text_between_braces_that_is_not_a_placeholder() {
+            for arg in [r#"{"a": 1}"#, "{}", "{", "a}b{", "{input"] {
+                assert_eq!(
+                    expand_file_placeholders(arg, &FILE_NAMES),
+                    Ok(arg.to_string())
+                );
+            }
+        }
+
+        #[test]
+        fn should_not_expand_unknown_or_ambiguous_placeholders() {
+            assert_eq!(
+                expand_file_placeholders("{output}", &FILE_NAMES),
+                Err("unknown file placeholder in args")
+            );
+            assert_eq!(
+                expand_file_placeholders("{data}", &FILE_NAMES),
+                Err("ambiguous file placeholder in args")
+            );
+        }
+    }
+    mod test_expand_vars {
+        use super::*;
+
+        fn vars() -> HashMap<String, String> {
+            HashMap::from([
+                ("n".to_string(), "42".to_string()),
+                ("name".to_string(), "Ana Maria".to_string()),
+            ])
+        }
+
+        #[test]
+        fn should_replace_the_placeholders_with_their_values() {
+            assert_eq!(
+                expand_vars("{{n}} {{ name }}{{n}}", &vars()),
+                Ok("42 Ana Maria42".to_string())
+            );
+        }
+
+        #[test]
+        fn should_keep_escaped_and_single_braces() {
+            assert_eq!(
+                expand_vars(r#"\{{n}} {"a": {n}}"#, &vars()),
+                Ok(r#"{{n}} {"a": {n}}"#.to_string())
+            );
+        }
+
+        #[test]
+        fn should_keep_the_text_without_vars() {
+            for text in ["{{n}", "{{typo}}", r"\{{n}}", "{{ name }} {% if x %}"] {
+                assert_eq!(expand_vars(text, &HashMap::new()), Ok(text.to_string()));
+            }
+        }
+
+        #[test]
+        fn should_not_expand_undefined_or_unterminated_placeholders() {
+            assert_eq!(
+                expand_vars("{{m}}", &vars()),
+                Err("undefined variable in placeholder")
+            );
+            assert_eq!(
+                expand_vars("{{n}", &vars()),
+                Err("unterminated variable placeholder")
+            );
+        }
+    }
+    mod test_parse_env_file {
+        use super::*;
+
+        #[test]
+        fn should_parse_variables_ignoring_comments_and_blank_lines() {
+            let content =
+                "# comment\n\nA=1\n  export B = two words \nC=\"quoted # value\"\nD='x=y'\nE=\n";
+            assert_eq!(
+                parse_env_file(content),
+                Ok(vec![
+                    ("A".to_string(), "1".to_string()),
+                    ("B".to_string(), "two words".to_string()),
+                    ("C".to_string(), "quoted # value".to_string()),
+                    ("D".to_string(), "x=y".to_string()),
+                    ("E".to_string(), "".to_string()),
+                ])
+            );
+        }
+
+        #[test]
+        fn should_not_parse_malformed_lines() {
+            assert_eq!(
+                parse_env_file("A=1\nB\n"),
+                Err("env_file has a line without '='")
+            );
+            for content in ["=1", "1A=1", "A B=1", "A-B=1"] {
+                assert_eq!(
+                    parse_env_file(content),
+                    Err("invalid environment variable name in env_file")
+                );
+            }
+        }
+    }
     mod test_table_cell_content {
         use super::*;
         use crate::config::test_macros::{
@@ -764,6 +2159,22 @@ mod tests {
             ]"#,
             Table
         );
+        test_invalid_deserialization!(
+            should_panic_with_negative_weight,
+            r#"[
+                ["args", "status", "weight"],
+                ["arg1 arg2", 0, -1]
+            ]"#,
+            Table
+        );
+        test_invalid_deserialization!(
+            should_panic_with_status_out_of_range,
+            r#"[
+                ["args", "status"],
+                ["arg1 arg2", 4294967296]
+            ]"#,
+            Table
+        );
         test_invalid_deserialization!(
             should_panic_when_header_is_not_first,
             r#"[
@@ -840,8 +2251,63 @@ mod tests {
                         ],
                     ],
                 };
-                invalid_table.build_grading_assertions(1).unwrap();
+                invalid_table
+                    .build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                    .unwrap();
+            }
+
+            #[test]
+            fn should_fail_when_cell_type_does_not_match_header() {
+                let invalid_table = Table {
+                    header: vec![TableHeaderType::Stdout, TableHeaderType::Status],
+                    tests: vec![vec![
+                        TableCellContent::String("stdout 1".to_string()),
+                        TableCellContent::String("0".to_string()),
+                    ]],
+                };
+                assert_eq!(
+                    invalid_table.build_grading_assertions(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        StdoutMatch::Exact
+                    ),
+                    Err("expected an integer in the table cell")
+                );
+            }
+
+            #[test]
+            fn should_fail_when_weight_is_negative() {
+                let invalid_table = Table {
+                    header: vec![TableHeaderType::Status, TableHeaderType::Weight],
+                    tests: vec![vec![TableCellContent::Int(0), TableCellContent::Int(-2)]],
+                };
+                assert!(
+                    invalid_table
+                        .build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .is_err()
+                );
+            }
+
+            #[test]
+            fn should_fail_when_row_size_does_not_match_header() {
+                let invalid_table = Table {
+                    header: vec![TableHeaderType::Stdout, TableHeaderType::Status],
+                    tests: vec![vec![TableCellContent::String("stdout 1".to_string())]],
+                };
+                assert_eq!(
+                    invalid_table.build_grading_assertions(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        StdoutMatch::Exact
+                    ),
+                    Err("inconsistent test case size")
+                );
             }
+
             #[test]
             fn should_match_a_simple_table_test() {
                 let t = Table::build(
@@ -853,7 +2319,8 @@ mod tests {
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertions(1).unwrap(),
+                    t.build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
                     vec![
                         UnitTestAssertion::build(
                             "test 1".to_string(),
@@ -869,6 +2336,87 @@ mod tests {
                 );
             }
             #[test]
+            fn should_apply_the_comparison_policy_to_the_stdout_only() {
+                let with_stdout = Table::build(
+                    vec![TableHeaderType::Stdout],
+                    vec![vec![TableCellContent::String("ok".to_string())]],
+                )
+                .unwrap();
+                let without_stdout = Table::build(
+                    vec![TableHeaderType::Status],
+                    vec![vec![TableCellContent::Int(0)]],
+                )
+                .unwrap();
+                for (table, expected) in [
+                    (with_stdout, StdoutMatch::TrimEnd),
+                    (without_stdout, StdoutMatch::Exact),
+                ] {
+                    let assertions = table
+                        .build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::TrimEnd)
+                        .unwrap();
+                    assert_eq!(assertions[0].stdout_match(), expected);
+                }
+            }
+            #[test]
+            fn should_split_tags_column_and_carry_hint() {
+                let t = Table::build(
+                    vec![
+                        TableHeaderType::Status,
+                        TableHeaderType::Tags,
+                        TableHeaderType::Hint,
+                    ],
+                    vec![vec![
+                        TableCellContent::Int(0),
+                        TableCellContent::String(" basic, ,edge-cases".to_string()),
+                        TableCellContent::String("check the status".to_string()),
+                    ]],
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
+                    vec![
+                        UnitTestAssertion::build(
+                            "Assertion 1".to_string(),
+                            vec![],
+                            None,
+                            None,
+                            None,
+                            Some(0),
+                            1,
+                        )
+                        .unwrap()
+                        .with_tags(vec!["basic".to_string(), "edge-cases".to_string()])
+                        .with_hint("check the status".to_string())
+                    ]
+                );
+            }
+
+            #[test]
+            fn should_use_default_weight_when_there_is_no_weight_column() {
+                let t = Table::build(
+                    vec![TableHeaderType::Status],
+                    vec![vec![TableCellContent::Int(0)]],
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertions(1, 5, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
+                    vec![
+                        UnitTestAssertion::build(
+                            "Assertion 1".to_string(),
+                            vec![],
+                            None,
+                            None,
+                            None,
+                            Some(0),
+                            5,
+                        )
+                        .unwrap()
+                    ]
+                );
+            }
+            #[test]
             fn should_match_args_correctly() {
                 let t = Table::build(
                     vec![TableHeaderType::Args, TableHeaderType::Status],
@@ -895,7 +2443,8 @@ mod tests {
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertions(1).unwrap(),
+                    t.build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
                     vec![
                         UnitTestAssertion::build(
                             "Assertion 1".to_string(),
@@ -980,7 +2529,8 @@ mod tests {
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertions(1).unwrap(),
+                    t.build_grading_assertions(1, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
                     vec![
                         UnitTestAssertion::build(
                             "test 1".to_string(),
@@ -1043,7 +2593,8 @@ mod tests {
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertions(2).unwrap(),
+                    t.build_grading_assertions(2, 1, &[], &HashMap::new(), StdoutMatch::Exact)
+                        .unwrap(),
                     vec![
                         UnitTestAssertion::build(
                             "Assertion 2".to_string(),
@@ -1087,6 +2638,7 @@ mod tests {
             test_invalid_deserialization, test_serialize_and_deserialize,
             test_valid_deserialization,
         };
+        use crate::grader::grading_tests::unit_test::regex_checks::Comparison;
 
         // serialization
         test_serialize_and_deserialize!(
@@ -1096,9 +2648,41 @@ mod tests {
                 weight: Some(2),
                 args: Some("a1 a2 a3".to_string()),
                 stdin: Some("input 1".to_string()),
+                stdin_base64: None,
+                stdin_hex: None,
+                cwd: None,
+                setup: vec![],
+                teardown: vec![],
                 stdout: Some("stdout1".to_string()),
+                stdout_any_of: vec!["stdout1".to_string(), "stdout2".to_string()],
                 stderr: Some("stderr1".to_string()),
-                status: Some(2),
+                status: Some(ExpectedStatus::Exact(2)),
+                stdout_not_contains: Some("debug".to_string()),
+                stderr_not_contains: Some("panic".to_string()),
+                stdout_line_count: None,
+                stdout_exact_bytes: None,
+                stdout_max_bytes: None,
+                stdout_non_empty: None,
+                stderr_empty: None,
+                stdout_equals_stderr: false,
+                stdout_extract: Some(
+                    StdoutExtract::build(r"accuracy: (\S+)".to_string(), 0.9).unwrap()
+                ),
+                stdout_regex_checks: Some(
+                    StdoutRegexChecks::build(
+                        r"(\d+) of (\d+)".to_string(),
+                        vec![GroupCheck::new("1", Comparison::Le, "2")]
+                    )
+                    .unwrap()
+                ),
+                interaction: None,
+                strip_ansi: false,
+                normalize_with: Some("sort".to_string()),
+                capture: CaptureMode::Auto,
+                stdout_match: None,
+                tags: vec!["basic".to_string()],
+                hint: Some("hint 1".to_string()),
+                skip: false,
             },
             DetailedTest
         );
@@ -1107,104 +2691,498 @@ mod tests {
             DetailedTest {
                 name: None,
                 stdin: None,
+                stdin_base64: None,
+                stdin_hex: None,
+                cwd: None,
+                setup: vec![],
+                teardown: vec![],
                 args: None,
                 stdout: None,
+                stdout_any_of: vec![],
+                stderr: None,
+                status: Some(ExpectedStatus::Exact(2)),
+                stdout_not_contains: None,
+                stderr_not_contains: None,
+                stdout_line_count: None,
+                stdout_exact_bytes: None,
+                stdout_max_bytes: None,
+                stdout_non_empty: None,
+                stderr_empty: None,
+                stdout_equals_stderr: false,
+                stdout_extract: None,
+                stdout_regex_checks: None,
+                interaction: None,
+                strip_ansi: false,
+                normalize_with: None,
+                capture: CaptureMode::None,
+                stdout_match: None,
+                tags: vec![],
+                hint: None,
+                weight: None,
+                skip: false,
+            },
+            DetailedTest
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_with_interaction,
+            DetailedTest {
+                name: None,
+                stdin: None,
+                stdin_base64: None,
+                stdin_hex: None,
+                cwd: None,
+                setup: vec![],
+                teardown: vec![],
+                args: None,
+                stdout: Some("prompt 1: done".to_string()),
+                stdout_any_of: vec![],
                 stderr: None,
-                status: Some(2),
+                status: None,
+                stdout_not_contains: None,
+                stderr_not_contains: None,
+                stdout_line_count: None,
+                stdout_exact_bytes: None,
+                stdout_max_bytes: None,
+                stdout_non_empty: None,
+                stderr_empty: None,
+                stdout_equals_stderr: false,
+                stdout_extract: None,
+                stdout_regex_checks: None,
+                interaction: Some(InteractionScript::new_dummy(1)),
+                strip_ansi: false,
+                normalize_with: None,
+                capture: CaptureMode::Auto,
+                stdout_match: None,
+                tags: vec![],
+                hint: None,
                 weight: None,
+                skip: false,
             },
             DetailedTest
         );
 
-        // invalid deserialization
-        test_invalid_deserialization!(should_panic_with_no_content_string, r#"\n"#, DetailedTest);
-        test_invalid_deserialization!(should_panic_with_empty_object, r#"{}"#, DetailedTest);
-        test_invalid_deserialization!(
-            should_panic_with_wrong_fields,
+        test_valid_deserialization!(
+            should_accept_only_not_contains_expectations,
             r#"
         {
-            "wrong field":123
+            "args": "a1",
+            "stdout_not_contains": "DEBUG",
+            "stderr_not_contains": "panicked"
         }"#,
             DetailedTest
         );
-        test_invalid_deserialization!(
-            should_panic_without_check_fields,
+        test_valid_deserialization!(
+            should_accept_only_stdout_equals_stderr,
             r#"
         {
-            "name":"name 1",
-            "args":"arg1",
-            "weight":2
+            "args": "a1",
+            "stdout_equals_stderr": true
         }"#,
             DetailedTest
         );
         test_invalid_deserialization!(
-            should_panic_with_wrong_type,
+            should_panic_with_stdout_equals_stderr_as_only_false_expectation,
             r#"
         {
-            "name":"name 1",
-            "args":"arg1",
-            "status":"34",
-            "weight":2
+            "args": "a1",
+            "stdout_equals_stderr": false
         }"#,
             DetailedTest
         );
-        test_invalid_deserialization!(
-            should_panic_with_wrong_field_name,
+        test_valid_deserialization!(
+            should_accept_only_stdout_predicates,
             r#"
         {
-            "name":"name 1",
-            "arg":"arg1",
-            "status":34,
-            "weight":2
+            "args": "a1",
+            "stdout_line_count": 3,
+            "stdout_non_empty": true
         }"#,
             DetailedTest
         );
-        test_invalid_deserialization!(
-            should_panic_with_extra_field,
+        test_valid_deserialization!(
+            should_accept_only_stdout_byte_counts,
             r#"
         {
-            "name":"name 1",
-            "args":"arg1",
-            "_extra":23,
-            "status":34,
-            "weight":2
+            "args": "input.txt",
+            "stdout_max_bytes": 1024,
+            "stdout_exact_bytes": 1000
         }"#,
             DetailedTest
         );
-        test_invalid_deserialization!(
-            should_panic_with_duplicated_field,
+        test_valid_deserialization!(
+            should_accept_only_stderr_empty,
             r#"
         {
-            "name":"name 1",
-            "args":"arg1",
-            "name":"name 1",
-            "status":34,
-            "name":"name 1",
-            "weight":2
+            "args": "--invalid",
+            "stderr_empty": false
         }"#,
             DetailedTest
         );
-
-        // valid deserialization
         test_valid_deserialization!(
-            should_accept_complete,
+            should_accept_cwd,
             r#"
         {
-            "name":"name 1",
-            "args":"arg1",
-            "status":34,
-            "stderr":"",
-            "stdout":"",
-            "weight":2
+            "cwd": "project/src",
+            "status": 0
         }"#,
             DetailedTest
         );
         test_valid_deserialization!(
-            should_accept_basic1,
+            should_accept_only_stdout_extract,
             r#"
         {
-            "stdin":"",
-            "name":"name 1",
+            "args": "--evaluate",
+            "stdout_extract": { "pattern": "accuracy: (\\S+)", "min": 0.9 }
+        }"#,
+            DetailedTest
+        );
+        test_valid_deserialization!(
+            should_accept_only_stdout_regex_checks,
+            r#"
+        {
+            "stdout_regex_checks": {
+                "pattern": "sum: (?<sum>\\d+), total: (?<total>\\d+)",
+                "checks": [{ "left": "sum", "op": "eq", "right": "total" }]
+            }
+        }"#,
+            DetailedTest
+        );
+        test_valid_deserialization!(
+            should_accept_only_interaction,
+            r#"
+        {
+            "interaction": {
+                "step_timeout_ms": 500,
+                "kill_grace_ms": 200,
+                "steps": [
+                    { "wait_for": "name: ", "send": "Ana\n" },
+                    { "wait_for": "age: ", "send": "30\n" }
+                ]
+            }
+        }"#,
+            DetailedTest
+        );
+        // invalid deserialization
+        test_invalid_deserialization!(should_panic_with_no_content_string, r#"\n"#, DetailedTest);
+        test_invalid_deserialization!(
+            should_panic_with_cwd_outside_the_temporary_directory,
+            r#"
+        {
+            "cwd": "../src",
+            "status": 0
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_expectations_but_only_stderr_captured,
+            r#"
+        {
+            "stdout_non_empty": true,
+            "capture": "stderr_only"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_match_but_without_stdout,
+            r#"
+        {
+            "status": 0,
+            "stdout_match": "trim_line_ends"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_unknown_stdout_match,
+            r#"
+        {
+            "stdout": "ok",
+            "stdout_match": "trim"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_normalize_with_but_without_stdout,
+            r#"
+        {
+            "status": 0,
+            "normalize_with": "sort"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_only_strip_ansi,
+            r#"
+        {
+            "strip_ansi": true
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stderr_empty_as_str,
+            r#"
+        {
+            "stderr_empty": "true"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_absolute_cwd,
+            r#"
+        {
+            "cwd": "/src",
+            "status": 0
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdin_and_stdin_hex,
+            r#"
+        {
+            "stdin": "Ana\n",
+            "stdin_hex": "0a",
+            "stdout": "Hi Ana\n"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdin_base64_and_interaction,
+            r#"
+        {
+            "stdin_base64": "QW5hCg==",
+            "interaction": { "steps": [{ "wait_for": "name: ", "send": "Ana\n" }] }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_invalid_stdin_base64,
+            r#"
+        {
+            "stdin_base64": "QW5h*",
+            "stdout": "Hi Ana\n"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_odd_stdin_hex,
+            r#"
+        {
+            "stdin_hex": "0a0",
+            "stdout": "Hi Ana\n"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_invalid_stdin_hex_digit,
+            r#"
+        {
+            "stdin_hex": "0g",
+            "stdout": "Hi Ana\n"
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_extract_without_capture_group,
+            r#"
+        {
+            "stdout_extract": { "pattern": "accuracy: \\S+", "min": 0.9 }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_invalid_stdout_extract_pattern,
+            r#"
+        {
+            "stdout_extract": { "pattern": "accuracy: (\\S+", "min": 0.9 }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_regex_checks_of_unknown_group,
+            r#"
+        {
+            "stdout_regex_checks": {
+                "pattern": "sum: (?<sum>\\d+)",
+                "checks": [{ "left": "sum", "op": "eq", "right": "total" }]
+            }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_regex_checks_without_checks,
+            r#"
+        {
+            "stdout_regex_checks": { "pattern": "(\\d+) (\\d+)", "checks": [] }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_unknown_stdout_regex_checks_operator,
+            r#"
+        {
+            "stdout_regex_checks": {
+                "pattern": "(\\d+) (\\d+)",
+                "checks": [{ "left": "1", "op": "<", "right": "2" }]
+            }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdout_extract_without_min,
+            r#"
+        {
+            "stdout_extract": { "pattern": "accuracy: (\\S+)" }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_negative_line_count,
+            r#"
+        {
+            "stdout_line_count": -1
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_negative_max_bytes,
+            r#"
+        {
+            "stdout_max_bytes": -1
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_stdin_and_interaction,
+            r#"
+        {
+            "stdin": "Ana\n",
+            "interaction": { "steps": [{ "wait_for": "name: ", "send": "Ana\n" }] }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_interaction_without_steps,
+            r#"
+        {
+            "interaction": { "steps": [] }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_zero_step_timeout,
+            r#"
+        {
+            "interaction": {
+                "step_timeout_ms": 0,
+                "steps": [{ "wait_for": "name: ", "send": "Ana\n" }]
+            }
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(should_panic_with_empty_object, r#"{}"#, DetailedTest);
+        test_invalid_deserialization!(
+            should_panic_with_wrong_fields,
+            r#"
+        {
+            "wrong field":123
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_without_check_fields,
+            r#"
+        {
+            "name":"name 1",
+            "args":"arg1",
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_wrong_type,
+            r#"
+        {
+            "name":"name 1",
+            "args":"arg1",
+            "status":"34",
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_wrong_field_name,
+            r#"
+        {
+            "name":"name 1",
+            "arg":"arg1",
+            "status":34,
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_extra_field,
+            r#"
+        {
+            "name":"name 1",
+            "args":"arg1",
+            "_extra":23,
+            "status":34,
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_duplicated_field,
+            r#"
+        {
+            "name":"name 1",
+            "args":"arg1",
+            "name":"name 1",
+            "status":34,
+            "name":"name 1",
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_blank_tag,
+            r#"{ "status": 0, "tags": ["basic", " "] }"#,
+            DetailedTest
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_blank_hint,
+            r#"{ "status": 0, "hint": "  " }"#,
+            DetailedTest
+        );
+
+        test_invalid_deserialization!(
+            should_panic_with_unknown_status_name,
+            r#"{ "status": "failure" }"#,
+            DetailedTest
+        );
+
+        // valid deserialization
+        test_valid_deserialization!(
+            should_accept_complete,
+            r#"
+        {
+            "name":"name 1",
+            "args":"arg1",
+            "status":34,
+            "stderr":"",
+            "stdout":"",
+            "weight":2
+        }"#,
+            DetailedTest
+        );
+        test_valid_deserialization!(
+            should_accept_basic1,
+            r#"
+        {
+            "stdin":"",
+            "name":"name 1",
             "args":"arg1",
             "status":34,
             "weight":2
@@ -1220,51 +3198,667 @@ mod tests {
             DetailedTest
         );
 
-        mod test_build_grading_assertion {
-            use super::*;
+        mod test_build_grading_assertion {
+            use super::*;
+
+            #[test]
+            #[should_panic]
+            fn should_panic_when_assertion_is_not_built_properly() {
+                let invalid_table = DetailedTest {
+                    name: None,
+                    weight: Some(1),
+                    args: None,
+                    stdin: Some("stdin 1".to_string()),
+                    stdin_base64: None,
+                    stdin_hex: None,
+                    cwd: None,
+                    setup: vec![],
+                    teardown: vec![],
+                    stdout: None,
+                    stdout_any_of: vec![],
+                    stderr: None,
+                    status: None,
+                    stdout_not_contains: None,
+                    stderr_not_contains: None,
+                    stdout_line_count: None,
+                    stdout_exact_bytes: None,
+                    stdout_max_bytes: None,
+                    stdout_non_empty: None,
+                    stderr_empty: None,
+                    stdout_equals_stderr: false,
+                    stdout_extract: None,
+                    stdout_regex_checks: None,
+                    interaction: None,
+                    strip_ansi: false,
+                    normalize_with: None,
+                    capture: CaptureMode::Auto,
+                    stdout_match: None,
+                    tags: vec![],
+                    hint: None,
+                    skip: false,
+                };
+                invalid_table
+                    .build_grading_assertion(1, 1, &[], &HashMap::new(), false, StdoutMatch::Exact)
+                    .unwrap();
+            }
+            #[test]
+            fn should_match_a_simple_detailed_test() {
+                let t = DetailedTest::build(
+                    None,
+                    Some("arg1 arg2 \" an arg \"".to_string()),
+                    Some("".to_string()),
+                    Some("".to_string()),
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        10,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::build(
+                        "Assertion 10".to_string(),
+                        vec![
+                            "arg1".to_string(),
+                            "arg2".to_string(),
+                            " an arg ".to_string()
+                        ],
+                        Some("".to_string()),
+                        Some("".to_string()),
+                        None,
+                        None,
+                        1,
+                    )
+                    .unwrap()
+                );
+            }
+
+            #[test]
+            fn should_use_default_weight_only_when_weight_is_missing() {
+                let without_weight =
+                    DetailedTest::build(None, None, None, None, None, Some(0), None).unwrap();
+                let with_weight =
+                    DetailedTest::build(None, None, None, None, None, Some(0), Some(2)).unwrap();
+
+                assert_eq!(
+                    without_weight
+                        .build_grading_assertion(
+                            1,
+                            5,
+                            &[],
+                            &HashMap::new(),
+                            false,
+                            StdoutMatch::Exact
+                        )
+                        .unwrap(),
+                    UnitTestAssertion::build(
+                        "Assertion 1".to_string(),
+                        vec![],
+                        None,
+                        None,
+                        None,
+                        Some(0),
+                        5
+                    )
+                    .unwrap()
+                );
+                assert_eq!(
+                    with_weight
+                        .build_grading_assertion(
+                            1,
+                            5,
+                            &[],
+                            &HashMap::new(),
+                            false,
+                            StdoutMatch::Exact
+                        )
+                        .unwrap(),
+                    UnitTestAssertion::build(
+                        "Assertion 1".to_string(),
+                        vec![],
+                        None,
+                        None,
+                        None,
+                        Some(0),
+                        2
+                    )
+                    .unwrap()
+                );
+            }
+
+            #[test]
+            fn should_carry_not_contains_expectations() {
+                let t = DetailedTest::build(None, None, None, None, None, Some(0), None)
+                    .unwrap()
+                    .with_stdout_not_contains("DEBUG".to_string());
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::build(
+                        "Assertion 1".to_string(),
+                        vec![],
+                        None,
+                        None,
+                        None,
+                        Some(0),
+                        1
+                    )
+                    .unwrap()
+                    .with_stdout_not_contains("DEBUG".to_string())
+                );
+            }
+
+            #[test]
+            fn should_carry_stdout_predicates() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout_line_count": 2, "stdout_non_empty": true }"#)
+                        .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_line_count(2)
+                        .with_stdout_non_empty(true)
+                );
+            }
+
+            #[test]
+            fn should_carry_the_setup_and_teardown_of_the_assertion() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "setup": ["cp a.txt b.txt"], "teardown": ["rm b.txt"], "status": 0 }"#,
+                )
+                .unwrap();
+                let split = |command: &str, args: &[&str]| {
+                    vec![(
+                        command.to_string(),
+                        args.iter().map(|arg| arg.to_string()).collect(),
+                    )]
+                };
+
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_status(0)
+                        .with_setup(split("cp", &["a.txt", "b.txt"]))
+                        .with_teardown(split("rm", &["b.txt"]))
+                );
+                assert_eq!(
+                    t.build_grading_assertion(1, 1, &[], &HashMap::new(), true, StdoutMatch::Exact)
+                        .unwrap()
+                        .setup(),
+                    split("sh", &["-c", "cp a.txt b.txt"])
+                );
+            }
+
+            #[test]
+            fn should_carry_a_named_status() {
+                let t: DetailedTest = serde_json::from_str(r#"{ "status": "nonzero" }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_expected_status(ExpectedStatus::NonZero)
+                );
+            }
+
+            #[test]
+            fn should_carry_stdout_byte_counts() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout_exact_bytes": 12, "stdout_max_bytes": 16 }"#)
+                        .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_exact_bytes(12)
+                        .with_stdout_max_bytes(16)
+                );
+            }
+
+            #[test]
+            fn should_decode_the_encoded_stdin() {
+                let raw_bytes = vec![0x00, 0xff, b'\n'];
+                for encoded in [
+                    r#"{ "stdin_hex": "00 FF 0a", "stdout": "ok\n" }"#,
+                    r#"{ "stdin_base64": "AP8K", "stdout": "ok\n" }"#,
+                ] {
+                    let t: DetailedTest = serde_json::from_str(encoded).unwrap();
+                    assert_eq!(
+                        t.build_grading_assertion(
+                            1,
+                            1,
+                            &[],
+                            &HashMap::new(),
+                            false,
+                            StdoutMatch::Exact
+                        )
+                        .unwrap(),
+                        UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                            .with_stdin_bytes(raw_bytes.clone())
+                            .with_stdout("ok\n".to_string())
+                    );
+                }
+            }
+
+            #[test]
+            fn should_carry_acceptable_outputs() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout_any_of": ["yes\n", "y\n"] }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_any_of(vec!["yes\n".to_string(), "y\n".to_string()])
+                );
+            }
+
+            #[test]
+            fn should_carry_stderr_empty() {
+                let t: DetailedTest = serde_json::from_str(r#"{ "stderr_empty": true }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stderr_empty(true)
+                );
+            }
+
+            #[test]
+            fn should_carry_strip_ansi() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout": "ok\n", "strip_ansi": true }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout("ok\n".to_string())
+                        .with_strip_ansi(true)
+                );
+            }
+
+            #[test]
+            fn should_carry_capture() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "status": 0, "capture": "none" }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_expected_status(ExpectedStatus::Exact(0))
+                        .with_capture(CaptureMode::None)
+                );
+            }
+
+            #[test]
+            fn should_carry_stdout_equals_stderr() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout_equals_stderr": true }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_equals_stderr(true)
+                );
+            }
 
             #[test]
-            #[should_panic]
-            fn should_panic_when_assertion_is_not_built_properly() {
-                let invalid_table = DetailedTest {
-                    name: None,
-                    weight: Some(1),
-                    args: None,
-                    stdin: Some("stdin 1".to_string()),
-                    stdout: None,
-                    stderr: None,
-                    status: None,
-                };
-                invalid_table.build_grading_assertion(1).unwrap();
+            fn should_carry_stdout_match() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "stdout": "ok\n", "stdout_match": "trim_line_ends" }"#,
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout("ok\n".to_string())
+                        .with_stdout_match(StdoutMatch::TrimLineEnds)
+                );
             }
+
             #[test]
-            fn should_match_a_simple_detailed_test() {
-                let t = DetailedTest::build(
-                    None,
-                    Some("arg1 arg2 \" an arg \"".to_string()),
-                    Some("".to_string()),
-                    Some("".to_string()),
-                    None,
-                    None,
-                    None,
+            fn should_default_to_the_comparison_policy() {
+                let t: DetailedTest = serde_json::from_str(r#"{ "stdout": "ok\n" }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::CollapseWhitespace
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout("ok\n".to_string())
+                        .with_stdout_match(StdoutMatch::CollapseWhitespace)
+                );
+            }
+
+            #[test]
+            fn should_prefer_the_stdout_match_over_the_comparison_policy() {
+                for stdout_match in [StdoutMatch::Exact, StdoutMatch::TrimLineEnds] {
+                    let t = DetailedTest {
+                        stdout: Some("ok\n".to_string()),
+                        stdout_match: Some(stdout_match),
+                        ..DetailedTest::new_dummy(1)
+                    };
+                    assert_eq!(
+                        t.build_grading_assertion(
+                            1,
+                            1,
+                            &[],
+                            &HashMap::new(),
+                            false,
+                            StdoutMatch::TrimEnd
+                        )
+                        .unwrap()
+                        .stdout_match(),
+                        stdout_match
+                    );
+                }
+            }
+
+            #[test]
+            fn should_ignore_the_comparison_policy_without_stdout() {
+                let t: DetailedTest = serde_json::from_str(r#"{ "status": 0 }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::TrimEnd
+                    )
+                    .unwrap()
+                    .stdout_match(),
+                    StdoutMatch::Exact
+                );
+            }
+
+            #[test]
+            fn should_carry_stdout_regex_checks() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "stdout_regex_checks": { "pattern": "(\\d+) of (\\d+)", "checks": [{ "left": "1", "op": "le", "right": "2" }] } }"#,
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertion(10).unwrap(),
-                    UnitTestAssertion::build(
-                        "Assertion 10".to_string(),
-                        vec![
-                            "arg1".to_string(),
-                            "arg2".to_string(),
-                            " an arg ".to_string()
-                        ],
-                        Some("".to_string()),
-                        Some("".to_string()),
-                        None,
-                        None,
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_regex_checks(
+                            GradingStdoutRegexChecks::build(
+                                r"(\d+) of (\d+)",
+                                vec![GroupCheck::new("1", Comparison::Le, "2")]
+                            )
+                            .unwrap()
+                        )
+                );
+            }
+
+            #[test]
+            fn should_carry_normalize_with() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "stdout": "a\nb\n", "normalize_with": "sort -r" }"#)
+                        .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
                         1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
                     )
                     .unwrap()
+                    .normalize_with(),
+                    Some(&("sort".to_string(), vec!["-r".to_string()]))
+                );
+                assert_eq!(
+                    t.build_grading_assertion(1, 1, &[], &HashMap::new(), true, StdoutMatch::Exact)
+                        .unwrap()
+                        .normalize_with(),
+                    Some(&(
+                        "sh".to_string(),
+                        vec!["-c".to_string(), "sort -r".to_string()]
+                    ))
+                );
+            }
+
+            #[test]
+            fn should_carry_cwd() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "cwd": "src", "status": 0 }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_cwd("src".into())
+                        .with_status(0)
+                );
+            }
+
+            #[test]
+            fn should_carry_stdout_extract() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "stdout_extract": { "pattern": "accuracy: (\\S+)", "min": 0.9 } }"#,
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_stdout_extract(
+                            GradingStdoutExtract::build(r"accuracy: (\S+)", 0.9).unwrap()
+                        )
+                );
+            }
+
+            #[test]
+            fn should_carry_tags() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "status": 0, "tags": ["basic", "io"] }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_status(0)
+                        .with_tags(vec!["basic".to_string(), "io".to_string()])
+                );
+            }
+
+            #[test]
+            fn should_carry_hint() {
+                let t: DetailedTest =
+                    serde_json::from_str(r#"{ "status": 0, "hint": "check the status" }"#).unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_status(0)
+                        .with_hint("check the status".to_string())
+                );
+            }
+
+            #[test]
+            fn should_carry_interaction_with_default_step_timeout() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "interaction": { "steps": [{ "wait_for": "a: ", "send": "1\n" }] } }"#,
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_interaction(
+                            GradingInteractionScript::build(
+                                vec![GradingInteractionStep::new(
+                                    "a: ".to_string(),
+                                    "1\n".to_string()
+                                )],
+                                Duration::from_millis(DEFAULT_INTERACTION_STEP_TIMEOUT_MS),
+                            )
+                            .unwrap()
+                        )
+                );
+            }
+
+            #[test]
+            fn should_carry_the_kill_grace_of_the_interaction() {
+                let t: DetailedTest = serde_json::from_str(
+                    r#"{ "interaction": { "kill_grace_ms": 300, "steps": [{ "wait_for": "a: ", "send": "1\n" }] } }"#,
+                )
+                .unwrap();
+                assert_eq!(
+                    t.build_grading_assertion(
+                        1,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    UnitTestAssertion::new("Assertion 1".to_string(), vec![], None, 1)
+                        .with_interaction(
+                            GradingInteractionScript::build(
+                                vec![GradingInteractionStep::new(
+                                    "a: ".to_string(),
+                                    "1\n".to_string()
+                                )],
+                                Duration::from_millis(DEFAULT_INTERACTION_STEP_TIMEOUT_MS),
+                            )
+                            .unwrap()
+                            .with_kill_grace(Duration::from_millis(300))
+                        )
                 );
             }
 
@@ -1281,7 +3875,15 @@ mod tests {
                 )
                 .unwrap();
                 assert_eq!(
-                    t.build_grading_assertion(10).unwrap(),
+                    t.build_grading_assertion(
+                        10,
+                        1,
+                        &[],
+                        &HashMap::new(),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
                     UnitTestAssertion::build(
                         "name abc".to_string(),
                         vec!["a1".to_string(), "a2".to_string(), "a3".to_string()],
@@ -1311,7 +3913,12 @@ mod tests {
                 title: Some("test1".to_string()),
                 program_name: Some("p1".to_string()),
                 table: Some(Table::new_dummy()),
-                detailed_tests: vec![DetailedTest::new_dummy(1)]
+                table_file: None,
+                detailed_tests: vec![DetailedTest::new_dummy(1)],
+                args_matrix: None,
+                vars: HashMap::new(),
+                weight: None,
+                skip: false,
             },
             UnitTest
         );
@@ -1321,7 +3928,12 @@ mod tests {
                 title: Some("test1".to_string()),
                 program_name: Some("p1".to_string()),
                 table: None,
-                detailed_tests: vec![DetailedTest::new_dummy(1)]
+                table_file: Some("tests.csv".to_string()),
+                detailed_tests: vec![DetailedTest::new_dummy(1)],
+                args_matrix: None,
+                vars: HashMap::new(),
+                weight: None,
+                skip: false,
             },
             UnitTest
         );
@@ -1332,13 +3944,71 @@ mod tests {
                 title: None,
                 program_name: None,
                 table: Some(Table::new_dummy()),
-                detailed_tests: vec![]
+                table_file: None,
+                detailed_tests: vec![],
+                args_matrix: None,
+                vars: HashMap::new(),
+                weight: None,
+                skip: false,
+            },
+            UnitTest
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_with_args_matrix,
+            UnitTest {
+                title: None,
+                program_name: None,
+                table: None,
+                table_file: None,
+                detailed_tests: vec![DetailedTest::new_dummy(1)],
+                args_matrix: Some(ArgsMatrix::File {
+                    file: "inputs.txt".to_string(),
+                }),
+                vars: HashMap::new(),
+                weight: None,
+                skip: false,
             },
             UnitTest
         );
 
         // invalid deserialization
         test_invalid_deserialization!(should_panic_with_no_content_string, r#"\n"#, UnitTest);
+        test_invalid_deserialization!(
+            should_panic_with_args_matrix_without_detailed_tests,
+            r#"
+        {
+            "table": [["args"], ["a"]],
+            "args_matrix": ["a.txt"]
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_empty_args_matrix,
+            r#"
+        {
+            "detailed_tests": [{ "status": 0 }],
+            "args_matrix": []
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_duplicated_args_matrix_entry,
+            r#"
+        {
+            "detailed_tests": [{ "status": 0 }],
+            "args_matrix": ["a.txt", "a.txt"]
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_empty_args_matrix_file,
+            r#"
+        {
+            "detailed_tests": [{ "status": 0 }],
+            "args_matrix": { "file": " " }
+        }"#,
+            UnitTest
+        );
         test_invalid_deserialization!(should_panic_with_empty_object, r#"{}"#, UnitTest);
         test_invalid_deserialization!(
             should_panic_with_wrong_fields,
@@ -1358,6 +4028,15 @@ mod tests {
         }"#,
             UnitTest
         );
+        test_invalid_deserialization!(
+            should_panic_with_invalid_variable_name,
+            r#"
+        {
+            "vars": { "my var": "1" },
+            "detailed_tests": [{ "stdout": "{{my var}}\n" }]
+        }"#,
+            UnitTest
+        );
         test_invalid_deserialization!(
             should_panic_with_empty_detailed_tests,
             r#"
@@ -1469,25 +4148,68 @@ mod tests {
             UnitTest
         );
         test_valid_deserialization!(
-            should_accept_with_full,
+            should_accept_with_full,
+            r#"
+        {
+            "title":"name 1",
+            "program_name":"main",
+            "table":[
+                ["name", "args", "status"],
+                ["test 1", "a1 a2", 0],
+                ["test 2", "a1 a3", 1],
+                ["test 3", "a1 a3 a4", 2]
+            ],
+            "detailed_tests":[
+                {
+                    "args":"a1 a2 a3",
+                    "name":"test 1",
+                    "status":0,
+                    "stdout":"hello world"
+                }
+            ]
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_zero_weight,
+            r#"
+        {
+            "weight": 0,
+            "detailed_tests": [{ "stdout": "a\n" }]
+        }"#,
+            UnitTest
+        );
+        test_valid_deserialization!(
+            should_accept_a_weight,
+            r#"
+        {
+            "weight": 10,
+            "detailed_tests": [{ "stdout": "a\n" }, { "stdout": "b\n", "weight": 2 }]
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_table_and_table_file,
+            r#"
+        {
+            "table":[["args", "stdout"], ["a", "a"]],
+            "table_file":"tests.csv"
+        }"#,
+            UnitTest
+        );
+        test_invalid_deserialization!(
+            should_panic_with_blank_table_file,
+            r#"
+        {
+            "table_file":" "
+        }"#,
+            UnitTest
+        );
+        test_valid_deserialization!(
+            should_accept_with_table_file,
             r#"
         {
-            "title":"name 1",
-            "program_name":"main",
-            "table":[
-                ["name", "args", "status"],
-                ["test 1", "a1 a2", 0],
-                ["test 2", "a1 a3", 1],
-                ["test 3", "a1 a3 a4", 2]
-            ],
-            "detailed_tests":[
-                {
-                    "args":"a1 a2 a3",
-                    "name":"test 1",
-                    "status":0,
-                    "stdout":"hello world"
-                }
-            ]
+            "table_file":"tests.tsv"
         }"#,
             UnitTest
         );
@@ -1540,20 +4262,479 @@ mod tests {
                         )
                         .unwrap(),
                     ),
+                    None,
                     vec![],
+                    None,
+                    HashMap::new(),
+                    None,
+                    false,
                 )
                 .unwrap();
 
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 let executables_by_name = HashMap::from_iter([
                     ("not some program".to_string(), executable.clone()),
                     ("program1".to_string(), executable.clone()),
                     ("p1".to_string(), executable.clone()),
                 ]);
-                u.build_grading_unit_test(2, &executables_by_name).unwrap();
+                u.build_grading_unit_test(
+                    2,
+                    1,
+                    &executables_by_name,
+                    DEFAULT_MAIN_PROGRAM_NAME,
+                    &[],
+                    Path::new(""),
+                    false,
+                    StdoutMatch::Exact,
+                )
+                .unwrap();
+            }
+
+            fn build_with_table_file(
+                file_name: &str,
+                content: &str,
+            ) -> Result<Vec<UnitTestAssertion>, String> {
+                let dir = tempfile::tempdir().unwrap();
+                fs::write(dir.path().join(file_name), content).unwrap();
+                let u = UnitTest::build(
+                    None,
+                    None,
+                    None,
+                    Some(file_name.to_string()),
+                    vec![],
+                    None,
+                    HashMap::new(),
+                    None,
+                    false,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+                u.build_grading_unit_test(
+                    1,
+                    1,
+                    &executables_by_name,
+                    "program1",
+                    &[],
+                    dir.path(),
+                    false,
+                    StdoutMatch::Exact,
+                )
+                .map(|unit_test| unit_test.assertions().to_vec())
+                // the errors start with the path, which is only kept from the file name
+                .map_err(|err| err.replace(&format!("{}/", dir.path().display()), ""))
+            }
+
+            #[test]
+            fn should_read_the_table_from_a_csv_or_tsv_file() {
+                let inline: UnitTest = serde_json::from_str(
+                    r#"{
+                        "table": [
+                            ["name", "args", "stdout", "status", "weight"],
+                            ["sum", "1 2", "3\n", 0, 2],
+                            ["quoted, \"with\" commas", "-n 'a b'", "a b\n", 1, 1]
+                        ]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+                let expected = inline
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap()
+                    .assertions()
+                    .to_vec();
+
+                let csv = "name,args,stdout,status,weight\n\
+                           sum,1 2,\"3\n\",0,2\n\
+                           \"quoted, \"\"with\"\" commas\",-n 'a b',\"a b\n\", 1 ,1\n";
+                assert_eq!(
+                    build_with_table_file("tests.csv", csv),
+                    Ok(expected.clone())
+                );
+                let tsv = "name\targs\tstdout\tstatus\tweight\n\
+                           sum\t1 2\t\"3\n\"\t0\t2\n\
+                           \"quoted, \"\"with\"\" commas\"\t-n 'a b'\t\"a b\n\"\t1\t1\n";
+                assert_eq!(build_with_table_file("tests.tsv", tsv), Ok(expected));
+            }
+
+            #[test]
+            fn should_validate_the_table_file() {
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,output\na,a\n").unwrap_err(),
+                    "tests.csv:1: unknown column type in table_file"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,status\na,0\nb,zero\n").unwrap_err(),
+                    "tests.csv:3: expected an integer in the table cell"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,weight\na,-1\n").unwrap_err(),
+                    "tests.csv:2: inconsistent type from table test content cell"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,status\na\n").unwrap_err(),
+                    "tests.csv:2: inconsistent test case size"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,name\na,b\n").unwrap_err(),
+                    "tests.csv:1: header must have at least one expect column type (stderr, stdout, or status"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "args,stdout\n").unwrap_err(),
+                    "tests.csv:1: table_file must have at least one test"
+                );
+                assert_eq!(
+                    build_with_table_file("tests.csv", "").unwrap_err(),
+                    "tests.csv:1: table_file must have a header"
+                );
+            }
+
+            #[test]
+            fn should_fail_when_the_table_file_can_not_be_read() {
+                let u = UnitTest::build(
+                    None,
+                    None,
+                    None,
+                    Some("missing.csv".to_string()),
+                    vec![],
+                    None,
+                    HashMap::new(),
+                    None,
+                    false,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                assert_eq!(
+                    u.build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new("/nonexistent"),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap_err(),
+                    "/nonexistent/missing.csv: could not read table_file"
+                );
+            }
+
+            #[test]
+            fn should_replace_the_vars_before_splitting_the_args() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{
+                        "vars": { "name": "Ana Maria" },
+                        "table": [
+                            ["args", "stdout"],
+                            ["--name '{{name}}'", "Hi {{name}}\n"]
+                        ],
+                        "detailed_tests": [{ "args": "{{name}}", "stdin": "{{name}}", "stderr": "" }]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                let unit_test = u
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert_eq!(
+                    unit_test.assertions(),
+                    [
+                        UnitTestAssertion::new(
+                            "Assertion 1".to_string(),
+                            vec!["--name".to_string(), "Ana Maria".to_string()],
+                            None,
+                            1
+                        )
+                        .with_stdout("Hi Ana Maria\n".to_string()),
+                        UnitTestAssertion::new(
+                            "Assertion 2".to_string(),
+                            vec!["Ana".to_string(), "Maria".to_string()],
+                            Some("Ana Maria".to_string()),
+                            1
+                        )
+                        .with_stderr("".to_string()),
+                    ]
+                );
+            }
+            #[test]
+            fn should_expand_the_detailed_tests_with_the_args_matrix() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{
+                        "args_matrix": ["a.txt", "b.txt --strict"],
+                        "detailed_tests": [
+                            { "name": "valid", "args": "-q", "status": 0 },
+                            { "status": 0, "skip": true }
+                        ]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                let unit_test = u
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert_eq!(
+                    unit_test.assertions(),
+                    [
+                        UnitTestAssertion::new(
+                            "valid [a.txt]".to_string(),
+                            vec!["-q".to_string(), "a.txt".to_string()],
+                            None,
+                            1
+                        )
+                        .with_status(0),
+                        UnitTestAssertion::new(
+                            "valid [b.txt --strict]".to_string(),
+                            vec![
+                                "-q".to_string(),
+                                "b.txt".to_string(),
+                                "--strict".to_string()
+                            ],
+                            None,
+                            1
+                        )
+                        .with_status(0),
+                    ]
+                );
+                assert_eq!(unit_test.skipped_assertions(), ["Assertion 2".to_string()]);
+            }
+
+            #[test]
+            fn should_read_the_args_matrix_from_a_file() {
+                let dir = tempfile::tempdir().unwrap();
+                fs::write(dir.path().join("inputs.txt"), "in1.txt\n\nin2.txt\n").unwrap();
+                let u: UnitTest = serde_json::from_str(
+                    r#"{
+                        "args_matrix": { "file": "inputs.txt" },
+                        "detailed_tests": [{ "status": 0 }]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+                let build = |base_dir: &Path| {
+                    u.build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        base_dir,
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                };
+
+                let unit_test = build(dir.path()).unwrap();
+                let names: Vec<&str> = unit_test
+                    .assertions()
+                    .iter()
+                    .map(UnitTestAssertion::name)
+                    .collect();
+                assert_eq!(names, ["Assertion 1 [in1.txt]", "Assertion 1 [in2.txt]"]);
+
+                assert_eq!(
+                    build(Path::new("/nonexistent")).unwrap_err(),
+                    "could not read args_matrix file"
+                );
+                fs::write(dir.path().join("inputs.txt"), "a\na\n").unwrap();
+                assert_eq!(
+                    build(dir.path()).unwrap_err(),
+                    "duplicated args_matrix entry"
+                );
+            }
+
+            #[test]
+            fn should_fail_to_build_with_an_undefined_variable() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{ "vars": { "n": "1" }, "detailed_tests": [{ "stdout": "{{m}}\n" }] }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                assert_eq!(
+                    u.build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact
+                    ),
+                    Err("undefined variable in placeholder".to_string())
+                );
+            }
+
+            #[test]
+            fn should_keep_the_placeholders_of_a_unit_test_without_vars() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{ "detailed_tests": [{ "stdin": "{{m}}", "stdout": "{{m}}\n" }] }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                let unit_test = u
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+                let assertion = &unit_test.assertions()[0];
+                assert_eq!(assertion.stdin(), Some(b"{{m}}".as_slice()));
+                assert_eq!(assertion.stdout(), Some("{{m}}\n"));
+            }
+
+            #[test]
+            fn should_carry_the_weight_of_the_unit_test() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{ "weight": 10, "detailed_tests": [{ "stdout": "a\n" }, { "stdout": "b\n", "weight": 2 }] }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                let unit_test = u
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert_eq!(unit_test.weight(), Some(10));
+            }
+
+            #[test]
+            fn should_fail_to_split_the_weight_between_weightless_assertions() {
+                let u: UnitTest = serde_json::from_str(
+                    r#"{ "weight": 10, "detailed_tests": [{ "stdout": "a\n", "weight": 0 }] }"#,
+                )
+                .unwrap();
+                let executables_by_name = HashMap::from_iter([(
+                    "program1".to_string(),
+                    ExecutableArtifact::new_dummy(1),
+                )]);
+
+                assert_eq!(
+                    u.build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "program1",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact
+                    ),
+                    Err(
+                        "the weight of a unit test can not be split between weightless assertions"
+                            .to_string()
+                    )
+                );
+            }
+
+            #[test]
+            fn should_run_the_default_program_without_program_name() {
+                let u: UnitTest =
+                    serde_json::from_str(r#"{ "detailed_tests": [{ "stdout": "ok\n" }] }"#)
+                        .unwrap();
+                let executable = |name: &str| ExecutableArtifact::CompiledProgram {
+                    name: name.to_string(),
+                    path: PathBuf::new(),
+                    base_args: vec![],
+                };
+                let executables_by_name = HashMap::from_iter([
+                    ("program1".to_string(), executable("first")),
+                    ("solver".to_string(), executable("second")),
+                ]);
+
+                let unit_test = u
+                    .build_grading_unit_test(
+                        1,
+                        1,
+                        &executables_by_name,
+                        "solver",
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert_eq!(unit_test.executable_name(), "second");
             }
 
             #[test]
@@ -1577,12 +4758,18 @@ mod tests {
                             ],
                         ],
                     }),
+                    table_file: None,
                     detailed_tests: vec![],
+                    args_matrix: None,
+                    vars: HashMap::new(),
+                    weight: None,
+                    skip: false,
                 };
 
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 let executables_by_name = HashMap::from_iter([
                     ("not some program".to_string(), executable.clone()),
@@ -1590,7 +4777,16 @@ mod tests {
                     ("p1".to_string(), executable.clone()),
                 ]);
                 invalid_unit_test
-                    .build_grading_unit_test(2, &executables_by_name)
+                    .build_grading_unit_test(
+                        2,
+                        1,
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact,
+                    )
                     .unwrap();
             }
 
@@ -1621,6 +4817,7 @@ mod tests {
                         )
                         .unwrap(),
                     ),
+                    None,
                     vec![
                         DetailedTest::build(
                             None,
@@ -1643,12 +4840,17 @@ mod tests {
                         )
                         .unwrap(),
                     ],
+                    None,
+                    HashMap::new(),
+                    None,
+                    false,
                 )
                 .unwrap();
 
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 // TODO (optimization): make the executables by name a map from string to a
                 // reference to an executable instead of the executable itself.
@@ -1658,7 +4860,17 @@ mod tests {
                 ]);
 
                 assert_eq!(
-                    u.build_grading_unit_test(2, &executables_by_name).unwrap(),
+                    u.build_grading_unit_test(
+                        2,
+                        1,
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &[],
+                        Path::new(""),
+                        false,
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
                     GradingUnitTest::new_dummy(
                         "UnitTest1".to_string(),
                         executable,
@@ -1721,15 +4933,23 @@ mod tests {
         test_serialize_and_deserialize!(
             should_serialize_deserialize_full,
             UnitTests {
-                env: vec![
+                env: Some(vec![
                     ("k1".to_string(), "v1".to_string()),
                     ("k2".to_string(), "v2".to_string())
-                ],
-                inherit_parent_env: true,
+                ]),
+                env_file: Some(".env".to_string()),
+                inherit_parent_env: Some(false),
+                env_passthrough: vec!["PATH".to_string()],
+                locale: Some("C.UTF-8".to_string()),
+                timezone: Some("UTC".to_string()),
                 files: vec![("file 1".to_string(), "content 1".to_string())],
 
-                setup: vec!["cmd1 abc".to_string(), "cmd2 abc".to_string()],
-                teardown: vec!["cmd1 abcd".to_string(), "cmd2 abcd".to_string()],
+                setup: Some(vec!["cmd1 abc".to_string(), "cmd2 abc".to_string()]),
+                teardown: Some(vec!["cmd1 abcd".to_string(), "cmd2 abcd".to_string()]),
+                use_shell: true,
+                stateful: true,
+                informational_stderr: true,
+                default_weight: 5,
                 tests: vec![UnitTest::new_dummy(0), UnitTest::new_dummy(1)]
             },
             UnitTests
@@ -1737,15 +4957,84 @@ mod tests {
         test_serialize_and_deserialize!(
             should_serialize_deserialize_empty,
             UnitTests {
-                env: vec![],
-                inherit_parent_env: true,
+                env: None,
+                env_file: None,
+                inherit_parent_env: None,
+                env_passthrough: vec![],
+                locale: None,
+                timezone: None,
                 files: vec![],
-                setup: vec![],
-                teardown: vec![],
+                setup: None,
+                teardown: None,
+                use_shell: false,
+                stateful: false,
+                informational_stderr: false,
+                default_weight: 1,
                 tests: vec![UnitTest::new_dummy(0)]
             },
             UnitTests
         );
+        test_invalid_deserialization!(
+            should_panic_with_empty_locale,
+            r#"
+        {
+            "locale": " ",
+            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+        }"#,
+            UnitTests
+        );
+        test_invalid_deserialization!(
+            should_panic_with_empty_timezone,
+            r#"
+        {
+            "timezone": "",
+            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+        }"#,
+            UnitTests
+        );
+        test_invalid_deserialization!(
+            should_panic_with_env_passthrough_while_inheriting_parent_env,
+            r#"
+        {
+            "inherit_parent_env": true,
+            "env_passthrough": ["PATH"],
+            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+        }"#,
+            UnitTests
+        );
+        test_invalid_deserialization!(
+            should_panic_with_invalid_env_passthrough_name,
+            r#"
+        {
+            "inherit_parent_env": false,
+            "env_passthrough": ["PATH=/bin"],
+            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+        }"#,
+            UnitTests
+        );
+        test_valid_deserialization!(
+            should_accept_env_passthrough_without_parent_env,
+            r#"
+        {
+            "inherit_parent_env": false,
+            "env_passthrough": ["PATH", "HOME"],
+            "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+        }"#,
+            UnitTests
+        );
+        test_valid_deserialization!(
+            should_accept_default_weight,
+            r#"
+        {
+            "default_weight": 5,
+            "tests": [
+                {
+                    "detailed_tests": [{ "args": "a1", "status": 0 }]
+                }
+            ]
+        }"#,
+            UnitTests
+        );
         // invalid deserialization
         test_invalid_deserialization!(should_panic_with_no_content_string, r#"\n"#, UnitTests);
         test_invalid_deserialization!(should_panic_with_empty_object, r#"{}"#, UnitTests);
@@ -1835,6 +5124,15 @@ mod tests {
         }"#,
             UnitTests
         );
+        test_invalid_deserialization!(
+            should_panic_with_blank_env_file,
+            r#"
+        {
+            "env_file":" ",
+            "tests": [{ "detailed_tests":[{ "name":"test 1", "stdout":"hello" }] }]
+        }"#,
+            UnitTests
+        );
         test_valid_deserialization!(
             should_accept_full_test,
             r#"
@@ -1872,15 +5170,23 @@ mod tests {
             #[should_panic]
             fn should_panic_with_empty_setup_command() {
                 let r = UnitTests::build(
+                    Some(vec![]),
+                    None,
+                    Some(true),
                     vec![],
-                    true,
+                    None,
+                    None,
                     vec![],
-                    vec![
+                    Some(vec![
                         "valid command1".to_string(),
                         "".to_string(),
                         "command1 a b c".to_string(),
-                    ],
-                    vec![],
+                    ]),
+                    Some(vec![]),
+                    false,
+                    false,
+                    false,
+                    1,
                     vec![UnitTest::new_dummy(1), UnitTest::new_dummy(2)],
                 )
                 .unwrap();
@@ -1888,6 +5194,7 @@ mod tests {
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 let executables_by_name = HashMap::from_iter([
                     ("some program".to_string(), executable.clone()),
@@ -1895,22 +5202,37 @@ mod tests {
                     ("program2".to_string(), executable.clone()),
                     ("p1".to_string(), executable.clone()),
                 ]);
-                r.build_grading_unit_tests(&executables_by_name).unwrap();
+                r.build_grading_unit_tests(
+                    &executables_by_name,
+                    DEFAULT_MAIN_PROGRAM_NAME,
+                    &DefaultsSection::default(),
+                    Path::new(""),
+                    StdoutMatch::Exact,
+                )
+                .unwrap();
             }
 
             #[test]
             #[should_panic]
             fn should_panic_with_empty_teardown_command() {
                 let r = UnitTests::build(
+                    Some(vec![]),
+                    None,
+                    Some(false),
                     vec![],
-                    false,
-                    vec![],
+                    None,
+                    None,
                     vec![],
-                    vec![
+                    Some(vec![]),
+                    Some(vec![
                         "valid command1".to_string(),
                         "".to_string(),
                         "command1 a b c".to_string(),
-                    ],
+                    ]),
+                    false,
+                    false,
+                    false,
+                    1,
                     vec![UnitTest::new_dummy(1), UnitTest::new_dummy(2)],
                 )
                 .unwrap();
@@ -1918,6 +5240,7 @@ mod tests {
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 let executables_by_name = HashMap::from_iter([
                     ("some program".to_string(), executable.clone()),
@@ -1925,7 +5248,209 @@ mod tests {
                     ("program2".to_string(), executable.clone()),
                     ("p1".to_string(), executable.clone()),
                 ]);
-                r.build_grading_unit_tests(&executables_by_name).unwrap();
+                r.build_grading_unit_tests(
+                    &executables_by_name,
+                    DEFAULT_MAIN_PROGRAM_NAME,
+                    &DefaultsSection::default(),
+                    Path::new(""),
+                    StdoutMatch::Exact,
+                )
+                .unwrap();
+            }
+
+            #[test]
+            fn should_expand_file_placeholders_of_every_test() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"
+                {
+                    "files": [["input.txt", "1 2 3"]],
+                    "tests": [
+                        {
+                            "table": [["args", "status"], ["{input}", 0]],
+                            "detailed_tests": [{ "args": "-v {input}", "status": 0 }]
+                        }
+                    ]
+                }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                let grading_unit_tests = u
+                    .build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                let args: Vec<&[String]> = grading_unit_tests.unit_tests()[0]
+                    .assertions()
+                    .iter()
+                    .map(|assertion| assertion.args())
+                    .collect();
+                assert_eq!(
+                    args,
+                    [
+                        &["input.txt".to_string()][..],
+                        &["-v".to_string(), "input.txt".to_string()][..]
+                    ]
+                );
+            }
+
+            #[test]
+            fn should_not_build_with_unknown_file_placeholder() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"
+                {
+                    "files": [["input.txt", "1 2 3"]],
+                    "tests": [{ "detailed_tests": [{ "args": "{output}", "status": 0 }] }]
+                }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                assert_eq!(
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    ),
+                    Err("unknown file placeholder in args".to_string())
+                );
+            }
+
+            #[test]
+            fn should_check_every_setup_and_teardown_against_the_allowed_commands() {
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+                let defaults = DefaultsSection::default()
+                    .with_allowed_commands(vec!["mkdir".to_string(), "rm".to_string()]);
+                let build = |json: &str| {
+                    serde_json::from_str::<UnitTests>(json)
+                        .unwrap()
+                        .build_grading_unit_tests(
+                            &executables_by_name,
+                            DEFAULT_MAIN_PROGRAM_NAME,
+                            &defaults,
+                            Path::new(""),
+                            StdoutMatch::Exact,
+                        )
+                        .map(|_| ())
+                };
+
+                assert_eq!(
+                    build(
+                        r#"{ "setup": ["mkdir out"], "teardown": ["rm -r out"], "tests": [{ "detailed_tests": [{ "status": 0, "setup": ["mkdir fixture"] }] }] }"#
+                    ),
+                    Ok(())
+                );
+                assert_eq!(
+                    build(
+                        r#"{ "setup": ["mkdir out"], "teardown": ["curl http://example.com"], "tests": [{ "detailed_tests": [{ "status": 0 }] }] }"#
+                    ),
+                    Err("setup or teardown runs a command that is not allowed".to_string())
+                );
+                assert_eq!(
+                    build(
+                        r#"{ "tests": [{ "detailed_tests": [{ "status": 0, "teardown": ["/bin/cp a b"] }] }] }"#
+                    ),
+                    Err("setup or teardown runs a command that is not allowed".to_string())
+                );
+            }
+
+            #[test]
+            fn should_run_setup_and_teardown_through_shell_when_requested() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"
+                {
+                    "setup": ["ls | wc -l > count.txt"],
+                    "teardown": ["rm count.txt"],
+                    "use_shell": true,
+                    "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+                }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                let grading_unit_tests = u
+                    .build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert_eq!(
+                    grading_unit_tests,
+                    GradingUnitTests::new(
+                        vec![],
+                        true,
+                        vec![],
+                        vec![(
+                            "sh".to_string(),
+                            vec!["-c".to_string(), "ls | wc -l > count.txt".to_string()]
+                        )],
+                        vec![(
+                            "sh".to_string(),
+                            vec!["-c".to_string(), "rm count.txt".to_string()]
+                        )],
+                        vec![
+                            u.get_tests()[0]
+                                .build_grading_unit_test(
+                                    1,
+                                    1,
+                                    &executables_by_name,
+                                    DEFAULT_MAIN_PROGRAM_NAME,
+                                    &[],
+                                    Path::new(""),
+                                    false,
+                                    StdoutMatch::Exact
+                                )
+                                .unwrap()
+                        ],
+                    )
+                );
+            }
+
+            #[test]
+            #[should_panic]
+            fn should_panic_with_empty_shell_command() {
+                let u = UnitTests::build(
+                    Some(vec![]),
+                    None,
+                    Some(true),
+                    vec![],
+                    None,
+                    None,
+                    vec![],
+                    Some(vec!["  ".to_string()]),
+                    Some(vec![]),
+                    true,
+                    false,
+                    false,
+                    1,
+                    vec![UnitTest::new_dummy(1)],
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+                u.build_grading_unit_tests(
+                    &executables_by_name,
+                    DEFAULT_MAIN_PROGRAM_NAME,
+                    &DefaultsSection::default(),
+                    Path::new(""),
+                    StdoutMatch::Exact,
+                )
+                .unwrap();
             }
 
             #[test]
@@ -1936,14 +5461,22 @@ mod tests {
                 ];
                 let files = vec![("f1".to_string(), "v1".to_string())];
                 let u = UnitTests::build(
-                    env.clone(),
-                    false,
+                    Some(env.clone()),
+                    None,
+                    Some(false),
+                    vec![],
+                    None,
+                    None,
                     files.clone(),
-                    vec![
+                    Some(vec![
                         "command1 a b c \"hey there\"".to_string(),
                         "command2 a b c".to_string(),
-                    ],
-                    vec!["cm1 a b c".to_string(), "cm2 a b c".to_string()],
+                    ]),
+                    Some(vec!["cm1 a b c".to_string(), "cm2 a b c".to_string()]),
+                    false,
+                    false,
+                    false,
+                    1,
                     vec![
                         UnitTest::new_dummy(1),
                         UnitTest::new_dummy(2),
@@ -1954,6 +5487,7 @@ mod tests {
                 let executable = ExecutableArtifact::CompiledProgram {
                     name: "some name".to_string(),
                     path: PathBuf::new(),
+                    base_args: vec![],
                 };
                 let executables_by_name = HashMap::from_iter([
                     ("some program".to_string(), executable.clone()),
@@ -1962,7 +5496,14 @@ mod tests {
                     ("p1".to_string(), executable.clone()),
                 ]);
                 assert_eq!(
-                    u.build_grading_unit_tests(&executables_by_name).unwrap(),
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
                     GradingUnitTests::new(
                         env,
                         false,
@@ -1994,18 +5535,333 @@ mod tests {
                         ],
                         vec![
                             UnitTest::new_dummy(1)
-                                .build_grading_unit_test(1, &executables_by_name)
+                                .build_grading_unit_test(
+                                    1,
+                                    1,
+                                    &executables_by_name,
+                                    DEFAULT_MAIN_PROGRAM_NAME,
+                                    &[],
+                                    Path::new(""),
+                                    false,
+                                    StdoutMatch::Exact
+                                )
                                 .unwrap(),
                             UnitTest::new_dummy(2)
-                                .build_grading_unit_test(1, &executables_by_name)
+                                .build_grading_unit_test(
+                                    1,
+                                    1,
+                                    &executables_by_name,
+                                    DEFAULT_MAIN_PROGRAM_NAME,
+                                    &[],
+                                    Path::new(""),
+                                    false,
+                                    StdoutMatch::Exact
+                                )
                                 .unwrap(),
                             UnitTest::new_dummy(1)
-                                .build_grading_unit_test(1, &executables_by_name)
+                                .build_grading_unit_test(
+                                    1,
+                                    1,
+                                    &executables_by_name,
+                                    DEFAULT_MAIN_PROGRAM_NAME,
+                                    &[],
+                                    Path::new(""),
+                                    false,
+                                    StdoutMatch::Exact
+                                )
                                 .unwrap(),
                         ]
                     )
                 );
             }
+
+            #[test]
+            fn should_inherit_the_defaults_that_are_not_overridden() {
+                let defaults = DefaultsSection::new(
+                    vec![("k1".to_string(), "default".to_string())],
+                    false,
+                    vec!["s1 a".to_string()],
+                    vec!["t1 b".to_string()],
+                );
+                let u = UnitTests::build(
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    None,
+                    None,
+                    vec![],
+                    Some(vec![]),
+                    None,
+                    false,
+                    false,
+                    false,
+                    1,
+                    vec![UnitTest::new_dummy(1)],
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                assert_eq!(
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &defaults,
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    )
+                    .unwrap(),
+                    GradingUnitTests::new(
+                        vec![("k1".to_string(), "default".to_string())],
+                        false,
+                        vec![],
+                        vec![],
+                        vec![("t1".to_string(), vec!["b".to_string()])],
+                        vec![
+                            UnitTest::new_dummy(1)
+                                .build_grading_unit_test(
+                                    1,
+                                    1,
+                                    &executables_by_name,
+                                    DEFAULT_MAIN_PROGRAM_NAME,
+                                    &[],
+                                    Path::new(""),
+                                    false,
+                                    StdoutMatch::Exact
+                                )
+                                .unwrap()
+                        ],
+                    )
+                );
+            }
+
+            #[test]
+            fn should_clear_the_default_env_with_an_empty_one() {
+                let defaults = DefaultsSection::new(
+                    vec![("k1".to_string(), "default".to_string())],
+                    true,
+                    vec![],
+                    vec![],
+                );
+                let build_with_env = |env| {
+                    UnitTests::build(
+                        env,
+                        None,
+                        None,
+                        vec![],
+                        None,
+                        None,
+                        vec![],
+                        None,
+                        None,
+                        false,
+                        false,
+                        false,
+                        1,
+                        vec![UnitTest::new_dummy(1)],
+                    )
+                    .unwrap()
+                };
+
+                assert_eq!(
+                    build_with_env(Some(vec![])).build_env(&defaults, Path::new("")),
+                    Ok(vec![])
+                );
+                assert_eq!(
+                    build_with_env(None).build_env(&defaults, Path::new("")),
+                    Ok(vec![("k1".to_string(), "default".to_string())])
+                );
+            }
+
+            #[test]
+            fn should_check_env_passthrough_against_the_default_parent_env() {
+                let u = UnitTests::build(
+                    None,
+                    None,
+                    None,
+                    vec!["HOME".to_string()],
+                    None,
+                    None,
+                    vec![],
+                    None,
+                    None,
+                    false,
+                    false,
+                    false,
+                    1,
+                    vec![UnitTest::new_dummy(1)],
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+                let isolated = DefaultsSection::new(vec![], false, vec![], vec![]);
+
+                assert_eq!(
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    ),
+                    Err("env_passthrough requires inherit_parent_env to be false".to_string())
+                );
+                assert!(
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &isolated,
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    )
+                    .is_ok()
+                );
+            }
+
+            #[test]
+            fn should_make_the_stderr_of_every_assertion_informational() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"{
+                        "informational_stderr": true,
+                        "tests": [{ "detailed_tests": [{ "stderr": "" }, { "status": 0 }] }]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+                let unit_tests = u
+                    .build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+
+                assert!(
+                    unit_tests.unit_tests()[0]
+                        .assertions()
+                        .iter()
+                        .all(UnitTestAssertion::has_informational_stderr)
+                );
+            }
+
+            #[test]
+            fn should_carry_stateful() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"{ "stateful": true, "tests": [{ "detailed_tests": [{ "status": 0 }] }] }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                assert!(
+                    u.build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact
+                    )
+                    .unwrap()
+                    .is_stateful()
+                );
+            }
+
+            #[test]
+            fn should_carry_locale_and_timezone() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"{
+                        "locale": "C.UTF-8",
+                        "timezone": "UTC",
+                        "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+
+                let unit_tests = u
+                    .build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &DefaultsSection::default(),
+                        Path::new(""),
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+                assert_eq!(unit_tests.locale(), Some("C.UTF-8"));
+                assert_eq!(unit_tests.timezone(), Some("UTC"));
+            }
+
+            #[test]
+            fn should_take_locale_and_timezone_from_the_defaults() {
+                let u: UnitTests = serde_json::from_str(
+                    r#"{
+                        "timezone": "UTC",
+                        "tests": [{ "detailed_tests": [{ "status": 0 }] }]
+                    }"#,
+                )
+                .unwrap();
+                let executables_by_name =
+                    HashMap::from([("program1".to_string(), ExecutableArtifact::new_dummy(1))]);
+                let defaults = DefaultsSection::default()
+                    .with_locale("C.UTF-8".to_string())
+                    .with_timezone("America/Sao_Paulo".to_string());
+
+                let unit_tests = u
+                    .build_grading_unit_tests(
+                        &executables_by_name,
+                        DEFAULT_MAIN_PROGRAM_NAME,
+                        &defaults,
+                        Path::new(""),
+                        StdoutMatch::Exact,
+                    )
+                    .unwrap();
+                assert_eq!(unit_tests.locale(), Some("C.UTF-8"));
+                assert_eq!(unit_tests.timezone(), Some("UTC"));
+            }
+
+            #[test]
+            fn should_merge_env_file_giving_precedence_to_env() {
+                let dir = tempfile::tempdir().unwrap();
+                fs::write(dir.path().join("test.env"), "k1=file\nk3=v3\n").unwrap();
+                let u = UnitTests::build(
+                    Some(vec![
+                        ("k1".to_string(), "v1".to_string()),
+                        ("k2".to_string(), "v2".to_string()),
+                    ]),
+                    Some("test.env".to_string()),
+                    Some(true),
+                    vec![],
+                    None,
+                    None,
+                    vec![],
+                    Some(vec![]),
+                    Some(vec![]),
+                    false,
+                    false,
+                    false,
+                    1,
+                    vec![UnitTest::new_dummy(1)],
+                )
+                .unwrap();
+
+                assert_eq!(
+                    u.build_env(&DefaultsSection::default(), dir.path()),
+                    Ok(vec![
+                        ("k3".to_string(), "v3".to_string()),
+                        ("k1".to_string(), "v1".to_string()),
+                        ("k2".to_string(), "v2".to_string()),
+                    ])
+                );
+                assert_eq!(
+                    u.build_env(&DefaultsSection::default(), &dir.path().join("other")),
+                    Err("could not read env_file")
+                );
+            }
         }
     }
 }
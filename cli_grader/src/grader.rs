@@ -1,9 +1,43 @@
+pub mod builder;
+pub mod diff;
+pub mod error;
 pub(crate) mod grading_tests;
+pub mod log_context;
 pub mod score;
+pub mod termination;
 
+use crate::grader::builder::GradingConfigBuilder;
+use crate::grader::error::GraderError;
+use crate::grader::grading_tests::unit_test::clear_parent_env;
 use crate::grader::grading_tests::{GradindTestsResult, GradingTests};
+use crate::grader::log_context::LogContext;
 use crate::grader::score::GradingMode;
+use crate::report::{
+    DEFAULT_OUTPUT_HEAD_LINES, DEFAULT_OUTPUT_TAIL_LINES, Reporter, SilentReporter, truncate_output,
+};
+use crate::utils::shuffled_indices;
 use score::Score;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Environment variable through which the seed of the run is exposed to every program.
+pub const SEED_ENV_VAR: &str = "GRADER_SEED";
+
+/// Environment variable through which the directory of the global setup and teardown is
+/// exposed to every program, e.g. to read a dataset prepared once for the assignment.
+pub const GLOBAL_DIR_ENV_VAR: &str = "GRADER_GLOBAL_DIR";
+
+/// Whether `deadline` is set and already passed, so no other test must be started.
+pub(crate) fn is_past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
 
 /// A semantic unit that stores one type of assessment. It also has a name and a weight
 /// multiplier.
@@ -11,46 +45,163 @@ use score::Score;
 pub struct GradingTestSection {
     name: String, // Default: `Section <number>`
     weight: u32,  // Default: 1
+    /// Bonus section: its score is added to the final one, but not to the maximum, so
+    /// it can only increase the grade.
+    extra_credit: bool,
     tests: GradingTests,
 }
 
 impl GradingTestSection {
-    fn run(&self, grading_mode: GradingMode) -> GradingTestSectionResult {
-        let mut result = GradingTestSectionResult::new(self.name.clone(), grading_mode);
-        let test_results = self.tests.run(grading_mode);
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &self,
+        grading_mode: GradingMode,
+        seed: u64,
+        global_dir: &Path,
+        shuffle_seed: Option<u64>,
+        max_parallelism: usize,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
+    ) -> Result<GradingTestSectionResult, GraderError> {
+        let _context = LogContext::enter_section(&self.name);
+        let mut result =
+            GradingTestSectionResult::new(self.name.clone(), grading_mode, self.extra_credit);
+        let start = Instant::now();
+        let test_results = self
+            .tests
+            .run(
+                grading_mode,
+                seed,
+                global_dir,
+                shuffle_seed,
+                max_parallelism,
+                interrupted,
+                deadline,
+            )
+            .map_err(|source| GraderError::Section {
+                section: self.name.clone(),
+                source,
+            })?;
+        result.set_duration(start.elapsed());
         result.set_test_results(test_results, self.weight);
-        result
+        Ok(result)
     }
 
     pub fn new(name: String, weight: u32, tests: GradingTests) -> Self {
         Self {
             name,
             weight,
+            extra_credit: false,
             tests,
         }
     }
+
+    pub fn with_extra_credit(mut self, extra_credit: bool) -> Self {
+        self.extra_credit = extra_credit;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn is_extra_credit(&self) -> bool {
+        self.extra_credit
+    }
+
+    pub fn tests(&self) -> &GradingTests {
+        &self.tests
+    }
+
+    /// Maximum score in `Weighted` mode, already multiplied by the weight of the section,
+    /// or `None` if it does not fit in an `u32`.
+    fn max_score(&self) -> Option<u32> {
+        self.tests.max_score()?.checked_mul(self.weight)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct GradingTestSectionResult {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GradingTestSectionResult {
     name: String, // Default: `Section <number>`
     score: Score,
+    /// Multiplier applied to the score of the tests to get `score`.
+    weight: u32,
+    extra_credit: bool,
     test_results: Option<GradindTestsResult>,
+    /// Total wall-clock time spent running the tests of the section.
+    duration: Duration,
+}
+
+/// The `duration` is ignored, as it is a measurement and not part of the outcome.
+impl PartialEq for GradingTestSectionResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.score == other.score
+            && self.weight == other.weight
+            && self.extra_credit == other.extra_credit
+            && self.test_results == other.test_results
+    }
 }
 
+impl Eq for GradingTestSectionResult {}
+
 impl GradingTestSectionResult {
-    fn new(name: String, grading_mode: GradingMode) -> Self {
+    fn new(name: String, grading_mode: GradingMode, extra_credit: bool) -> Self {
         Self {
             name,
             score: Score::default(grading_mode),
+            weight: 1,
+            extra_credit,
             test_results: None,
+            duration: Duration::ZERO,
         }
     }
 
+    fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
     fn set_test_results(&mut self, test_results: GradindTestsResult, weight: u32) {
         self.score = test_results.score() * weight;
+        self.weight = weight;
         self.test_results = Some(test_results);
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The score of the tests multiplied by the weight of the section.
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// The score of the tests before applying the weight of the section.
+    pub fn raw_score(&self) -> Score {
+        self.test_results
+            .as_ref()
+            .map_or(self.score, GradindTestsResult::score)
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn is_extra_credit(&self) -> bool {
+        self.extra_credit
+    }
+
+    pub fn test_results(&self) -> Option<&GradindTestsResult> {
+        self.test_results.as_ref()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
 }
 
 /// This document has all the configuration for a complete assessment of one or more
@@ -60,7 +211,31 @@ pub struct GradingConfig {
     name: String,
     author: Option<String>,
     grading_mode: GradingMode,
+    /// Commands executed only once, before the first section.
+    global_setup: Vec<(String, Vec<String>)>,
+    /// Commands executed only once, after the last section.
+    global_teardown: Vec<(String, Vec<String>)>,
+    /// Environment variables of the global setup and teardown.
+    global_env: Vec<(String, String)>,
+    /// Whether the global setup and teardown inherit the environment of the parent
+    /// process. Even if they do not, `PATH` is kept unless `global_env` sets it.
+    global_inherit_parent_env: bool,
+    /// Seed exposed to the programs. If it is not set, a random one is used in each run.
+    seed: Option<u64>,
     grading_sections: Vec<GradingTestSection>,
+    /// Names of the sections disabled in the configuration. They are not executed, only
+    /// reported.
+    skipped_sections: Vec<String>,
+    /// How many lines are kept from the start and from the end of the output of failed
+    /// global commands.
+    output_lines: (usize, usize),
+    /// Maximum number of unit tests of a section that run at the same time.
+    max_parallelism: usize,
+    /// Seed of the order in which the sections and their unit tests are executed. If it
+    /// is not set, they are executed in declaration order.
+    shuffle_seed: Option<u64>,
+    /// Maximum duration of a whole run, after which the remaining tests are not run.
+    deadline: Option<Duration>,
 }
 
 impl GradingConfig {
@@ -69,60 +244,636 @@ impl GradingConfig {
             name,
             author,
             grading_mode,
+            global_setup: vec![],
+            global_teardown: vec![],
+            global_env: vec![],
+            global_inherit_parent_env: true,
+            seed: None,
             grading_sections: vec![],
+            skipped_sections: vec![],
+            output_lines: (DEFAULT_OUTPUT_HEAD_LINES, DEFAULT_OUTPUT_TAIL_LINES),
+            max_parallelism: 1,
+            shuffle_seed: None,
+            deadline: None,
         }
     }
 
+    /// Starts building a configuration from code, see `GradingConfigBuilder`.
+    pub fn builder(name: impl Into<String>) -> GradingConfigBuilder {
+        GradingConfigBuilder::new(name.into())
+    }
+
     pub fn add_grading_section(&mut self, grading_section: GradingTestSection) {
         self.grading_sections.push(grading_section);
     }
 
-    fn run(&self) -> GradingResult {
-        let mut result =
-            GradingResult::new(self.name.clone(), self.author.clone(), self.grading_mode);
+    pub fn add_skipped_section(&mut self, name: String) {
+        self.skipped_sections.push(name);
+    }
+
+    pub fn skipped_sections(&self) -> &[String] {
+        &self.skipped_sections
+    }
+
+    pub fn set_global_setup(&mut self, global_setup: Vec<(String, Vec<String>)>) {
+        self.global_setup = global_setup;
+    }
+
+    pub fn set_global_teardown(&mut self, global_teardown: Vec<(String, Vec<String>)>) {
+        self.global_teardown = global_teardown;
+    }
+
+    /// Environment of the global setup and teardown, handled like the one of the setup of
+    /// the unit tests, e.g. the `defaults` of the configuration. The seed and
+    /// `GLOBAL_DIR_ENV_VAR` are added to `env`.
+    pub fn set_global_env(&mut self, env: Vec<(String, String)>, inherit_parent_env: bool) {
+        self.global_env = env;
+        self.global_inherit_parent_env = inherit_parent_env;
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Lines kept from the start (`head`) and from the end (`tail`) of the captured output
+    /// of failed global commands, see `truncate_output`. Both default to 10.
+    pub fn set_output_lines(&mut self, head: usize, tail: usize) {
+        self.output_lines = (head, tail);
+    }
+
+    /// Maximum number of unit tests of a section that run at the same time. It defaults
+    /// to 1, running everything in order, and 0 is treated as 1.
+    ///
+    /// The sections are still graded one after the other, so they are reported in order
+    /// and the performance tests are timed without the noise of other programs.
+    pub fn set_max_parallelism(&mut self, max_parallelism: usize) {
+        self.max_parallelism = max_parallelism.max(1);
+    }
+
+    pub fn max_parallelism(&self) -> usize {
+        self.max_parallelism
+    }
+
+    /// Executes the sections, and the unit tests of each section, in an order shuffled
+    /// with `shuffle_seed`, e.g. to find tests that depend on each other. The same seed
+    /// always gives the same order.
+    ///
+    /// Only the execution order changes: the results are still reported in declaration
+    /// order and the score is the same.
+    pub fn set_shuffle_seed(&mut self, shuffle_seed: u64) {
+        self.shuffle_seed = Some(shuffle_seed);
+    }
+
+    pub fn shuffle_seed(&self) -> Option<u64> {
+        self.shuffle_seed
+    }
+
+    /// Bounds the duration of a whole run, e.g. to give CI an upper bound regardless of
+    /// the number of tests. Once it is exceeded, no other test is started: the remaining
+    /// ones fail as not run, so they still count towards the maximum score, and the result
+    /// is marked as past the deadline. The running tests and the global teardown are
+    /// still waited for.
+    pub fn set_deadline(&mut self, deadline: Duration) {
+        self.deadline = Some(deadline);
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn grading_mode(&self) -> GradingMode {
+        self.grading_mode
+    }
+
+    pub fn grading_sections(&self) -> &[GradingTestSection] {
+        &self.grading_sections
+    }
+
+    /// Maximum score in `Weighted` mode, or `None` if it does not fit in an `u32`. The
+    /// extra credit sections are not part of it.
+    pub fn max_score(&self) -> Option<u32> {
+        self.grading_sections
+            .iter()
+            .filter(|section| !section.extra_credit)
+            .try_fold(0u32, |sum, section| sum.checked_add(section.max_score()?))
+    }
+
+    /// Highest score reachable in `Weighted` mode, extra credit included, or `None` if it
+    /// does not fit in an `u32`.
+    ///
+    /// The score is saturated while grading, so a configuration for which this is `None`
+    /// would have a meaningless score.
+    pub fn max_reachable_score(&self) -> Option<u32> {
+        self.grading_sections
+            .iter()
+            .try_fold(0u32, |sum, section| sum.checked_add(section.max_score()?))
+    }
+
+    /// Makes every assertion keep the raw stdout, stderr and exit status of its program in
+    /// its result, e.g. to check them in ways that the configuration can not express. It
+    /// is disabled by default, as the outputs may take a lot of memory.
+    pub fn set_retain_output(&mut self, retain_output: bool) {
+        for section in &mut self.grading_sections {
+            section.tests.set_retain_output(retain_output);
+        }
+    }
+
+    /// Keeps only the assertions tagged with `tag`, removing everything that ends up
+    /// empty. It fails if no assertion has the tag.
+    pub fn retain_tagged(&mut self, tag: &str) -> Result<(), &'static str> {
+        for section in &mut self.grading_sections {
+            section.tests.retain_tagged(tag);
+        }
+        self.grading_sections
+            .retain(|section| !section.tests.is_empty());
+        if self.grading_sections.is_empty() {
+            return Err("no assertion has the given tag");
+        }
+        Ok(())
+    }
+
+    fn run(&self) -> Result<GradingResult, GraderError> {
+        self.run_with_reporter(&mut SilentReporter, &AtomicBool::new(false))
+    }
+
+    /// Runs every section, notifying `reporter` as soon as each one of them finishes
+    /// and, in the end, with the aggregated result.
+    ///
+    /// The global setup is executed before the first section and the global teardown
+    /// after the last one, both inside the same temporary directory, which lives during
+    /// the whole run and is exposed to every program in `GLOBAL_DIR_ENV_VAR`. If any of
+    /// the global setup commands fails, nothing is graded. Once the setup succeeds, the
+    /// teardown runs even if a section or the reporter fails. A failed teardown does not
+    /// discard the result, it is recorded in it instead, see
+    /// `GradingResult::global_teardown_error`.
+    ///
+    /// Every program is executed with the seed of the run in `SEED_ENV_VAR`.
+    ///
+    /// With a shuffle seed, the sections are executed in a shuffled order, but each one is
+    /// reported once all the ones declared before it are complete, so the reporter and the
+    /// result still get them in declaration order.
+    ///
+    /// Once `interrupted` is set, no other assertion is started: the sections that were
+    /// not reached are left out, the global teardown is executed anyway and the result
+    /// is marked as interrupted. Past the deadline, see `set_deadline`, the remaining
+    /// tests fail as not run instead.
+    ///
+    /// Failures of the programs are part of the result. It only fails if the grading
+    /// itself can not go on, e.g. a file of a section can not be created.
+    fn run_with_reporter(
+        &self,
+        reporter: &mut dyn Reporter,
+        interrupted: &AtomicBool,
+    ) -> Result<GradingResult, GraderError> {
+        let seed = self.seed.unwrap_or_else(random_seed);
+        log::info!("Using seed {seed}");
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+        let mut result = GradingResult::new(
+            self.name.clone(),
+            self.author.clone(),
+            self.grading_mode,
+            seed,
+        );
+        result.skipped_sections = self.skipped_sections.clone();
+        reporter
+            .on_grading_start(&result)
+            .map_err(GraderError::Report)?;
+
+        let tmp_dir = tempfile::tempdir().inspect_err(|err| {
+            log::error!("error while creating the global temporary directory");
+            log::debug!("error: {err:?}");
+        })?;
+        let mut global_env = self.global_env.clone();
+        global_env.push((SEED_ENV_VAR.to_string(), seed.to_string()));
+        global_env.push((
+            GLOBAL_DIR_ENV_VAR.to_string(),
+            tmp_dir.path().to_string_lossy().into_owned(),
+        ));
+        run_global_commands(
+            "global setup",
+            &self.global_setup,
+            tmp_dir.path(),
+            &global_env,
+            self.global_inherit_parent_env,
+            self.output_lines,
+        )?;
+
+        let graded = self.run_sections(
+            reporter,
+            interrupted,
+            seed,
+            tmp_dir.path(),
+            deadline,
+            &mut result,
+        );
+        let teardown = run_global_commands(
+            "global teardown",
+            &self.global_teardown,
+            tmp_dir.path(),
+            &global_env,
+            self.global_inherit_parent_env,
+            self.output_lines,
+        );
+        graded?;
+        if let Err(err) = teardown {
+            result.global_teardown_error = Some(err.to_string());
+        }
+        reporter
+            .on_grading_complete(&result)
+            .map_err(GraderError::Report)?;
+        Ok(result)
+    }
+
+    /// Runs the sections of `run_with_reporter` and adds their results to `result`.
+    fn run_sections(
+        &self,
+        reporter: &mut dyn Reporter,
+        interrupted: &AtomicBool,
+        seed: u64,
+        global_dir: &Path,
+        deadline: Option<Instant>,
+        result: &mut GradingResult,
+    ) -> Result<(), GraderError> {
+        let order = match self.shuffle_seed {
+            Some(shuffle_seed) => shuffled_indices(self.grading_sections.len(), shuffle_seed),
+            None => (0..self.grading_sections.len()).collect(),
+        };
+        let mut section_results: Vec<Option<GradingTestSectionResult>> =
+            self.grading_sections.iter().map(|_| None).collect();
+        let mut next_to_report = 0;
+        for i in order {
+            if interrupted.load(Ordering::Relaxed) {
+                break;
+            }
+            // every section shuffles its unit tests differently
+            let section_shuffle_seed = self
+                .shuffle_seed
+                .map(|shuffle_seed| shuffle_seed.wrapping_add(i as u64 + 1));
+            section_results[i] = Some(self.grading_sections[i].run(
+                self.grading_mode,
+                seed,
+                global_dir,
+                section_shuffle_seed,
+                self.max_parallelism,
+                interrupted,
+                deadline,
+            )?);
+            while let Some(Some(section_result)) = section_results.get_mut(next_to_report) {
+                reporter
+                    .on_section_complete(section_result)
+                    .map_err(GraderError::Report)?;
+                next_to_report += 1;
+            }
+        }
+        // the sections completed after one that was not reached because of an interruption
+        for section_result in section_results[next_to_report..].iter().flatten() {
+            reporter
+                .on_section_complete(section_result)
+                .map_err(GraderError::Report)?;
+        }
+        for section_result in section_results.into_iter().flatten() {
+            result.add_section_result(section_result);
+        }
+        result.interrupted = interrupted.load(Ordering::Relaxed);
+        result.deadline_exceeded = is_past(deadline);
+        Ok(())
+    }
+}
 
-        for sec in &self.grading_sections {
-            result.add_section_result(sec.run(self.grading_mode));
+/// Executes each command in `dir` with `envs`, failing if any of them can not be executed
+/// or does not finish successfully. The `stage` is only used to give context to the
+/// errors, which carry the stderr truncated to the `(head, tail)` lines of `output_lines`.
+fn run_global_commands(
+    stage: &'static str,
+    commands: &[(String, Vec<String>)],
+    dir: &Path,
+    envs: &[(String, String)],
+    inherit_parent_env: bool,
+    output_lines: (usize, usize),
+) -> Result<(), GraderError> {
+    for (cmd, args) in commands {
+        log::info!("Executing {stage} command: '{cmd}'");
+        let mut command = process::Command::new(cmd);
+        if !inherit_parent_env {
+            clear_parent_env(&mut command, envs);
+        }
+        let output = command
+            .args(args)
+            .current_dir(dir)
+            .envs(envs.iter().map(|(key, value)| (key, value)))
+            .output()
+            .map_err(|err| {
+                log::error!("error while executing {stage}");
+                log::debug!("error: {err:?}");
+                GraderError::GlobalCommand {
+                    stage,
+                    command: cmd.clone(),
+                    reason: format!("failed: {err}"),
+                    stderr: String::new(),
+                }
+            })?;
+        if !output.status.success() {
+            log::error!("{stage} command '{cmd}' did not finish successfully");
+            log::debug!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            return Err(GraderError::GlobalCommand {
+                stage,
+                command: cmd.clone(),
+                reason: format!("finished with {}", output.status),
+                stderr: truncate_output(
+                    &String::from_utf8_lossy(&output.stderr),
+                    output_lines.0,
+                    output_lines.1,
+                ),
+            });
         }
-        result
     }
+    Ok(())
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// A seed that is different in each call, without depending on an external crate.
+fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GradingResult {
     name: String,
     author: Option<String>,
     score: Score,
+    /// Seed exposed to the programs. Running again with it reproduces the run.
+    seed: u64,
+    /// When the run started, in seconds since the Unix epoch.
+    started_at: u64,
     grading_section_results: Vec<GradingTestSectionResult>,
+    /// Names of the sections that were not executed because they are skipped.
+    skipped_sections: Vec<String>,
+    /// Whether the run was interrupted, so some of the tests were not executed.
+    #[serde(default)]
+    interrupted: bool,
+    /// Whether the run went past its deadline, so some of the tests may have failed
+    /// without being run.
+    #[serde(default)]
+    deadline_exceeded: bool,
+    /// Why the global teardown failed, if it did. The result is complete anyway.
+    #[serde(default)]
+    global_teardown_error: Option<String>,
+}
+
+/// The `started_at` is ignored, as it depends on when the run happened and not on its
+/// outcome.
+impl PartialEq for GradingResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.author == other.author
+            && self.score == other.score
+            && self.seed == other.seed
+            && self.grading_section_results == other.grading_section_results
+            && self.skipped_sections == other.skipped_sections
+            && self.interrupted == other.interrupted
+            && self.deadline_exceeded == other.deadline_exceeded
+            && self.global_teardown_error == other.global_teardown_error
+    }
 }
 
+impl Eq for GradingResult {}
+
 impl GradingResult {
-    fn new(name: String, author: Option<String>, grading_mode: GradingMode) -> Self {
+    fn new(name: String, author: Option<String>, grading_mode: GradingMode, seed: u64) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
         Self {
             name,
             author,
             score: Score::default(grading_mode),
+            seed,
+            started_at,
             grading_section_results: vec![],
+            skipped_sections: vec![],
+            interrupted: false,
+            deadline_exceeded: false,
+            global_teardown_error: None,
         }
     }
 
+    /// The score of an extra credit section only adds to `current`, so failing it never
+    /// lowers the grade.
     fn add_section_result(&mut self, grading_section_result: GradingTestSectionResult) {
-        self.score += grading_section_result.score;
+        if !grading_section_result.extra_credit {
+            self.score += grading_section_result.score;
+        } else if let Score::Weighted { current, .. } = grading_section_result.score {
+            self.score += Score::Weighted { current, max: 0 };
+        }
         self.grading_section_results.push(grading_section_result);
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Title of the assignment, the same as `name`.
+    pub fn title(&self) -> &str {
+        &self.name
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// Final score with the maximum reachable one, as every report shows it, see
+    /// `Score::summary`.
+    pub fn score_line(&self) -> String {
+        self.score.summary()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// When the run started, in seconds since the Unix epoch.
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+
+    /// Whether the final score reaches `threshold`, a percentage of the maximum score.
+    pub fn meets_threshold(&self, threshold: u32) -> bool {
+        self.score.meets_threshold(threshold)
+    }
+
+    pub fn section_results(&self) -> &[GradingTestSectionResult] {
+        &self.grading_section_results
+    }
+
+    pub fn skipped_sections(&self) -> &[String] {
+        &self.skipped_sections
+    }
+
+    /// Whether the run was interrupted, in which case the result is incomplete.
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// Whether the run went past its deadline, see `GradingConfig::set_deadline`.
+    pub fn is_deadline_exceeded(&self) -> bool {
+        self.deadline_exceeded
+    }
+
+    /// Why the global teardown failed, e.g. so what the setup created can be cleaned by
+    /// hand.
+    pub fn global_teardown_error(&self) -> Option<&str> {
+        self.global_teardown_error.as_deref()
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// Results of several configurations graded for the same programs, e.g. one per problem
+/// of an assignment, each one labeled, e.g. with the path of its configuration.
+///
+/// The scores are summed, so each configuration weighs as much as its maximum score.
+/// When every score is `Absolute`, the combined one is `Absolute` too and only passes if
+/// all of them do. Otherwise, it is `Weighted` and an `Absolute` score counts as `1` out
+/// of `1` when it passes and as `0` out of `1` when it fails.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct CombinedGradingResult {
+    results: Vec<(String, GradingResult)>,
+}
+
+impl CombinedGradingResult {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_result(&mut self, label: String, result: GradingResult) {
+        self.results.push((label, result));
+    }
+
+    /// The labeled results, in the order they were added.
+    pub fn results(&self) -> &[(String, GradingResult)] {
+        &self.results
+    }
+
+    pub fn score(&self) -> Score {
+        let scores = self.results.iter().map(|(_, result)| result.score());
+        if scores
+            .clone()
+            .all(|score| matches!(score, Score::Absolute(_)))
+        {
+            return Score::Absolute(scores.clone().all(|score| score.is_pass()));
+        }
+        scores.fold(Score::default(GradingMode::Weighted), |mut sum, score| {
+            sum += Score::Weighted {
+                current: score.current(),
+                max: score.max(),
+            };
+            sum
+        })
+    }
+
+    /// Combined score with the maximum reachable one, see `GradingResult::score_line`.
+    pub fn score_line(&self) -> String {
+        self.score().summary()
+    }
+
+    /// Whether the combined score reaches `threshold`, a percentage of the maximum score.
+    pub fn meets_threshold(&self, threshold: u32) -> bool {
+        self.score().meets_threshold(threshold)
+    }
+
+    /// Whether any of the runs was interrupted, in which case the result is incomplete.
+    pub fn is_interrupted(&self) -> bool {
+        self.results
+            .iter()
+            .any(|(_, result)| result.is_interrupted())
+    }
+}
+
+/// Results of the same configuration graded for several submissions, e.g. the ones of a
+/// manifest, each one labeled with the identifier of its submission.
+///
+/// Unlike in `CombinedGradingResult`, the scores are not summed, as each submission is
+/// graded on its own.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct BatchResult {
+    results: Vec<(String, GradingResult)>,
+}
+
+impl BatchResult {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_result(&mut self, id: String, result: GradingResult) {
+        self.results.push((id, result));
+    }
+
+    /// The results by submission, in the order they were added.
+    pub fn results(&self) -> &[(String, GradingResult)] {
+        &self.results
+    }
+
+    /// Number of submissions whose score reaches `threshold`, a percentage of the maximum
+    /// score.
+    pub fn count_meeting_threshold(&self, threshold: u32) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, result)| result.meets_threshold(threshold))
+            .count()
+    }
+
+    /// Whether the score of every submission reaches `threshold`.
+    pub fn meets_threshold(&self, threshold: u32) -> bool {
+        self.count_meeting_threshold(threshold) == self.results.len()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Grader<'a> {
     config: &'a GradingConfig,
+    /// Flag that stops the run when it is set, e.g. from a signal handler.
+    interrupted: Option<&'a AtomicBool>,
 }
 
 impl<'a> Grader<'a> {
     pub fn new(config: &'a GradingConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            interrupted: None,
+        }
+    }
+
+    /// Stops the run as soon as `interrupted` is set, letting the assertions that are
+    /// running finish, so the partial result can still be reported.
+    pub fn with_interruption_flag(mut self, interrupted: &'a AtomicBool) -> Self {
+        self.interrupted = Some(interrupted);
+        self
+    }
+
+    pub fn run(&self) -> Result<GradingResult, GraderError> {
+        self.run_with_reporter(&mut SilentReporter)
     }
-    pub fn run(&self) -> GradingResult {
-        self.config.run()
+
+    /// Same as `run`, but streams the results to `reporter` while grading.
+    pub fn run_with_reporter(
+        &self,
+        reporter: &mut dyn Reporter,
+    ) -> Result<GradingResult, GraderError> {
+        match self.interrupted {
+            Some(interrupted) => self.config.run_with_reporter(reporter, interrupted),
+            None => self
+                .config
+                .run_with_reporter(reporter, &AtomicBool::new(false)),
+        }
     }
 }
 
@@ -173,6 +924,7 @@ mod tests {
                             ExecutableArtifact::CompiledProgram{
                                  name: "program1".to_string(),
                                  path:"cat".into(),
+                                 base_args: vec![],
                             },
                         )
                         .with_assertion(Assertion::new_dummy(1, true, false, true, Some(2), 2))
@@ -182,6 +934,7 @@ mod tests {
                             ExecutableArtifact::CompiledProgram{
                                  name: "program2".to_string(),
                                  path:"echo".into(),
+                                 base_args: vec![],
                             },
                         )
                         .with_assertion(Assertion::new_dummy(1, true, true, true, None, 2)),
@@ -210,6 +963,7 @@ mod tests {
                             ExecutableArtifact::CompiledProgram {
                                 name: "program2".to_string(),
                                 path: "cat2".into(),
+                                base_args: vec![],
                             },
                         )
                         .with_assertion(Assertion::new_dummy(3, true, false, true, Some(2), 2))
@@ -220,6 +974,7 @@ mod tests {
                             ExecutableArtifact::CompiledProgram {
                                 name: "program4".to_string(),
                                 path: "echo".into(),
+                                base_args: vec![],
                             },
                         )
                         .with_assertion(Assertion::new_dummy(6, true, true, true, None, 2)),
@@ -228,6 +983,7 @@ mod tests {
                             ExecutableArtifact::CompiledProgram {
                                 name: "program5".to_string(),
                                 path: "echo5".into(),
+                                base_args: vec![],
                             },
                         )
                         .with_assertion(Assertion::new_dummy(7, true, true, true, None, 2)),
@@ -243,12 +999,16 @@ mod tests {
     mod grader_tests {
         use super::*;
         use crate::{
-            grader::grading_tests::unit_test::{
-                UnitTest, UnitTestResult, UnitTests, UnitTestsResult, assertion::Assertion,
+            grader::grading_tests::{
+                performance_test::{PerformanceTest, PerformanceTests},
+                unit_test::{
+                    UnitTest, UnitTestResult, UnitTests, UnitTestsResult,
+                    assertion::{Assertion, AssertionResult, ExecutionStatus, ExpectedStatus},
+                },
             },
             input::ExecutableArtifact,
         };
-        use std::vec;
+        use std::{io, vec};
 
         #[test_log::test]
         fn should_cat_a_file() {
@@ -270,6 +1030,7 @@ mod tests {
             let target_program = ExecutableArtifact::CompiledProgram {
                 name: "program1".to_string(),
                 path: "cat".into(),
+                base_args: vec![],
             };
             // Add the first grading section
             let assertion1 = Assertion::build(
@@ -282,7 +1043,9 @@ mod tests {
                 1,
             )
             .unwrap();
-            let expected_assertion1 = assertion1.expected_result(None, true, None, None, None);
+            let expected_assertion1 = assertion1
+                .expected_result(None, true, None, None, None)
+                .with_reproduction_command("cat file.txt".to_string());
             let assertion2 = Assertion::build(
                 "should return \"hello   world\"".to_string(),
                 vec!["file2.txt".to_string()],
@@ -293,7 +1056,9 @@ mod tests {
                 13,
             )
             .unwrap();
-            let expected_assertion2 = assertion2.expected_result(None, true, None, None, None);
+            let expected_assertion2 = assertion2
+                .expected_result(None, true, None, None, None)
+                .with_reproduction_command("cat file2.txt".to_string());
             let section1_tests = GradingTests::UnitTests(UnitTests::new(
                 vec![],
                 true,
@@ -312,8 +1077,9 @@ mod tests {
             let section1 = GradingTestSection::new("section 1".to_string(), 1, section1_tests);
 
             config.add_grading_section(section1.clone());
+            config.set_seed(7);
 
-            let result = config.run();
+            let result = config.run().unwrap();
 
             assert_eq!(
                 result,
@@ -324,12 +1090,16 @@ mod tests {
                         current: 14,
                         max: 14
                     },
+                    seed: 7,
+                    started_at: 0,
                     grading_section_results: vec![GradingTestSectionResult {
                         name: "section 1".to_string(),
                         score: Score::Weighted {
                             current: 14,
                             max: 14
                         },
+                        weight: 1,
+                        extra_credit: false,
                         test_results: Some(GradindTestsResult::UnitTests(
                             UnitTestsResult::new_with(
                                 Score::Weighted {
@@ -347,9 +1117,1561 @@ mod tests {
                                 ]
                             )
                         )),
-                    }]
+                        duration: Duration::ZERO,
+                    }],
+                    skipped_sections: vec![],
+                    interrupted: false,
+                    deadline_exceeded: false,
+                    global_teardown_error: None,
+                }
+            );
+        }
+
+        #[derive(Default)]
+        struct RecordingReporter {
+            events: Vec<String>,
+        }
+
+        impl Reporter for RecordingReporter {
+            fn on_section_complete(
+                &mut self,
+                section_result: &GradingTestSectionResult,
+            ) -> io::Result<()> {
+                self.events
+                    .push(format!("section: {}", section_result.name));
+                Ok(())
+            }
+
+            fn on_grading_complete(&mut self, result: &GradingResult) -> io::Result<()> {
+                self.events.push(format!("complete: {}", result.score));
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn should_notify_reporter_as_each_section_completes() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            for i in 1..=3 {
+                let assertion = Assertion::build(
+                    format!("assertion {i}"),
+                    vec![],
+                    None,
+                    None,
+                    None,
+                    Some(0),
+                    i,
+                )
+                .unwrap();
+                let tests = GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![
+                        UnitTest::new(
+                            "unit test".to_string(),
+                            ExecutableArtifact::CompiledProgram {
+                                name: "program1".to_string(),
+                                path: "true".into(),
+                                base_args: vec![],
+                            },
+                        )
+                        .with_assertion(assertion),
+                    ],
+                ));
+                config.add_grading_section(GradingTestSection::new(format!("s{i}"), 1, tests));
+            }
+            config.set_seed(1);
+
+            let mut reporter = RecordingReporter::default();
+            let result = Grader::new(&config)
+                .run_with_reporter(&mut reporter)
+                .unwrap();
+
+            assert_eq!(
+                reporter.events,
+                vec!["section: s1", "section: s2", "section: s3", "complete: 6/6"]
+            );
+            assert_eq!(result, config.run().unwrap());
+        }
+
+        /// Section with a single assertion of weight `weight` that runs `program` and
+        /// expects it to succeed.
+        fn status_section(name: &str, program: &str, weight: u32) -> GradingTestSection {
+            let assertion =
+                Assertion::build(name.to_string(), vec![], None, None, None, Some(0), weight)
+                    .unwrap();
+            let unit_test = UnitTest::new(
+                "unit test".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: program.into(),
+                    base_args: vec![],
+                },
+            )
+            .with_assertion(assertion);
+            GradingTestSection::new(
+                name.to_string(),
+                1,
+                GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![unit_test],
+                )),
+            )
+        }
+
+        #[test]
+        fn should_serialize_and_deserialize_the_result() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(status_section("base ok", "true", 3));
+            config.add_grading_section(status_section("base ko", "false", 1));
+            config.add_grading_section(status_section("bonus", "true", 2).with_extra_credit(true));
+            config.add_grading_section(GradingTestSection::new(
+                "performance".to_string(),
+                1,
+                GradingTests::PerformanceTests(PerformanceTests::new(vec![
+                    PerformanceTest::build(
+                        "fast".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "true".into(),
+                            base_args: vec![],
+                        },
+                        vec![],
+                        None,
+                        Duration::from_secs(5),
+                        None,
+                        1,
+                    )
+                    .unwrap(),
+                ])),
+            ));
+            config.add_skipped_section("skipped".to_string());
+            let result = config.run().unwrap();
+
+            let json = serde_json::to_string_pretty(&result).unwrap();
+            let re_deserialized: GradingResult = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(re_deserialized, result);
+            assert_eq!(
+                re_deserialized.section_results()[0].duration(),
+                result.section_results()[0].duration()
+            );
+            assert!(result.started_at() > 0);
+            assert_eq!(re_deserialized.started_at(), result.started_at());
+        }
+
+        #[test]
+        fn should_add_extra_credit_only_to_the_current_score() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(status_section("base ok", "true", 3));
+            config.add_grading_section(status_section("base ko", "false", 1));
+            config.add_grading_section(status_section("bonus", "true", 2).with_extra_credit(true));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(config.max_score(), Some(4));
+            assert_eq!(config.max_reachable_score(), Some(6));
+            assert_eq!(result.score(), Score::Weighted { current: 5, max: 4 });
+            assert!(result.meets_threshold(100));
+            assert!(result.section_results()[2].is_extra_credit());
+        }
+
+        #[test]
+        fn should_split_the_weight_of_a_unit_test_between_its_assertions() {
+            // 1 out of 3 assertions of the same weight is worth 10 / 3 = 3.33 points
+            let status_assertion = |name: &str, args: &str| {
+                Assertion::new(
+                    name.to_string(),
+                    vec!["-c".to_string(), args.to_string()],
+                    None,
+                    1,
+                )
+                .with_expected_status(ExpectedStatus::Exact(0))
+            };
+            let unit_test = UnitTest::new(
+                "unit test".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: "sh".into(),
+                    base_args: vec![],
+                },
+            )
+            .with_weight(10)
+            .with_assertion(status_assertion("passes", "exit 0"))
+            .with_assertion(status_assertion("fails", "exit 1"))
+            .with_assertion(status_assertion("fails too", "exit 1"));
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 2, tests));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(config.max_score(), Some(20));
+            assert_eq!(
+                result.score(),
+                Score::Weighted {
+                    current: 6,
+                    max: 20
                 }
             );
         }
+
+        #[test]
+        fn should_not_lower_the_score_when_extra_credit_fails() {
+            for grading_mode in [GradingMode::Weighted, GradingMode::Absolute] {
+                let mut config = GradingConfig::new("config".to_string(), None, grading_mode);
+                config.add_grading_section(status_section("base", "true", 2));
+                let expected = config.run().unwrap().score();
+                config.add_grading_section(
+                    status_section("bonus", "false", 2).with_extra_credit(true),
+                );
+
+                let result = config.run().unwrap();
+
+                assert_eq!(result.score(), expected);
+                assert!(result.meets_threshold(100));
+            }
+        }
+
+        #[test]
+        fn should_expose_the_result_tree() {
+            let mut config = GradingConfig::new(
+                "config".to_string(),
+                Some("author".to_string()),
+                GradingMode::Weighted,
+            );
+            let assertion = Assertion::build(
+                "should say bye".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("bye\n".to_string()),
+                None,
+                None,
+                2,
+            )
+            .unwrap();
+            let unit_test = UnitTest::new(
+                "echo".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: "echo".into(),
+                    base_args: vec![],
+                },
+            )
+            .with_assertion(assertion);
+            config.add_grading_section(GradingTestSection::new(
+                "s1".to_string(),
+                1,
+                GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![unit_test],
+                )),
+            ));
+
+            let result = Grader::new(&config).run().unwrap();
+
+            assert_eq!(result.name(), "config");
+            assert_eq!(result.author(), Some("author"));
+            assert_eq!(result.score(), Score::Weighted { current: 0, max: 2 });
+            let [section_result] = result.section_results() else {
+                panic!("expected exactly one section result");
+            };
+            assert_eq!(section_result.name(), "s1");
+            let Some(GradindTestsResult::UnitTests(unit_tests_result)) =
+                section_result.test_results()
+            else {
+                panic!("expected unit tests result");
+            };
+            let [unit_test_result] = unit_tests_result.unit_test_results() else {
+                panic!("expected exactly one unit test result");
+            };
+            assert_eq!(unit_test_result.name(), "echo");
+            assert_eq!(unit_test_result.executable_name(), "program1");
+            let [assertion_result] = unit_test_result.assertion_results() else {
+                panic!("expected exactly one assertion result");
+            };
+            assert_eq!(assertion_result.name(), "should say bye");
+            assert!(!assertion_result.passed());
+            assert_eq!(
+                assertion_result.execution_status(),
+                ExecutionStatus::Success
+            );
+            let stdout_diagnostics = assertion_result.stdout_diagnostics().unwrap();
+            assert_eq!(stdout_diagnostics.expected(), "bye\n");
+            assert_eq!(
+                stdout_diagnostics.obtained().map(String::as_str),
+                Some("hello\n")
+            );
+            assert!(assertion_result.status_diagnostics().is_none());
+        }
+
+        fn append_to_log_cmd(log: &std::path::Path, line: &str) -> (String, Vec<String>) {
+            (
+                "sh".to_string(),
+                vec![
+                    "-c".to_string(),
+                    format!("echo {line} >> '{}'", log.display()),
+                ],
+            )
+        }
+
+        #[test]
+        fn should_run_global_setup_and_teardown_once() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![append_to_log_cmd(&log, "setup")]);
+            config.set_global_teardown(vec![append_to_log_cmd(&log, "teardown")]);
+            for i in 1..=2 {
+                let (cmd, args) = append_to_log_cmd(&log, &format!("section{i}"));
+                let assertion =
+                    Assertion::build(format!("assertion {i}"), args, None, None, None, Some(0), 1)
+                        .unwrap();
+                let tests = GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![
+                        UnitTest::new(
+                            "unit test".to_string(),
+                            ExecutableArtifact::CompiledProgram {
+                                name: "program1".to_string(),
+                                path: cmd.into(),
+                                base_args: vec![],
+                            },
+                        )
+                        .with_assertion(assertion),
+                    ],
+                ));
+                config.add_grading_section(GradingTestSection::new(format!("s{i}"), 1, tests));
+            }
+
+            config.run().unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(&log).unwrap(),
+                "setup\nsection1\nsection2\nteardown\n"
+            );
+        }
+
+        /// Section where the second unit test reads what the first one and the setup
+        /// wrote in their working directory.
+        fn workspace_config(stateful: bool, log: &std::path::Path) -> GradingConfig {
+            let sh_unit_test = |name: &str, script: &str, stdout: &str| {
+                UnitTest::new(
+                    name.to_string(),
+                    ExecutableArtifact::CompiledProgram {
+                        name: "program1".to_string(),
+                        path: "sh".into(),
+                        base_args: vec![],
+                    },
+                )
+                .with_assertion(
+                    Assertion::new(
+                        name.to_string(),
+                        vec!["-c".to_string(), script.to_string()],
+                        None,
+                        1,
+                    )
+                    .with_stdout(stdout.to_string()),
+                )
+            };
+            let tests = GradingTests::UnitTests(
+                UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![(
+                        "sh".to_string(),
+                        vec!["-c".to_string(), "echo setup >> setup.txt".to_string()],
+                    )],
+                    vec![append_to_log_cmd(log, "teardown")],
+                    vec![
+                        sh_unit_test("build", "echo built > artifact.txt", ""),
+                        sh_unit_test("run", "cat artifact.txt setup.txt", "built\nsetup\n"),
+                    ],
+                )
+                .with_stateful(stateful),
+            );
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+            config.set_max_parallelism(2);
+            config
+        }
+
+        #[test]
+        fn should_share_the_workspace_between_the_unit_tests_when_stateful() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let result = workspace_config(true, &log).run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 2, max: 2 });
+            assert_eq!(std::fs::read_to_string(&log).unwrap(), "teardown\n");
+        }
+
+        #[test]
+        fn should_isolate_the_unit_tests_when_not_stateful() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let result = workspace_config(false, &log).run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 2 });
+            assert_eq!(
+                std::fs::read_to_string(&log).unwrap(),
+                "teardown\nteardown\n"
+            );
+        }
+
+        #[test]
+        fn should_run_the_setup_and_teardown_of_an_assertion_around_it() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+            let cat_fixture = |name: &str| {
+                Assertion::new(name.to_string(), vec!["fixture.txt".to_string()], None, 1)
+                    .with_stdout("fixture\n".to_string())
+            };
+            let fixture_setup = vec![
+                append_to_log_cmd(&log, "assertion setup"),
+                (
+                    "sh".to_string(),
+                    vec!["-c".to_string(), "echo fixture > fixture.txt".to_string()],
+                ),
+            ];
+            let unit_test = UnitTest::new(
+                "cat".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: "cat".into(),
+                    base_args: vec![],
+                },
+            )
+            .with_assertion(
+                cat_fixture("with fixture")
+                    .with_setup(fixture_setup)
+                    .with_teardown(vec![append_to_log_cmd(&log, "assertion teardown")]),
+            )
+            .with_assertion(cat_fixture("without fixture"))
+            .with_assertion(
+                cat_fixture("with failed setup")
+                    .with_setup(vec![("false".to_string(), vec![])])
+                    .with_teardown(vec![append_to_log_cmd(&log, "unexpected teardown")]),
+            );
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![append_to_log_cmd(&log, "setup")],
+                vec![append_to_log_cmd(&log, "teardown")],
+                vec![unit_test],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 3 });
+            let Some(GradindTestsResult::UnitTests(unit_tests_result)) =
+                result.section_results()[0].test_results()
+            else {
+                panic!("expected the results of unit tests");
+            };
+            let execution_statuses: Vec<_> = unit_tests_result.unit_test_results()[0]
+                .assertion_results()
+                .iter()
+                .map(AssertionResult::execution_status)
+                .collect();
+            assert_eq!(
+                execution_statuses,
+                [
+                    ExecutionStatus::Success,
+                    ExecutionStatus::FailureWithStatus(1),
+                    ExecutionStatus::FailureInSetup
+                ]
+            );
+            assert_eq!(
+                std::fs::read_to_string(&log).unwrap(),
+                "setup\nassertion setup\nassertion teardown\nteardown\n\
+                 setup\nteardown\n\
+                 setup\nteardown\n"
+            );
+        }
+
+        #[test]
+        fn should_run_the_teardowns_when_an_assertion_stops_midway() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+            let assertion = Assertion::new("cwd".to_string(), vec![], None, 1)
+                .with_stdout(String::new())
+                .with_cwd("missing".into())
+                .with_teardown(vec![append_to_log_cmd(&log, "assertion teardown")])
+                .check()
+                .unwrap();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![append_to_log_cmd(&log, "teardown")],
+                vec![
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "true".into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion),
+                ],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+
+            assert!(config.run().is_err());
+            assert_eq!(
+                std::fs::read_to_string(&log).unwrap(),
+                "assertion teardown\nteardown\n"
+            );
+        }
+        #[test]
+        fn should_retain_the_output_of_every_assertion_when_asked() {
+            let assertion = Assertion::new("status".to_string(), vec!["hi".to_string()], None, 1)
+                .with_status(0)
+                .check()
+                .unwrap();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "echo".into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion),
+                ],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+            config.set_retain_output(true);
+
+            let result = config.run().unwrap();
+
+            let Some(GradindTestsResult::UnitTests(unit_tests_result)) =
+                result.section_results()[0].test_results()
+            else {
+                panic!("expected unit tests result");
+            };
+            let assertion_result = &unit_tests_result.unit_test_results()[0].assertion_results()[0];
+            assert!(assertion_result.passed());
+            assert_eq!(assertion_result.retained_stdout(), Some(b"hi\n".as_slice()));
+            assert_eq!(assertion_result.retained_stderr(), Some(b"".as_slice()));
+            assert!(assertion_result.retained_status().unwrap().success());
+        }
+
+        #[test]
+        fn should_abort_when_global_setup_fails() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![("false".to_string(), vec![])]);
+            config.set_global_teardown(vec![append_to_log_cmd(&log, "teardown")]);
+            let mut reporter = RecordingReporter::default();
+
+            let err = Grader::new(&config)
+                .run_with_reporter(&mut reporter)
+                .unwrap_err();
+
+            assert!(err.to_string().contains("global setup command 'false'"));
+            assert!(reporter.events.is_empty());
+            assert!(
+                !log.exists(),
+                "teardown should not run after a failed setup"
+            );
+        }
+
+        /// Section with a single unit test that runs `script` with `sh` and expects
+        /// `stdout`.
+        fn sh_section(name: &str, script: &str, stdout: &str) -> GradingTestSection {
+            let unit_test = UnitTest::new(
+                "unit test".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: "sh".into(),
+                    base_args: vec![],
+                },
+            )
+            .with_assertion(
+                Assertion::new(
+                    "assertion".to_string(),
+                    vec!["-c".to_string(), script.to_string()],
+                    None,
+                    1,
+                )
+                .with_stdout(stdout.to_string()),
+            );
+            GradingTestSection::new(
+                name.to_string(),
+                1,
+                GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![unit_test],
+                )),
+            )
+        }
+
+        #[test]
+        fn should_run_the_global_teardown_after_a_failing_section() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![append_to_log_cmd(&log, "setup")]);
+            config.set_global_teardown(vec![append_to_log_cmd(&log, "teardown")]);
+            config.add_grading_section(GradingTestSection::new(
+                "section 1".to_string(),
+                1,
+                GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![("____missing_setup_command".to_string(), vec![])],
+                    vec![],
+                    vec![
+                        UnitTest::new("unit test".to_string(), ExecutableArtifact::new_dummy(1))
+                            .with_assertion(Assertion::new(
+                                "assertion".to_string(),
+                                vec![],
+                                None,
+                                1,
+                            )),
+                    ],
+                )),
+            ));
+
+            let err = config.run().unwrap_err();
+
+            assert!(matches!(err, GraderError::Section { .. }));
+            assert_eq!(std::fs::read_to_string(&log).unwrap(), "setup\nteardown\n");
+        }
+
+        #[test]
+        fn should_keep_the_result_when_the_global_teardown_fails() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_teardown(vec![("false".to_string(), vec![])]);
+            config.add_grading_section(sh_section("s1", "echo hi", "hi\n"));
+            let mut reporter = RecordingReporter::default();
+
+            let result = Grader::new(&config)
+                .run_with_reporter(&mut reporter)
+                .unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+            assert_eq!(
+                result.global_teardown_error(),
+                Some("global teardown command 'false' finished with exit status: 1")
+            );
+            assert_eq!(reporter.events, ["section: s1", "complete: 1/1"]);
+        }
+
+        #[test]
+        fn should_expose_the_directory_of_the_global_setup_to_the_tests() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![(
+                "sh".to_string(),
+                vec!["-c".to_string(), "echo prepared > dataset.txt".to_string()],
+            )]);
+            config.add_grading_section(sh_section(
+                "s1",
+                &format!("cat \"${GLOBAL_DIR_ENV_VAR}/dataset.txt\""),
+                "prepared\n",
+            ));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        #[test]
+        fn should_run_the_global_commands_with_the_global_env() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_env(vec![("GREETING".to_string(), "hi".to_string())], false);
+            config.set_global_setup(vec![(
+                "sh".to_string(),
+                vec![
+                    "-c".to_string(),
+                    "echo \"$GREETING ${CARGO:-cleared}\" > env.txt".to_string(),
+                ],
+            )]);
+            config.add_grading_section(sh_section(
+                "s1",
+                &format!("cat \"${GLOBAL_DIR_ENV_VAR}/env.txt\""),
+                "hi cleared\n",
+            ));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        #[test]
+        fn should_report_the_truncated_stderr_of_a_failed_global_command() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![(
+                "sh".to_string(),
+                vec!["-c".to_string(), "seq 1 50 >&2; exit 1".to_string()],
+            )]);
+            config.set_output_lines(2, 1);
+
+            let err = config.run().unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "global setup command 'sh' finished with exit status: 1:\n\
+                 1\n\
+                 2\n\
+                 ... (47 lines omitted) ...\n\
+                 50"
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_when_a_section_can_not_run() {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            let unit_test = UnitTest::new(
+                "unit test".to_string(),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: "true".into(),
+                    base_args: vec![],
+                },
+            )
+            .with_assertion(
+                Assertion::build(
+                    "assertion".to_string(),
+                    vec![],
+                    None,
+                    None,
+                    None,
+                    Some(0),
+                    1,
+                )
+                .unwrap(),
+            );
+            config.add_grading_section(GradingTestSection::new(
+                "section 1".to_string(),
+                1,
+                GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![("____missing_setup_command".to_string(), vec![])],
+                    vec![],
+                    vec![unit_test],
+                )),
+            ));
+            let mut reporter = RecordingReporter::default();
+
+            let err = Grader::new(&config)
+                .run_with_reporter(&mut reporter)
+                .unwrap_err();
+
+            assert!(matches!(&err, GraderError::Section { section, .. } if section == "section 1"));
+            assert!(
+                err.to_string()
+                    .starts_with("error while running section 'section 1': ")
+            );
+            assert!(reporter.events.is_empty());
+        }
+
+        #[test]
+        fn should_expose_the_seed_to_every_program() {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.set_global_setup(vec![append_to_log_cmd(&log, "setup-$GRADER_SEED")]);
+            config.set_seed(42);
+            let assertion = Assertion::build(
+                "seed".to_string(),
+                vec!["-c".to_string(), format!("echo ${SEED_ENV_VAR}")],
+                None,
+                Some("42\n".to_string()),
+                None,
+                Some(0),
+                1,
+            )
+            .unwrap();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                false,
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "sh".into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion),
+                ],
+            ));
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.seed(), 42);
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+            assert_eq!(std::fs::read_to_string(&log).unwrap(), "setup-42\n");
+        }
+
+        fn cwd_config(cwd: &str) -> GradingConfig {
+            let assertion = Assertion::new(
+                "cwd".to_string(),
+                vec!["-c".to_string(), "basename \"$(pwd)\"".to_string()],
+                None,
+                1,
+            )
+            .with_stdout("src\n".to_string())
+            .with_cwd(cwd.into())
+            .check()
+            .unwrap();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![(
+                    "mkdir".to_string(),
+                    vec!["-p".to_string(), "project/src".to_string()],
+                )],
+                vec![],
+                vec![
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "sh".into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion),
+                ],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+            config
+        }
+
+        #[test]
+        fn should_launch_the_program_from_the_cwd_created_by_the_setup() {
+            let result = cwd_config("project/src").run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        #[test]
+        fn should_return_an_error_when_the_cwd_does_not_exist() {
+            let err = cwd_config("project/missing").run().unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "error while running section 's1': working directory 'project/missing' does not exist after the setup"
+            );
+        }
+
+        #[test]
+        fn should_pass_through_only_listed_parent_env() {
+            let assertion = Assertion::build(
+                "env".to_string(),
+                vec![
+                    "-c".to_string(),
+                    "echo \"$PATH|$CARGO_MANIFEST_DIR|$K1\"".to_string(),
+                ],
+                None,
+                Some(format!("{}||v1\n", std::env::var("PATH").unwrap())),
+                None,
+                Some(0),
+                1,
+            )
+            .unwrap();
+            let tests = GradingTests::UnitTests(
+                UnitTests::new(
+                    vec![("K1".to_string(), "v1".to_string())],
+                    false,
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![
+                        UnitTest::new(
+                            "unit test".to_string(),
+                            ExecutableArtifact::CompiledProgram {
+                                name: "program1".to_string(),
+                                path: "/bin/sh".into(),
+                                base_args: vec![],
+                            },
+                        )
+                        .with_assertion(assertion),
+                    ],
+                )
+                .with_env_passthrough(vec!["PATH".to_string(), "____UNSET_VAR".to_string()]),
+            );
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+        #[test]
+        fn should_pin_the_locale_and_the_timezone_of_every_program() {
+            let assertion = Assertion::new(
+                "locale".to_string(),
+                vec![
+                    "-c".to_string(),
+                    "cat setup.txt; echo \"$LC_ALL $LANG $TZ\"".to_string(),
+                ],
+                None,
+                1,
+            )
+            .with_stdout("C.UTF-8 C.UTF-8 UTC\nC.UTF-8 C.UTF-8 UTC\n".to_string())
+            .check()
+            .unwrap();
+            let tests = GradingTests::UnitTests(
+                UnitTests::new(
+                    // the pinned ones take precedence
+                    vec![
+                        ("LANG".to_string(), "en_US.UTF-8".to_string()),
+                        ("TZ".to_string(), "Europe/Madrid".to_string()),
+                    ],
+                    true,
+                    vec![],
+                    vec![(
+                        "sh".to_string(),
+                        vec![
+                            "-c".to_string(),
+                            "echo \"$LC_ALL $LANG $TZ\" > setup.txt".to_string(),
+                        ],
+                    )],
+                    vec![],
+                    vec![
+                        UnitTest::new(
+                            "unit test".to_string(),
+                            ExecutableArtifact::CompiledProgram {
+                                name: "program1".to_string(),
+                                path: "/bin/sh".into(),
+                                base_args: vec![],
+                            },
+                        )
+                        .with_assertion(assertion),
+                    ],
+                )
+                .with_locale("C.UTF-8".to_string())
+                .with_timezone("UTC".to_string()),
+            );
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        /// Many `echo` and `cat` unit tests, where every fifth one fails.
+        fn many_unit_tests_config(
+            grading_mode: GradingMode,
+            max_parallelism: usize,
+        ) -> GradingConfig {
+            let unit_tests = (0..60)
+                .map(|i| {
+                    let expected = if i % 5 == 0 {
+                        "wrong\n".to_string()
+                    } else {
+                        format!("{i}\n")
+                    };
+                    let (path, assertion) = if i % 2 == 0 {
+                        (
+                            "echo",
+                            Assertion::new(format!("echo {i}"), vec![i.to_string()], None, 1),
+                        )
+                    } else {
+                        (
+                            "cat",
+                            Assertion::new(format!("cat {i}"), vec![], Some(format!("{i}\n")), 1),
+                        )
+                    };
+                    UnitTest::new(
+                        format!("unit test {i}"),
+                        ExecutableArtifact::CompiledProgram {
+                            name: path.to_string(),
+                            path: path.into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion.with_stdout(expected))
+                })
+                .collect();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                unit_tests,
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, grading_mode);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+            config.set_seed(3);
+            config.set_max_parallelism(max_parallelism);
+            config
+        }
+
+        #[test]
+        fn should_grade_in_parallel_as_in_order() {
+            for grading_mode in [GradingMode::Weighted, GradingMode::Absolute] {
+                let sequential = many_unit_tests_config(grading_mode, 1).run().unwrap();
+                let parallel = many_unit_tests_config(grading_mode, 8).run().unwrap();
+
+                assert_eq!(parallel, sequential);
+                let names: Vec<_> = parallel.grading_section_results[0]
+                    .test_results()
+                    .and_then(|results| match results {
+                        GradindTestsResult::UnitTests(results) => Some(results),
+                        GradindTestsResult::PerformanceTests(_) => None,
+                    })
+                    .unwrap()
+                    .unit_test_results()
+                    .iter()
+                    .map(|result| result.name().to_string())
+                    .collect();
+                let expected_names: Vec<_> = (0..60).map(|i| format!("unit test {i}")).collect();
+                assert_eq!(names, expected_names);
+            }
+            assert_eq!(
+                many_unit_tests_config(GradingMode::Weighted, 8)
+                    .run()
+                    .unwrap()
+                    .score(),
+                Score::Weighted {
+                    current: 48,
+                    max: 60
+                }
+            );
+            assert_eq!(
+                many_unit_tests_config(GradingMode::Absolute, 8)
+                    .run()
+                    .unwrap()
+                    .score(),
+                Score::Absolute(false)
+            );
+        }
+
+        /// Sections whose unit tests append `<section>-<unit test>` to `log` when executed.
+        fn logging_config(log: &std::path::Path, shuffle_seed: Option<u64>) -> GradingConfig {
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            for i in 0..4 {
+                let unit_tests = (0..4)
+                    .map(|j| {
+                        let (_, args) = append_to_log_cmd(log, &format!("{i}-{j}"));
+                        UnitTest::new(
+                            format!("unit test {j}"),
+                            ExecutableArtifact::CompiledProgram {
+                                name: "sh".to_string(),
+                                path: "sh".into(),
+                                base_args: vec![],
+                            },
+                        )
+                        // the second unit test of every section fails
+                        .with_assertion(
+                            Assertion::build(
+                                format!("log {j}"),
+                                args,
+                                None,
+                                None,
+                                None,
+                                Some(if j == 1 { 1 } else { 0 }),
+                                1,
+                            )
+                            .unwrap(),
+                        )
+                    })
+                    .collect();
+                let tests = GradingTests::UnitTests(UnitTests::new(
+                    vec![],
+                    true,
+                    vec![],
+                    vec![],
+                    vec![],
+                    unit_tests,
+                ));
+                config.add_grading_section(GradingTestSection::new(format!("s{i}"), 1, tests));
+            }
+            config.set_seed(3);
+            if let Some(shuffle_seed) = shuffle_seed {
+                config.set_shuffle_seed(shuffle_seed);
+            }
+            config
+        }
+
+        fn run_logging_config(shuffle_seed: Option<u64>) -> (GradingResult, Vec<String>, String) {
+            let log_dir = tempfile::tempdir().unwrap();
+            let log = log_dir.path().join("log.txt");
+            let mut reporter = RecordingReporter::default();
+            let result = logging_config(&log, shuffle_seed)
+                .run_with_reporter(&mut reporter, &AtomicBool::new(false))
+                .unwrap();
+            (
+                result,
+                reporter.events,
+                std::fs::read_to_string(&log).unwrap(),
+            )
+        }
+
+        #[test]
+        fn should_run_in_a_shuffled_order_but_report_in_declaration_order() {
+            let (in_order, in_order_events, in_order_log) = run_logging_config(None);
+            let (shuffled, shuffled_events, shuffled_log) = run_logging_config(Some(5));
+
+            let declaration_order: Vec<_> = (0..4)
+                .flat_map(|i| (0..4).map(move |j| format!("{i}-{j}")))
+                .collect();
+            assert_eq!(in_order_log.lines().collect::<Vec<_>>(), declaration_order);
+            assert_ne!(shuffled_log, in_order_log);
+            let mut executed: Vec<_> = shuffled_log.lines().collect();
+            executed.sort();
+            assert_eq!(executed, declaration_order);
+
+            assert_eq!(shuffled_events, in_order_events);
+            assert_eq!(shuffled.score(), in_order.score());
+            assert_eq!(shuffled.failed_assertions(), in_order.failed_assertions());
+        }
+
+        #[test]
+        fn should_shuffle_the_same_way_with_the_same_seed() {
+            let (_, _, first_log) = run_logging_config(Some(5));
+            let (_, _, second_log) = run_logging_config(Some(5));
+            let (_, _, other_log) = run_logging_config(Some(6));
+
+            assert_eq!(first_log, second_log);
+            assert_ne!(first_log, other_log);
+        }
+
+        #[test]
+        fn should_treat_no_parallelism_as_running_in_order() {
+            let mut config = many_unit_tests_config(GradingMode::Weighted, 0);
+            assert_eq!(config.max_parallelism(), 1);
+
+            config.set_max_parallelism(4);
+
+            assert_eq!(config.max_parallelism(), 4);
+        }
+
+        fn isolated_env_config(env: Vec<(String, String)>, expected_path: String) -> GradingConfig {
+            let assertion =
+                Assertion::new("path".to_string(), vec!["path.txt".to_string()], None, 1)
+                    .with_stdout(format!("{expected_path}\n"))
+                    .check()
+                    .unwrap();
+            let tests = GradingTests::UnitTests(UnitTests::new(
+                env,
+                false,
+                vec![],
+                // given by name, so it is looked up in the `PATH`
+                vec![(
+                    "sh".to_string(),
+                    vec!["-c".to_string(), "echo \"$PATH\" > path.txt".to_string()],
+                )],
+                vec![],
+                vec![
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "/bin/cat".into(),
+                            base_args: vec![],
+                        },
+                    )
+                    .with_assertion(assertion),
+                ],
+            ));
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(GradingTestSection::new("s1".to_string(), 1, tests));
+            config
+        }
+
+        #[test]
+        fn should_keep_the_parent_path_without_inheriting_the_env() {
+            let config = isolated_env_config(vec![], std::env::var("PATH").unwrap());
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        #[test]
+        fn should_replace_the_parent_path_with_the_configured_one() {
+            let path = format!("/bin:{}", std::env::var("PATH").unwrap());
+            let config = isolated_env_config(vec![("PATH".to_string(), path.clone())], path);
+
+            let result = config.run().unwrap();
+
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        /// Two sections, the first one with three assertions that take 300ms each.
+        fn sleep_config() -> GradingConfig {
+            sleep_config_with_weight(None)
+        }
+
+        /// Same as `sleep_config`, but the unit tests have `weight`.
+        fn sleep_config_with_weight(weight: Option<u32>) -> GradingConfig {
+            let section = |title: &str, size: usize| {
+                let mut unit_test = (0..size).fold(
+                    UnitTest::new(
+                        "sleep".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "sleep".into(),
+                            base_args: vec![],
+                        },
+                    ),
+                    |unit_test, i| {
+                        unit_test.with_assertion(
+                            Assertion::new(format!("sleep {i}"), vec!["0.3".to_string()], None, 1)
+                                .with_status(0),
+                        )
+                    },
+                );
+                if let Some(weight) = weight {
+                    unit_test = unit_test.with_weight(weight);
+                }
+                GradingTestSection::new(
+                    title.to_string(),
+                    1,
+                    GradingTests::UnitTests(UnitTests::new(
+                        vec![],
+                        true,
+                        vec![],
+                        vec![],
+                        vec![],
+                        vec![unit_test],
+                    )),
+                )
+            };
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(section("s1", 3));
+            config.add_grading_section(section("s2", 1));
+            config
+        }
+
+        #[test]
+        fn should_not_run_anything_once_interrupted() {
+            let config = sleep_config();
+            let interrupted = AtomicBool::new(true);
+
+            let result = Grader::new(&config)
+                .with_interruption_flag(&interrupted)
+                .run()
+                .unwrap();
+
+            assert!(result.is_interrupted());
+            assert!(result.section_results().is_empty());
+        }
+
+        #[test]
+        fn should_finish_only_the_running_assertion_when_interrupted() {
+            let config = sleep_config();
+            let interrupted = AtomicBool::new(false);
+
+            let result = std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    std::thread::sleep(Duration::from_millis(100));
+                    interrupted.store(true, Ordering::Relaxed);
+                });
+                Grader::new(&config)
+                    .with_interruption_flag(&interrupted)
+                    .run()
+                    .unwrap()
+            });
+
+            assert!(result.is_interrupted());
+            assert_eq!(result.section_results().len(), 1);
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+
+        #[test]
+        fn should_not_scale_up_the_weight_of_an_interrupted_unit_test() {
+            let config = sleep_config_with_weight(Some(10));
+            let interrupted = AtomicBool::new(false);
+
+            let result = std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    std::thread::sleep(Duration::from_millis(100));
+                    interrupted.store(true, Ordering::Relaxed);
+                });
+                Grader::new(&config)
+                    .with_interruption_flag(&interrupted)
+                    .run()
+                    .unwrap()
+            });
+
+            assert!(result.is_interrupted());
+            assert_eq!(result.section_results().len(), 1);
+            // 1 out of the 3 assertions of the same weight, even if only 1 of them ran
+            assert_eq!(
+                result.score(),
+                Score::Weighted {
+                    current: 3,
+                    max: 10
+                }
+            );
+        }
+        #[test]
+        fn should_fail_the_tests_not_started_before_the_deadline() {
+            let mut config = sleep_config();
+            config.set_deadline(Duration::from_millis(100));
+
+            let result = config.run().unwrap();
+
+            assert!(result.is_deadline_exceeded());
+            assert!(!result.is_interrupted());
+            assert_eq!(result.section_results().len(), 2);
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 4 });
+            let statuses: Vec<_> = result
+                .section_results()
+                .iter()
+                .flat_map(|section| match section.test_results() {
+                    Some(GradindTestsResult::UnitTests(result)) => result
+                        .unit_test_results()
+                        .iter()
+                        .flat_map(|unit_test| unit_test.assertion_results())
+                        .map(|assertion| assertion.execution_status())
+                        .collect::<Vec<_>>(),
+                    _ => vec![],
+                })
+                .collect();
+            assert_eq!(
+                statuses,
+                [
+                    ExecutionStatus::Success,
+                    ExecutionStatus::DeadlineExceeded,
+                    ExecutionStatus::DeadlineExceeded,
+                    ExecutionStatus::DeadlineExceeded,
+                ]
+            );
+        }
+
+        #[test]
+        fn should_not_mark_the_deadline_as_exceeded_when_finishing_in_time() {
+            let mut config = sleep_config();
+            config.set_deadline(Duration::from_secs(60));
+
+            let result = config.run().unwrap();
+
+            assert!(!result.is_deadline_exceeded());
+            assert_eq!(result.score(), Score::Weighted { current: 4, max: 4 });
+        }
+
+        #[test]
+        fn should_retain_only_tagged_assertions() {
+            let assertion = |name: &str, tags: &[&str]| {
+                Assertion::build(
+                    name.to_string(),
+                    vec![name.to_string()],
+                    None,
+                    Some(format!("{name}\n")),
+                    None,
+                    None,
+                    1,
+                )
+                .unwrap()
+                .with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+            };
+            let section = |title: &str, assertions: Vec<Assertion>| {
+                let unit_test = assertions.into_iter().fold(
+                    UnitTest::new(
+                        "unit test".to_string(),
+                        ExecutableArtifact::CompiledProgram {
+                            name: "program1".to_string(),
+                            path: "echo".into(),
+                            base_args: vec![],
+                        },
+                    ),
+                    UnitTest::with_assertion,
+                );
+                GradingTestSection::new(
+                    title.to_string(),
+                    1,
+                    GradingTests::UnitTests(UnitTests::new(
+                        vec![],
+                        false,
+                        vec![],
+                        vec![],
+                        vec![],
+                        vec![unit_test],
+                    )),
+                )
+            };
+            let mut config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+            config.add_grading_section(section(
+                "s1",
+                vec![assertion("a", &["basic"]), assertion("b", &[])],
+            ));
+            config.add_grading_section(section("s2", vec![assertion("c", &["edge"])]));
+
+            assert_eq!(
+                config.clone().retain_tagged("missing"),
+                Err("no assertion has the given tag")
+            );
+            config.retain_tagged("basic").unwrap();
+            let result = config.run().unwrap();
+
+            assert_eq!(result.section_results().len(), 1);
+            assert_eq!(result.score(), Score::Weighted { current: 1, max: 1 });
+        }
+    }
+
+    mod combined_grading_result_tests {
+        use super::*;
+
+        fn result_with(score: Score) -> GradingResult {
+            let mut result =
+                GradingResult::new("config".to_string(), None, GradingMode::Weighted, 3);
+            result.score = score;
+            result
+        }
+
+        fn combined(scores: &[Score]) -> CombinedGradingResult {
+            let mut combined = CombinedGradingResult::new();
+            for (i, score) in scores.iter().enumerate() {
+                combined.add_result(format!("config{i}.json"), result_with(*score));
+            }
+            combined
+        }
+
+        #[test]
+        fn should_sum_weighted_scores() {
+            let combined = combined(&[
+                Score::Weighted { current: 2, max: 4 },
+                Score::Weighted { current: 5, max: 6 },
+            ]);
+
+            assert_eq!(
+                combined.score(),
+                Score::Weighted {
+                    current: 7,
+                    max: 10
+                }
+            );
+            assert!(combined.meets_threshold(70));
+            assert!(!combined.meets_threshold(71));
+            assert_eq!(combined.results()[1].0, "config1.json");
+        }
+
+        #[test]
+        fn should_pass_absolute_scores_only_if_all_pass() {
+            assert_eq!(
+                combined(&[Score::Absolute(true), Score::Absolute(true)]).score(),
+                Score::Absolute(true)
+            );
+            assert_eq!(
+                combined(&[Score::Absolute(true), Score::Absolute(false)]).score(),
+                Score::Absolute(false)
+            );
+        }
+
+        #[test]
+        fn should_count_absolute_scores_as_one_point_when_mixed() {
+            let combined = combined(&[
+                Score::Absolute(true),
+                Score::Absolute(false),
+                Score::Weighted { current: 3, max: 5 },
+            ]);
+
+            assert_eq!(combined.score(), Score::Weighted { current: 4, max: 7 });
+        }
+
+        #[test]
+        fn should_show_the_score_line_with_the_maximum_score() {
+            assert_eq!(
+                result_with(Score::Weighted {
+                    current: 37,
+                    max: 50
+                })
+                .score_line(),
+                "37 / 50 (74.0%)"
+            );
+            assert_eq!(result_with(Score::Absolute(false)).score_line(), "FAIL");
+            assert_eq!(
+                combined(&[
+                    Score::Absolute(true),
+                    Score::Weighted { current: 1, max: 3 }
+                ])
+                .score_line(),
+                "2 / 4 (50.0%)"
+            );
+        }
+
+        #[test]
+        fn should_count_the_submissions_of_a_batch_meeting_the_threshold() {
+            let mut batch = BatchResult::new();
+            batch.add_result(
+                "alice".to_string(),
+                result_with(Score::Weighted { current: 2, max: 2 }),
+            );
+            batch.add_result(
+                "bob".to_string(),
+                result_with(Score::Weighted { current: 1, max: 2 }),
+            );
+
+            assert_eq!(batch.count_meeting_threshold(50), 2);
+            assert_eq!(batch.count_meeting_threshold(51), 1);
+            assert!(batch.meets_threshold(50));
+            assert!(!batch.meets_threshold(51));
+            assert_eq!(batch.results()[1].0, "bob");
+        }
+
+        #[test]
+        fn should_show_the_score_line_without_a_maximum_score() {
+            let config = GradingConfig::new("config".to_string(), None, GradingMode::Weighted);
+
+            assert_eq!(config.run().unwrap().score_line(), "0 / 0");
+        }
     }
 }
@@ -0,0 +1,385 @@
+//! Builders to assemble a `GradingConfig` from code, without going through a
+//! configuration file.
+
+use crate::grader::{
+    GradingConfig, GradingTestSection,
+    grading_tests::{
+        GradingTests,
+        unit_test::{UnitTest, UnitTests, assertion::Assertion},
+    },
+    score::GradingMode,
+};
+use crate::input::ExecutableArtifact;
+use std::path::PathBuf;
+
+/// Builder of a `GradingConfig`, created with `GradingConfig::builder`.
+#[derive(Debug)]
+pub struct GradingConfigBuilder {
+    name: String,
+    author: Option<String>,
+    grading_mode: GradingMode,
+    global_setup: Vec<(String, Vec<String>)>,
+    global_teardown: Vec<(String, Vec<String>)>,
+    seed: Option<u64>,
+    output_lines: Option<(usize, usize)>,
+    max_parallelism: usize,
+    shuffle_seed: Option<u64>,
+    sections: Vec<SectionBuilder>,
+}
+
+impl GradingConfigBuilder {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            author: None,
+            grading_mode: GradingMode::Weighted,
+            global_setup: vec![],
+            global_teardown: vec![],
+            seed: None,
+            output_lines: None,
+            max_parallelism: 1,
+            shuffle_seed: None,
+            sections: vec![],
+        }
+    }
+
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// It defaults to `GradingMode::Weighted`.
+    pub fn with_grading_mode(mut self, grading_mode: GradingMode) -> Self {
+        self.grading_mode = grading_mode;
+        self
+    }
+
+    /// Command executed only once, before the first section.
+    pub fn global_setup(mut self, command: impl Into<String>, args: Vec<String>) -> Self {
+        self.global_setup.push((command.into(), args));
+        self
+    }
+
+    /// Command executed only once, after the last section.
+    pub fn global_teardown(mut self, command: impl Into<String>, args: Vec<String>) -> Self {
+        self.global_teardown.push((command.into(), args));
+        self
+    }
+
+    /// If it is not set, a random seed is used in each run.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Lines kept from the start and from the end of the output of failed global
+    /// commands. Both default to 10.
+    pub fn with_output_lines(mut self, head: usize, tail: usize) -> Self {
+        self.output_lines = Some((head, tail));
+        self
+    }
+
+    /// Maximum number of unit tests of a section that run at the same time. It defaults
+    /// to 1.
+    pub fn with_max_parallelism(mut self, max_parallelism: usize) -> Self {
+        self.max_parallelism = max_parallelism;
+        self
+    }
+
+    /// Executes the sections and their unit tests in an order shuffled with this seed,
+    /// see `GradingConfig::set_shuffle_seed`. If it is not set, they run in order.
+    pub fn with_shuffle_seed(mut self, shuffle_seed: u64) -> Self {
+        self.shuffle_seed = Some(shuffle_seed);
+        self
+    }
+
+    pub fn section(mut self, section: SectionBuilder) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    /// Validates every assertion and that there is something to grade.
+    pub fn build(self) -> Result<GradingConfig, &'static str> {
+        if self.sections.is_empty() {
+            return Err("at least one test section is expected");
+        }
+        if self.max_parallelism == 0 {
+            return Err("max_parallelism must be at least 1");
+        }
+        let mut config = GradingConfig::new(self.name, self.author, self.grading_mode);
+        config.set_global_setup(self.global_setup);
+        config.set_global_teardown(self.global_teardown);
+        if let Some(seed) = self.seed {
+            config.set_seed(seed);
+        }
+        if let Some((head, tail)) = self.output_lines {
+            config.set_output_lines(head, tail);
+        }
+        config.set_max_parallelism(self.max_parallelism);
+        if let Some(shuffle_seed) = self.shuffle_seed {
+            config.set_shuffle_seed(shuffle_seed);
+        }
+        for section in self.sections {
+            config.add_grading_section(section.build()?);
+        }
+        if config.max_reachable_score().is_none() {
+            return Err("the sum of the weights is too large");
+        }
+        Ok(config)
+    }
+}
+
+/// Builder of a section of unit tests.
+#[derive(Debug)]
+pub struct SectionBuilder {
+    name: String,
+    weight: u32,
+    extra_credit: bool,
+    env: Vec<(String, String)>,
+    inherit_parent_env: bool,
+    files: Vec<(String, String)>,
+    stateful: bool,
+    informational_stderr: bool,
+    unit_tests: Vec<UnitTestBuilder>,
+}
+
+impl SectionBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            weight: 1,
+            extra_credit: false,
+            env: vec![],
+            inherit_parent_env: true,
+            files: vec![],
+            stateful: false,
+            informational_stderr: false,
+            unit_tests: vec![],
+        }
+    }
+
+    /// It defaults to 1.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_extra_credit(mut self, extra_credit: bool) -> Self {
+        self.extra_credit = extra_credit;
+        self
+    }
+
+    /// Whether the programs inherit the environment variables of the grader. It defaults
+    /// to `true`.
+    pub fn with_inherit_parent_env(mut self, inherit_parent_env: bool) -> Self {
+        self.inherit_parent_env = inherit_parent_env;
+        self
+    }
+
+    /// Environment variable set for every program of the section.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// File created in the working directory of each program of the section.
+    pub fn file(mut self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        self.files.push((name.into(), content.into()));
+        self
+    }
+
+    /// Whether the unit tests of the section share their working directory, running one
+    /// at a time in order. It defaults to `false`.
+    pub fn with_stateful(mut self, stateful: bool) -> Self {
+        self.stateful = stateful;
+        self
+    }
+
+    /// Whether the stderr of the programs of the section is only shown, never failing an
+    /// assertion. It defaults to `false`.
+    pub fn with_informational_stderr(mut self, informational_stderr: bool) -> Self {
+        self.informational_stderr = informational_stderr;
+        self
+    }
+
+    pub fn unit_test(mut self, unit_test: UnitTestBuilder) -> Self {
+        self.unit_tests.push(unit_test);
+        self
+    }
+
+    fn build(self) -> Result<GradingTestSection, &'static str> {
+        if self.unit_tests.is_empty() {
+            return Err("must contain at least one test");
+        }
+        let unit_tests = self
+            .unit_tests
+            .into_iter()
+            .map(UnitTestBuilder::build)
+            .collect::<Result<_, _>>()?;
+        Ok(GradingTestSection::new(
+            self.name,
+            self.weight,
+            GradingTests::UnitTests(
+                UnitTests::new(
+                    self.env,
+                    self.inherit_parent_env,
+                    self.files,
+                    vec![],
+                    vec![],
+                    unit_tests,
+                )
+                .with_stateful(self.stateful)
+                .with_informational_stderr(self.informational_stderr),
+            ),
+        )
+        .with_extra_credit(self.extra_credit))
+    }
+}
+
+/// Builder of a set of assertions applied to the same program.
+#[derive(Debug)]
+pub struct UnitTestBuilder {
+    name: String,
+    path: PathBuf,
+    base_args: Vec<String>,
+    assertions: Vec<Assertion>,
+}
+
+impl UnitTestBuilder {
+    /// The `path` is resolved like any command, so it may be the name of a program in
+    /// the `PATH`. It is also the name of the program shown in the reports.
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            base_args: vec![],
+            assertions: vec![],
+        }
+    }
+
+    /// Arguments passed before the ones of each assertion.
+    pub fn with_base_args(mut self, base_args: Vec<String>) -> Self {
+        self.base_args = base_args;
+        self
+    }
+
+    pub fn assertion(mut self, assertion: Assertion) -> Self {
+        self.assertions.push(assertion);
+        self
+    }
+
+    fn build(self) -> Result<UnitTest, &'static str> {
+        if self.assertions.is_empty() {
+            return Err("each unit test must have at least one assertion");
+        }
+        let mut unit_test = UnitTest::new(
+            self.name,
+            ExecutableArtifact::CompiledProgram {
+                name: self.path.display().to_string(),
+                path: self.path,
+                base_args: self.base_args,
+            },
+        );
+        for assertion in self.assertions {
+            unit_test.add_assertion(assertion.check()?);
+        }
+        Ok(unit_test)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo_assertion() -> Assertion {
+        Assertion::new("should echo".to_string(), vec!["hi".to_string()], None, 2)
+            .with_stdout("hi\n".to_string())
+    }
+
+    #[test]
+    fn should_build_the_same_config_as_the_constructors() {
+        let config = GradingConfig::builder("echo")
+            .with_author("author")
+            .with_grading_mode(GradingMode::Absolute)
+            .with_seed(3)
+            .section(
+                SectionBuilder::new("section 1")
+                    .with_weight(2)
+                    .env("k", "v")
+                    .unit_test(
+                        UnitTestBuilder::new("echo args", "echo").assertion(echo_assertion()),
+                    ),
+            )
+            .build()
+            .unwrap();
+
+        let mut expected = GradingConfig::new(
+            "echo".to_string(),
+            Some("author".to_string()),
+            GradingMode::Absolute,
+        );
+        expected.set_seed(3);
+        let mut unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "echo".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        );
+        unit_test.add_assertion(echo_assertion());
+        expected.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            2,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![("k".to_string(), "v".to_string())],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn should_not_build_without_sections() {
+        assert!(GradingConfig::builder("empty").build().is_err());
+    }
+
+    #[test]
+    fn should_not_build_without_parallelism() {
+        let result =
+            GradingConfig::builder("echo")
+                .with_max_parallelism(0)
+                .section(SectionBuilder::new("section 1").unit_test(
+                    UnitTestBuilder::new("echo args", "echo").assertion(echo_assertion()),
+                ))
+                .build();
+        assert_eq!(result, Err("max_parallelism must be at least 1"));
+    }
+
+    #[test]
+    fn should_not_build_a_section_without_unit_tests() {
+        let result = GradingConfig::builder("empty")
+            .section(SectionBuilder::new("section 1"))
+            .build();
+        assert_eq!(result, Err("must contain at least one test"));
+    }
+
+    #[test]
+    fn should_not_build_an_assertion_without_expectation() {
+        let result = GradingConfig::builder("echo")
+            .section(SectionBuilder::new("section 1").unit_test(
+                UnitTestBuilder::new("echo args", "echo").assertion(Assertion::new(
+                    "nothing".to_string(),
+                    vec![],
+                    None,
+                    1,
+                )),
+            ))
+            .build();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Comparison of two `GradingResult`s, e.g. before and after changing a reference solution
+//! or a configuration.
+
+use crate::grader::{
+    GradingResult, GradingTestSectionResult,
+    grading_tests::{GradindTestsResult, unit_test::assertion::AssertionResult},
+    score::Score,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+/// Names that identify an assertion in a `GradingResult`. Performance tests have no
+/// assertions, so their `assertion` is `None`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct AssertionPath {
+    section: String,
+    test: String,
+    assertion: Option<String>,
+}
+
+impl AssertionPath {
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    pub fn test(&self) -> &str {
+        &self.test
+    }
+
+    pub fn assertion(&self) -> Option<&str> {
+        self.assertion.as_deref()
+    }
+}
+
+/// Shown as `<section> / <test>[ / <assertion>]`.
+impl fmt::Display for AssertionPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {}", self.section, self.test)?;
+        if let Some(ref assertion) = self.assertion {
+            write!(f, " / {assertion}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the assertions changed from one `GradingResult` (the old one) to another (the new
+/// one). The assertions are matched by their `AssertionPath` and, when several share the
+/// same path, by the order in which they appear.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ResultDiff {
+    old_score: Score,
+    new_score: Score,
+    newly_passed: Vec<AssertionPath>,
+    newly_failed: Vec<AssertionPath>,
+    /// Assertions with the same outcome whose diagnostics are different, e.g. a failed
+    /// assertion that now fails with another output.
+    changed_diagnostics: Vec<AssertionPath>,
+    /// Assertions that are only in the new result.
+    added: Vec<AssertionPath>,
+    /// Assertions that are only in the old result.
+    removed: Vec<AssertionPath>,
+}
+
+impl ResultDiff {
+    pub fn old_score(&self) -> Score {
+        self.old_score
+    }
+
+    pub fn new_score(&self) -> Score {
+        self.new_score
+    }
+
+    pub fn newly_passed(&self) -> &[AssertionPath] {
+        &self.newly_passed
+    }
+
+    pub fn newly_failed(&self) -> &[AssertionPath] {
+        &self.newly_failed
+    }
+
+    pub fn changed_diagnostics(&self) -> &[AssertionPath] {
+        &self.changed_diagnostics
+    }
+
+    pub fn added(&self) -> &[AssertionPath] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[AssertionPath] {
+        &self.removed
+    }
+
+    /// Whether no assertion changed, regardless of the scores.
+    pub fn is_empty(&self) -> bool {
+        self.newly_passed.is_empty()
+            && self.newly_failed.is_empty()
+            && self.changed_diagnostics.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+    }
+}
+
+/// Outcome of an assertion or of a performance test. The diagnostics of the latter are
+/// measurements, so only whether it passed is compared.
+struct Outcome<'a> {
+    passed: bool,
+    diagnostics: Option<&'a AssertionResult>,
+}
+
+/// Every assertion of `result`, in the order in which they were run.
+fn outcomes(result: &GradingResult) -> Vec<(AssertionPath, Outcome<'_>)> {
+    result
+        .section_results()
+        .iter()
+        .flat_map(section_outcomes)
+        .collect()
+}
+
+/// Every assertion of `section_result`, in the order in which they were run.
+fn section_outcomes(
+    section_result: &GradingTestSectionResult,
+) -> Vec<(AssertionPath, Outcome<'_>)> {
+    let mut outcomes = vec![];
+    let section = section_result.name();
+    match section_result.test_results() {
+        Some(GradindTestsResult::UnitTests(unit_tests_result)) => {
+            for unit_test_result in unit_tests_result.unit_test_results() {
+                for assertion_result in unit_test_result.assertion_results() {
+                    let path = AssertionPath {
+                        section: section.to_string(),
+                        test: unit_test_result.name().to_string(),
+                        assertion: Some(assertion_result.name().to_string()),
+                    };
+                    let outcome = Outcome {
+                        passed: assertion_result.passed(),
+                        diagnostics: Some(assertion_result),
+                    };
+                    outcomes.push((path, outcome));
+                }
+            }
+        }
+        Some(GradindTestsResult::PerformanceTests(performance_tests_result)) => {
+            for performance_test_result in performance_tests_result.performance_test_results() {
+                let path = AssertionPath {
+                    section: section.to_string(),
+                    test: performance_test_result.name().to_string(),
+                    assertion: None,
+                };
+                let outcome = Outcome {
+                    passed: performance_test_result.passed(),
+                    diagnostics: None,
+                };
+                outcomes.push((path, outcome));
+            }
+        }
+        None => {}
+    }
+    outcomes
+}
+
+impl GradingResult {
+    /// Assertions and performance tests that failed, in the order in which they were run.
+    /// The ones of extra credit sections are left out, as they never lower the score.
+    pub fn failed_assertions(&self) -> Vec<AssertionPath> {
+        self.section_results()
+            .iter()
+            .filter(|section_result| !section_result.is_extra_credit())
+            .flat_map(section_outcomes)
+            .filter(|(_, outcome)| !outcome.passed)
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Compares the assertions of this result, taken as the old one, with the ones of
+    /// `other`, taken as the new one.
+    pub fn diff(&self, other: &GradingResult) -> ResultDiff {
+        let old_outcomes = outcomes(self);
+        let mut new_outcomes: Vec<Option<(AssertionPath, Outcome)>> =
+            outcomes(other).into_iter().map(Some).collect();
+        // indices of the new outcomes of each path that were not matched yet
+        let mut unmatched: HashMap<AssertionPath, VecDeque<usize>> = HashMap::new();
+        for (i, outcome) in new_outcomes.iter().enumerate() {
+            if let Some((path, _)) = outcome {
+                unmatched.entry(path.clone()).or_default().push_back(i);
+            }
+        }
+
+        let mut diff = ResultDiff {
+            old_score: self.score(),
+            new_score: other.score(),
+            newly_passed: vec![],
+            newly_failed: vec![],
+            changed_diagnostics: vec![],
+            added: vec![],
+            removed: vec![],
+        };
+        for (path, old_outcome) in old_outcomes {
+            let matched = unmatched
+                .get_mut(&path)
+                .and_then(VecDeque::pop_front)
+                .and_then(|i| new_outcomes[i].take());
+            let Some((_, new_outcome)) = matched else {
+                diff.removed.push(path);
+                continue;
+            };
+            match (old_outcome.passed, new_outcome.passed) {
+                (false, true) => diff.newly_passed.push(path),
+                (true, false) => diff.newly_failed.push(path),
+                _ if old_outcome.diagnostics != new_outcome.diagnostics => {
+                    diff.changed_diagnostics.push(path)
+                }
+                _ => {}
+            }
+        }
+        diff.added = new_outcomes
+            .into_iter()
+            .flatten()
+            .map(|(path, _)| path)
+            .collect();
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grader::{
+        GradingConfig,
+        builder::{SectionBuilder, UnitTestBuilder},
+        grading_tests::unit_test::assertion::Assertion,
+    };
+
+    /// Runs `echo hello` once per `(assertion name, expected stdout)`.
+    fn echo_result(expected_stdouts: &[(&str, &str)]) -> GradingResult {
+        let unit_test = expected_stdouts.iter().fold(
+            UnitTestBuilder::new("echo args", "echo"),
+            |unit_test, (name, stdout)| {
+                unit_test.assertion(
+                    Assertion::new(name.to_string(), vec!["hello".to_string()], None, 1)
+                        .with_stdout(stdout.to_string()),
+                )
+            },
+        );
+        GradingConfig::builder("echo")
+            .with_seed(3)
+            .section(SectionBuilder::new("section 1").unit_test(unit_test))
+            .build()
+            .unwrap()
+            .run()
+            .unwrap()
+    }
+
+    fn path(assertion: &str) -> AssertionPath {
+        AssertionPath {
+            section: "section 1".to_string(),
+            test: "echo args".to_string(),
+            assertion: Some(assertion.to_string()),
+        }
+    }
+
+    #[test]
+    fn should_not_find_changes_between_equivalent_runs() {
+        let expected_stdouts = [("a", "hello\n"), ("b", "bye\n")];
+        let diff = echo_result(&expected_stdouts).diff(&echo_result(&expected_stdouts));
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.old_score(), Score::Weighted { current: 1, max: 2 });
+        assert_eq!(diff.new_score(), diff.old_score());
+    }
+
+    #[test]
+    fn should_classify_the_assertions_by_their_transition() {
+        let old = echo_result(&[
+            ("passed", "hello\n"),
+            ("failed", "bye\n"),
+            ("changed", "bye\n"),
+            ("removed", "hello\n"),
+        ]);
+        let new = echo_result(&[
+            ("passed", "bye\n"),
+            ("failed", "hello\n"),
+            ("changed", "hi\n"),
+            ("added", "hello\n"),
+        ]);
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.newly_passed(), [path("failed")]);
+        assert_eq!(diff.newly_failed(), [path("passed")]);
+        assert_eq!(diff.changed_diagnostics(), [path("changed")]);
+        assert_eq!(diff.added(), [path("added")]);
+        assert_eq!(diff.removed(), [path("removed")]);
+        assert_eq!(path("a").to_string(), "section 1 / echo args / a");
+    }
+
+    #[test]
+    fn should_list_the_failed_assertions_in_order() {
+        let result = echo_result(&[("a", "bye\n"), ("b", "hello\n"), ("c", "hi\n")]);
+
+        assert_eq!(result.failed_assertions(), [path("a"), path("c")]);
+    }
+
+    #[test]
+    fn should_match_assertions_with_the_same_path_in_order() {
+        let old = echo_result(&[("same", "hello\n"), ("same", "bye\n")]);
+        let new = echo_result(&[("same", "hello\n"), ("same", "hello\n"), ("same", "bye\n")]);
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.newly_passed(), [path("same")]);
+        assert_eq!(diff.added(), [path("same")]);
+        assert!(diff.newly_failed().is_empty() && diff.removed().is_empty());
+    }
+}
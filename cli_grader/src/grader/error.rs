@@ -0,0 +1,67 @@
+use std::{error::Error, fmt, io};
+
+/// Reason why a grading run could not finish.
+///
+/// A program that fails its tests is not an error, it is part of the `GradingResult`.
+/// These are the failures of the grading environment itself, e.g. a temporary directory
+/// that could not be created.
+#[derive(Debug)]
+pub enum GraderError {
+    /// The environment of a section could not be prepared or cleaned, or one of its
+    /// programs could not be executed.
+    Section { section: String, source: io::Error },
+    /// A global setup or teardown command could not be executed or did not finish
+    /// successfully.
+    GlobalCommand {
+        stage: &'static str,
+        command: String,
+        reason: String,
+        /// Stderr of the command, already truncated. It is empty if the command could not
+        /// be executed.
+        stderr: String,
+    },
+    /// Any other IO failure, like creating the global temporary directory.
+    Io(io::Error),
+    /// The reporter could not write the results.
+    Report(io::Error),
+}
+
+impl fmt::Display for GraderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraderError::Section { section, source } => {
+                write!(f, "error while running section '{section}': {source}")
+            }
+            GraderError::GlobalCommand {
+                stage,
+                command,
+                reason,
+                stderr,
+            } => {
+                write!(f, "{stage} command '{command}' {reason}")?;
+                if !stderr.trim().is_empty() {
+                    write!(f, ":\n{}", stderr.trim_end())?;
+                }
+                Ok(())
+            }
+            GraderError::Io(err) => write!(f, "{err}"),
+            GraderError::Report(err) => write!(f, "could not write the report: {err}"),
+        }
+    }
+}
+
+impl Error for GraderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GraderError::Section { source, .. } => Some(source),
+            GraderError::GlobalCommand { .. } => None,
+            GraderError::Io(err) | GraderError::Report(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for GraderError {
+    fn from(err: io::Error) -> Self {
+        GraderError::Io(err)
+    }
+}
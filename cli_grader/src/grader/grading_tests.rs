@@ -1,34 +1,104 @@
+pub(crate) mod performance_test;
 pub(crate) mod unit_test;
+use crate::grader::grading_tests::performance_test::{PerformanceTests, PerformanceTestsResult};
 use crate::grader::grading_tests::unit_test::{UnitTests, UnitTestsResult};
 use crate::grader::score::{GradingMode, Score};
+use serde::{Deserialize, Serialize};
+use std::{io, path::Path, sync::atomic::AtomicBool, time::Instant};
 
 /// This is the interface between the grader and the assessment modalities.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum GradingTests {
     /// Modality in which only one program is tested against multiple simple test cases.
     UnitTests(UnitTests),
+    /// Modality in which programs are graded by how long they take to finish.
+    PerformanceTests(PerformanceTests),
     // integration tests
-    // performance tests
 }
 impl GradingTests {
-    pub fn run(&self, grading_mode: GradingMode) -> GradindTestsResult {
+    pub fn retain_tagged(&mut self, tag: &str) {
         match self {
-            GradingTests::UnitTests(unit_test) => {
-                GradindTestsResult::UnitTests(unit_test.run(grading_mode))
+            GradingTests::UnitTests(unit_tests) => unit_tests.retain_tagged(tag),
+            GradingTests::PerformanceTests(performance_tests) => {
+                performance_tests.retain_tagged(tag)
             }
         }
     }
+
+    /// Only the unit tests have an output to retain.
+    pub fn set_retain_output(&mut self, retain_output: bool) {
+        match self {
+            GradingTests::UnitTests(unit_tests) => unit_tests.set_retain_output(retain_output),
+            GradingTests::PerformanceTests(_) => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            GradingTests::UnitTests(unit_tests) => unit_tests.is_empty(),
+            GradingTests::PerformanceTests(performance_tests) => performance_tests.is_empty(),
+        }
+    }
+
+    /// Maximum score in `Weighted` mode, or `None` if it does not fit in an `u32`.
+    pub fn max_score(&self) -> Option<u32> {
+        match self {
+            GradingTests::UnitTests(unit_tests) => unit_tests.max_score(),
+            GradingTests::PerformanceTests(performance_tests) => performance_tests.max_score(),
+        }
+    }
+
+    /// Up to `max_parallelism` unit tests run at the same time. The performance tests always
+    /// run one at a time, so they are not slowed down by each other.
+    ///
+    /// Once `interrupted` is set, no other test is started. Past the `deadline`, the
+    /// remaining tests fail without being run.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &self,
+        grading_mode: GradingMode,
+        seed: u64,
+        global_dir: &Path,
+        shuffle_seed: Option<u64>,
+        max_parallelism: usize,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
+    ) -> io::Result<GradindTestsResult> {
+        Ok(match self {
+            GradingTests::UnitTests(unit_test) => GradindTestsResult::UnitTests(unit_test.run(
+                grading_mode,
+                seed,
+                global_dir,
+                shuffle_seed,
+                max_parallelism,
+                interrupted,
+                deadline,
+            )?),
+            GradingTests::PerformanceTests(performance_tests) => {
+                GradindTestsResult::PerformanceTests(performance_tests.run(
+                    grading_mode,
+                    seed,
+                    global_dir,
+                    interrupted,
+                    deadline,
+                )?)
+            }
+        })
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "snake_case")]
 pub enum GradindTestsResult {
     UnitTests(UnitTestsResult),
+    PerformanceTests(PerformanceTestsResult),
 }
 
 impl GradindTestsResult {
     pub fn score(&self) -> Score {
         match self {
             GradindTestsResult::UnitTests(r) => r.score(),
+            GradindTestsResult::PerformanceTests(r) => r.score(),
         }
     }
 }
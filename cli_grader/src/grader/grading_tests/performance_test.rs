@@ -0,0 +1,771 @@
+use crate::grader::log_context::LogContext;
+use crate::grader::score::{GradingMode, Score};
+use crate::grader::termination::{Termination, terminate};
+use crate::grader::{GLOBAL_DIR_ENV_VAR, SEED_ENV_VAR, is_past};
+use crate::input::ExecutableArtifact;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, Write},
+    path::Path,
+    process::{self, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Interval between the checks of whether the program finished, which bounds the error of
+/// the measured durations.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Execution of an executable artifact which is graded by how long it takes to finish.
+///
+/// It passes if the program finishes successfully in less than `max_duration`. If
+/// `min_duration` is defined, it is also required to take at least that long, which
+/// helps catching programs that do nothing. A program still running at `max_duration` is
+/// stopped, as it already failed, so a program that never exits can not hang the grading:
+/// it is sent SIGTERM and only killed if it is still running after `kill_grace`.
+///
+/// When `repeat` is greater than 1, the program is executed that many times and the mean
+/// duration is the one compared against the limits, which reduces the noise of the
+/// system. Only the status of the first execution is checked.
+///
+/// The `warmup` executions happen before the measured ones, e.g. to fill the caches, and
+/// neither their status nor their duration is taken into account.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PerformanceTest {
+    name: String, // Default: `Performance Test <n>`
+    executable: ExecutableArtifact,
+    args: Vec<String>,
+    stdin: Option<String>,
+    max_duration: Duration,
+    min_duration: Option<Duration>,
+    repeat: usize,
+    warmup: usize,
+    kill_grace: Duration,
+    weight: u32,
+}
+
+/// Outcome of a single execution of a performance test.
+struct Run {
+    success: bool,
+    duration: Duration,
+    /// It is `None` unless the program was stopped at `max_duration`.
+    termination: Option<Termination>,
+}
+
+impl PerformanceTest {
+    pub fn build(
+        name: String,
+        executable: ExecutableArtifact,
+        args: Vec<String>,
+        stdin: Option<String>,
+        max_duration: Duration,
+        min_duration: Option<Duration>,
+        weight: u32,
+    ) -> Result<Self, &'static str> {
+        if min_duration.is_some_and(|min| min >= max_duration) {
+            return Err("min_duration must be lower than max_duration");
+        }
+        Ok(Self {
+            name,
+            executable,
+            args,
+            stdin,
+            max_duration,
+            min_duration,
+            repeat: 1,
+            warmup: 0,
+            kill_grace: Duration::ZERO,
+            weight,
+        })
+    }
+
+    pub fn with_repeat(mut self, repeat: usize) -> Result<Self, &'static str> {
+        if repeat == 0 {
+            return Err("repeat must be greater than 0");
+        }
+        self.repeat = repeat;
+        Ok(self)
+    }
+
+    /// Executions before the measured ones. It defaults to 0.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Time a program still running at `max_duration` is given to exit after SIGTERM,
+    /// before being killed. It defaults to zero, which kills it right away.
+    pub fn with_kill_grace(mut self, kill_grace: Duration) -> Self {
+        self.kill_grace = kill_grace;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn executable_name(&self) -> String {
+        self.executable.name()
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn stdin(&self) -> Option<&str> {
+        self.stdin.as_deref()
+    }
+
+    pub fn max_duration(&self) -> Duration {
+        self.max_duration
+    }
+
+    pub fn min_duration(&self) -> Option<Duration> {
+        self.min_duration
+    }
+
+    pub fn repeat(&self) -> usize {
+        self.repeat
+    }
+
+    pub fn warmup(&self) -> usize {
+        self.warmup
+    }
+
+    pub fn kill_grace(&self) -> Duration {
+        self.kill_grace
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    fn not_executed_result(&self) -> PerformanceTestResult {
+        PerformanceTestResult {
+            name: self.name.clone(),
+            executable_name: self.executable.name(),
+            passed: false,
+            weight: self.weight,
+            measured: None,
+            statistics: None,
+            max_duration: self.max_duration,
+            min_duration: self.min_duration,
+            deadline_exceeded: false,
+            termination: None,
+        }
+    }
+
+    /// Result of the test when the deadline of the run was exceeded before it: it fails
+    /// without being executed.
+    fn deadline_exceeded_result(&self) -> PerformanceTestResult {
+        info!(
+            "❌ Performance test '{}' not run: deadline exceeded",
+            self.name
+        );
+        PerformanceTestResult {
+            deadline_exceeded: true,
+            ..self.not_executed_result()
+        }
+    }
+
+    fn run(&self, seed: u64, global_dir: &Path) -> io::Result<PerformanceTestResult> {
+        info!("🚀 Executing performance test: '{}'", self.name);
+        let mut result = self.not_executed_result();
+
+        let Some(mut cmd) = self.executable.new_cmd() else {
+            warn!("❌ Program '{}' not submitted", self.executable.name());
+            return Ok(result);
+        };
+        let tmp_dir = tempfile::tempdir().inspect_err(|err| {
+            log::error!("error while creating a temporary directory");
+            debug!("error: {err:?}");
+        })?;
+        cmd.args(&self.args)
+            .current_dir(&tmp_dir)
+            .env(SEED_ENV_VAR, seed.to_string())
+            .env(GLOBAL_DIR_ENV_VAR, global_dir)
+            .stdin(if self.stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        for i in 0..self.warmup {
+            let Some(run) = self.run_once(&mut cmd) else {
+                return Ok(result);
+            };
+            debug!("⏱️  Duration of warmup run {}: {:?}", i + 1, run.duration);
+        }
+        let mut durations = Vec::with_capacity(self.repeat);
+        let mut succeeded = false;
+        for i in 0..self.repeat {
+            let Some(run) = self.run_once(&mut cmd) else {
+                return Ok(result);
+            };
+            debug!("⏱️  Duration of run {}: {:?}", i + 1, run.duration);
+            // the following runs are only used to measure
+            if i == 0 {
+                succeeded = run.success;
+            }
+            // the first stop is the one reported
+            result.termination = result.termination.or(run.termination);
+            durations.push(run.duration);
+        }
+        let statistics = DurationStatistics::new(&durations);
+        let measured = statistics.mean;
+
+        result.measured = Some(measured);
+        if self.repeat > 1 {
+            result.statistics = Some(statistics);
+        }
+        result.passed = succeeded
+            && measured < self.max_duration
+            && self.min_duration.is_none_or(|min| measured >= min);
+        if result.passed {
+            info!("✅ Performance test passed");
+        } else {
+            info!("❌ Performance test not passed");
+        }
+        Ok(result)
+    }
+
+    /// Executes the program once. It is `None` when the program could not be executed. A
+    /// program still running at `max_duration` is stopped and counts as failed.
+    fn run_once(&self, cmd: &mut process::Command) -> Option<Run> {
+        let start = Instant::now();
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                warn!("❌ Unable to execute the command");
+                debug!("💥 Error: '{err:?}'");
+                return None;
+            }
+        };
+        if let Some(ref stdin_content) = self.stdin {
+            let mut stdin = child
+                .stdin
+                .take()
+                .expect("expected stdin from configuration");
+            let stdin_content = stdin_content.clone();
+            thread::spawn(move || stdin.write_all(stdin_content.as_bytes()));
+        }
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    return Some(Run {
+                        success: status.success(),
+                        duration: start.elapsed(),
+                        termination: None,
+                    });
+                }
+                Ok(None) if start.elapsed() >= self.max_duration => {
+                    warn!("⏱️  Stopping the program, which reached the maximum duration");
+                    let termination = terminate(&mut child, self.kill_grace)
+                        .inspect_err(|err| {
+                            debug!("💥 Error while stopping the program: '{err:?}'");
+                        })
+                        .unwrap_or(Some(Termination::Killed));
+                    if let Some(termination) = termination {
+                        debug!("⏱️  Program {termination}");
+                    }
+                    return Some(Run {
+                        success: false,
+                        duration: start.elapsed(),
+                        termination,
+                    });
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(err) => {
+                    warn!("⏱️  Unable to wait the command finish");
+                    debug!("💥 Error: '{err:?}'");
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Summary of the durations of the executions of a repeated performance test.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DurationStatistics {
+    runs: usize,
+    mean: Duration,
+    median: Duration,
+    /// Population standard deviation.
+    std_dev: Duration,
+}
+
+impl DurationStatistics {
+    /// `durations` must not be empty.
+    fn new(durations: &[Duration]) -> Self {
+        let runs = durations.len();
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        } else {
+            sorted[runs / 2]
+        };
+        let mean_secs = durations.iter().map(Duration::as_secs_f64).sum::<f64>() / runs as f64;
+        let variance = durations
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean_secs).powi(2))
+            .sum::<f64>()
+            / runs as f64;
+        Self {
+            runs,
+            mean: durations.iter().sum::<Duration>() / runs as u32,
+            median,
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+
+    pub fn median(&self) -> Duration {
+        self.median
+    }
+
+    pub fn std_dev(&self) -> Duration {
+        self.std_dev
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct PerformanceTestResult {
+    name: String,
+    executable_name: String,
+    passed: bool,
+    weight: u32,
+    /// It is `None` when the program could not be executed. When the test is repeated,
+    /// it is the mean duration.
+    measured: Option<Duration>,
+    /// Only defined when the test is repeated.
+    statistics: Option<DurationStatistics>,
+    max_duration: Duration,
+    min_duration: Option<Duration>,
+    /// Whether it was not executed because the deadline of the run was exceeded.
+    #[serde(default)]
+    deadline_exceeded: bool,
+    /// How the program was stopped when it reached `max_duration`. When the test is
+    /// repeated, it is the first execution that was stopped.
+    #[serde(default)]
+    termination: Option<Termination>,
+}
+
+impl PerformanceTestResult {
+    pub fn score(&self, grading_mode: GradingMode) -> Score {
+        match grading_mode {
+            GradingMode::Absolute => Score::Absolute(self.passed),
+            GradingMode::Weighted => Score::Weighted {
+                current: if self.passed { self.weight } else { 0 },
+                max: self.weight,
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn executable_name(&self) -> &str {
+        &self.executable_name
+    }
+
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+
+    pub fn measured(&self) -> Option<Duration> {
+        self.measured
+    }
+
+    pub fn statistics(&self) -> Option<&DurationStatistics> {
+        self.statistics.as_ref()
+    }
+
+    pub fn max_duration(&self) -> Duration {
+        self.max_duration
+    }
+
+    pub fn min_duration(&self) -> Option<Duration> {
+        self.min_duration
+    }
+
+    pub fn is_deadline_exceeded(&self) -> bool {
+        self.deadline_exceeded
+    }
+
+    pub fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+}
+
+/// Set of `PerformanceTest`s.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PerformanceTests {
+    performance_tests: Vec<PerformanceTest>,
+}
+
+impl PerformanceTests {
+    pub fn new(performance_tests: Vec<PerformanceTest>) -> Self {
+        Self { performance_tests }
+    }
+
+    /// Performance tests can not be tagged, so none of them is kept.
+    pub fn retain_tagged(&mut self, _tag: &str) {
+        self.performance_tests.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.performance_tests.is_empty()
+    }
+
+    pub fn performance_tests(&self) -> &[PerformanceTest] {
+        &self.performance_tests
+    }
+
+    /// Maximum score in `Weighted` mode, or `None` if it does not fit in an `u32`.
+    pub fn max_score(&self) -> Option<u32> {
+        self.performance_tests
+            .iter()
+            .try_fold(0u32, |sum, performance_test| {
+                sum.checked_add(performance_test.weight)
+            })
+    }
+
+    /// Runs the performance tests in order, stopping before the next one once `interrupted`
+    /// is set. Past the `deadline`, the remaining ones fail without being executed.
+    pub fn run(
+        &self,
+        grading_mode: GradingMode,
+        seed: u64,
+        global_dir: &Path,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
+    ) -> io::Result<PerformanceTestsResult> {
+        let mut result = PerformanceTestsResult::new(grading_mode);
+        for performance_test in &self.performance_tests {
+            let _context = LogContext::enter_unit_test(&performance_test.name);
+            if interrupted.load(Ordering::Relaxed) {
+                info!(
+                    "Interrupted before the performance test '{}'",
+                    performance_test.name
+                );
+                break;
+            }
+            if is_past(deadline) {
+                result.add_result(performance_test.deadline_exceeded_result(), grading_mode);
+                continue;
+            }
+            let res = performance_test.run(seed, global_dir)?;
+            result.add_result(res, grading_mode);
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct PerformanceTestsResult {
+    score: Score,
+    performance_test_results: Vec<PerformanceTestResult>,
+}
+
+impl PerformanceTestsResult {
+    fn new(grading_mode: GradingMode) -> Self {
+        Self {
+            score: Score::default(grading_mode),
+            performance_test_results: vec![],
+        }
+    }
+
+    fn add_result(&mut self, result: PerformanceTestResult, grading_mode: GradingMode) {
+        self.score += result.score(grading_mode);
+        self.performance_test_results.push(result);
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn performance_test_results(&self) -> &[PerformanceTestResult] {
+        &self.performance_test_results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sleep_test(seconds: &str, max_ms: u64, min_ms: Option<u64>) -> PerformanceTest {
+        PerformanceTest::build(
+            format!("sleep {seconds}"),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "sleep".into(),
+                base_args: vec![],
+            },
+            vec![seconds.to_string()],
+            None,
+            Duration::from_millis(max_ms),
+            min_ms.map(Duration::from_millis),
+            2,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_not_build_with_min_duration_above_max_duration() {
+        let result = PerformanceTest::build(
+            "test".to_string(),
+            ExecutableArtifact::new_dummy(1),
+            vec![],
+            None,
+            Duration::from_millis(10),
+            Some(Duration::from_millis(10)),
+            1,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_pass_when_under_max_duration() {
+        let result = sleep_test("0", 5_000, None).run(0, Path::new(".")).unwrap();
+        assert!(result.passed());
+        assert!(result.measured().is_some());
+        assert_eq!(
+            result.score(GradingMode::Weighted),
+            Score::Weighted { current: 2, max: 2 }
+        );
+    }
+
+    #[test]
+    fn should_fail_when_over_max_duration() {
+        let result = sleep_test("0.2", 50, None).run(0, Path::new(".")).unwrap();
+        assert!(!result.passed());
+        assert!(
+            result
+                .measured()
+                .is_some_and(|d| d >= Duration::from_millis(50))
+        );
+        assert_eq!(
+            result.score(GradingMode::Weighted),
+            Score::Weighted { current: 0, max: 2 }
+        );
+    }
+
+    #[test]
+    fn should_kill_the_program_at_max_duration() {
+        let start = Instant::now();
+        let result = sleep_test("30", 100, None).run(0, Path::new(".")).unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(!result.passed());
+        assert!(
+            result
+                .measured()
+                .is_some_and(|d| d >= Duration::from_millis(100) && d < Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn should_report_how_the_program_was_stopped() {
+        let result = sleep_test("30", 100, None).run(0, Path::new(".")).unwrap();
+        assert_eq!(result.termination(), Some(Termination::Killed));
+
+        let result = sleep_test("0", 5_000, None).run(0, Path::new(".")).unwrap();
+        assert_eq!(result.termination(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_let_the_program_exit_during_the_kill_grace() {
+        let test = PerformanceTest::build(
+            "graceful".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "sh".into(),
+                base_args: vec![],
+            },
+            vec![
+                "-c".to_string(),
+                "trap 'exit 0' TERM; sleep 30 & wait".to_string(),
+            ],
+            None,
+            Duration::from_millis(200),
+            None,
+            1,
+        )
+        .unwrap()
+        .with_kill_grace(Duration::from_secs(5));
+
+        let result = test.run(0, Path::new(".")).unwrap();
+        assert!(!result.passed());
+        assert_eq!(result.termination(), Some(Termination::Terminated));
+    }
+
+    #[test]
+    fn should_fail_when_under_min_duration() {
+        let result = sleep_test("0", 5_000, Some(1_000))
+            .run(0, Path::new("."))
+            .unwrap();
+        assert!(!result.passed());
+    }
+
+    #[test]
+    fn should_fail_when_the_program_can_not_be_executed() {
+        let test = PerformanceTest::build(
+            "invalid".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "____invalid_command".into(),
+                base_args: vec![],
+            },
+            vec![],
+            None,
+            Duration::from_secs(1),
+            None,
+            1,
+        )
+        .unwrap();
+
+        let result = test.run(0, Path::new(".")).unwrap();
+        assert!(!result.passed());
+        assert_eq!(result.measured(), None);
+    }
+
+    /// Slow only in its first execution, as it leaves a file behind.
+    fn cold_start_test() -> PerformanceTest {
+        PerformanceTest::build(
+            "cold start".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "sh".into(),
+                base_args: vec![],
+            },
+            vec![
+                "-c".to_string(),
+                "[ -e warm ] || { touch warm; sleep 0.5; }".to_string(),
+            ],
+            None,
+            Duration::from_millis(400),
+            None,
+            1,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_not_measure_the_warmup_runs() {
+        assert!(!cold_start_test().run(0, Path::new(".")).unwrap().passed());
+
+        let result = cold_start_test()
+            .with_warmup(1)
+            .run(0, Path::new("."))
+            .unwrap();
+        assert!(result.passed());
+        assert!(
+            result
+                .measured()
+                .is_some_and(|d| d < Duration::from_millis(400))
+        );
+    }
+
+    #[test]
+    fn should_not_count_the_warmup_runs_in_the_statistics() {
+        let result = cold_start_test()
+            .with_warmup(2)
+            .with_repeat(3)
+            .unwrap()
+            .run(0, Path::new("."))
+            .unwrap();
+        assert_eq!(result.statistics().unwrap().runs(), 3);
+        assert!(result.passed());
+    }
+
+    #[test]
+    fn should_sum_the_scores_of_the_performance_tests() {
+        let tests = PerformanceTests::new(vec![
+            sleep_test("0", 5_000, None),
+            sleep_test("0.2", 50, None),
+        ]);
+        let result = tests
+            .run(
+                GradingMode::Weighted,
+                0,
+                Path::new("."),
+                &AtomicBool::new(false),
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.score(), Score::Weighted { current: 2, max: 4 });
+        assert_eq!(result.performance_test_results().len(), 2);
+    }
+    #[test]
+    fn should_fail_the_performance_tests_past_the_deadline() {
+        let tests = PerformanceTests::new(vec![sleep_test("0", 5_000, None)]);
+        let result = tests
+            .run(
+                GradingMode::Weighted,
+                0,
+                Path::new("."),
+                &AtomicBool::new(false),
+                Some(Instant::now()),
+            )
+            .unwrap();
+        assert_eq!(result.score(), Score::Weighted { current: 0, max: 2 });
+        let test_result = &result.performance_test_results()[0];
+        assert!(test_result.is_deadline_exceeded());
+        assert_eq!(test_result.measured(), None);
+    }
+
+    #[test]
+    fn should_not_repeat_zero_times() {
+        assert!(sleep_test("0", 5_000, None).with_repeat(0).is_err());
+    }
+
+    #[test]
+    fn should_summarize_the_durations() {
+        let statistics = DurationStatistics::new(&[
+            Duration::from_millis(40),
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+            Duration::from_millis(20),
+        ]);
+        assert_eq!(statistics.runs(), 4);
+        assert_eq!(statistics.mean(), Duration::from_millis(25));
+        assert_eq!(statistics.median(), Duration::from_millis(25));
+        // sqrt(125) ms
+        assert_eq!(statistics.std_dev().as_micros(), 11_180);
+    }
+
+    #[test]
+    fn should_measure_every_repetition() {
+        let result = sleep_test("0.05", 5_000, None)
+            .with_repeat(3)
+            .unwrap()
+            .run(0, Path::new("."))
+            .unwrap();
+        assert!(result.passed());
+        let statistics = result.statistics().unwrap();
+        assert_eq!(statistics.runs(), 3);
+        assert!(statistics.median() >= Duration::from_millis(50));
+        assert_eq!(result.measured(), Some(statistics.mean()));
+    }
+
+    #[test]
+    fn should_not_have_statistics_without_repetition() {
+        let result = sleep_test("0", 5_000, None).run(0, Path::new(".")).unwrap();
+        assert_eq!(result.statistics(), None);
+    }
+}
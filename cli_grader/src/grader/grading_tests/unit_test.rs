@@ -1,11 +1,25 @@
 use crate::grader::grading_tests::unit_test::assertion::Assertion;
+use crate::grader::log_context::LogContext;
 use crate::grader::score::{GradingMode, Score};
+use crate::grader::{GLOBAL_DIR_ENV_VAR, SEED_ENV_VAR, is_past};
+use serde::{Deserialize, Serialize};
 
 pub(crate) mod assertion;
+pub(crate) mod extraction;
+pub(crate) mod interaction;
+pub(crate) mod regex_checks;
 
 use crate::input::ExecutableArtifact;
+use crate::utils::{map_bounded, shuffled_indices};
 use assertion::AssertionResult;
-use std::{fs, io, process};
+use std::{
+    fs, io,
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+use tempfile::TempDir;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct UnitTest {
@@ -14,6 +28,12 @@ pub struct UnitTest {
     // of assertions?
     executable: ExecutableArtifact,
     assertions: Vec<Assertion>,
+    /// Names of the assertions disabled in the configuration. They are not executed, only
+    /// reported.
+    skipped_assertions: Vec<String>,
+    /// Score of the unit test, split between the assertions in proportion to their
+    /// weights. Without it, the score is the sum of the weights of the assertions.
+    weight: Option<u32>,
 }
 
 impl UnitTest {
@@ -22,6 +42,8 @@ impl UnitTest {
             name,
             executable,
             assertions: vec![],
+            skipped_assertions: vec![],
+            weight: None,
         }
     }
 
@@ -35,6 +57,8 @@ impl UnitTest {
             name,
             executable,
             assertions,
+            skipped_assertions: vec![],
+            weight: None,
         }
     }
 
@@ -52,11 +76,96 @@ impl UnitTest {
         self.assertions.extend(assertions);
     }
 
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn weight(&self) -> Option<u32> {
+        self.weight
+    }
+
+    pub fn add_skipped_assertion(&mut self, name: String) {
+        self.skipped_assertions.push(name);
+    }
+
+    pub fn skipped_assertions(&self) -> &[String] {
+        &self.skipped_assertions
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn executable_name(&self) -> String {
+        self.executable.name()
+    }
+
+    pub fn assertions(&self) -> &[Assertion] {
+        &self.assertions
+    }
+
     /// Get the number of assertions.
     pub fn size(&self) -> usize {
         self.assertions.len()
     }
 
+    /// Shell command that runs the program like `assertion` does, so a failure can be
+    /// reproduced locally, e.g. `printf '%s' 'in' | ./program arg1 'arg 2'`. The files,
+    /// setup and environment of the assertion are not part of it. It is `None` when the
+    /// program was not submitted.
+    pub fn reproduction_command(&self, assertion: &Assertion) -> Option<String> {
+        let ExecutableArtifact::CompiledProgram {
+            path, base_args, ..
+        } = &self.executable
+        else {
+            return None;
+        };
+        let words: Vec<String> = std::iter::once(quote(&path.to_string_lossy()))
+            .chain(
+                base_args
+                    .iter()
+                    .chain(assertion.args())
+                    .map(|arg| quote(arg)),
+            )
+            .collect();
+        let command = words.join(" ");
+        Some(match assertion.stdin() {
+            Some(stdin) => format!("{} | {command}", printf_command(stdin)),
+            None => command,
+        })
+    }
+
+    /// The `weight` of the unit test, or else the sum of the weights of the assertions,
+    /// or `None` if it does not fit in an `u32`.
+    fn max_score(&self) -> Option<u32> {
+        if let Some(weight) = self.weight {
+            return Some(weight);
+        }
+        self.assertions
+            .iter()
+            .try_fold(0u32, |sum, assertion| sum.checked_add(assertion.weight()))
+    }
+
+    /// Keeps only the assertions tagged with `tag`.
+    fn retain_tagged(&mut self, tag: &str) {
+        self.assertions.retain(|assertion| assertion.has_tag(tag));
+    }
+
+    fn set_retain_output(&mut self, retain_output: bool) {
+        for assertion in &mut self.assertions {
+            assertion.set_retain_output(retain_output);
+        }
+    }
+
+    /// Runs the assertions in order, stopping before the next one once `interrupted` is
+    /// set, so the result only has the assertions that finished. Past the `deadline`, the
+    /// remaining assertions fail without being executed.
+    ///
+    /// Each assertion runs in its own temporary directory, with `files`, `setup` and
+    /// `teardown`, unless a `shared_workspace` is given. In that case, they all run in it
+    /// and the caller is in charge of preparing and cleaning it.
+    #[allow(clippy::too_many_arguments)]
     fn run(
         &self,
         envs: &[(String, String)],
@@ -64,82 +173,259 @@ impl UnitTest {
         files: &[(String, String)],
         setup: &[(String, Vec<String>)],
         teardown: &[(String, Vec<String>)],
+        shared_workspace: Option<&Path>,
         grading_mode: GradingMode,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
     ) -> io::Result<UnitTestResult> {
+        let _context = LogContext::enter_unit_test(&self.name);
         let mut result =
-            UnitTestResult::new(self.name.clone(), self.executable.name(), grading_mode);
+            UnitTestResult::new(self.name.clone(), self.executable.name(), grading_mode)
+                .with_skipped_assertions(self.skipped_assertions.clone());
         for assertion in self.assertions.iter() {
-            let tmp_dir = match tempfile::tempdir() {
-                Ok(dir) => dir,
-                Err(err) => {
-                    log::error!("error while creating a temporary directory");
-                    log::debug!("error: {err:?}");
-                    return Err(err);
-                }
+            let _context = LogContext::enter_assertion(assertion.name());
+            if interrupted.load(Ordering::Relaxed) {
+                log::info!("Interrupted before the assertion '{}'", assertion.name());
+                break;
+            }
+            if is_past(deadline) {
+                result.add_assertion_result(assertion.deadline_exceeded_result());
+                continue;
+            }
+            // nothing is prepared for a program that can not be executed
+            let Some(mut cmd) = self.executable.new_cmd() else {
+                result.add_assertion_result(assertion.not_submitted_result());
+                continue;
             };
-            // create files
-            let mut file_path;
-            for (name, content) in files {
-                log::debug!("Creating file: {name}");
-                file_path = tmp_dir.path().join(name);
-                if let Err(err) = fs::write(&file_path, content) {
-                    log::error!("error while creating the file: {name}");
-                    log::debug!("error: {err:?}");
-                    return Err(err);
+            let tmp_dir;
+            let workspace = match shared_workspace {
+                Some(workspace) => workspace,
+                None => {
+                    tmp_dir = prepare_workspace(envs, inherited_parent_envs, files, setup)?;
+                    tmp_dir.path()
                 }
-            }
-            // execute setup
-            let make_env_iter = || envs.iter().map(|e| (e.0.as_str(), e.1.as_str()));
-            for (setup_cmd, args) in setup {
-                let mut setup_cmd = process::Command::new(setup_cmd);
-                setup_cmd.args(args);
+            };
+            // dropped before the directory is removed, even when the assertion panics
+            let test_teardown = shared_workspace.is_none().then(|| {
+                TeardownGuard::new(|| {
+                    run_commands("teardown", teardown, workspace, envs, inherited_parent_envs)
+                })
+            });
+
+            // the own setup and teardown of the assertion only affect its result
+            let assertion_result = if let Err(err) =
+                run_assertion_commands(assertion.setup(), workspace, envs, inherited_parent_envs)
+            {
+                log::error!(
+                    "setup of the assertion '{}' failed: {err}",
+                    assertion.name()
+                );
+                assertion.setup_failure_result()
+            } else {
+                let assertion_teardown = TeardownGuard::new(|| {
+                    run_assertion_commands(
+                        assertion.teardown(),
+                        workspace,
+                        envs,
+                        inherited_parent_envs,
+                    )
+                    .map_err(io::Error::other)
+                });
                 if !inherited_parent_envs {
-                    setup_cmd.env_clear();
+                    clear_parent_env(&mut cmd, envs);
                 }
-                setup_cmd.current_dir(&tmp_dir);
-                setup_cmd.envs(make_env_iter());
-                if let Err(err) = setup_cmd.output() {
-                    log::error!("error while executing setup");
-                    log::debug!("error: {err:?}");
-                    return Err(err);
+                // resolved only now, as the directory may be created by the files or the
+                // setup
+                match assertion.cwd() {
+                    Some(cwd) => {
+                        let cwd_path = workspace.join(cwd);
+                        if !cwd_path.is_dir() {
+                            log::error!("working directory not found: {}", cwd.display());
+                            return Err(io::Error::new(
+                                io::ErrorKind::NotFound,
+                                format!(
+                                    "working directory '{}' does not exist after the setup",
+                                    cwd.display()
+                                ),
+                            ));
+                        }
+                        cmd.current_dir(cwd_path);
+                    }
+                    None => {
+                        cmd.current_dir(workspace);
+                    }
                 }
+                cmd.envs(envs.iter().map(|e| (e.0.as_str(), e.1.as_str())));
+                let assertion_result =
+                    assertion.unsafe_assert_cmd_in_env(cmd, envs, inherited_parent_envs);
+                if let Err(err) = assertion_teardown.run() {
+                    log::warn!(
+                        "teardown of the assertion '{}' failed: {err}",
+                        assertion.name()
+                    );
+                }
+                assertion_result
+            };
+            let assertion_result = match self.reproduction_command(assertion) {
+                Some(command) => assertion_result.with_reproduction_command(command),
+                None => assertion_result,
+            };
+            result.add_assertion_result(assertion_result);
+
+            if let Some(test_teardown) = test_teardown {
+                test_teardown.run()?;
             }
+        }
+        if let Some(weight) = self.weight {
+            // out of every declared assertion, so an interrupted run is not scaled up
+            let declared = self.assertions.iter().fold(0u32, |sum, assertion| {
+                sum.saturating_add(assertion.weight())
+            });
+            result.split_weight(weight, declared);
+        }
+        Ok(result)
+    }
+}
+
+/// Runs `teardown` once, either explicitly or when dropped. That way, it also runs when
+/// an assertion returns early or panics, which would leave behind what the setup created
+/// outside of the temporary directory.
+struct TeardownGuard<F: FnOnce() -> io::Result<()>> {
+    teardown: Option<F>,
+}
+
+impl<F: FnOnce() -> io::Result<()>> TeardownGuard<F> {
+    fn new(teardown: F) -> Self {
+        Self {
+            teardown: Some(teardown),
+        }
+    }
+
+    fn run(mut self) -> io::Result<()> {
+        self.teardown.take().map_or(Ok(()), |teardown| teardown())
+    }
+}
 
-            // setup cmd
-            let mut cmd = self.executable.new_cmd();
-            if !inherited_parent_envs {
-                cmd.env_clear();
+impl<F: FnOnce() -> io::Result<()>> Drop for TeardownGuard<F> {
+    fn drop(&mut self) {
+        if let Some(teardown) = self.teardown.take() {
+            if std::thread::panicking() {
+                log::warn!("running the teardown after a panic");
             }
-            cmd.current_dir(&tmp_dir);
-            cmd.envs(make_env_iter());
-            result.add_assertion_result(assertion.unsafe_assert_cmd(cmd));
-
-            // execute teardown
-            for (teardown_cmd, args) in teardown {
-                let mut teardown_cmd = process::Command::new(teardown_cmd);
-                teardown_cmd.args(args);
-                if !inherited_parent_envs {
-                    teardown_cmd.env_clear();
-                }
-                teardown_cmd.current_dir(&tmp_dir);
-                teardown_cmd.envs(make_env_iter());
-                if let Err(err) = teardown_cmd.output() {
-                    log::error!("error while executing teardown");
-                    log::debug!("error: {err:?}");
-                    return Err(err);
-                }
+            if let Err(err) = teardown() {
+                log::warn!("teardown failed: {err}");
             }
         }
-        Ok(result)
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// Quotes `word` for a POSIX shell, which splits it back into the same word. Only the
+/// words with special characters are quoted, e.g. `'arg 2'` or `'it'\''s'`.
+fn quote(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !word.is_empty() && word.chars().all(is_safe) {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// Command that writes exactly `stdin` to its stdout. Bytes that can not be quoted, i.e.
+/// invalid UTF-8 or NUL, are written as octal escapes.
+fn printf_command(stdin: &[u8]) -> String {
+    match str::from_utf8(stdin) {
+        Ok(text) if !text.contains('\0') => format!("printf '%s' {}", quote(text)),
+        _ => {
+            let escaped: String = stdin.iter().map(|byte| format!("\\{byte:03o}")).collect();
+            format!("printf '{escaped}'")
+        }
+    }
+}
+
+/// Creates a temporary directory with `files` and executes `setup` in it.
+fn prepare_workspace(
+    envs: &[(String, String)],
+    inherited_parent_envs: bool,
+    files: &[(String, String)],
+    setup: &[(String, Vec<String>)],
+) -> io::Result<TempDir> {
+    let tmp_dir = match tempfile::tempdir() {
+        Ok(dir) => dir,
+        Err(err) => {
+            log::error!("error while creating a temporary directory");
+            log::debug!("error: {err:?}");
+            return Err(err);
+        }
+    };
+    for (name, content) in files {
+        log::debug!("Creating file: {name}");
+        if let Err(err) = fs::write(tmp_dir.path().join(name), content) {
+            log::error!("error while creating the file: {name}");
+            log::debug!("error: {err:?}");
+            return Err(err);
+        }
+    }
+    run_commands("setup", setup, tmp_dir.path(), envs, inherited_parent_envs)?;
+    Ok(tmp_dir)
+}
+
+/// Executes the `stage` (setup or teardown) commands in `dir`, one after the other.
+fn run_commands(
+    stage: &str,
+    commands: &[(String, Vec<String>)],
+    dir: &Path,
+    envs: &[(String, String)],
+    inherited_parent_envs: bool,
+) -> io::Result<()> {
+    for (command, args) in commands {
+        let mut cmd = process::Command::new(command);
+        cmd.args(args);
+        if !inherited_parent_envs {
+            clear_parent_env(&mut cmd, envs);
+        }
+        cmd.current_dir(dir);
+        cmd.envs(envs.iter().map(|e| (e.0.as_str(), e.1.as_str())));
+        if let Err(err) = cmd.output() {
+            log::error!("error while executing {stage}");
+            log::debug!("error: {err:?}");
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Executes the setup or teardown commands of an assertion in `dir`, one after the other.
+/// Unlike the ones of the unit tests, they fail when a command does not succeed.
+fn run_assertion_commands(
+    commands: &[(String, Vec<String>)],
+    dir: &Path,
+    envs: &[(String, String)],
+    inherited_parent_envs: bool,
+) -> Result<(), String> {
+    for (command, args) in commands {
+        let mut cmd = process::Command::new(command);
+        cmd.args(args);
+        if !inherited_parent_envs {
+            clear_parent_env(&mut cmd, envs);
+        }
+        cmd.current_dir(dir);
+        cmd.envs(envs.iter().map(|e| (e.0.as_str(), e.1.as_str())));
+        match cmd.output() {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => return Err(format!("'{command}' finished with {}", output.status)),
+            Err(err) => return Err(format!("'{command}' could not be executed: {err}")),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UnitTestResult {
     name: String,
     executable_name: String,
     score: Score,
     assertion_results: Vec<AssertionResult>,
+    /// Names of the assertions that were not executed because they are skipped.
+    skipped_assertions: Vec<String>,
 }
 
 impl UnitTestResult {
@@ -149,9 +435,15 @@ impl UnitTestResult {
             executable_name,
             score: Score::default(grading_mode),
             assertion_results: vec![],
+            skipped_assertions: vec![],
         }
     }
 
+    pub fn with_skipped_assertions(mut self, skipped_assertions: Vec<String>) -> Self {
+        self.skipped_assertions = skipped_assertions;
+        self
+    }
+
     pub fn with_assertion_result(mut self, assertion_result: AssertionResult) -> Self {
         self.add_assertion_result(assertion_result);
         self
@@ -163,17 +455,72 @@ impl UnitTestResult {
                 Score::Absolute(b && assertion_result.score() == assertion_result.max_score())
             }
             Score::Weighted { current: c, max: m } => Score::Weighted {
-                current: c + assertion_result.score(),
-                max: m + assertion_result.max_score(),
+                current: c.saturating_add(assertion_result.score()),
+                max: m.saturating_add(assertion_result.max_score()),
             },
         };
         self.assertion_results.push(assertion_result);
     }
+
+    /// Turns the sum of the weights of the assertions into a score out of `weight`, each
+    /// assertion being worth its share of it. The score reached is rounded down, e.g. 1
+    /// out of 3 assertions of the same weight is worth 3 out of a weight of 10, so the
+    /// whole weight is only reached when every assertion passes.
+    ///
+    /// The shares are taken from `declared`, the sum of the weights of every assertion of
+    /// the unit test, so the ones that were not run because of an interruption still
+    /// count.
+    fn split_weight(&mut self, weight: u32, declared: u32) {
+        if let Score::Weighted { current, .. } = self.score {
+            let current = match declared {
+                0 => 0,
+                declared => {
+                    u64::from(weight) * u64::from(current.min(declared)) / u64::from(declared)
+                }
+            };
+            self.score = Score::Weighted {
+                // it is at most `weight`, as `current` is at most `max`
+                current: u32::try_from(current).unwrap_or(weight),
+                max: weight,
+            };
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn executable_name(&self) -> &str {
+        &self.executable_name
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn assertion_results(&self) -> &[AssertionResult] {
+        &self.assertion_results
+    }
+
+    pub fn skipped_assertions(&self) -> &[String] {
+        &self.skipped_assertions
+    }
 }
 
 type Key = String;
 type Value = String;
 type Command = String;
+/// Clears the environment of `cmd`, but keeps the `PATH` of the parent process unless
+/// `envs` replaces it, so commands given by their name can still be found.
+pub(crate) fn clear_parent_env(cmd: &mut process::Command, envs: &[(String, String)]) {
+    cmd.env_clear();
+    if !envs.iter().any(|(key, _)| key == "PATH")
+        && let Some(path) = std::env::var_os("PATH")
+    {
+        cmd.env("PATH", path);
+    }
+}
+
 type Arg = String;
 type FileContent = String;
 /// Set of `UnitTest`s.
@@ -186,16 +533,35 @@ type FileContent = String;
 ///
 /// # Fields
 /// - `inherit_parent_env`: whether it will inherit the environment variables from
-///   parent process.
+///   parent process. Even if it does not, `PATH` is kept unless `env` or
+///   `env_passthrough` sets it.
+/// - `env_passthrough`: names of the environment variables copied from the parent process
+///   when `inherit_parent_env` is `false`. The ones that are not set or are not valid
+///   unicode are skipped.
+/// - `locale`: value of `LC_ALL` and `LANG` for every program, including the setup and
+///   teardown commands, e.g. to pin the formatting of dates and numbers. It takes
+///   precedence over `env`.
+/// - `timezone`: value of `TZ` for every program, like `locale`.
 /// - `files`: Vec of `(<filename>, <file_content>)`.
+/// - `stateful`: whether every unit test shares a single temporary directory, so the
+///   later ones see what the earlier ones left. `files` and `setup` are then prepared
+///   once before the first test, `teardown` runs once after the last one, and the unit
+///   tests run one at a time in declaration order, regardless of `max_parallelism` and
+///   of the shuffling.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct UnitTests {
     env: Vec<(Key, Value)>,
     inherit_parent_env: bool,
+    env_passthrough: Vec<Key>,
+    locale: Option<String>,
+    timezone: Option<String>,
     files: Vec<(String, FileContent)>,
     setup: Vec<(Command, Vec<Arg>)>,
     teardown: Vec<(Command, Vec<Arg>)>,
     unit_tests: Vec<UnitTest>,
+    /// Names of the unit tests disabled in the configuration.
+    skipped_unit_tests: Vec<String>,
+    stateful: bool,
 }
 
 impl UnitTests {
@@ -210,40 +576,232 @@ impl UnitTests {
         Self {
             env,
             inherit_parent_env,
+            env_passthrough: vec![],
+            locale: None,
+            timezone: None,
             files,
             setup,
             teardown,
             unit_tests,
+            skipped_unit_tests: vec![],
+            stateful: false,
+        }
+    }
+
+    pub fn with_skipped_unit_tests(mut self, skipped_unit_tests: Vec<String>) -> Self {
+        self.skipped_unit_tests = skipped_unit_tests;
+        self
+    }
+
+    pub fn skipped_unit_tests(&self) -> &[String] {
+        &self.skipped_unit_tests
+    }
+
+    pub fn with_env_passthrough(mut self, env_passthrough: Vec<String>) -> Self {
+        self.env_passthrough = env_passthrough;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: String) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: String) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn with_stateful(mut self, stateful: bool) -> Self {
+        self.stateful = stateful;
+        self
+    }
+
+    pub fn is_stateful(&self) -> bool {
+        self.stateful
+    }
+
+    /// Makes the stderr of every assertion informational: it is shown in the report, but
+    /// its expectations never fail them.
+    pub fn with_informational_stderr(mut self, informational_stderr: bool) -> Self {
+        for unit_test in &mut self.unit_tests {
+            unit_test.assertions = std::mem::take(&mut unit_test.assertions)
+                .into_iter()
+                .map(|assertion| assertion.with_informational_stderr(informational_stderr))
+                .collect();
         }
+        self
+    }
+
+    /// Keeps only the assertions tagged with `tag`, removing the unit tests that end up
+    /// without any assertion.
+    pub fn retain_tagged(&mut self, tag: &str) {
+        for unit_test in &mut self.unit_tests {
+            unit_test.retain_tagged(tag);
+        }
+        self.unit_tests.retain(|unit_test| unit_test.size() > 0);
+    }
+
+    pub fn set_retain_output(&mut self, retain_output: bool) {
+        for unit_test in &mut self.unit_tests {
+            unit_test.set_retain_output(retain_output);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unit_tests.is_empty()
+    }
+
+    pub fn unit_tests(&self) -> &[UnitTest] {
+        &self.unit_tests
     }
 
-    pub fn run(&self, grading_mode: GradingMode) -> UnitTestsResult {
+    /// Maximum score in `Weighted` mode, or `None` if it does not fit in an `u32`.
+    pub fn max_score(&self) -> Option<u32> {
+        self.unit_tests.iter().try_fold(0u32, |sum, unit_test| {
+            sum.checked_add(unit_test.max_score()?)
+        })
+    }
+
+    /// Runs every unit test, failing only if the environment of one of them can not be
+    /// prepared or the program can not be executed.
+    ///
+    /// Up to `max_parallelism` unit tests run at the same time, each assertion in its own
+    /// temporary directory. The results keep the order of the unit tests anyway. When
+    /// `stateful`, they run one at a time, in order, in the same directory instead.
+    ///
+    /// Once `interrupted` is set, no other assertion is started and the unit tests that
+    /// did not start are left out of the result. Past the `deadline`, the remaining
+    /// assertions fail without being executed, so they are still graded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &self,
+        grading_mode: GradingMode,
+        seed: u64,
+        global_dir: &Path,
+        shuffle_seed: Option<u64>,
+        max_parallelism: usize,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
+    ) -> io::Result<UnitTestsResult> {
         let mut result = UnitTestsResult::new(grading_mode);
-        for program_unit_assertion in self.unit_tests.iter() {
-            let res = program_unit_assertion
+        result.skipped_unit_tests = self.skipped_unit_tests.clone();
+        let mut env = vec![];
+        if !self.inherit_parent_env {
+            env.extend(
+                self.env_passthrough
+                    .iter()
+                    .filter_map(|name| Some((name.clone(), std::env::var(name).ok()?))),
+            );
+        }
+        // the configured variables take precedence over the parent ones
+        env.extend(self.env.iter().cloned());
+        if let Some(ref locale) = self.locale {
+            env.push(("LC_ALL".to_string(), locale.clone()));
+            env.push(("LANG".to_string(), locale.clone()));
+        }
+        if let Some(ref timezone) = self.timezone {
+            env.push(("TZ".to_string(), timezone.clone()));
+        }
+        env.push((SEED_ENV_VAR.to_string(), seed.to_string()));
+        env.push((
+            GLOBAL_DIR_ENV_VAR.to_string(),
+            global_dir.to_string_lossy().into_owned(),
+        ));
+        if self.stateful {
+            self.run_stateful(&env, grading_mode, interrupted, deadline, &mut result)?;
+            return Ok(result);
+        }
+        let order = match shuffle_seed {
+            Some(shuffle_seed) => shuffled_indices(self.unit_tests.len(), shuffle_seed),
+            None => (0..self.unit_tests.len()).collect(),
+        };
+        // the threads that run the unit tests do not inherit it otherwise
+        let context = LogContext::current();
+        let mut unit_test_results: Vec<_> = map_bounded(&order, max_parallelism, |&i| {
+            let _context = context.clone().enter();
+            if interrupted.load(Ordering::Relaxed) {
+                return (i, Ok(None));
+            }
+            let unit_test_result = self.unit_tests[i]
                 .run(
-                    &self.env,
+                    &env,
                     self.inherit_parent_env,
                     &self.files,
                     &self.setup,
                     &self.teardown,
+                    None,
                     grading_mode,
+                    interrupted,
+                    deadline,
                 )
-                // TODO (handle error): instead of panicking, it should incorporate
-                // the error into the result, making it clear why did it fail.
-                // Maybe, it would be better to incorporate the error to a more fine
-                // grained level of assertion.
-                .expect("error during assertion");
-            result.add_result(res);
+                .map(Some);
+            (i, unit_test_result)
+        });
+        // back to declaration order
+        unit_test_results.sort_by_key(|(i, _)| *i);
+        for (_, unit_test_result) in unit_test_results {
+            if let Some(unit_test_result) = unit_test_result? {
+                result.add_result(unit_test_result);
+            }
         }
-        result
+        Ok(result)
+    }
+
+    /// Runs the unit tests in order in a single workspace, which is prepared before the
+    /// first one and torn down after the last one that ran.
+    fn run_stateful(
+        &self,
+        env: &[(String, String)],
+        grading_mode: GradingMode,
+        interrupted: &AtomicBool,
+        deadline: Option<Instant>,
+        result: &mut UnitTestsResult,
+    ) -> io::Result<()> {
+        let workspace = prepare_workspace(env, self.inherit_parent_env, &self.files, &self.setup)?;
+        let teardown = TeardownGuard::new(|| {
+            run_commands(
+                "teardown",
+                &self.teardown,
+                workspace.path(),
+                env,
+                self.inherit_parent_env,
+            )
+        });
+        for unit_test in &self.unit_tests {
+            if interrupted.load(Ordering::Relaxed) {
+                break;
+            }
+            result.add_result(unit_test.run(
+                env,
+                self.inherit_parent_env,
+                &self.files,
+                &self.setup,
+                &self.teardown,
+                Some(workspace.path()),
+                grading_mode,
+                interrupted,
+                deadline,
+            )?);
+        }
+        teardown.run()
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UnitTestsResult {
     score: Score,
     assertions_per_executable_results: Vec<UnitTestResult>,
+    /// Names of the unit tests that were not executed because they are skipped.
+    skipped_unit_tests: Vec<String>,
 }
 
 impl UnitTestsResult {
@@ -251,6 +809,7 @@ impl UnitTestsResult {
         Self {
             score: Score::default(grading_mode),
             assertions_per_executable_results: vec![],
+            skipped_unit_tests: vec![],
         }
     }
 
@@ -259,6 +818,7 @@ impl UnitTestsResult {
         Self {
             score,
             assertions_per_executable_results: assertions_per_program_results,
+            skipped_unit_tests: vec![],
         }
     }
 
@@ -270,4 +830,117 @@ impl UnitTestsResult {
     pub fn score(&self) -> Score {
         self.score
     }
+
+    pub fn unit_test_results(&self) -> &[UnitTestResult] {
+        &self.assertions_per_executable_results
+    }
+
+    pub fn skipped_unit_tests(&self) -> &[String] {
+        &self.skipped_unit_tests
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, panic};
+
+    #[test]
+    fn should_run_the_teardown_once_when_run_explicitly() {
+        let runs = Cell::new(0);
+        let guard = TeardownGuard::new(|| {
+            runs.set(runs.get() + 1);
+            Err(io::Error::other("teardown failed"))
+        });
+
+        assert_eq!(guard.run().unwrap_err().to_string(), "teardown failed");
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn should_run_the_teardown_when_an_assertion_panics() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("torn_down");
+        let teardown = [("touch".to_string(), vec!["torn_down".to_string()])];
+
+        let result = panic::catch_unwind(|| {
+            let _teardown =
+                TeardownGuard::new(|| run_commands("teardown", &teardown, dir.path(), &[], true));
+            panic!("comparator failed midway");
+        });
+
+        assert!(result.is_err());
+        assert!(marker.exists());
+    }
+
+    fn echo_unit_test(base_args: Vec<String>) -> UnitTest {
+        UnitTest::new(
+            "echo".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "./my program".into(),
+                base_args,
+            },
+        )
+    }
+
+    #[test]
+    fn should_quote_the_reproduction_command_for_the_shell() {
+        let assertion = Assertion::new(
+            "quotes".to_string(),
+            vec![
+                "arg1".to_string(),
+                "arg 2".to_string(),
+                "it's".to_string(),
+                String::new(),
+            ],
+            Some("a \"b\"\n".to_string()),
+            1,
+        );
+
+        let command = echo_unit_test(vec!["-u".to_string()])
+            .reproduction_command(&assertion)
+            .unwrap();
+
+        assert_eq!(
+            command,
+            r#"printf '%s' 'a "b"
+' | './my program' -u arg1 'arg 2' 'it'\''s' ''"#
+        );
+        let (printf, program) = command.split_once(" | ").unwrap();
+        assert_eq!(shlex::split(printf).unwrap(), ["printf", "%s", "a \"b\"\n"]);
+        assert_eq!(
+            shlex::split(program).unwrap(),
+            ["./my program", "-u", "arg1", "arg 2", "it's", ""]
+        );
+    }
+
+    #[test]
+    fn should_reproduce_binary_stdin_with_octal_escapes() {
+        let assertion = Assertion::new("binary".to_string(), vec![], None, 1)
+            .with_stdin_bytes(vec![0, 0xff, b'a']);
+
+        assert_eq!(
+            echo_unit_test(vec![]).reproduction_command(&assertion),
+            Some(r"printf '\000\377\141' | './my program'".to_string())
+        );
+        let output = process::Command::new("sh")
+            .args(["-c", r"printf '\000\377\141'"])
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, [0, 0xff, b'a']);
+    }
+
+    #[test]
+    fn should_not_reproduce_programs_that_were_not_submitted() {
+        let unit_test = UnitTest::new(
+            "echo".to_string(),
+            ExecutableArtifact::NotSubmitted {
+                name: "program1".to_string(),
+            },
+        );
+        let assertion = Assertion::new("missing".to_string(), vec![], None, 1);
+
+        assert_eq!(unit_test.reproduction_command(&assertion), None);
+    }
 }
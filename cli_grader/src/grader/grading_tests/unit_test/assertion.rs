@@ -1,52 +1,516 @@
+use regex::bytes::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
+    fmt,
     io::Write,
-    process::{Command, Stdio},
+    path::{Component, Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    sync::LazyLock,
     thread,
+    time::{Duration, Instant},
 };
 
+use crate::grader::termination::{Termination, terminate};
 use log::{debug, info, warn};
 
+use super::{
+    clear_parent_env,
+    extraction::{ExtractionResult, StdoutExtract},
+    interaction::{InteractionFailure, InteractionScript},
+    regex_checks::{RegexChecksResult, StdoutRegexChecks},
+};
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Assertion {
     name: String,
     // Configuration
     args: Vec<String>,
-    stdin: Option<String>,
+    /// Raw bytes written to the stdin, which are not necessarily valid UTF-8.
+    stdin: Option<Vec<u8>>,
+    /// Subdirectory of the temporary directory where the program is launched, e.g. `src`.
+    cwd: Option<PathBuf>,
+    /// Commands executed in the temporary directory after the setup of the unit tests, to
+    /// prepare what only this assertion needs, e.g. a fixture.
+    setup: Vec<(String, Vec<String>)>,
+    /// Commands executed in the temporary directory after the program, before the
+    /// teardown of the unit tests.
+    teardown: Vec<(String, Vec<String>)>,
     // Expectation
     stdout: Option<String>,
+    /// Outputs that are all accepted as the stdout. The program must print exactly one of
+    /// them. It is independent of `stdout`.
+    stdout_any_of: Vec<String>,
     stderr: Option<String>,
-    status: Option<i32>,
+    status: Option<ExpectedStatus>,
+    /// Substring that must not appear in the stdout.
+    stdout_not_contains: Option<String>,
+    /// Substring that must not appear in the stderr.
+    stderr_not_contains: Option<String>,
+    /// Number of lines that the stdout must have.
+    stdout_line_count: Option<usize>,
+    /// Number of bytes that the stdout must have, regardless of their content.
+    stdout_exact_bytes: Option<usize>,
+    /// Maximum number of bytes that the stdout may have.
+    stdout_max_bytes: Option<usize>,
+    /// Whether the stdout must have some content (`true`) or be empty (`false`).
+    stdout_non_empty: Option<bool>,
+    /// Whether the stderr must be empty (`true`) or have some content (`false`).
+    stderr_empty: Option<bool>,
+    /// Whether the stdout and the stderr must be equal, e.g. for a program that echoes
+    /// its messages to both.
+    stdout_equals_stderr: bool,
+    /// Number captured from the stdout that must reach a minimum.
+    stdout_extract: Option<StdoutExtract>,
+    /// Pattern that the stdout must match, with relations between its groups.
+    stdout_regex_checks: Option<StdoutRegexChecks>,
+    /// Prompts that must be seen in the stdout, each one answered through the stdin.
+    interaction: Option<InteractionScript>,
+    /// Whether the ANSI escape codes, e.g. colors, are removed from the stdout and the
+    /// stderr before comparing them. The diagnostics still show them.
+    strip_ansi: bool,
+    /// Command through which both the obtained and the expected stdout are piped before
+    /// comparing them, e.g. `sort`. It only applies to `stdout`.
+    normalize_with: Option<(String, Vec<String>)>,
+    /// Outputs that may be captured. The others are discarded even if they would be
+    /// needed, which `check` rejects.
+    capture: CaptureMode,
+    /// How the stdout is compared against `stdout` and `stdout_any_of`.
+    stdout_match: StdoutMatch,
+    /// Whether the stderr is only captured to be shown in the report, so the stderr
+    /// expectations never fail the assertion.
+    informational_stderr: bool,
+    /// Whether the result keeps the raw output of the program, see
+    /// `AssertionResult::retained_stdout`. Both streams are then captured, unless
+    /// `capture` discards them.
+    retain_output: bool,
     // Grading
+    /// Labels used to group and filter assertions (e.g. "edge-cases").
+    tags: Vec<String>,
+    /// Feedback shown in the report when the assertion fails. It does not affect the
+    /// score.
+    hint: Option<String>,
     weight: u32,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct ExpectedObtainedResult<T> {
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ExpectedObtainedResult<T> {
     expected: T,
     obtained: Option<T>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+impl<T> ExpectedObtainedResult<T> {
+    pub fn expected(&self) -> &T {
+        &self.expected
+    }
+
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn obtained(&self) -> Option<&T> {
+        self.obtained.as_ref()
+    }
+}
+
+/// Exit status that a program must finish with. It is serialized as the code for `Exact`,
+/// and as `"zero"` or `"nonzero"` for the others, e.g. `2` or `"nonzero"`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(from = "ExpectedStatusRepr", into = "ExpectedStatusRepr")]
+#[schemars(with = "ExpectedStatusRepr")]
+pub enum ExpectedStatus {
+    /// The program must succeed.
+    Zero,
+    /// The program must fail with any exit code. A termination by a signal does not count.
+    NonZero,
+    Exact(i32),
+}
+
+impl ExpectedStatus {
+    /// Whether a program that exited with `code` (`None` if it was terminated by a signal)
+    /// meets this expectation.
+    pub fn accepts(&self, code: Option<i32>) -> bool {
+        match (self, code) {
+            (_, None) => false,
+            (Self::Zero, Some(code)) => code == 0,
+            (Self::NonZero, Some(code)) => code != 0,
+            (Self::Exact(expected), Some(code)) => *expected == code,
+        }
+    }
+}
+
+/// Shown as the code for `Exact` and `Zero`, and as `nonzero` for `NonZero`.
+impl fmt::Display for ExpectedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zero => write!(f, "0"),
+            Self::NonZero => write!(f, "nonzero"),
+            Self::Exact(code) => write!(f, "{code}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[serde(untagged)]
+enum ExpectedStatusRepr {
+    Exact(i32),
+    Named(NamedStatus),
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum NamedStatus {
+    Zero,
+    NonZero,
+}
+
+impl From<ExpectedStatusRepr> for ExpectedStatus {
+    fn from(repr: ExpectedStatusRepr) -> Self {
+        match repr {
+            ExpectedStatusRepr::Exact(code) => Self::Exact(code),
+            ExpectedStatusRepr::Named(NamedStatus::Zero) => Self::Zero,
+            ExpectedStatusRepr::Named(NamedStatus::NonZero) => Self::NonZero,
+        }
+    }
+}
+
+impl From<ExpectedStatus> for ExpectedStatusRepr {
+    fn from(status: ExpectedStatus) -> Self {
+        match status {
+            ExpectedStatus::Exact(code) => Self::Exact(code),
+            ExpectedStatus::Zero => Self::Named(NamedStatus::Zero),
+            ExpectedStatus::NonZero => Self::Named(NamedStatus::NonZero),
+        }
+    }
+}
+
+/// Outputs of the program that may be captured. By default (`Auto`), an output is only
+/// captured when something needs it, e.g. an expectation, and the others are discarded.
+/// It is serialized in snake case, e.g. `"stdout_only"`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    #[default]
+    Auto,
+    /// Both outputs are discarded, e.g. for a program only graded by its status that
+    /// prints a lot.
+    None,
+    StdoutOnly,
+    StderrOnly,
+}
+
+impl CaptureMode {
+    /// Whether the stdout is captured when needed.
+    pub fn captures_stdout(&self) -> bool {
+        matches!(self, Self::Auto | Self::StdoutOnly)
+    }
+
+    /// Whether the stderr is captured when needed.
+    pub fn captures_stderr(&self) -> bool {
+        matches!(self, Self::Auto | Self::StderrOnly)
+    }
+}
+
+/// How the obtained stdout is compared against the expected one. It is serialized in
+/// snake case, e.g. `"trim_line_ends"`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum StdoutMatch {
+    /// Byte by byte.
+    #[default]
+    Exact,
+    /// Ignoring the whitespace at the end of every line, e.g. the invisible trailing spaces
+    /// of a formatted table. The leading whitespace and the line breaks still count.
+    TrimLineEnds,
+    /// Ignoring the whitespace at the end of the whole output, e.g. a missing final newline.
+    TrimEnd,
+    /// Comparing only the words, i.e. any run of whitespace, including the line breaks,
+    /// counts as a single separator and the leading and trailing whitespace is ignored.
+    CollapseWhitespace,
+}
+
+impl StdoutMatch {
+    /// Whether `obtained` matches `expected`.
+    pub fn matches(&self, obtained: &[u8], expected: &[u8]) -> bool {
+        match self {
+            Self::Exact => obtained == expected,
+            Self::TrimLineEnds => trim_line_ends(obtained).eq(trim_line_ends(expected)),
+            Self::TrimEnd => obtained.trim_ascii_end() == expected.trim_ascii_end(),
+            Self::CollapseWhitespace => words(obtained).eq(words(expected)),
+        }
+    }
+}
+
+/// Lines of `output`, split on `\n`, without their trailing whitespace.
+fn trim_line_ends(output: &[u8]) -> impl Iterator<Item = &[u8]> {
+    output.split(|&b| b == b'\n').map(<[u8]>::trim_ascii_end)
+}
+
+/// Runs of non-whitespace bytes of `output`.
+fn words(output: &[u8]) -> impl Iterator<Item = &[u8]> {
+    output
+        .split(u8::is_ascii_whitespace)
+        .filter(|word| !word.is_empty())
+}
+
+/// Exit status that did not meet the expected one.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StatusResult {
+    expected: ExpectedStatus,
+    obtained: Option<i32>,
+}
+
+impl StatusResult {
+    pub fn expected(&self) -> ExpectedStatus {
+        self.expected
+    }
+
+    /// It is `None` when the program could not be executed or was terminated by a signal.
+    pub fn obtained(&self) -> Option<i32> {
+        self.obtained
+    }
+}
+
+/// Stdout that did not match any of the acceptable outputs.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AnyOfResult {
+    acceptable: Vec<String>,
+    obtained: Option<String>,
+}
+
+impl AnyOfResult {
+    pub fn acceptable(&self) -> &[String] {
+        &self.acceptable
+    }
+
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn obtained(&self) -> Option<&str> {
+        self.obtained.as_deref()
+    }
+}
+
+/// Stderr that was expected to be empty, or to have some content, but was not.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct EmptyOutputResult {
+    expected_empty: bool,
+    obtained: Option<String>,
+}
+
+impl EmptyOutputResult {
+    pub fn expected_empty(&self) -> bool {
+        self.expected_empty
+    }
+
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn obtained(&self) -> Option<&str> {
+        self.obtained.as_deref()
+    }
+}
+
+/// Stdout and stderr that were expected to be equal, but were not.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UnequalStreamsResult {
+    stdout: Option<String>,
+    stderr: Option<String>,
+}
+
+impl UnequalStreamsResult {
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn stdout(&self) -> Option<&str> {
+        self.stdout.as_deref()
+    }
+
+    /// It is `None` when nothing could be obtained from the program, like `stdout`.
+    pub fn stderr(&self) -> Option<&str> {
+        self.stderr.as_deref()
+    }
+}
+
+/// Where a forbidden substring was found in the output of a program.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ForbiddenOutputResult {
+    forbidden: String,
+    /// 1-based line of the first occurrence.
+    line: usize,
+    /// 1-based column, in characters, of the first occurrence.
+    column: usize,
+}
+
+impl ForbiddenOutputResult {
+    /// Looks for the first occurrence of `forbidden` in `output`.
+    fn find(forbidden: &str, output: &str) -> Option<Self> {
+        let position = output.find(forbidden)?;
+        let before = &output[..position];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Some(Self {
+            forbidden: forbidden.to_string(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        })
+    }
+
+    pub fn forbidden(&self) -> &str {
+        &self.forbidden
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// How the execution of the program ended.
+///
+/// It is serialized with its kind in snake case and, for `FailureWithStatus`, the status
+/// code, e.g. `{"kind": "success"}` or `{"kind": "failure_with_status", "status": 1}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(tag = "kind", content = "status", rename_all = "snake_case")]
 pub enum ExecutionStatus {
     Success,
     FailureWithStatus(i32),
     FailureBeforeExecution,
     FailureBeforeWait,
     FailureWithSignalTermination,
+    /// The program was not submitted, so nothing was executed.
+    ProgramNotSubmitted,
+    /// The setup of the assertion could not be executed or failed, so the program was not
+    /// executed.
+    FailureInSetup,
+    /// The program was executed, but the command that normalizes its stdout could not be
+    /// executed or failed, which is an error of the configuration.
+    FailureInNormalization,
+    /// The deadline of the whole run was exceeded before the assertion, so the program was
+    /// not executed.
+    DeadlineExceeded,
     Undefined,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+impl ExecutionStatus {
+    /// Whether the program did not get to exit by itself, e.g. it could not be executed or
+    /// it was terminated by a signal, which tells more than its outputs.
+    pub fn is_abnormal(&self) -> bool {
+        matches!(
+            self,
+            Self::FailureBeforeExecution
+                | Self::FailureBeforeWait
+                | Self::FailureWithSignalTermination
+                | Self::ProgramNotSubmitted
+                | Self::FailureInSetup
+                | Self::FailureInNormalization
+                | Self::DeadlineExceeded
+        )
+    }
+}
+
+/// Shown as what happened to the program, e.g. `terminated by a signal`.
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Success => write!(f, "exited successfully"),
+            Self::FailureWithStatus(status) => write!(f, "exited with status {status}"),
+            Self::FailureBeforeExecution => write!(f, "could not be executed"),
+            Self::FailureBeforeWait => write!(f, "could not be waited for"),
+            Self::FailureWithSignalTermination => write!(f, "terminated by a signal"),
+            Self::ProgramNotSubmitted => write!(f, "not submitted"),
+            Self::FailureInSetup => write!(f, "not executed, as the setup of the assertion failed"),
+            Self::FailureInNormalization => {
+                write!(f, "executed, but its stdout could not be normalized")
+            }
+            Self::DeadlineExceeded => write!(f, "not run: deadline exceeded"),
+            Self::Undefined => write!(f, "did not run"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AssertionResult {
     execution_status: ExecutionStatus,
     name: String,
     passed: bool,
     weight: u32,
     stdout_diagnostics: Option<ExpectedObtainedResult<String>>,
+    stdout_any_of_diagnostics: Option<AnyOfResult>,
     stderr_diagnostics: Option<ExpectedObtainedResult<String>>,
-    status_diagnostics: Option<ExpectedObtainedResult<i32>>,
+    status_diagnostics: Option<StatusResult>,
+    stdout_not_contains_diagnostics: Option<ForbiddenOutputResult>,
+    stderr_not_contains_diagnostics: Option<ForbiddenOutputResult>,
+    stdout_line_count_diagnostics: Option<ExpectedObtainedResult<usize>>,
+    stdout_exact_bytes_diagnostics: Option<ExpectedObtainedResult<usize>>,
+    /// The expected value is the maximum number of bytes.
+    stdout_max_bytes_diagnostics: Option<ExpectedObtainedResult<usize>>,
+    stdout_non_empty_diagnostics: Option<ExpectedObtainedResult<bool>>,
+    stderr_empty_diagnostics: Option<EmptyOutputResult>,
+    stdout_equals_stderr_diagnostics: Option<UnequalStreamsResult>,
+    stdout_extract_diagnostics: Option<ExtractionResult>,
+    stdout_regex_checks_diagnostics: Option<RegexChecksResult>,
+    interaction_diagnostics: Option<InteractionFailure>,
+    /// Stderr captured only to be shown, when it is informational.
+    informational_stderr: Option<String>,
+    tags: Vec<String>,
+    hint: Option<String>,
+    /// Arguments that the program actually received (the program itself is not included),
+    /// which makes clear how the args string was split.
+    argv: Vec<String>,
+    /// Shell command that runs the program the same way, e.g. to reproduce a failure
+    /// locally. It is `None` when the program was not submitted.
+    reproduction_command: Option<String>,
+    /// Wall-clock time between spawning the program and its end. It is `None` when the
+    /// program could not be spawned.
+    duration: Option<Duration>,
+    /// Output of the program as it was captured, kept only when the assertion retains it,
+    /// see `GradingConfig::set_retain_output`. It is not serialized, as it may be large.
+    #[serde(skip)]
+    retained_output: Option<RetainedOutput>,
+}
+
+/// Raw output of a program, for library users that check it on their own.
+#[derive(Debug, Clone)]
+struct RetainedOutput {
+    /// `None` when the stdout was not captured.
+    stdout: Option<Vec<u8>>,
+    /// `None` when the stderr was not captured.
+    stderr: Option<Vec<u8>>,
+    status: ExitStatus,
+}
+
+/// The `duration` and the `retained_output` are ignored, as they are measurements that
+/// change from one execution to another, which the diagnostics already summarize.
+impl PartialEq for AssertionResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.execution_status == other.execution_status
+            && self.name == other.name
+            && self.passed == other.passed
+            && self.weight == other.weight
+            && self.stdout_diagnostics == other.stdout_diagnostics
+            && self.stdout_any_of_diagnostics == other.stdout_any_of_diagnostics
+            && self.stderr_diagnostics == other.stderr_diagnostics
+            && self.status_diagnostics == other.status_diagnostics
+            && self.stdout_not_contains_diagnostics == other.stdout_not_contains_diagnostics
+            && self.stderr_not_contains_diagnostics == other.stderr_not_contains_diagnostics
+            && self.stdout_line_count_diagnostics == other.stdout_line_count_diagnostics
+            && self.stdout_exact_bytes_diagnostics == other.stdout_exact_bytes_diagnostics
+            && self.stdout_max_bytes_diagnostics == other.stdout_max_bytes_diagnostics
+            && self.stdout_non_empty_diagnostics == other.stdout_non_empty_diagnostics
+            && self.stderr_empty_diagnostics == other.stderr_empty_diagnostics
+            && self.stdout_equals_stderr_diagnostics == other.stdout_equals_stderr_diagnostics
+            && self.stdout_extract_diagnostics == other.stdout_extract_diagnostics
+            && self.stdout_regex_checks_diagnostics == other.stdout_regex_checks_diagnostics
+            && self.interaction_diagnostics == other.interaction_diagnostics
+            && self.informational_stderr == other.informational_stderr
+            && self.tags == other.tags
+            && self.hint == other.hint
+            && self.argv == other.argv
+            && self.reproduction_command == other.reproduction_command
+    }
 }
 
+impl Eq for AssertionResult {}
+
 impl AssertionResult {
     fn new(name: String, weight: u32) -> Self {
         Self {
@@ -54,9 +518,28 @@ impl AssertionResult {
             passed: false,
             execution_status: ExecutionStatus::Undefined,
             stdout_diagnostics: None,
+            stdout_any_of_diagnostics: None,
             stderr_diagnostics: None,
             status_diagnostics: None,
+            stdout_not_contains_diagnostics: None,
+            stderr_not_contains_diagnostics: None,
+            stdout_line_count_diagnostics: None,
+            stdout_exact_bytes_diagnostics: None,
+            stdout_max_bytes_diagnostics: None,
+            stdout_non_empty_diagnostics: None,
+            stderr_empty_diagnostics: None,
+            stdout_equals_stderr_diagnostics: None,
+            stdout_extract_diagnostics: None,
+            stdout_regex_checks_diagnostics: None,
+            interaction_diagnostics: None,
+            informational_stderr: None,
+            tags: vec![],
+            hint: None,
+            argv: vec![],
+            reproduction_command: None,
             weight,
+            duration: None,
+            retained_output: None,
         }
     }
 
@@ -70,10 +553,135 @@ impl AssertionResult {
         self.weight
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+
+    pub fn execution_status(&self) -> ExecutionStatus {
+        self.execution_status
+    }
+
+    pub fn stdout_diagnostics(&self) -> Option<&ExpectedObtainedResult<String>> {
+        self.stdout_diagnostics.as_ref()
+    }
+
+    pub fn stdout_any_of_diagnostics(&self) -> Option<&AnyOfResult> {
+        self.stdout_any_of_diagnostics.as_ref()
+    }
+
+    pub fn stderr_diagnostics(&self) -> Option<&ExpectedObtainedResult<String>> {
+        self.stderr_diagnostics.as_ref()
+    }
+
+    pub fn status_diagnostics(&self) -> Option<&StatusResult> {
+        self.status_diagnostics.as_ref()
+    }
+
+    pub fn stdout_not_contains_diagnostics(&self) -> Option<&ForbiddenOutputResult> {
+        self.stdout_not_contains_diagnostics.as_ref()
+    }
+
+    pub fn stderr_not_contains_diagnostics(&self) -> Option<&ForbiddenOutputResult> {
+        self.stderr_not_contains_diagnostics.as_ref()
+    }
+
+    pub fn stdout_line_count_diagnostics(&self) -> Option<&ExpectedObtainedResult<usize>> {
+        self.stdout_line_count_diagnostics.as_ref()
+    }
+
+    pub fn stdout_exact_bytes_diagnostics(&self) -> Option<&ExpectedObtainedResult<usize>> {
+        self.stdout_exact_bytes_diagnostics.as_ref()
+    }
+
+    pub fn stdout_max_bytes_diagnostics(&self) -> Option<&ExpectedObtainedResult<usize>> {
+        self.stdout_max_bytes_diagnostics.as_ref()
+    }
+
+    pub fn stdout_non_empty_diagnostics(&self) -> Option<&ExpectedObtainedResult<bool>> {
+        self.stdout_non_empty_diagnostics.as_ref()
+    }
+
+    pub fn stderr_empty_diagnostics(&self) -> Option<&EmptyOutputResult> {
+        self.stderr_empty_diagnostics.as_ref()
+    }
+
+    pub fn stdout_equals_stderr_diagnostics(&self) -> Option<&UnequalStreamsResult> {
+        self.stdout_equals_stderr_diagnostics.as_ref()
+    }
+
+    pub fn stdout_extract_diagnostics(&self) -> Option<&ExtractionResult> {
+        self.stdout_extract_diagnostics.as_ref()
+    }
+
+    pub fn stdout_regex_checks_diagnostics(&self) -> Option<&RegexChecksResult> {
+        self.stdout_regex_checks_diagnostics.as_ref()
+    }
+
+    pub fn interaction_diagnostics(&self) -> Option<&InteractionFailure> {
+        self.interaction_diagnostics.as_ref()
+    }
+
+    /// Stderr of the program when it is informational, see
+    /// `Assertion::with_informational_stderr`.
+    pub fn informational_stderr(&self) -> Option<&str> {
+        self.informational_stderr.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    pub fn argv(&self) -> &[String] {
+        &self.argv
+    }
+
+    pub fn reproduction_command(&self) -> Option<&str> {
+        self.reproduction_command.as_deref()
+    }
+
+    pub(crate) fn with_reproduction_command(mut self, reproduction_command: String) -> Self {
+        self.reproduction_command = Some(reproduction_command);
+        self
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Stdout captured from the program, as it was printed. It is `None` unless the
+    /// assertion retains its output and the stdout was captured.
+    pub fn retained_stdout(&self) -> Option<&[u8]> {
+        self.retained_output.as_ref()?.stdout.as_deref()
+    }
+
+    /// Stderr captured from the program, as it was printed. It is `None` unless the
+    /// assertion retains its output and the stderr was captured.
+    pub fn retained_stderr(&self) -> Option<&[u8]> {
+        self.retained_output.as_ref()?.stderr.as_deref()
+    }
+
+    /// Exit status of the program. It is `None` unless the assertion retains its output
+    /// and the program ran until the end.
+    pub fn retained_status(&self) -> Option<ExitStatus> {
+        self.retained_output.as_ref().map(|output| output.status)
+    }
+
     fn set_passed(&mut self, v: bool) {
         self.passed = v;
     }
 
+    fn set_duration(&mut self, duration: Duration) {
+        self.duration = Some(duration);
+    }
+
     fn set_execution_status(&mut self, status: ExecutionStatus) {
         self.execution_status = status;
     }
@@ -85,12 +693,49 @@ impl AssertionResult {
         self.stderr_diagnostics = Some(ExpectedObtainedResult { expected, obtained });
     }
 
-    fn set_status_diagnostics(&mut self, expected: i32, obtained: Option<i32>) {
-        self.status_diagnostics = Some(ExpectedObtainedResult { expected, obtained });
+    fn set_status_diagnostics(&mut self, expected: ExpectedStatus, obtained: Option<i32>) {
+        self.status_diagnostics = Some(StatusResult { expected, obtained });
     }
 }
 
 impl Assertion {
+    /// Creates an assertion without any expectation. The expectations must be added with
+    /// the `with_*` methods and, in the end, the assertion must be validated with `check`.
+    pub fn new(name: String, args: Vec<String>, stdin: Option<String>, weight: u32) -> Self {
+        Self {
+            name,
+            args,
+            stdin: stdin.map(String::into_bytes),
+            cwd: None,
+            setup: vec![],
+            teardown: vec![],
+            stdout: None,
+            stdout_any_of: vec![],
+            stderr: None,
+            status: None,
+            stdout_not_contains: None,
+            stderr_not_contains: None,
+            stdout_line_count: None,
+            stdout_exact_bytes: None,
+            stdout_max_bytes: None,
+            stdout_non_empty: None,
+            stderr_empty: None,
+            stdout_equals_stderr: false,
+            stdout_extract: None,
+            stdout_regex_checks: None,
+            interaction: None,
+            strip_ansi: false,
+            normalize_with: None,
+            capture: CaptureMode::Auto,
+            stdout_match: StdoutMatch::Exact,
+            informational_stderr: false,
+            retain_output: false,
+            tags: vec![],
+            hint: None,
+            weight,
+        }
+    }
+
     pub fn build(
         name: String,
         // input
@@ -103,572 +748,2671 @@ impl Assertion {
         // grading
         weight: u32,
     ) -> Result<Self, &'static str> {
-        if stdout.is_none() && stderr.is_none() && status.is_none() {
-            return Err("at least one expect field must be non-null (stdout, stderr, or status)");
-        }
-
-        Ok(Self {
-            name,
-            args,
-            stdin,
+        Self {
             stdout,
             stderr,
-            status,
-            weight,
-        })
-    }
-    fn config_cmd(&self, cmd: &mut Command) {
-        debug!("Configuring command '{:?}'", cmd.get_program());
-        debug!("- Adding args: '{:?}'", self.args);
-        cmd.args(&self.args)
-            .stdin(if self.stdin.is_some() {
-                debug!("- Setting stdin");
-                Stdio::piped()
-            } else {
-                Stdio::null()
-            })
-            .stdout(if self.stdout.is_some() {
-                debug!("- Setting stdout");
-                Stdio::piped()
-            } else {
-                Stdio::null()
-            })
-            .stderr(if self.stderr.is_some() {
-                debug!("- Setting stderr");
-                Stdio::piped()
-            } else {
-                Stdio::null()
-            });
+            status: status.map(ExpectedStatus::Exact),
+            ..Self::new(name, args, stdin, weight)
+        }
+        .check()
     }
 
-    fn assert_stdout_stderr_status_against_null(&self, assertion_result: &mut AssertionResult) {
-        if let Some(ref expected_stdout) = self.stdout {
-            assertion_result.set_stdout_diagnostics(expected_stdout.clone(), None);
+    /// Validates that there is at least one expectation, that the stdin is not given
+    /// twice, that the stdout is expected if it is normalized, that the outputs with
+    /// expectations are captured and that the working directory does not leave the temporary directory.
+    pub fn check(self) -> Result<Self, &'static str> {
+        if self.stdout.is_none()
+            && self.stdout_any_of.is_empty()
+            && self.stderr.is_none()
+            && self.status.is_none()
+            && self.stdout_not_contains.is_none()
+            && self.stderr_not_contains.is_none()
+            && self.stdout_line_count.is_none()
+            && self.stdout_exact_bytes.is_none()
+            && self.stdout_max_bytes.is_none()
+            && self.stdout_non_empty.is_none()
+            && self.stderr_empty.is_none()
+            && !self.stdout_equals_stderr
+            && self.stdout_extract.is_none()
+            && self.stdout_regex_checks.is_none()
+            && self.interaction.is_none()
+        {
+            return Err(
+                "at least one expect field must be non-null (stdout, stdout_any_of, stderr, status, stdout_not_contains, stderr_not_contains, stdout_line_count, stdout_exact_bytes, stdout_max_bytes, stdout_non_empty, stderr_empty, stdout_equals_stderr, stdout_extract, stdout_regex_checks, or interaction)",
+            );
         }
-        if let Some(ref expected_stderr) = self.stderr {
-            assertion_result.set_stderr_diagnostics(expected_stderr.clone(), None);
+        if self.stdin.is_some() && self.interaction.is_some() {
+            return Err("stdin and interaction can not be used together");
         }
-        if let Some(expected_status) = self.status {
-            assertion_result.set_status_diagnostics(expected_status, None);
+        if self.normalize_with.is_some() && self.stdout.is_none() {
+            return Err("normalize_with requires stdout");
+        }
+        if self.stdout_match != StdoutMatch::Exact
+            && self.stdout.is_none()
+            && self.stdout_any_of.is_empty()
+        {
+            return Err("stdout_match requires stdout or stdout_any_of");
+        }
+        if self.expects_stdout() && !self.capture.captures_stdout() {
+            return Err("the stdout has expectations, but capture discards it");
+        }
+        if self.expects_stderr() && !self.capture.captures_stderr() {
+            return Err("the stderr has expectations, but capture discards it");
+        }
+        if self.cwd.as_deref().is_some_and(|cwd| !is_inner_path(cwd)) {
+            return Err("cwd must be a relative path inside the temporary directory");
         }
+        Ok(self)
     }
 
-    pub fn unsafe_assert_cmd(&self, mut cmd: Command) -> AssertionResult {
-        info!("🚀 Executing assertion: '{}'", self.name);
-        warn!("⚠️  This assertion is UNSAFE!");
-        self.config_cmd(&mut cmd);
+    /// Replaces the stdin with raw bytes, e.g. decoded from base64.
+    pub fn with_stdin_bytes(mut self, stdin: Vec<u8>) -> Self {
+        self.stdin = Some(stdin);
+        self
+    }
 
-        let mut assertion_result = AssertionResult::new(self.name.clone(), self.weight);
-        info!("🔄 Trying to execute the program...");
-        let mut child = match cmd.spawn() {
-            Ok(handler) => handler,
-            Err(err) => {
-                warn!("❌ Unable to execute the command");
-                debug!("💥 Error: '{err:?}'");
-                info!("❌ Assertion not passed");
-                assertion_result.set_execution_status(ExecutionStatus::FailureBeforeExecution);
-                self.assert_stdout_stderr_status_against_null(&mut assertion_result);
-                return assertion_result;
-            }
-        };
+    /// Launches the program from `cwd`, relative to the temporary directory of the test.
+    pub fn with_cwd(mut self, cwd: PathBuf) -> Self {
+        self.cwd = Some(cwd);
+        self
+    }
 
-        if let Some(ref stdin_content) = self.stdin {
-            info!("📥 Injecting stdin");
-            debug!("📝 stdin: '{}'", stdin_content.replace('\n', "\\n"));
-            let mut stdin = child
-                .stdin
-                .take()
-                .expect("expected stdin from configuration");
-            let stdin_content = stdin_content.clone();
+    pub fn with_stdout(mut self, stdout: String) -> Self {
+        self.stdout = Some(stdout);
+        self
+    }
 
-            thread::spawn(move || stdin.write_all(stdin_content.as_bytes()));
-        }
+    pub fn with_stdout_any_of(mut self, acceptable: Vec<String>) -> Self {
+        self.stdout_any_of = acceptable;
+        self
+    }
 
-        info!("Trying to wait the command to finish");
-        let output = match child.wait_with_output() {
-            Ok(output) => output,
-            Err(err) => {
-                warn!("⏱️  Unable to wait the command finish");
-                debug!("💥 Error: '{err:?}'");
-                info!("❌ Assertion not passed");
-                assertion_result.set_execution_status(ExecutionStatus::FailureBeforeWait);
-                self.assert_stdout_stderr_status_against_null(&mut assertion_result);
-                return assertion_result;
-            }
-        };
-        if !output.stdout.is_empty() {
-            debug!(
-                "- STDOUT: '{}'",
-                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
-            );
+    pub fn with_stderr(mut self, stderr: String) -> Self {
+        self.stderr = Some(stderr);
+        self
+    }
+
+    pub fn with_status(mut self, status: i32) -> Self {
+        self.status = Some(ExpectedStatus::Exact(status));
+        self
+    }
+
+    pub fn with_expected_status(mut self, status: ExpectedStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn with_stdout_not_contains(mut self, forbidden: String) -> Self {
+        self.stdout_not_contains = Some(forbidden);
+        self
+    }
+
+    pub fn with_stderr_not_contains(mut self, forbidden: String) -> Self {
+        self.stderr_not_contains = Some(forbidden);
+        self
+    }
+
+    pub fn with_stdout_line_count(mut self, line_count: usize) -> Self {
+        self.stdout_line_count = Some(line_count);
+        self
+    }
+
+    pub fn with_stdout_exact_bytes(mut self, bytes: usize) -> Self {
+        self.stdout_exact_bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_stdout_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.stdout_max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_stdout_non_empty(mut self, non_empty: bool) -> Self {
+        self.stdout_non_empty = Some(non_empty);
+        self
+    }
+
+    pub fn with_stderr_empty(mut self, empty: bool) -> Self {
+        self.stderr_empty = Some(empty);
+        self
+    }
+
+    pub fn with_stdout_equals_stderr(mut self, equals: bool) -> Self {
+        self.stdout_equals_stderr = equals;
+        self
+    }
+
+    pub fn with_stdout_extract(mut self, extract: StdoutExtract) -> Self {
+        self.stdout_extract = Some(extract);
+        self
+    }
+
+    pub fn with_stdout_regex_checks(mut self, regex_checks: StdoutRegexChecks) -> Self {
+        self.stdout_regex_checks = Some(regex_checks);
+        self
+    }
+
+    pub fn with_strip_ansi(mut self, strip_ansi: bool) -> Self {
+        self.strip_ansi = strip_ansi;
+        self
+    }
+
+    pub fn with_normalize_with(mut self, normalizer: (String, Vec<String>)) -> Self {
+        self.normalize_with = Some(normalizer);
+        self
+    }
+
+    pub fn with_capture(mut self, capture: CaptureMode) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    pub fn with_stdout_match(mut self, stdout_match: StdoutMatch) -> Self {
+        self.stdout_match = stdout_match;
+        self
+    }
+
+    pub fn with_informational_stderr(mut self, informational_stderr: bool) -> Self {
+        self.informational_stderr = informational_stderr;
+        self
+    }
+
+    pub(crate) fn set_retain_output(&mut self, retain_output: bool) {
+        self.retain_output = retain_output;
+    }
+
+    pub fn with_setup(mut self, setup: Vec<(String, Vec<String>)>) -> Self {
+        self.setup = setup;
+        self
+    }
+
+    pub fn with_teardown(mut self, teardown: Vec<(String, Vec<String>)>) -> Self {
+        self.teardown = teardown;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_hint(mut self, hint: String) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn stdin(&self) -> Option<&[u8]> {
+        self.stdin.as_deref()
+    }
+
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    pub fn setup(&self) -> &[(String, Vec<String>)] {
+        &self.setup
+    }
+
+    pub fn teardown(&self) -> &[(String, Vec<String>)] {
+        &self.teardown
+    }
+
+    pub fn stdout(&self) -> Option<&str> {
+        self.stdout.as_deref()
+    }
+
+    pub fn stdout_any_of(&self) -> &[String] {
+        &self.stdout_any_of
+    }
+
+    pub fn stderr(&self) -> Option<&str> {
+        self.stderr.as_deref()
+    }
+
+    pub fn status(&self) -> Option<ExpectedStatus> {
+        self.status
+    }
+
+    pub fn stdout_not_contains(&self) -> Option<&str> {
+        self.stdout_not_contains.as_deref()
+    }
+
+    pub fn stderr_not_contains(&self) -> Option<&str> {
+        self.stderr_not_contains.as_deref()
+    }
+
+    pub fn stdout_line_count(&self) -> Option<usize> {
+        self.stdout_line_count
+    }
+
+    pub fn stdout_exact_bytes(&self) -> Option<usize> {
+        self.stdout_exact_bytes
+    }
+
+    pub fn stdout_max_bytes(&self) -> Option<usize> {
+        self.stdout_max_bytes
+    }
+
+    pub fn stdout_non_empty(&self) -> Option<bool> {
+        self.stdout_non_empty
+    }
+
+    pub fn stderr_empty(&self) -> Option<bool> {
+        self.stderr_empty
+    }
+
+    pub fn stdout_equals_stderr(&self) -> bool {
+        self.stdout_equals_stderr
+    }
+
+    pub fn stdout_extract(&self) -> Option<&StdoutExtract> {
+        self.stdout_extract.as_ref()
+    }
+
+    pub fn stdout_regex_checks(&self) -> Option<&StdoutRegexChecks> {
+        self.stdout_regex_checks.as_ref()
+    }
+
+    pub fn interaction(&self) -> Option<&InteractionScript> {
+        self.interaction.as_ref()
+    }
+
+    pub fn strips_ansi(&self) -> bool {
+        self.strip_ansi
+    }
+
+    pub fn normalize_with(&self) -> Option<&(String, Vec<String>)> {
+        self.normalize_with.as_ref()
+    }
+
+    pub fn capture(&self) -> CaptureMode {
+        self.capture
+    }
+
+    pub fn stdout_match(&self) -> StdoutMatch {
+        self.stdout_match
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn has_informational_stderr(&self) -> bool {
+        self.informational_stderr
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn with_interaction(mut self, interaction: InteractionScript) -> Self {
+        self.interaction = Some(interaction);
+        self
+    }
+
+    /// Whether something needs the stdout, e.g. an expectation or the interaction.
+    fn expects_stdout(&self) -> bool {
+        self.stdout.is_some()
+            || !self.stdout_any_of.is_empty()
+            || self.stdout_not_contains.is_some()
+            || self.stdout_line_count.is_some()
+            || self.stdout_exact_bytes.is_some()
+            || self.stdout_max_bytes.is_some()
+            || self.stdout_non_empty.is_some()
+            || self.stdout_extract.is_some()
+            || self.stdout_regex_checks.is_some()
+            || self.interaction.is_some()
+            || self.stdout_equals_stderr
+    }
+
+    fn expects_stderr(&self) -> bool {
+        self.stderr.is_some()
+            || self.stderr_not_contains.is_some()
+            || self.stderr_empty.is_some()
+            || self.stdout_equals_stderr
+    }
+
+    /// Whether the stderr is captured, only to be shown, when it is informational.
+    fn captures_informational_stderr(&self) -> bool {
+        self.informational_stderr && self.capture.captures_stderr()
+    }
+
+    fn config_cmd(&self, cmd: &mut Command) {
+        debug!("Configuring command '{:?}'", cmd.get_program());
+        debug!("- Adding args: '{:?}'", self.args);
+        cmd.args(&self.args)
+            .stdin(if self.stdin.is_some() || self.interaction.is_some() {
+                debug!("- Setting stdin");
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(if self.pipes_stdout() {
+                debug!("- Setting stdout");
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stderr(if self.pipes_stderr() {
+                debug!("- Setting stderr");
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            });
+    }
+
+    fn pipes_stdout(&self) -> bool {
+        self.capture.captures_stdout() && (self.expects_stdout() || self.retain_output)
+    }
+
+    fn pipes_stderr(&self) -> bool {
+        (self.capture.captures_stderr() && (self.expects_stderr() || self.retain_output))
+            || self.captures_informational_stderr()
+    }
+
+    fn assert_stdout_stderr_status_against_null(&self, assertion_result: &mut AssertionResult) {
+        if let Some(ref expected_stdout) = self.stdout {
+            assertion_result.set_stdout_diagnostics(expected_stdout.clone(), None);
+        }
+        if !self.stdout_any_of.is_empty() {
+            assertion_result.stdout_any_of_diagnostics = Some(AnyOfResult {
+                acceptable: self.stdout_any_of.clone(),
+                obtained: None,
+            });
+        }
+        if let Some(ref expected_stderr) = self.stderr
+            && !self.informational_stderr
+        {
+            assertion_result.set_stderr_diagnostics(expected_stderr.clone(), None);
+        }
+        if let Some(expected_status) = self.status {
+            assertion_result.set_status_diagnostics(expected_status, None);
+        }
+        if let Some(expected_line_count) = self.stdout_line_count {
+            assertion_result.stdout_line_count_diagnostics = Some(ExpectedObtainedResult {
+                expected: expected_line_count,
+                obtained: None,
+            });
+        }
+        if let Some(expected_bytes) = self.stdout_exact_bytes {
+            assertion_result.stdout_exact_bytes_diagnostics = Some(ExpectedObtainedResult {
+                expected: expected_bytes,
+                obtained: None,
+            });
+        }
+        if let Some(max_bytes) = self.stdout_max_bytes {
+            assertion_result.stdout_max_bytes_diagnostics = Some(ExpectedObtainedResult {
+                expected: max_bytes,
+                obtained: None,
+            });
+        }
+        if let Some(expected_non_empty) = self.stdout_non_empty {
+            assertion_result.stdout_non_empty_diagnostics = Some(ExpectedObtainedResult {
+                expected: expected_non_empty,
+                obtained: None,
+            });
+        }
+        if let Some(expected_empty) = self.stderr_empty
+            && !self.informational_stderr
+        {
+            assertion_result.stderr_empty_diagnostics = Some(EmptyOutputResult {
+                expected_empty,
+                obtained: None,
+            });
+        }
+        if self.stdout_equals_stderr && !self.informational_stderr {
+            assertion_result.stdout_equals_stderr_diagnostics = Some(UnequalStreamsResult {
+                stdout: None,
+                stderr: None,
+            });
+        }
+        if let Some(ref extract) = self.stdout_extract {
+            assertion_result.stdout_extract_diagnostics = Some(extract.result(None));
+        }
+        if let Some(ref regex_checks) = self.stdout_regex_checks {
+            assertion_result.stdout_regex_checks_diagnostics = Some(regex_checks.result(None));
+        }
+    }
+
+    /// Result of the assertion when its program was not submitted: it fails without being
+    /// executed, and every expectation is compared against nothing.
+    pub fn not_submitted_result(&self) -> AssertionResult {
+        info!(
+            "❌ Assertion '{}' not passed: program not submitted",
+            self.name
+        );
+        self.not_executed_result(ExecutionStatus::ProgramNotSubmitted)
+    }
+
+    /// Result of the assertion when its own setup failed: it fails without the program
+    /// being executed, like `not_submitted_result`.
+    pub fn setup_failure_result(&self) -> AssertionResult {
+        info!("❌ Assertion '{}' not passed: setup failed", self.name);
+        self.not_executed_result(ExecutionStatus::FailureInSetup)
+    }
+
+    /// Result of the assertion when the deadline of the run was exceeded before it: it
+    /// fails without being executed, like `not_submitted_result`.
+    pub fn deadline_exceeded_result(&self) -> AssertionResult {
+        info!("❌ Assertion '{}' not run: deadline exceeded", self.name);
+        self.not_executed_result(ExecutionStatus::DeadlineExceeded)
+    }
+
+    fn not_executed_result(&self, execution_status: ExecutionStatus) -> AssertionResult {
+        let mut assertion_result = AssertionResult::new(self.name.clone(), self.weight);
+        assertion_result.tags = self.tags.clone();
+        assertion_result.hint = self.hint.clone();
+        assertion_result.argv = self.args.clone();
+        assertion_result.set_execution_status(execution_status);
+        self.assert_stdout_stderr_status_against_null(&mut assertion_result);
+        assertion_result
+    }
+
+    pub fn unsafe_assert_cmd(&self, cmd: Command) -> AssertionResult {
+        self.unsafe_assert_cmd_in_env(cmd, &[], true)
+    }
+
+    /// The same as `unsafe_assert_cmd`, but the `normalize_with` command gets the
+    /// environment of the unit tests, i.e. `envs` and whether the variables of the parent
+    /// process are inherited, as `cmd` already does.
+    pub(crate) fn unsafe_assert_cmd_in_env(
+        &self,
+        mut cmd: Command,
+        envs: &[(String, String)],
+        inherit_parent_env: bool,
+    ) -> AssertionResult {
+        info!("🚀 Executing assertion: '{}'", self.name);
+        warn!("⚠️  This assertion is UNSAFE!");
+        self.config_cmd(&mut cmd);
+
+        let mut assertion_result = AssertionResult::new(self.name.clone(), self.weight);
+        assertion_result.tags = self.tags.clone();
+        assertion_result.hint = self.hint.clone();
+        assertion_result.argv = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        info!("🔄 Trying to execute the program...");
+        let start = Instant::now();
+        let mut child = match cmd.spawn() {
+            Ok(handler) => handler,
+            Err(err) => {
+                warn!("❌ Unable to execute the command");
+                debug!("💥 Error: '{err:?}'");
+                info!("❌ Assertion not passed");
+                assertion_result.set_execution_status(ExecutionStatus::FailureBeforeExecution);
+                self.assert_stdout_stderr_status_against_null(&mut assertion_result);
+                return assertion_result;
+            }
+        };
+
+        if let Some(ref stdin_content) = self.stdin {
+            info!("📥 Injecting stdin");
+            debug!(
+                "📝 stdin: '{}'",
+                String::from_utf8_lossy(stdin_content).replace('\n', "\\n")
+            );
+            let mut stdin = child
+                .stdin
+                .take()
+                .expect("expected stdin from configuration");
+            let stdin_content = stdin_content.clone();
+
+            thread::spawn(move || stdin.write_all(&stdin_content));
+        }
+
+        let mut interaction = None;
+        if let Some(ref script) = self.interaction {
+            info!("💬 Running the interaction");
+            let stdin = child
+                .stdin
+                .take()
+                .expect("expected stdin from configuration");
+            let stdout = child
+                .stdout
+                .take()
+                .expect("expected stdout from configuration");
+            let (stdout, mut result) = script.run(stdin, stdout);
+            if let Err(ref mut failure) = result {
+                // the program is probably waiting for an input that will never come
+                let termination = terminate(&mut child, script.kill_grace())
+                    .inspect_err(|err| {
+                        debug!("💥 Error while stopping the program: '{err:?}'");
+                    })
+                    .unwrap_or(Some(Termination::Killed));
+                failure.set_termination(termination);
+            }
+            interaction = Some((stdout, result));
+        }
+
+        info!("Trying to wait the command to finish");
+        let output = child.wait_with_output();
+        let duration = start.elapsed();
+        debug!("⏱️  Duration: {duration:?}");
+        assertion_result.set_duration(duration);
+        let mut output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                warn!("⏱️  Unable to wait the command finish");
+                debug!("💥 Error: '{err:?}'");
+                info!("❌ Assertion not passed");
+                assertion_result.set_execution_status(ExecutionStatus::FailureBeforeWait);
+                self.assert_stdout_stderr_status_against_null(&mut assertion_result);
+                return assertion_result;
+            }
+        };
+        let mut passed = true;
+        if let Some((stdout, result)) = interaction {
+            // the stdout was consumed during the interaction
+            output.stdout = stdout;
+            if let Err(failure) = result {
+                debug!("  ❌ Failed interaction.");
+                debug!(
+                    "   -⏳ Step {} never saw '{}'",
+                    failure.step(),
+                    failure.expected_prompt().replace('\n', "\\n")
+                );
+                passed = false;
+                assertion_result.interaction_diagnostics = Some(failure);
+            }
+        }
+        if !output.stdout.is_empty() {
+            debug!(
+                "- STDOUT: '{}'",
+                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
+            );
+        }
+        if !output.stderr.is_empty() {
+            debug!(
+                "- STDERR: '{}'",
+                String::from_utf8_lossy(&output.stderr).replace('\n', "\\n")
+            );
+        }
+        debug!("Output details: {output:?}");
+        if self.retain_output {
+            assertion_result.retained_output = Some(RetainedOutput {
+                stdout: self.pipes_stdout().then(|| output.stdout.clone()),
+                stderr: self.pipes_stderr().then(|| output.stderr.clone()),
+                status: output.status,
+            });
+        }
+        // the outputs are compared without the escape codes, but reported as printed
+        let (stdout, stderr) = if self.strip_ansi {
+            (
+                strip_ansi_codes(&output.stdout),
+                strip_ansi_codes(&output.stderr),
+            )
+        } else {
+            (Cow::from(&output.stdout), Cow::from(&output.stderr))
+        };
+
+        let obtained_status = output.status.code();
+        if let Some(expected_status) = self.status
+            && !expected_status.accepts(obtained_status)
+        {
+            debug!("  ❌ Failed status assertion.");
+            debug!("   -📋 Expected: {expected_status}");
+            match obtained_status {
+                Some(obtained_status) => debug!("   -📊 Obtained: {obtained_status}"),
+                None => debug!("   -📊 Obtained: None"),
+            }
+            passed = false;
+            assertion_result.set_status_diagnostics(expected_status, obtained_status);
+        }
+        if output.status.success() {
+            assertion_result.set_execution_status(ExecutionStatus::Success);
+        } else {
+            match obtained_status {
+                Some(obtained_status) => assertion_result
+                    .set_execution_status(ExecutionStatus::FailureWithStatus(obtained_status)),
+                None => assertion_result
+                    .set_execution_status(ExecutionStatus::FailureWithSignalTermination),
+            }
+        }
+
+        let normalized_stdouts = match (&self.normalize_with, &self.stdout) {
+            (Some(normalizer), Some(expected_stdout)) => {
+                let dir = cmd.get_current_dir();
+                let normalize =
+                    |output: &[u8]| normalize(normalizer, dir, envs, inherit_parent_env, output);
+                match normalize(&stdout)
+                    .and_then(|obtained| Ok((obtained, normalize(expected_stdout.as_bytes())?)))
+                {
+                    Ok(normalized) => Some(normalized),
+                    Err(err) => {
+                        log::error!(
+                            "could not normalize the stdout of the assertion '{}' with '{}': {err}",
+                            self.name,
+                            normalizer.0
+                        );
+                        passed = false;
+                        assertion_result
+                            .set_execution_status(ExecutionStatus::FailureInNormalization);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if let Some((obtained, expected)) = normalized_stdouts {
+            if !self.stdout_match.matches(&obtained, &expected) {
+                debug!("  ❌ Failed normalized stdout assertion.");
+                let (obtained, expected) = (
+                    String::from_utf8_lossy(&obtained).into_owned(),
+                    String::from_utf8_lossy(&expected).into_owned(),
+                );
+                debug!("   -📋 Expected: '{}'", expected.replace('\n', "\\n"));
+                debug!("   -📊 Obtained: '{}'", obtained.replace('\n', "\\n"));
+                passed = false;
+                // the normalized outputs are the ones that were compared
+                assertion_result.set_stdout_diagnostics(expected, Some(obtained));
+            }
+        } else if let Some(ref expected_stdout) = self.stdout
+            && assertion_result.execution_status != ExecutionStatus::FailureInNormalization
+            && !self
+                .stdout_match
+                .matches(&stdout, expected_stdout.as_bytes())
+        {
+            debug!("  ❌ Failed stdout assertion.");
+            debug!(
+                "   -📋 Expected: '{}'",
+                expected_stdout.replace('\n', "\\n")
+            );
+            debug!(
+                "   -📊 Obtained: '{}'",
+                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
+            );
+            passed = false;
+            assertion_result.set_stdout_diagnostics(
+                expected_stdout.clone(),
+                Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+            );
+        }
+        if !self.stdout_any_of.is_empty()
+            && !self
+                .stdout_any_of
+                .iter()
+                .any(|acceptable| self.stdout_match.matches(&stdout, acceptable.as_bytes()))
+        {
+            debug!("  ❌ Failed stdout_any_of assertion.");
+            debug!(
+                "   -📋 Expected one of {} acceptable output(s)",
+                self.stdout_any_of.len()
+            );
+            debug!(
+                "   -📊 Obtained: '{}'",
+                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
+            );
+            passed = false;
+            assertion_result.stdout_any_of_diagnostics = Some(AnyOfResult {
+                acceptable: self.stdout_any_of.clone(),
+                obtained: Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+            });
+        }
+        if self.captures_informational_stderr() {
+            assertion_result.informational_stderr =
+                Some(String::from_utf8_lossy(&output.stderr).into_owned());
+        }
+        if let Some(ref expected_stderr) = self.stderr
+            && !self.informational_stderr
+            && *stderr != *expected_stderr.as_bytes()
+        {
+            debug!("  ❌ Failed stderr assertion.");
+            debug!(
+                "   -📋 Expected: '{}'",
+                expected_stderr.replace('\n', "\\n")
+            );
+            debug!(
+                "   -📊 Obtained: '{}'",
+                String::from_utf8_lossy(&output.stderr).replace('\n', "\\n")
+            );
+            passed = false;
+            assertion_result.set_stderr_diagnostics(
+                expected_stderr.clone(),
+                Some(String::from_utf8_lossy(&output.stderr).into_owned()),
+            );
+        }
+
+        if let Some(ref forbidden) = self.stdout_not_contains
+            && let Some(found) =
+                ForbiddenOutputResult::find(forbidden, &String::from_utf8_lossy(&stdout))
+        {
+            debug!("  ❌ Failed stdout_not_contains assertion.");
+            debug!(
+                "   -🚫 Found '{}' at line {}, column {}",
+                forbidden.replace('\n', "\\n"),
+                found.line,
+                found.column
+            );
+            passed = false;
+            assertion_result.stdout_not_contains_diagnostics = Some(found);
+        }
+        if let Some(ref forbidden) = self.stderr_not_contains
+            && !self.informational_stderr
+            && let Some(found) =
+                ForbiddenOutputResult::find(forbidden, &String::from_utf8_lossy(&stderr))
+        {
+            debug!("  ❌ Failed stderr_not_contains assertion.");
+            debug!(
+                "   -🚫 Found '{}' at line {}, column {}",
+                forbidden.replace('\n', "\\n"),
+                found.line,
+                found.column
+            );
+            passed = false;
+            assertion_result.stderr_not_contains_diagnostics = Some(found);
+        }
+
+        if let Some(expected_line_count) = self.stdout_line_count {
+            let obtained_line_count = String::from_utf8_lossy(&stdout).lines().count();
+            if obtained_line_count != expected_line_count {
+                debug!("  ❌ Failed stdout_line_count assertion.");
+                debug!("   -📋 Expected: {expected_line_count} line(s)");
+                debug!("   -📊 Obtained: {obtained_line_count} line(s)");
+                passed = false;
+                assertion_result.stdout_line_count_diagnostics = Some(ExpectedObtainedResult {
+                    expected: expected_line_count,
+                    obtained: Some(obtained_line_count),
+                });
+            }
+        }
+        // the bytes that were written, even if the escape codes are ignored when comparing
+        let obtained_bytes = output.stdout.len();
+        if let Some(expected_bytes) = self.stdout_exact_bytes
+            && obtained_bytes != expected_bytes
+        {
+            debug!("  ❌ Failed stdout_exact_bytes assertion.");
+            debug!("   -📋 Expected: {expected_bytes} byte(s)");
+            debug!("   -📊 Obtained: {obtained_bytes} byte(s)");
+            passed = false;
+            assertion_result.stdout_exact_bytes_diagnostics = Some(ExpectedObtainedResult {
+                expected: expected_bytes,
+                obtained: Some(obtained_bytes),
+            });
+        }
+        if let Some(max_bytes) = self.stdout_max_bytes
+            && obtained_bytes > max_bytes
+        {
+            debug!("  ❌ Failed stdout_max_bytes assertion.");
+            debug!("   -📋 Expected: at most {max_bytes} byte(s)");
+            debug!("   -📊 Obtained: {obtained_bytes} byte(s)");
+            passed = false;
+            assertion_result.stdout_max_bytes_diagnostics = Some(ExpectedObtainedResult {
+                expected: max_bytes,
+                obtained: Some(obtained_bytes),
+            });
+        }
+        if let Some(expected_non_empty) = self.stdout_non_empty {
+            let obtained_non_empty = !stdout.is_empty();
+            if obtained_non_empty != expected_non_empty {
+                debug!("  ❌ Failed stdout_non_empty assertion.");
+                debug!("   -📋 Expected: {expected_non_empty}");
+                debug!("   -📊 Obtained: {obtained_non_empty}");
+                passed = false;
+                assertion_result.stdout_non_empty_diagnostics = Some(ExpectedObtainedResult {
+                    expected: expected_non_empty,
+                    obtained: Some(obtained_non_empty),
+                });
+            }
+        }
+        if let Some(expected_empty) = self.stderr_empty
+            && !self.informational_stderr
+            && stderr.is_empty() != expected_empty
+        {
+            debug!("  ❌ Failed stderr_empty assertion.");
+            debug!("   -📋 Expected empty: {expected_empty}");
+            debug!(
+                "   -📊 Obtained: '{}'",
+                String::from_utf8_lossy(&output.stderr).replace('\n', "\\n")
+            );
+            passed = false;
+            assertion_result.stderr_empty_diagnostics = Some(EmptyOutputResult {
+                expected_empty,
+                obtained: Some(String::from_utf8_lossy(&output.stderr).into_owned()),
+            });
+        }
+        if self.stdout_equals_stderr && !self.informational_stderr && stdout != stderr {
+            debug!("  ❌ Failed stdout_equals_stderr assertion.");
+            debug!(
+                "   -📊 Stdout: '{}'",
+                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
+            );
+            debug!(
+                "   -📊 Stderr: '{}'",
+                String::from_utf8_lossy(&output.stderr).replace('\n', "\\n")
+            );
+            passed = false;
+            assertion_result.stdout_equals_stderr_diagnostics = Some(UnequalStreamsResult {
+                stdout: Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+                stderr: Some(String::from_utf8_lossy(&output.stderr).into_owned()),
+            });
+        }
+        if let Some(ref extract) = self.stdout_extract {
+            let extracted = extract.extract(&String::from_utf8_lossy(&stdout));
+            if !extract.accepts(&extracted) {
+                debug!("  ❌ Failed stdout_extract assertion.");
+                debug!(
+                    "   -📋 Expected: a number >= {} captured by '{}'",
+                    extract.min(),
+                    extract.pattern()
+                );
+                debug!("   -📊 Obtained: {extracted:?}");
+                passed = false;
+                assertion_result.stdout_extract_diagnostics = Some(extract.result(Some(extracted)));
+            }
+        }
+        if let Some(ref regex_checks) = self.stdout_regex_checks
+            && let Some(outcome) = regex_checks.evaluate(&String::from_utf8_lossy(&stdout))
+        {
+            debug!("  ❌ Failed stdout_regex_checks assertion.");
+            debug!("   -📋 Pattern: '{}'", regex_checks.pattern());
+            debug!("   -📊 Obtained: {outcome:?}");
+            passed = false;
+            assertion_result.stdout_regex_checks_diagnostics =
+                Some(regex_checks.result(Some(outcome)));
+        }
+
+        assertion_result.set_passed(passed);
+        if passed {
+            info!("✅ Assertion passed");
+        } else {
+            info!("❌ Assertion not passed");
+        }
+        info!("----------------------------------------------------------");
+        assertion_result
+    }
+
+    /// Returns the expected result of the assertion `self` applied to some program
+    /// adjusted by its fields and the parameters of this function.
+    /// This is useful for testing purposes, avoiding manual building the assertion result.
+    ///
+    /// If `passed`, the assertion will be constructed without diagnostics. Otherwise, the
+    /// diagnostics must be defined by passing values to `obtained_stdout`,
+    /// `obtained_stderr`, and `obtained_status`.
+    ///
+    /// # Parameters
+    /// - `execution_status_if_no_status`: this parameter is mandatory when no status is
+    ///   expected. In that situation, this execution status will be used as the expected
+    ///   one.
+    /// - `passed`: specify if it is expected to pass the assertion or not.
+    /// - `obtained_stdout`: specify the obtained stdout. This helps complementing the
+    ///   stdout diagnostics.
+    /// - `obtained_stderr`: specify the obtained stderr. This helps complementing the
+    ///   stderr diagnostics.
+    /// - `obtained_status`: specify the obtained status. This helps complementing the
+    ///   status diagnostics.
+    #[cfg(test)]
+    pub fn expected_result(
+        &self,
+        execution_status_if_no_status: Option<ExecutionStatus>,
+        passed: bool,
+        obtained_stdout: Option<String>,
+        obtained_stderr: Option<String>,
+        obtained_status: Option<i32>,
+    ) -> AssertionResult {
+        let execution_status = match self.status {
+            Some(ExpectedStatus::Zero | ExpectedStatus::Exact(0)) => ExecutionStatus::Success,
+            Some(ExpectedStatus::Exact(status)) => ExecutionStatus::FailureWithStatus(status),
+            _ => execution_status_if_no_status.expect(
+                "no exact status was defined, thus, execution status must be defined manually",
+            ),
+        };
+        let stdout_diagnostics = if let Some(stdout) = self.stdout.clone() {
+            if passed {
+                None
+            } else {
+                Some(ExpectedObtainedResult {
+                    expected: stdout,
+                    obtained: obtained_stdout,
+                })
+            }
+        } else {
+            None
+        };
+        let stderr_diagnostics = if let Some(stderr) = self.stderr.clone() {
+            if passed {
+                None
+            } else {
+                Some(ExpectedObtainedResult {
+                    expected: stderr,
+                    obtained: obtained_stderr,
+                })
+            }
+        } else {
+            None
+        };
+        let status_diagnostics = if let Some(status) = self.status {
+            if passed {
+                None
+            } else {
+                Some(StatusResult {
+                    expected: status,
+                    obtained: obtained_status,
+                })
+            }
+        } else {
+            None
+        };
+        AssertionResult {
+            execution_status,
+            name: self.name.clone(),
+            passed,
+            weight: self.weight,
+            stdout_diagnostics,
+            stdout_any_of_diagnostics: None,
+            stderr_diagnostics,
+            status_diagnostics,
+            stdout_not_contains_diagnostics: None,
+            stderr_not_contains_diagnostics: None,
+            stdout_line_count_diagnostics: None,
+            stdout_exact_bytes_diagnostics: None,
+            stdout_max_bytes_diagnostics: None,
+            stdout_non_empty_diagnostics: None,
+            stderr_empty_diagnostics: None,
+            stdout_equals_stderr_diagnostics: None,
+            stdout_extract_diagnostics: None,
+            stdout_regex_checks_diagnostics: None,
+            interaction_diagnostics: None,
+            informational_stderr: None,
+            tags: self.tags.clone(),
+            hint: self.hint.clone(),
+            argv: self.args.clone(),
+            reproduction_command: None,
+            duration: None,
+            retained_output: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_dummy(
+        id: u32,
+        with_stdin: bool,
+        with_stdout: bool,
+        with_stderr: bool,
+        status: Option<i32>,
+        weight: u32,
+    ) -> Self {
+        Self::build(
+            format!("name {id}"),
+            (0..4).map(|i| format!("arg {}", i + id)).collect(),
+            if with_stdin {
+                Some(format!("expected stdin: {id}"))
+            } else {
+                None
+            },
+            if with_stdout {
+                Some(format!("expected stdout: {id}"))
+            } else {
+                None
+            },
+            if with_stderr {
+                Some(format!("expected stderr: {id}"))
+            } else {
+                None
+            },
+            status,
+            weight,
+        )
+        .unwrap()
+    }
+}
+
+/// Control sequences (CSI), e.g. colors and cursor movements, operating system commands
+/// (OSC), e.g. hyperlinks, and the other escape sequences, e.g. charset selections.
+static ANSI_ESCAPE_CODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|[ -/]*[0-~])")
+        .expect("the ANSI escape code pattern is valid")
+});
+
+/// Removes the ANSI escape codes from `output`, e.g. `"\x1b[31mred\x1b[0m"` becomes `"red"`.
+pub(crate) fn strip_ansi_codes(output: &[u8]) -> Cow<'_, [u8]> {
+    ANSI_ESCAPE_CODE.replace_all(output, &b""[..])
+}
+
+/// Pipes `output` through `normalizer`, launched from `dir` if given and with the
+/// environment of the unit tests, and returns what it printed. It fails if the normalizer
+/// can not be executed or does not succeed.
+fn normalize(
+    normalizer: &(String, Vec<String>),
+    dir: Option<&Path>,
+    envs: &[(String, String)],
+    inherit_parent_env: bool,
+    output: &[u8],
+) -> Result<Vec<u8>, String> {
+    let (program, args) = normalizer;
+    let mut cmd = Command::new(program);
+    if !inherit_parent_env {
+        clear_parent_env(&mut cmd, envs);
+    }
+    cmd.args(args)
+        .envs(
+            envs.iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let mut child = cmd.spawn().map_err(|err| err.to_string())?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .expect("expected stdin from configuration");
+    let output = output.to_vec();
+    // written from another thread, so a normalizer that prints while reading never blocks
+    let writer = thread::spawn(move || stdin.write_all(&output));
+    let normalized = child.wait_with_output().map_err(|err| err.to_string())?;
+    // a normalizer may exit without reading its whole input, e.g. `head`
+    let _ = writer.join();
+    if !normalized.status.success() {
+        return Err(normalized.status.to_string());
+    }
+    Ok(normalized.stdout)
+}
+
+/// Whether `path` is relative and never goes above the directory it is joined to, e.g.
+/// `src/bin`, but not `../src` nor `/src`.
+pub(crate) fn is_inner_path(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+#[allow(clippy::unnecessary_literal_unwrap)]
+mod tests {
+    use super::*;
+    //use test_log;
+
+    mod unsecure_assert_cmd_test {
+        use super::*;
+        #[test]
+        fn should_expect_failure_before_execution() {
+            let args = vec!["arg1".to_string(), "arg2".to_string(), "arg3".to_string()];
+            let expected_stdout = Some("stdout 1".to_string());
+            let expected_stderr = Some("stderr 1".to_string());
+            let expected_status = Some(0);
+            let assertion_name = "name 123".to_string();
+            let assertion_weight = 1;
+            let not_passed_assertion = Assertion::build(
+                assertion_name.clone(),
+                args,
+                Some("stdin 1".to_string()),
+                expected_stdout.clone(),
+                expected_stderr.clone(),
+                expected_status,
+                assertion_weight,
+            )
+            .unwrap();
+
+            let mut cmd = Command::new("____invalid_command");
+
+            cmd.env_clear();
+
+            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
+            assert_eq!(
+                result.execution_status,
+                ExecutionStatus::FailureBeforeExecution
+            );
+            assert_eq!(result.name, assertion_name);
+            assert!(!result.passed, "assertion should not pass");
+            assert_eq!(result.weight, assertion_weight);
+            assert_eq!(result.duration, None);
+            let stdout_diagnostics = result.stdout_diagnostics;
+            assert!(stdout_diagnostics.is_some_and(|v| {
+                if v.expected == expected_stdout.unwrap() && v.obtained.is_none() {
+                    return true;
+                }
+                false
+            }));
+
+            let stderr_diagnostics = result.stderr_diagnostics;
+            assert!(stderr_diagnostics.is_some_and(|v| {
+                if v.expected == expected_stderr.unwrap() && v.obtained.is_none() {
+                    return true;
+                }
+                false
+            }));
+            let status_diagnostics = result.status_diagnostics;
+            assert!(status_diagnostics.is_some_and(|v| {
+                if v.expected == ExpectedStatus::Exact(expected_status.unwrap())
+                    && v.obtained.is_none()
+                {
+                    return true;
+                }
+                false
+            }));
+        }
+
+        #[test]
+        fn should_measure_the_duration_of_the_program() {
+            let assertion = Assertion::build(
+                "sleep".to_string(),
+                vec!["0.1".to_string()],
+                None,
+                None,
+                None,
+                Some(0),
+                1,
+            )
+            .unwrap();
+
+            let result = assertion.unsafe_assert_cmd(Command::new("sleep"));
+
+            assert!(result.passed);
+            assert!(
+                result
+                    .duration
+                    .is_some_and(|d| d >= Duration::from_millis(100))
+            );
+        }
+
+        #[test]
+        fn should_carry_tags_and_hint_to_the_result() {
+            let assertion =
+                Assertion::build("false".to_string(), vec![], None, None, None, Some(0), 1)
+                    .unwrap()
+                    .with_tags(vec!["basic".to_string()])
+                    .with_hint("check the status".to_string());
+
+            let result = assertion.unsafe_assert_cmd(Command::new("false"));
+
+            assert!(!result.passed());
+            assert_eq!(result.tags(), ["basic".to_string()]);
+            assert_eq!(result.hint(), Some("check the status"));
+        }
+        #[test]
+        fn should_retain_the_raw_output_only_when_asked() {
+            let mut assertion = Assertion::build(
+                "retained".to_string(),
+                vec![
+                    "-c".to_string(),
+                    "echo out; echo err >&2; exit 3".to_string(),
+                ],
+                None,
+                None,
+                None,
+                Some(3),
+                1,
+            )
+            .unwrap();
+
+            let result = assertion.unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
+            assert_eq!(result.retained_stdout(), None);
+            assert_eq!(result.retained_stderr(), None);
+            assert_eq!(result.retained_status(), None);
+
+            assertion.set_retain_output(true);
+            let result = assertion.unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
+            assert_eq!(result.retained_stdout(), Some(b"out\n".as_slice()));
+            assert_eq!(result.retained_stderr(), Some(b"err\n".as_slice()));
+            assert_eq!(result.retained_status().unwrap().code(), Some(3));
+
+            let assertion = assertion.with_capture(CaptureMode::StdoutOnly);
+            let result = assertion.unsafe_assert_cmd(Command::new("sh"));
+            assert_eq!(result.retained_stdout(), Some(b"out\n".as_slice()));
+            assert_eq!(result.retained_stderr(), None);
+        }
+
+        #[test]
+        fn should_fail_without_executing_when_program_is_not_submitted() {
+            let assertion = Assertion::new_dummy(1, false, true, false, Some(0), 2);
+
+            let result = assertion.not_submitted_result();
+
+            assert!(!result.passed());
+            assert_eq!(result.score(), 0);
+            assert_eq!(
+                result.execution_status(),
+                ExecutionStatus::ProgramNotSubmitted
+            );
+            assert_eq!(
+                result.stdout_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: "expected stdout: 1".to_string(),
+                    obtained: None,
+                })
+            );
+            assert_eq!(result.duration(), None);
+        }
+
+        #[test]
+        fn should_record_the_received_argv() {
+            let assertion = Assertion::build(
+                "argv".to_string(),
+                vec!["arg1".to_string(), "two words".to_string()],
+                None,
+                None,
+                None,
+                Some(0),
+                1,
+            )
+            .unwrap();
+            let mut cmd = Command::new("echo");
+            cmd.arg("base");
+
+            let result = assertion.unsafe_assert_cmd(cmd);
+
+            assert_eq!(
+                result.argv(),
+                [
+                    "base".to_string(),
+                    "arg1".to_string(),
+                    "two words".to_string()
+                ]
+            );
+        }
+
+        #[test]
+        fn should_expect_success_for_echo() {
+            let args = vec![
+                "arg1".to_string(),
+                "arg2  0".to_string(),
+                "arg3".to_string(),
+            ];
+
+            // Passing expectation
+            let passing_expected_stdout = Some("arg1 arg2  0 arg3\n".to_string());
+            let passing_expected_stderr = Some("".to_string());
+            let passing_expected_status = Some(0);
+
+            let assertion_name = "assertion name".to_string();
+            let assertion_weight = 3;
+            let passed_assertion = Assertion::build(
+                assertion_name.clone(),
+                args.clone(),
+                None,
+                passing_expected_stdout.clone(),
+                passing_expected_stderr.clone(),
+                passing_expected_status,
+                assertion_weight,
+            )
+            .unwrap();
+
+            let cmd = Command::new("echo");
+
+            let result = passed_assertion.unsafe_assert_cmd(cmd);
+            assert_eq!(
+                result,
+                AssertionResult {
+                    execution_status: ExecutionStatus::Success,
+                    name: assertion_name.clone(),
+                    passed: true,
+                    weight: assertion_weight,
+                    stdout_diagnostics: None,
+                    stdout_any_of_diagnostics: None,
+                    stderr_diagnostics: None,
+                    status_diagnostics: None,
+                    stdout_not_contains_diagnostics: None,
+                    stderr_not_contains_diagnostics: None,
+                    stdout_line_count_diagnostics: None,
+                    stdout_exact_bytes_diagnostics: None,
+                    stdout_max_bytes_diagnostics: None,
+                    stdout_non_empty_diagnostics: None,
+                    stderr_empty_diagnostics: None,
+                    stdout_equals_stderr_diagnostics: None,
+                    stdout_extract_diagnostics: None,
+                    stdout_regex_checks_diagnostics: None,
+                    interaction_diagnostics: None,
+                    informational_stderr: None,
+                    tags: vec![],
+                    hint: None,
+                    argv: args.clone(),
+                    reproduction_command: None,
+                    duration: None,
+                    retained_output: None,
+                }
+            );
+
+            // Not passing expectation
+            let not_passing_expected_stdout = Some("arg1 arg2 0 arg3".to_string());
+            let not_passing_expected_stderr = Some("invalid error".to_string());
+            let not_passing_expected_status = Some(23);
+
+            let not_passed_assertion = Assertion::build(
+                assertion_name.clone(),
+                args.clone(),
+                None,
+                not_passing_expected_stdout.clone(),
+                not_passing_expected_stderr.clone(),
+                not_passing_expected_status,
+                assertion_weight,
+            )
+            .unwrap();
+
+            let cmd = Command::new("echo");
+
+            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
+            assert_eq!(
+                result,
+                AssertionResult {
+                    execution_status: ExecutionStatus::Success,
+                    name: assertion_name,
+                    passed: false,
+                    weight: assertion_weight,
+                    stdout_diagnostics: Some(ExpectedObtainedResult {
+                        expected: not_passing_expected_stdout.unwrap(),
+                        obtained: passing_expected_stdout
+                    }),
+                    stdout_any_of_diagnostics: None,
+                    stderr_diagnostics: Some(ExpectedObtainedResult {
+                        expected: not_passing_expected_stderr.unwrap(),
+                        obtained: passing_expected_stderr
+                    }),
+                    status_diagnostics: Some(StatusResult {
+                        expected: ExpectedStatus::Exact(not_passing_expected_status.unwrap()),
+                        obtained: passing_expected_status
+                    }),
+                    stdout_not_contains_diagnostics: None,
+                    stderr_not_contains_diagnostics: None,
+                    stdout_line_count_diagnostics: None,
+                    stdout_exact_bytes_diagnostics: None,
+                    stdout_max_bytes_diagnostics: None,
+                    stdout_non_empty_diagnostics: None,
+                    stderr_empty_diagnostics: None,
+                    stdout_equals_stderr_diagnostics: None,
+                    stdout_extract_diagnostics: None,
+                    stdout_regex_checks_diagnostics: None,
+                    interaction_diagnostics: None,
+                    informational_stderr: None,
+                    tags: vec![],
+                    hint: None,
+                    argv: args,
+                    reproduction_command: None,
+                    duration: None,
+                    retained_output: None,
+                }
+            );
+        }
+        #[test]
+        fn should_write_raw_stdin_bytes() {
+            let assertion = Assertion::new("raw".to_string(), vec![], None, 1)
+                .with_stdin_bytes(vec![0x00, 0x1b, b'a', b'\n'])
+                .with_stdout("\0\u{1b}a\n".to_string());
+
+            assert!(assertion.unsafe_assert_cmd(Command::new("cat")).passed());
+        }
+
+        #[test]
+        fn should_expect_success_for_cat_using_stdin() {
+            // Passing expectation
+            let stdin = Some("this is the input    !\n and this also".to_string());
+            let passing_expected_stdout =
+                Some("this is the input    !\n and this also".to_string());
+            let passing_expected_stderr = Some("".to_string());
+            let passing_expected_status = Some(0);
+
+            let assertion_name = "assertion name".to_string();
+            let assertion_weight = 8;
+            let passed_assertion = Assertion::build(
+                assertion_name.clone(),
+                vec![],
+                stdin.clone(),
+                passing_expected_stdout.clone(),
+                passing_expected_stderr.clone(),
+                passing_expected_status,
+                assertion_weight,
+            )
+            .unwrap();
+
+            let cmd = Command::new("cat");
+
+            let result = passed_assertion.unsafe_assert_cmd(cmd);
+
+            assert_eq!(
+                result,
+                AssertionResult {
+                    execution_status: ExecutionStatus::Success,
+                    name: assertion_name.clone(),
+                    passed: true,
+                    weight: assertion_weight,
+                    stdout_diagnostics: None,
+                    stdout_any_of_diagnostics: None,
+                    stderr_diagnostics: None,
+                    status_diagnostics: None,
+                    stdout_not_contains_diagnostics: None,
+                    stderr_not_contains_diagnostics: None,
+                    stdout_line_count_diagnostics: None,
+                    stdout_exact_bytes_diagnostics: None,
+                    stdout_max_bytes_diagnostics: None,
+                    stdout_non_empty_diagnostics: None,
+                    stderr_empty_diagnostics: None,
+                    stdout_equals_stderr_diagnostics: None,
+                    stdout_extract_diagnostics: None,
+                    stdout_regex_checks_diagnostics: None,
+                    interaction_diagnostics: None,
+                    informational_stderr: None,
+                    tags: vec![],
+                    hint: None,
+                    argv: vec![],
+                    reproduction_command: None,
+                    duration: None,
+                    retained_output: None,
+                }
+            );
+
+            // Not passing expectation
+            // (one space missing)
+            let not_passing_expected_stdout =
+                Some("this is the input   !\n and this also".to_string());
+
+            let not_passed_assertion = Assertion::build(
+                assertion_name.clone(),
+                vec![],
+                stdin.clone(),
+                not_passing_expected_stdout.clone(),
+                passing_expected_stderr.clone(),
+                passing_expected_status,
+                assertion_weight,
+            )
+            .unwrap();
+
+            let cmd = Command::new("cat");
+
+            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
+            assert_eq!(
+                result,
+                AssertionResult {
+                    execution_status: ExecutionStatus::Success,
+                    name: assertion_name,
+                    passed: false,
+                    weight: assertion_weight,
+                    stdout_diagnostics: Some(ExpectedObtainedResult {
+                        expected: not_passing_expected_stdout.unwrap(),
+                        obtained: passing_expected_stdout
+                    }),
+                    stdout_any_of_diagnostics: None,
+                    stderr_diagnostics: None,
+                    status_diagnostics: None,
+                    stdout_not_contains_diagnostics: None,
+                    stderr_not_contains_diagnostics: None,
+                    stdout_line_count_diagnostics: None,
+                    stdout_exact_bytes_diagnostics: None,
+                    stdout_max_bytes_diagnostics: None,
+                    stdout_non_empty_diagnostics: None,
+                    stderr_empty_diagnostics: None,
+                    stdout_equals_stderr_diagnostics: None,
+                    stdout_extract_diagnostics: None,
+                    stdout_regex_checks_diagnostics: None,
+                    interaction_diagnostics: None,
+                    informational_stderr: None,
+                    tags: vec![],
+                    hint: None,
+                    argv: vec![],
+                    reproduction_command: None,
+                    duration: None,
+                    retained_output: None,
+                }
+            );
+        }
+    }
+
+    mod serialization_test {
+        use super::*;
+        use crate::config::test_macros::test_serialize_and_deserialize;
+
+        fn failed_result() -> AssertionResult {
+            Assertion::build(
+                "should say bye".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("bye\n".to_string()),
+                None,
+                Some(0),
+                2,
+            )
+            .unwrap()
+            .with_stdout_not_contains("hello".to_string())
+            .with_tags(vec!["basic".to_string()])
+            .with_hint("say bye".to_string())
+            .unsafe_assert_cmd(Command::new("echo"))
+        }
+
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_failed_result,
+            failed_result(),
+            AssertionResult
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_result_of_invalid_command,
+            Assertion::build("name".to_string(), vec![], None, None, None, Some(0), 1)
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("____invalid_command")),
+            AssertionResult
+        );
+
+        #[test]
+        fn should_have_a_stable_execution_status_representation() {
+            assert_eq!(
+                serde_json::to_string(&ExecutionStatus::Success).unwrap(),
+                r#"{"kind":"success"}"#
+            );
+            assert_eq!(
+                serde_json::to_string(&ExecutionStatus::FailureWithStatus(3)).unwrap(),
+                r#"{"kind":"failure_with_status","status":3}"#
+            );
+            assert_eq!(
+                serde_json::from_str::<ExecutionStatus>(r#"{"kind":"failure_before_wait"}"#)
+                    .unwrap(),
+                ExecutionStatus::FailureBeforeWait
+            );
+        }
+
+        #[test]
+        fn should_describe_the_execution_status() {
+            assert_eq!(
+                ExecutionStatus::FailureWithStatus(3).to_string(),
+                "exited with status 3"
+            );
+            assert_eq!(
+                ExecutionStatus::FailureWithSignalTermination.to_string(),
+                "terminated by a signal"
+            );
+            assert!(ExecutionStatus::FailureBeforeExecution.is_abnormal());
+            assert!(!ExecutionStatus::FailureWithStatus(3).is_abnormal());
+        }
+
+        #[test]
+        fn should_represent_the_expected_status_as_a_code_or_a_name() {
+            assert_eq!(
+                serde_json::to_string(&ExpectedStatus::Exact(3)).unwrap(),
+                "3"
+            );
+            assert_eq!(
+                serde_json::to_string(&ExpectedStatus::NonZero).unwrap(),
+                r#""nonzero""#
+            );
+            assert_eq!(
+                serde_json::from_str::<ExpectedStatus>(r#""zero""#).unwrap(),
+                ExpectedStatus::Zero
+            );
+            assert!(serde_json::from_str::<ExpectedStatus>(r#""failure""#).is_err());
+        }
+    }
+
+    mod not_contains_test {
+        use super::*;
+
+        #[test]
+        fn should_not_build_without_any_expectation() {
+            assert!(
+                Assertion::new("name".to_string(), vec![], None, 1)
+                    .check()
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn should_accept_only_not_contains_expectation() {
+            assert!(
+                Assertion::new("name".to_string(), vec![], None, 1)
+                    .with_stderr_not_contains("panic".to_string())
+                    .check()
+                    .is_ok()
+            );
+        }
+
+        #[test]
+        fn should_find_line_and_column_of_forbidden_output() {
+            assert_eq!(
+                ForbiddenOutputResult::find("DEBUG", "ok\nvalue: DEBUG 1\n"),
+                Some(ForbiddenOutputResult {
+                    forbidden: "DEBUG".to_string(),
+                    line: 2,
+                    column: 8,
+                })
+            );
+            assert_eq!(ForbiddenOutputResult::find("DEBUG", "ok\n"), None);
+        }
+
+        #[test]
+        fn should_pass_when_forbidden_output_is_absent() {
+            let assertion = Assertion::build(
+                "name".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("hello\n".to_string()),
+                None,
+                None,
+                1,
+            )
+            .unwrap()
+            .with_stdout_not_contains("DEBUG".to_string());
+
+            let result = assertion.unsafe_assert_cmd(Command::new("echo"));
+
+            assert!(result.passed);
+            assert_eq!(result.stdout_not_contains_diagnostics, None);
+        }
+
+        #[test]
+        fn should_fail_when_forbidden_output_is_present() {
+            let assertion = Assertion::new(
+                "name".to_string(),
+                vec!["-c".to_string(), "echo ok; echo 'áé DEBUG' >&2".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_not_contains("DEBUG".to_string())
+            .with_stderr_not_contains("DEBUG".to_string())
+            .check()
+            .unwrap();
+
+            let result = assertion.unsafe_assert_cmd(Command::new("sh"));
+
+            assert!(!result.passed);
+            assert_eq!(result.stdout_not_contains_diagnostics, None);
+            assert_eq!(
+                result.stderr_not_contains_diagnostics,
+                Some(ForbiddenOutputResult {
+                    forbidden: "DEBUG".to_string(),
+                    line: 1,
+                    column: 4,
+                })
+            );
+        }
+    }
+
+    mod config_cmd_test {
+        use super::*;
+        use std::ffi::OsString;
+
+        #[test]
+        fn should_configure_every_field() {
+            let expected_args = vec!["arg1".to_string(), "arg2".to_string()];
+            let expected_stdout = Some("stdout 1".to_string());
+            let expected_stderr = Some("stderr 1".to_string());
+            let expected_status = Some(ExpectedStatus::Exact(13));
+            let a = Assertion {
+                name: "name 1".to_string().clone(),
+                args: expected_args.clone(),
+                stdin: Some(b"stdin 1".to_vec()),
+                cwd: None,
+                setup: vec![],
+                teardown: vec![],
+                stdout: expected_stdout.clone(),
+                stdout_any_of: vec![],
+                stderr: expected_stderr.clone(),
+                status: expected_status,
+                stdout_not_contains: None,
+                stderr_not_contains: None,
+                stdout_line_count: None,
+                stdout_exact_bytes: None,
+                stdout_max_bytes: None,
+                stdout_non_empty: None,
+                stderr_empty: None,
+                stdout_equals_stderr: false,
+                stdout_extract: None,
+                stdout_regex_checks: None,
+                interaction: None,
+                strip_ansi: false,
+                normalize_with: None,
+                capture: CaptureMode::Auto,
+                stdout_match: StdoutMatch::Exact,
+                informational_stderr: false,
+                retain_output: false,
+                tags: vec![],
+                hint: None,
+                weight: 1,
+            };
+            let mut cmd = Command::new("some command");
+            a.config_cmd(&mut cmd);
+
+            assert_eq!(
+                cmd.get_args().collect::<Vec<_>>(),
+                expected_args.iter().map(OsString::from).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    mod interaction_test {
+        use super::*;
+        use crate::grader::grading_tests::unit_test::interaction::InteractionStep;
+
+        fn greeter_assertion(prompt: &str) -> Assertion {
+            Assertion::new("greeter".to_string(), vec![], None, 1)
+                .with_interaction(
+                    InteractionScript::build(
+                        vec![InteractionStep::new(
+                            prompt.to_string(),
+                            "Ana\n".to_string(),
+                        )],
+                        Duration::from_millis(500),
+                    )
+                    .unwrap(),
+                )
+                .with_stdout("name: hello, Ana\n".to_string())
+                .check()
+                .unwrap()
+        }
+
+        fn greeter_cmd() -> Command {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", r#"printf 'name: '; read name; echo "hello, $name""#]);
+            cmd
+        }
+
+        #[test]
+        fn should_not_accept_stdin_and_interaction() {
+            let assertion = Assertion::new("name".to_string(), vec![], Some("a".to_string()), 1)
+                .with_interaction(
+                    InteractionScript::build(
+                        vec![InteractionStep::new("".to_string(), "b".to_string())],
+                        Duration::from_secs(1),
+                    )
+                    .unwrap(),
+                );
+            assert!(assertion.check().is_err());
+        }
+
+        #[test]
+        fn should_pass_when_every_prompt_is_answered() {
+            let result = greeter_assertion("name: ").unsafe_assert_cmd(greeter_cmd());
+            assert!(result.passed());
+            assert_eq!(result.interaction_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_fail_when_a_prompt_is_not_seen() {
+            let result = greeter_assertion("age: ").unsafe_assert_cmd(greeter_cmd());
+            assert!(!result.passed());
+            let failure = result.interaction_diagnostics().unwrap();
+            assert_eq!(failure.step(), 1);
+            assert_eq!(failure.expected_prompt(), "age: ");
+            assert_eq!(failure.obtained(), "name: ");
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn should_let_the_program_exit_during_the_kill_grace() {
+            let assertion = Assertion::new("greeter".to_string(), vec![], None, 1)
+                .with_interaction(
+                    InteractionScript::build(
+                        vec![InteractionStep::new(
+                            "age: ".to_string(),
+                            "30\n".to_string(),
+                        )],
+                        Duration::from_millis(200),
+                    )
+                    .unwrap()
+                    .with_kill_grace(Duration::from_secs(5)),
+                )
+                .check()
+                .unwrap();
+            let mut cmd = Command::new("sh");
+            cmd.args([
+                "-c",
+                "trap 'exit 0' TERM; printf 'name: '; sleep 30 >/dev/null 2>&1 & wait",
+            ]);
+
+            let result = assertion.unsafe_assert_cmd(cmd);
+            assert!(!result.passed());
+            let failure = result.interaction_diagnostics().unwrap();
+            assert_eq!(failure.termination(), Some(Termination::Terminated));
+        }
+    }
+
+    mod stdout_predicate_test {
+        use super::*;
+
+        fn echo_assertion(args: &[&str]) -> Assertion {
+            Assertion::new(
+                "echo".to_string(),
+                args.iter().map(|arg| arg.to_string()).collect(),
+                None,
+                1,
+            )
+        }
+
+        #[test]
+        fn should_accept_only_predicate_expectations() {
+            assert!(
+                echo_assertion(&[])
+                    .with_stdout_line_count(1)
+                    .check()
+                    .is_ok()
+            );
+            assert!(
+                echo_assertion(&[])
+                    .with_stdout_non_empty(true)
+                    .check()
+                    .is_ok()
+            );
+        }
+
+        #[test]
+        fn should_pass_when_line_count_matches() {
+            let result = echo_assertion(&["a\\nb\\nc\\n"])
+                .with_stdout_line_count(3)
+                .with_stdout_non_empty(true)
+                .with_status(0)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("printf"));
+            assert!(result.passed());
+        }
+
+        #[test]
+        fn should_report_obtained_line_count() {
+            let result = echo_assertion(&["a"])
+                .with_stdout_line_count(2)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_line_count_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: 2,
+                    obtained: Some(1),
+                })
+            );
+        }
+
+        #[test]
+        fn should_compare_the_number_of_bytes_of_the_stdout() {
+            let assertion = |exact_bytes: usize, max_bytes: usize| {
+                echo_assertion(&["hello"])
+                    .with_stdout_exact_bytes(exact_bytes)
+                    .with_stdout_max_bytes(max_bytes)
+                    .check()
+                    .unwrap()
+            };
+
+            assert!(
+                assertion(6, 6)
+                    .unsafe_assert_cmd(Command::new("echo"))
+                    .passed()
+            );
+            let result = assertion(5, 4).unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_exact_bytes_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: 5,
+                    obtained: Some(6),
+                })
+            );
+            assert_eq!(
+                result.stdout_max_bytes_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: 4,
+                    obtained: Some(6),
+                })
+            );
+        }
+
+        #[test]
+        fn should_report_empty_stdout() {
+            let result = echo_assertion(&[])
+                .with_stdout_non_empty(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("true"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_non_empty_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: true,
+                    obtained: Some(false),
+                })
+            );
+        }
+    }
+    mod stdout_any_of_test {
+        use super::*;
+
+        fn echo_assertion(text: &str) -> Assertion {
+            Assertion::new("echo".to_string(), vec![text.to_string()], None, 1)
+        }
+
+        fn acceptable(outputs: &[&str]) -> Vec<String> {
+            outputs.iter().map(|output| output.to_string()).collect()
+        }
+
+        #[test]
+        fn should_accept_only_any_of_expectation() {
+            assert!(
+                echo_assertion("a")
+                    .with_stdout_any_of(acceptable(&["a\n"]))
+                    .check()
+                    .is_ok()
+            );
+            assert!(
+                echo_assertion("a")
+                    .with_stdout_any_of(vec![])
+                    .check()
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn should_pass_when_any_output_matches() {
+            let result = echo_assertion("b")
+                .with_stdout_any_of(acceptable(&["a\n", "b\n", "c\n"]))
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(result.passed());
+            assert_eq!(result.stdout_any_of_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_report_obtained_output_when_nothing_matches() {
+            let result = echo_assertion("d")
+                .with_stdout_any_of(acceptable(&["a\n", "b\n"]))
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_any_of_diagnostics(),
+                Some(&AnyOfResult {
+                    acceptable: acceptable(&["a\n", "b\n"]),
+                    obtained: Some("d\n".to_string()),
+                })
+            );
+            assert_eq!(result.stdout_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_check_stdout_and_any_of_independently() {
+            let result = echo_assertion("a")
+                .with_stdout("a\n".to_string())
+                .with_stdout_any_of(acceptable(&["b\n"]))
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            assert_eq!(result.stdout_diagnostics(), None);
+            assert!(result.stdout_any_of_diagnostics().is_some());
+        }
+    }
+    mod stderr_test {
+        use super::*;
+
+        // the debug logs are enabled, so the logging of both outputs is exercised too
+        #[test_log::test]
+        fn should_report_stdout_and_stderr_separately() {
+            let result = Assertion::new(
+                "outputs".to_string(),
+                vec!["-c".to_string(), "echo out; echo err >&2".to_string()],
+                None,
+                1,
+            )
+            .with_stdout("expected out\n".to_string())
+            .with_stderr("expected err\n".to_string())
+            .check()
+            .unwrap()
+            .unsafe_assert_cmd(Command::new("sh"));
+
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: "expected out\n".to_string(),
+                    obtained: Some("out\n".to_string()),
+                })
+            );
+            assert_eq!(
+                result.stderr_diagnostics(),
+                Some(&ExpectedObtainedResult {
+                    expected: "expected err\n".to_string(),
+                    obtained: Some("err\n".to_string()),
+                })
+            );
         }
-        if !output.stderr.is_empty() {
-            debug!(
-                "- STDERR: '{}'",
-                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
+    }
+    mod normalize_with_test {
+        use super::*;
+
+        fn unsorted_assertion(expected_stdout: &str, normalizer: &str) -> Assertion {
+            Assertion::new(
+                "unsorted".to_string(),
+                vec!["-c".to_string(), "printf 'b\\na\\n'".to_string()],
+                None,
+                1,
+            )
+            .with_stdout(expected_stdout.to_string())
+            .with_normalize_with((normalizer.to_string(), vec![]))
+        }
+
+        #[test]
+        fn should_compare_the_normalized_stdouts() {
+            let result = unsorted_assertion("a\nb\n", "sort").unsafe_assert_cmd(Command::new("sh"));
+
+            assert!(result.passed());
+            assert_eq!(result.execution_status(), ExecutionStatus::Success);
+        }
+
+        #[test]
+        fn should_report_the_normalized_stdouts() {
+            let result = unsorted_assertion("c\na\n", "sort").unsafe_assert_cmd(Command::new("sh"));
+
+            assert!(!result.passed());
+            let diagnostics = result.stdout_diagnostics().unwrap();
+            assert_eq!(diagnostics.expected(), "a\nc\n");
+            assert_eq!(diagnostics.obtained().unwrap(), "a\nb\n");
+        }
+
+        #[test]
+        fn should_run_the_normalizer_with_the_env_of_the_unit_tests() {
+            let assertion = unsorted_assertion("a\nb\n", "sh").with_normalize_with((
+                "sh".to_string(),
+                vec![
+                    "-c".to_string(),
+                    r#"test "$MARK" = x && test -z "$HOME" && sort"#.to_string(),
+                ],
+            ));
+            let mut cmd = Command::new("sh");
+            clear_parent_env(&mut cmd, &[]);
+
+            let result = assertion.unsafe_assert_cmd_in_env(
+                cmd,
+                &[("MARK".to_string(), "x".to_string())],
+                false,
             );
+
+            assert!(result.passed());
+            assert_eq!(result.execution_status(), ExecutionStatus::Success);
         }
-        debug!("Output details: {output:?}");
 
-        let mut passed = true;
-        if output.status.success() {
-            if let Some(expected_status) = self.status
-                && expected_status != 0
-            {
-                debug!("  ❌ Failed status assertion.");
-                debug!("   -📋 Expected: {expected_status}");
-                debug!("   -📊 Obtained: 0 (success)");
-                passed = false;
-                assertion_result.set_status_diagnostics(expected_status, Some(0));
-            }
-            assertion_result.set_execution_status(ExecutionStatus::Success);
-        } else {
-            match output.status.code() {
-                Some(obtained_status) => {
-                    if let Some(expected_status) = self.status
-                        && expected_status != obtained_status
-                    {
-                        debug!("  ❌ Failed status assertion.");
-                        debug!("   -📋 Expected: {expected_status}");
-                        debug!("   -📊 Obtained: {obtained_status}");
-                        passed = false;
-                        assertion_result
-                            .set_status_diagnostics(expected_status, Some(obtained_status));
-                    }
-                    assertion_result
-                        .set_execution_status(ExecutionStatus::FailureWithStatus(obtained_status))
-                }
-                None => {
-                    if let Some(expected_status) = self.status {
-                        debug!("  ❌ Failed status assertion.");
-                        debug!("   -📋 Expected: {expected_status}");
-                        debug!("   -📊 Obtained: None");
-                        passed = false;
-                        assertion_result.set_status_diagnostics(expected_status, None);
-                    }
-                    assertion_result
-                        .set_execution_status(ExecutionStatus::FailureWithSignalTermination);
-                }
+        #[test]
+        fn should_fail_when_the_normalizer_fails() {
+            for normalizer in ["false", "nonexistent normalizer"] {
+                let result =
+                    unsorted_assertion("b\na\n", normalizer).unsafe_assert_cmd(Command::new("sh"));
+
+                assert!(!result.passed());
+                assert_eq!(
+                    result.execution_status(),
+                    ExecutionStatus::FailureInNormalization
+                );
+                assert!(result.stdout_diagnostics().is_none());
             }
         }
 
-        if let Some(ref expected_stdout) = self.stdout
-            && output.stdout != expected_stdout.as_bytes()
-        {
-            debug!("  ❌ Failed stdout assertion.");
-            debug!(
-                "   -📋 Expected: '{}'",
-                expected_stdout.replace('\n', "\\n")
-            );
-            debug!(
-                "   -📊 Obtained: '{}'",
-                String::from_utf8_lossy(&output.stdout).replace('\n', "\\n")
-            );
-            passed = false;
-            assertion_result.set_stdout_diagnostics(
-                expected_stdout.clone(),
-                Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+        #[test]
+        fn should_require_the_stdout() {
+            assert!(
+                Assertion::new("a".to_string(), vec![], None, 1)
+                    .with_status(0)
+                    .with_normalize_with(("sort".to_string(), vec![]))
+                    .check()
+                    .is_err()
             );
         }
-        if let Some(ref expected_stderr) = self.stderr
-            && output.stderr != expected_stderr.as_bytes()
-        {
-            debug!("  ❌ Failed stderr assertion.");
-            debug!(
-                "   -📋 Expected: '{}'",
-                expected_stderr.replace('\n', "\\n")
+    }
+    mod capture_test {
+        use super::*;
+
+        #[test]
+        fn should_discard_the_outputs_that_are_not_captured() {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "yes | head -c 1000000; echo error >&2; exit 3"]);
+            let result = Assertion::new("status only".to_string(), vec![], None, 1)
+                .with_status(3)
+                .with_informational_stderr(true)
+                .with_capture(CaptureMode::StdoutOnly)
+                .unsafe_assert_cmd(cmd);
+
+            assert!(result.passed());
+            assert_eq!(result.informational_stderr(), None);
+        }
+
+        #[test]
+        fn should_reject_expectations_on_discarded_outputs() {
+            let assertion = || Assertion::new("a".to_string(), vec![], None, 1);
+            assert!(
+                assertion()
+                    .with_stdout("out".to_string())
+                    .with_capture(CaptureMode::None)
+                    .check()
+                    .is_err()
             );
-            debug!(
-                "   -📊 Obtained: '{}'",
-                String::from_utf8_lossy(&output.stderr).replace('\n', "\\n")
+            assert!(
+                assertion()
+                    .with_stderr_empty(true)
+                    .with_capture(CaptureMode::StdoutOnly)
+                    .check()
+                    .is_err()
             );
-            passed = false;
-            assertion_result.set_stderr_diagnostics(
-                expected_stderr.clone(),
-                Some(String::from_utf8_lossy(&output.stderr).into_owned()),
+            assert!(
+                assertion()
+                    .with_stderr_empty(true)
+                    .with_capture(CaptureMode::StderrOnly)
+                    .check()
+                    .is_ok()
             );
         }
+    }
+    mod informational_stderr_test {
+        use super::*;
 
-        assertion_result.set_passed(passed);
-        if passed {
-            info!("✅ Assertion passed");
-        } else {
-            info!("❌ Assertion not passed");
+        #[test]
+        fn should_capture_the_stderr_without_checking_it() {
+            let result = Assertion::new(
+                "warnings".to_string(),
+                vec!["-c".to_string(), "echo out; echo warning >&2".to_string()],
+                None,
+                1,
+            )
+            .with_stdout("out\n".to_string())
+            .with_stderr("".to_string())
+            .with_stderr_not_contains("warning".to_string())
+            .with_stderr_empty(true)
+            .with_informational_stderr(true)
+            .unsafe_assert_cmd(Command::new("sh"));
+
+            assert!(result.passed());
+            assert_eq!(result.informational_stderr(), Some("warning\n"));
+            assert!(result.stderr_diagnostics().is_none());
+        }
+
+        #[test]
+        fn should_not_capture_the_stderr_by_default() {
+            let result = Assertion::new("out".to_string(), vec!["out".to_string()], None, 1)
+                .with_stdout("out\n".to_string())
+                .unsafe_assert_cmd(Command::new("echo"));
+
+            assert_eq!(result.informational_stderr(), None);
         }
-        info!("----------------------------------------------------------");
-        assertion_result
     }
+    mod status_test {
+        use super::*;
 
-    /// Returns the expected result of the assertion `self` applied to some program
-    /// adjusted by its fields and the parameters of this function.
-    /// This is useful for testing purposes, avoiding manual building the assertion result.
-    ///
-    /// If `passed`, the assertion will be constructed without diagnostics. Otherwise, the
-    /// diagnostics must be defined by passing values to `obtained_stdout`,
-    /// `obtained_stderr`, and `obtained_status`.
-    ///
-    /// # Parameters
-    /// - `execution_status_if_no_status`: this parameter is mandatory when no status is
-    /// expected. In that situation, this execution status will be used as the expected one.
-    /// - `passed`: specify if it is expected to pass the assertion or not.
-    /// - `obtained_stdout`: specify the obtained stdout. This helps complementing the
-    /// stdout diagnostics.
-    /// - `obtained_stderr`: specify the obtained stderr. This helps complementing the
-    /// stderr diagnostics.
-    /// - `obtained_status`: specify the obtained status. This helps complementing the
-    /// status diagnostics.
-    #[cfg(test)]
-    pub fn expected_result(
-        &self,
-        execution_status_if_no_status: Option<ExecutionStatus>,
-        passed: bool,
-        obtained_stdout: Option<String>,
-        obtained_stderr: Option<String>,
-        obtained_status: Option<i32>,
-    ) -> AssertionResult {
-        let execution_status = if let Some(status) = self.status {
-            if status == 0 {
-                ExecutionStatus::Success
-            } else {
-                ExecutionStatus::FailureWithStatus(status)
-            }
-        } else {
-            execution_status_if_no_status
-                .expect("no status was defined, thus, execution status must be defined manually")
-        };
-        let stdout_diagnostics = if let Some(stdout) = self.stdout.clone() {
-            if passed {
-                None
-            } else {
-                Some(ExpectedObtainedResult {
-                    expected: stdout,
-                    obtained: obtained_stdout,
-                })
-            }
-        } else {
-            None
-        };
-        let stderr_diagnostics = if let Some(stderr) = self.stderr.clone() {
-            if passed {
-                None
-            } else {
-                Some(ExpectedObtainedResult {
-                    expected: stderr,
-                    obtained: obtained_stderr,
-                })
+        fn sh_assertion(script: &str, status: ExpectedStatus) -> Assertion {
+            Assertion::new(
+                "status".to_string(),
+                vec!["-c".to_string(), script.to_string()],
+                None,
+                1,
+            )
+            .with_expected_status(status)
+        }
+
+        #[test]
+        fn should_accept_any_nonzero_status() {
+            for script in ["exit 1", "exit 42"] {
+                let result = sh_assertion(script, ExpectedStatus::NonZero)
+                    .unsafe_assert_cmd(Command::new("sh"));
+                assert!(result.passed());
             }
-        } else {
-            None
-        };
-        let status_diagnostics = if let Some(status) = self.status {
-            if passed {
-                None
-            } else {
-                Some(ExpectedObtainedResult {
-                    expected: status,
-                    obtained: obtained_status,
+            let result = sh_assertion("exit 0", ExpectedStatus::NonZero)
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.status_diagnostics(),
+                Some(&StatusResult {
+                    expected: ExpectedStatus::NonZero,
+                    obtained: Some(0),
                 })
-            }
-        } else {
-            None
-        };
-        AssertionResult {
-            execution_status,
-            name: self.name.clone(),
-            passed,
-            weight: self.weight,
-            stdout_diagnostics,
-            stderr_diagnostics,
-            status_diagnostics,
+            );
         }
-    }
 
-    #[cfg(test)]
-    pub fn new_dummy(
-        id: u32,
-        with_stdin: bool,
-        with_stdout: bool,
-        with_stderr: bool,
-        status: Option<i32>,
-        weight: u32,
-    ) -> Self {
-        Self::build(
-            format!("name {id}"),
-            (0..4)
-                .into_iter()
-                .map(|i| format!("arg {}", i + id))
-                .collect(),
-            if with_stdin {
-                Some(format!("expected stdin: {id}"))
-            } else {
-                None
-            },
-            if with_stdout {
-                Some(format!("expected stdout: {id}"))
-            } else {
-                None
-            },
-            if with_stderr {
-                Some(format!("expected stderr: {id}"))
-            } else {
-                None
-            },
-            status,
-            weight,
-        )
-        .unwrap()
+        #[test]
+        fn should_not_accept_a_signal_termination_as_a_nonzero_status() {
+            let result = sh_assertion("kill -9 $$", ExpectedStatus::NonZero)
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.execution_status(),
+                ExecutionStatus::FailureWithSignalTermination
+            );
+            assert_eq!(result.status_diagnostics().unwrap().obtained(), None);
+        }
+
+        #[test]
+        fn should_only_accept_a_success_as_a_zero_status() {
+            assert!(
+                sh_assertion("exit 0", ExpectedStatus::Zero)
+                    .unsafe_assert_cmd(Command::new("sh"))
+                    .passed()
+            );
+            let result =
+                sh_assertion("exit 3", ExpectedStatus::Zero).unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(result.status_diagnostics().unwrap().obtained(), Some(3));
+        }
     }
-}
+    mod stderr_empty_test {
+        use super::*;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    //use test_log;
+        fn sh_assertion(script: &str) -> Assertion {
+            Assertion::new(
+                "sh".to_string(),
+                vec!["-c".to_string(), script.to_string()],
+                None,
+                1,
+            )
+        }
 
-    mod unsecure_assert_cmd_test {
-        use super::*;
         #[test]
-        fn should_expect_failure_before_execution() {
-            let args = vec!["arg1".to_string(), "arg2".to_string(), "arg3".to_string()];
-            let expected_stdout = Some("stdout 1".to_string());
-            let expected_stderr = Some("stderr 1".to_string());
-            let expected_status = Some(0);
-            let assertion_name = "name 123".to_string();
-            let assertion_weight = 1;
-            let not_passed_assertion = Assertion::build(
-                assertion_name.clone(),
-                args,
-                Some("stdin 1".to_string()),
-                expected_stdout.clone(),
-                expected_stderr.clone(),
-                expected_status,
-                assertion_weight,
+        fn should_accept_only_stderr_empty_expectation() {
+            assert!(sh_assertion("true").with_stderr_empty(true).check().is_ok());
+        }
+
+        #[test]
+        fn should_pass_when_stderr_is_as_expected() {
+            let silent = sh_assertion("echo out")
+                .with_stderr_empty(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(silent.passed());
+
+            let complaining = sh_assertion("echo 'bad input' >&2; exit 1")
+                .with_stderr_empty(false)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(complaining.passed());
+            assert_eq!(complaining.stderr_empty_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_report_the_obtained_stderr() {
+            let result = sh_assertion("echo warning >&2")
+                .with_stderr_empty(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stderr_empty_diagnostics(),
+                Some(&EmptyOutputResult {
+                    expected_empty: true,
+                    obtained: Some("warning\n".to_string()),
+                })
+            );
+
+            let result = sh_assertion("true")
+                .with_stderr_empty(false)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert_eq!(
+                result.stderr_empty_diagnostics(),
+                Some(&EmptyOutputResult {
+                    expected_empty: false,
+                    obtained: Some(String::new()),
+                })
+            );
+        }
+    }
+    mod stdout_equals_stderr_test {
+        use super::*;
+
+        fn sh_assertion(script: &str) -> Assertion {
+            Assertion::new(
+                "sh".to_string(),
+                vec!["-c".to_string(), script.to_string()],
+                None,
+                1,
             )
-            .unwrap();
+            .with_stdout_equals_stderr(true)
+        }
 
-            let mut cmd = Command::new("____invalid_command");
+        #[test]
+        fn should_accept_only_stdout_equals_stderr_expectation() {
+            assert!(sh_assertion("true").check().is_ok());
+        }
 
-            cmd.env_clear();
+        #[test]
+        fn should_pass_when_both_streams_are_equal() {
+            let result = sh_assertion("echo ping; echo ping >&2")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
+            assert_eq!(result.stdout_equals_stderr_diagnostics(), None);
+        }
 
-            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
+        #[test]
+        fn should_report_both_streams_when_they_differ() {
+            let result = sh_assertion("echo ping; echo pong >&2")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
             assert_eq!(
-                result.execution_status,
-                ExecutionStatus::FailureBeforeExecution
+                result.stdout_equals_stderr_diagnostics(),
+                Some(&UnequalStreamsResult {
+                    stdout: Some("ping\n".to_string()),
+                    stderr: Some("pong\n".to_string()),
+                })
             );
-            assert_eq!(result.name, assertion_name);
-            assert_eq!(result.passed, false, "assertion should not pass");
-            assert_eq!(result.weight, assertion_weight);
-            let stdout_diagnostics = result.stdout_diagnostics;
-            assert!(stdout_diagnostics.is_some_and(|v| {
-                if v.expected == expected_stdout.unwrap() && v.obtained.is_none() {
-                    return true;
-                }
-                false
-            }));
-
-            let stderr_diagnostics = result.stderr_diagnostics;
-            assert!(stderr_diagnostics.is_some_and(|v| {
-                if v.expected == expected_stderr.unwrap() && v.obtained.is_none() {
-                    return true;
-                }
-                false
-            }));
-            let status_diagnostics = result.status_diagnostics;
-            assert!(status_diagnostics.is_some_and(|v| {
-                if v.expected == expected_status.unwrap() && v.obtained.is_none() {
-                    return true;
-                }
-                false
-            }));
         }
 
         #[test]
-        fn should_expect_success_for_echo() {
-            let args = vec![
-                "arg1".to_string(),
-                "arg2  0".to_string(),
-                "arg3".to_string(),
-            ];
+        fn should_be_ignored_when_the_stderr_is_informational() {
+            let result = sh_assertion("echo ping; echo pong >&2")
+                .with_informational_stderr(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
+        }
 
-            // Passing expectation
-            let passing_expected_stdout = Some("arg1 arg2  0 arg3\n".to_string());
-            let passing_expected_stderr = Some("".to_string());
-            let passing_expected_status = Some(0);
+        #[test]
+        fn should_need_both_streams_to_be_captured() {
+            assert_eq!(
+                sh_assertion("true")
+                    .with_capture(CaptureMode::StdoutOnly)
+                    .check(),
+                Err("the stderr has expectations, but capture discards it")
+            );
+        }
+    }
+    mod strip_ansi_test {
+        use super::*;
 
-            let assertion_name = "assertion name".to_string();
-            let assertion_weight = 3;
-            let passed_assertion = Assertion::build(
-                assertion_name.clone(),
-                args.clone(),
+        fn colored_assertion(expected_stdout: &str) -> Assertion {
+            Assertion::new(
+                "colored".to_string(),
+                vec![
+                    "-c".to_string(),
+                    r"printf '\033[1;32mok\033[0m\n'; printf '\033]8;;http://a\007link\033]8;;\007' >&2"
+                        .to_string(),
+                ],
                 None,
-                passing_expected_stdout.clone(),
-                passing_expected_stderr.clone(),
-                passing_expected_status,
-                assertion_weight,
+                1,
             )
-            .unwrap();
-
-            let cmd = Command::new("echo");
+            .with_stdout(expected_stdout.to_string())
+            .with_stderr("link".to_string())
+        }
 
-            let result = passed_assertion.unsafe_assert_cmd(cmd);
+        #[test]
+        fn should_remove_the_escape_codes() {
             assert_eq!(
-                result,
-                AssertionResult {
-                    execution_status: ExecutionStatus::Success,
-                    name: assertion_name.clone(),
-                    passed: true,
-                    weight: assertion_weight,
-                    stdout_diagnostics: None,
-                    stderr_diagnostics: None,
-                    status_diagnostics: None
-                }
+                &*strip_ansi_codes(b"\x1b[31mred\x1b[0m \x1b[2Kline\x1b(B"),
+                b"red line"
             );
+            assert_eq!(
+                &*strip_ansi_codes(b"\x1b]8;;http://a\x1b\\link\x1b]8;;\x1b\\"),
+                b"link"
+            );
+            assert_eq!(&*strip_ansi_codes(b"plain [text]"), b"plain [text]");
+        }
 
-            // Not passing expectation
-            let not_passing_expected_stdout = Some("arg1 arg2 0 arg3".to_string());
-            let not_passing_expected_stderr = Some("invalid error".to_string());
-            let not_passing_expected_status = Some(23);
+        #[test]
+        fn should_compare_the_outputs_without_the_escape_codes() {
+            let result = colored_assertion("ok\n")
+                .with_strip_ansi(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
 
-            let not_passed_assertion = Assertion::build(
-                assertion_name.clone(),
-                args,
+            let result = colored_assertion("ok\n")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+        }
+
+        #[test]
+        fn should_report_the_outputs_with_the_escape_codes() {
+            let result = colored_assertion("ko\n")
+                .with_strip_ansi(true)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_diagnostics().unwrap().obtained(),
+                Some(&"\x1b[1;32mok\x1b[0m\n".to_string())
+            );
+            assert_eq!(result.stderr_diagnostics(), None);
+        }
+    }
+    mod stdout_match_test {
+        use super::*;
+
+        fn padded_assertion(expected_stdout: &str) -> Assertion {
+            Assertion::new(
+                "padded".to_string(),
+                vec!["-c".to_string(), r"printf 'a  \n b\t\n\nc '".to_string()],
                 None,
-                not_passing_expected_stdout.clone(),
-                not_passing_expected_stderr.clone(),
-                not_passing_expected_status,
-                assertion_weight,
+                1,
             )
-            .unwrap();
+            .with_stdout(expected_stdout.to_string())
+        }
 
-            let cmd = Command::new("echo");
+        #[test]
+        fn should_ignore_the_whitespace_at_the_end_of_the_lines() {
+            assert!(StdoutMatch::TrimLineEnds.matches(b"a  \n b\t\r\n", b"a\n b\n"));
+            assert!(!StdoutMatch::TrimLineEnds.matches(b"a\n b\n", b"a\nb\n"));
+            assert!(!StdoutMatch::TrimLineEnds.matches(b"a\nb\n", b"a\n\nb\n"));
+            assert!(!StdoutMatch::Exact.matches(b"a \n", b"a\n"));
+        }
 
-            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
-            assert_eq!(
-                result,
-                AssertionResult {
-                    execution_status: ExecutionStatus::Success,
-                    name: assertion_name,
-                    passed: false,
-                    weight: assertion_weight,
-                    stdout_diagnostics: Some(ExpectedObtainedResult {
-                        expected: not_passing_expected_stdout.unwrap(),
-                        obtained: passing_expected_stdout
-                    }),
-                    stderr_diagnostics: Some(ExpectedObtainedResult {
-                        expected: not_passing_expected_stderr.unwrap(),
-                        obtained: passing_expected_stderr
-                    }),
-                    status_diagnostics: Some(ExpectedObtainedResult {
-                        expected: not_passing_expected_status.unwrap(),
-                        obtained: passing_expected_status
-                    })
-                }
-            );
+        #[test]
+        fn should_ignore_the_whitespace_at_the_end_of_the_output() {
+            assert!(StdoutMatch::TrimEnd.matches(b"a \nb", b"a \nb\n\n"));
+            assert!(!StdoutMatch::TrimEnd.matches(b"a\nb\n", b"a \nb\n"));
+            assert!(!StdoutMatch::TrimEnd.matches(b" a\n", b"a\n"));
         }
+
         #[test]
-        fn should_expect_success_for_cat_using_stdin() {
-            // Passing expectation
-            let stdin = Some("this is the input    !\n and this also".to_string());
-            let passing_expected_stdout =
-                Some("this is the input    !\n and this also".to_string());
-            let passing_expected_stderr = Some("".to_string());
-            let passing_expected_status = Some(0);
+        fn should_compare_the_words_of_the_output() {
+            assert!(StdoutMatch::CollapseWhitespace.matches(b" 1  2\n3\t\n", b"1 2 3"));
+            assert!(!StdoutMatch::CollapseWhitespace.matches(b"1 2 3", b"1 23"));
+            assert!(StdoutMatch::CollapseWhitespace.matches(b" \n", b""));
+        }
 
-            let assertion_name = "assertion name".to_string();
-            let assertion_weight = 8;
-            let passed_assertion = Assertion::build(
-                assertion_name.clone(),
-                vec![],
-                stdin.clone(),
-                passing_expected_stdout.clone(),
-                passing_expected_stderr.clone(),
-                passing_expected_status,
-                assertion_weight,
-            )
-            .unwrap();
+        #[test]
+        fn should_compare_the_stdout_without_the_trailing_whitespace() {
+            let result = padded_assertion("a\n b\n\nc")
+                .with_stdout_match(StdoutMatch::TrimLineEnds)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
 
-            let cmd = Command::new("cat");
+            let result = padded_assertion("a\n b\n\nc")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+        }
 
-            let result = passed_assertion.unsafe_assert_cmd(cmd);
+        #[test]
+        fn should_report_the_stdout_as_it_was_printed() {
+            let result = padded_assertion("a\nb\n\nc")
+                .with_stdout_match(StdoutMatch::TrimLineEnds)
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("sh"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_diagnostics().unwrap().obtained(),
+                Some(&"a  \n b\t\n\nc ".to_string())
+            );
+        }
+
+        #[test]
+        fn should_apply_to_the_acceptable_outputs() {
+            let result = Assertion::new(
+                "padded".to_string(),
+                vec!["-c".to_string(), "echo 'yes '".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_any_of(vec!["no\n".to_string(), "yes\n".to_string()])
+            .with_stdout_match(StdoutMatch::TrimLineEnds)
+            .check()
+            .unwrap()
+            .unsafe_assert_cmd(Command::new("sh"));
+            assert!(result.passed());
+        }
 
+        #[test]
+        fn should_require_an_expected_stdout() {
             assert_eq!(
-                result,
-                AssertionResult {
-                    execution_status: ExecutionStatus::Success,
-                    name: assertion_name.clone(),
-                    passed: true,
-                    weight: assertion_weight,
-                    stdout_diagnostics: None,
-                    stderr_diagnostics: None,
-                    status_diagnostics: None
-                }
+                Assertion::new("status".to_string(), vec![], None, 1)
+                    .with_status(0)
+                    .with_stdout_match(StdoutMatch::TrimLineEnds)
+                    .check(),
+                Err("stdout_match requires stdout or stdout_any_of")
             );
+        }
+    }
+    mod cwd_test {
+        use super::*;
 
-            // Not passing expectation
-            // (one space missing)
-            let not_passing_expected_stdout =
-                Some("this is the input   !\n and this also".to_string());
+        fn status_assertion() -> Assertion {
+            Assertion::new("cwd".to_string(), vec![], None, 1).with_status(0)
+        }
 
-            let not_passed_assertion = Assertion::build(
-                assertion_name.clone(),
-                vec![],
-                stdin.clone(),
-                not_passing_expected_stdout.clone(),
-                passing_expected_stderr.clone(),
-                passing_expected_status,
-                assertion_weight,
-            )
-            .unwrap();
+        #[test]
+        fn should_accept_paths_inside_the_temporary_directory() {
+            for cwd in ["src", "./src/bin", "."] {
+                assert!(status_assertion().with_cwd(cwd.into()).check().is_ok());
+            }
+        }
 
-            let cmd = Command::new("cat");
+        #[test]
+        fn should_not_accept_paths_leaving_the_temporary_directory() {
+            for cwd in ["../src", "src/../..", "/tmp"] {
+                assert_eq!(
+                    status_assertion().with_cwd(cwd.into()).check(),
+                    Err("cwd must be a relative path inside the temporary directory")
+                );
+            }
+        }
+    }
+    mod stdout_extract_test {
+        use super::*;
+        use crate::grader::grading_tests::unit_test::extraction::Extracted;
 
-            let result = not_passed_assertion.unsafe_assert_cmd(cmd);
+        fn echo_assertion(text: &str) -> Assertion {
+            Assertion::new("echo".to_string(), vec![text.to_string()], None, 1)
+                .with_stdout_extract(StdoutExtract::build(r"accuracy: (\S+)", 0.9).unwrap())
+        }
+
+        #[test]
+        fn should_accept_only_extract_expectation() {
+            assert!(echo_assertion("accuracy: 1").check().is_ok());
+        }
+
+        #[test]
+        fn should_pass_when_the_number_reaches_the_minimum() {
+            let result = echo_assertion("epoch 3, accuracy: 0.93")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(result.passed());
+            assert_eq!(result.stdout_extract_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_report_the_number_below_the_minimum() {
+            let result = echo_assertion("accuracy: 0.85")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            let diagnostics = result.stdout_extract_diagnostics().unwrap();
+            assert_eq!(diagnostics.pattern(), r"accuracy: (\S+)");
+            assert_eq!(diagnostics.min(), 0.9);
+            assert_eq!(diagnostics.obtained(), Some(&Extracted::Number(0.85)));
+        }
+
+        #[test]
+        fn should_report_no_match_and_non_numeric_captures() {
+            let result = echo_assertion("loss: 0.1")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
             assert_eq!(
-                result,
-                AssertionResult {
-                    execution_status: ExecutionStatus::Success,
-                    name: assertion_name,
-                    passed: false,
-                    weight: assertion_weight,
-                    stdout_diagnostics: Some(ExpectedObtainedResult {
-                        expected: not_passing_expected_stdout.unwrap(),
-                        obtained: passing_expected_stdout
-                    }),
-                    stderr_diagnostics: None,
-                    status_diagnostics: None
-                }
+                result.stdout_extract_diagnostics().unwrap().obtained(),
+                Some(&Extracted::NoMatch)
+            );
+
+            let result = echo_assertion("accuracy: high")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            assert_eq!(
+                result.stdout_extract_diagnostics().unwrap().obtained(),
+                Some(&Extracted::NotNumeric("high".to_string()))
             );
         }
     }
-
-    mod config_cmd_test {
+    mod stdout_regex_checks_test {
         use super::*;
-        use std::ffi::OsString;
+        use crate::grader::grading_tests::unit_test::regex_checks::{
+            Comparison, GroupCheck, RegexChecksOutcome,
+        };
+
+        fn echo_assertion(text: &str) -> Assertion {
+            Assertion::new("echo".to_string(), vec![text.to_string()], None, 1)
+                .with_stdout_regex_checks(
+                    StdoutRegexChecks::build(
+                        r"sum: (?<sum>\d+), total: (?<total>\d+)",
+                        vec![GroupCheck::new("sum", Comparison::Eq, "total")],
+                    )
+                    .unwrap(),
+                )
+        }
 
         #[test]
-        fn should_configure_every_field() {
-            let expected_args = vec!["arg1".to_string(), "arg2".to_string()];
-            let expected_stdout = Some("stdout 1".to_string());
-            let expected_stderr = Some("stderr 1".to_string());
-            let expected_status = Some(13);
-            let a = Assertion {
-                name: "name 1".to_string().clone(),
-                args: expected_args.clone(),
-                stdin: Some("stdin 1".to_string()).clone(),
-                stdout: expected_stdout.clone(),
-                stderr: expected_stderr.clone(),
-                status: expected_status,
-                weight: 1,
+        fn should_pass_when_every_check_holds() {
+            let result = echo_assertion("sum: 10, total: 10")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(result.passed());
+            assert_eq!(result.stdout_regex_checks_diagnostics(), None);
+        }
+
+        #[test]
+        fn should_report_the_failed_checks_and_no_match() {
+            let result = echo_assertion("sum: 9, total: 10")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
+            let diagnostics = result.stdout_regex_checks_diagnostics().unwrap();
+            assert_eq!(
+                diagnostics.pattern(),
+                r"sum: (?<sum>\d+), total: (?<total>\d+)"
+            );
+            let Some(RegexChecksOutcome::FailedChecks(failed)) = diagnostics.obtained() else {
+                panic!("expected failed checks");
             };
-            let mut cmd = Command::new("some command");
-            a.config_cmd(&mut cmd);
+            assert_eq!(failed.len(), 1);
+            assert_eq!(
+                (failed[0].left(), failed[0].right()),
+                (Some("9"), Some("10"))
+            );
 
+            let result = echo_assertion("nothing")
+                .check()
+                .unwrap()
+                .unsafe_assert_cmd(Command::new("echo"));
+            assert!(!result.passed());
             assert_eq!(
-                cmd.get_args().collect::<Vec<_>>(),
-                expected_args
-                    .iter()
-                    .map(|s| OsString::from(s))
-                    .collect::<Vec<_>>()
+                result.stdout_regex_checks_diagnostics().unwrap().obtained(),
+                Some(&RegexChecksOutcome::NoMatch)
             );
         }
     }
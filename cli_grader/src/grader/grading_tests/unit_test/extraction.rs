@@ -0,0 +1,167 @@
+//! Numbers extracted from the output of programs that report their own metrics, e.g.
+//! `accuracy: 0.93`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Extracts a number from the stdout with the first capture group of `pattern`. The
+/// number must be at least `min`.
+#[derive(Debug, Clone)]
+pub struct StdoutExtract {
+    pattern: Regex,
+    min: f64,
+}
+
+/// The patterns are compared by their source, as a `Regex` can not be compared.
+impl PartialEq for StdoutExtract {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.min == other.min
+    }
+}
+
+/// `min` is never NaN, as `build` only accepts finite numbers.
+impl Eq for StdoutExtract {}
+
+/// What the pattern captured from the stdout.
+///
+/// It is serialized with its kind in snake case, e.g. `{"kind": "no_match"}` or
+/// `{"kind": "number", "value": 0.93}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Extracted {
+    /// The pattern did not match, or its capture group did not take part in the match.
+    NoMatch,
+    /// The captured text, which is not a number.
+    NotNumeric(String),
+    Number(f64),
+}
+
+/// Extraction that did not reach the minimum.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ExtractionResult {
+    pattern: String,
+    min: f64,
+    obtained: Option<Extracted>,
+}
+
+impl ExtractionResult {
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn obtained(&self) -> Option<&Extracted> {
+        self.obtained.as_ref()
+    }
+}
+
+impl StdoutExtract {
+    pub fn build(pattern: &str, min: f64) -> Result<Self, &'static str> {
+        let pattern = Regex::new(pattern).map_err(|_| "stdout_extract pattern is not valid")?;
+        // the whole match is always the group 0
+        if pattern.captures_len() < 2 {
+            return Err("stdout_extract pattern must have a capture group");
+        }
+        if !min.is_finite() {
+            return Err("stdout_extract min must be a finite number");
+        }
+        Ok(Self { pattern, min })
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Parses the first capture group of the first match in `stdout`, ignoring the
+    /// whitespace around it.
+    pub(crate) fn extract(&self, stdout: &str) -> Extracted {
+        let Some(captured) = self
+            .pattern
+            .captures(stdout)
+            .and_then(|captures| captures.get(1))
+        else {
+            return Extracted::NoMatch;
+        };
+        let captured = captured.as_str().trim();
+        match captured.parse() {
+            Ok(number) => Extracted::Number(number),
+            Err(_) => Extracted::NotNumeric(captured.to_string()),
+        }
+    }
+
+    /// Whether `extracted` is a number that reaches the minimum.
+    pub(crate) fn accepts(&self, extracted: &Extracted) -> bool {
+        matches!(extracted, Extracted::Number(number) if *number >= self.min)
+    }
+
+    pub(crate) fn result(&self, obtained: Option<Extracted>) -> ExtractionResult {
+        ExtractionResult {
+            pattern: self.pattern.as_str().to_string(),
+            min: self.min,
+            obtained,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_not_build_without_capture_group() {
+        assert_eq!(
+            StdoutExtract::build(r"accuracy: \d+", 0.5),
+            Err("stdout_extract pattern must have a capture group")
+        );
+    }
+
+    #[test]
+    fn should_not_build_with_invalid_pattern_or_min() {
+        assert_eq!(
+            StdoutExtract::build(r"accuracy: (\d+", 0.5),
+            Err("stdout_extract pattern is not valid")
+        );
+        assert_eq!(
+            StdoutExtract::build(r"accuracy: (\S+)", f64::NAN),
+            Err("stdout_extract min must be a finite number")
+        );
+    }
+
+    #[test]
+    fn should_extract_the_first_capture_group() {
+        let extract = StdoutExtract::build(r"accuracy: (\S+)", 0.9).unwrap();
+
+        let extracted = extract.extract("loss: 0.1\naccuracy: 0.93\naccuracy: 0.2\n");
+        assert_eq!(extracted, Extracted::Number(0.93));
+        assert!(extract.accepts(&extracted));
+
+        let extracted = extract.extract("accuracy: 0.85\n");
+        assert_eq!(extracted, Extracted::Number(0.85));
+        assert!(!extract.accepts(&extracted));
+    }
+
+    #[test]
+    fn should_not_accept_missing_or_non_numeric_captures() {
+        let extract = StdoutExtract::build(r"accuracy: (\S+)|loss: (\S+)", 0.0).unwrap();
+
+        let extracted = extract.extract("nothing here\n");
+        assert_eq!(extracted, Extracted::NoMatch);
+        assert!(!extract.accepts(&extracted));
+
+        let extracted = extract.extract("loss: 0.1\n");
+        assert_eq!(extracted, Extracted::NoMatch);
+
+        let extracted = extract.extract("accuracy: high\n");
+        assert_eq!(extracted, Extracted::NotNumeric("high".to_string()));
+        assert!(!extract.accepts(&extracted));
+    }
+}
@@ -0,0 +1,293 @@
+//! Scripted interaction with programs that prompt the user for input.
+
+use crate::grader::termination::Termination;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    process::{ChildStdin, ChildStdout},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Waits for `wait_for` to appear in the stdout and then writes `send` to the stdin.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InteractionStep {
+    wait_for: String,
+    send: String,
+}
+
+impl InteractionStep {
+    pub fn new(wait_for: String, send: String) -> Self {
+        Self { wait_for, send }
+    }
+
+    pub fn wait_for(&self) -> &str {
+        &self.wait_for
+    }
+
+    pub fn send(&self) -> &str {
+        &self.send
+    }
+}
+
+/// Sequence of `InteractionStep`s executed in order against a running program.
+///
+/// Each step must see its prompt in less than `step_timeout`, otherwise the interaction
+/// fails and the program is stopped: it is sent SIGTERM and only killed if it is still
+/// running after `kill_grace`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InteractionScript {
+    steps: Vec<InteractionStep>,
+    step_timeout: Duration,
+    kill_grace: Duration,
+}
+
+/// Why an interaction did not reach its end.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct InteractionFailure {
+    /// 1-based index of the step that failed.
+    step: usize,
+    expected_prompt: String,
+    /// Everything that was read from the stdout until the failure.
+    obtained: String,
+    /// How the program was stopped after the failure. It is `None` when it had already
+    /// exited.
+    #[serde(default)]
+    termination: Option<Termination>,
+}
+
+impl InteractionFailure {
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn expected_prompt(&self) -> &str {
+        &self.expected_prompt
+    }
+
+    pub fn obtained(&self) -> &str {
+        &self.obtained
+    }
+
+    pub fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+
+    pub(crate) fn set_termination(&mut self, termination: Option<Termination>) {
+        self.termination = termination;
+    }
+}
+
+impl InteractionScript {
+    pub fn build(
+        steps: Vec<InteractionStep>,
+        step_timeout: Duration,
+    ) -> Result<Self, &'static str> {
+        if steps.is_empty() {
+            return Err("an interaction script must have at least one step");
+        }
+        Ok(Self {
+            steps,
+            step_timeout,
+            kill_grace: Duration::ZERO,
+        })
+    }
+
+    /// Time the program is given to exit after SIGTERM when the interaction fails, before
+    /// being killed. It defaults to zero, which kills it right away.
+    pub fn with_kill_grace(mut self, kill_grace: Duration) -> Self {
+        self.kill_grace = kill_grace;
+        self
+    }
+
+    pub fn steps(&self) -> &[InteractionStep] {
+        &self.steps
+    }
+
+    pub fn kill_grace(&self) -> Duration {
+        self.kill_grace
+    }
+
+    /// Executes every step, returning the whole stdout of the program.
+    ///
+    /// The stdin is closed after the last step and the stdout is read until its end. If
+    /// any of the prompts is not seen in time, the interaction stops and the caller is
+    /// expected to stop the program.
+    pub(crate) fn run(
+        &self,
+        mut stdin: ChildStdin,
+        stdout: ChildStdout,
+    ) -> (Vec<u8>, Result<(), InteractionFailure>) {
+        let chunks = spawn_reader(stdout);
+        let mut output: Vec<u8> = vec![];
+        // prompts are only searched after the end of the previous one
+        let mut searched_from = 0;
+
+        for (i, step) in self.steps.iter().enumerate() {
+            debug!(
+                "Waiting for prompt: '{}'",
+                step.wait_for.replace('\n', "\\n")
+            );
+            let deadline = Instant::now() + self.step_timeout;
+            let prompt_end = loop {
+                if let Some(position) = find(&output[searched_from..], step.wait_for.as_bytes()) {
+                    break Some(searched_from + position + step.wait_for.len());
+                }
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match chunks.recv_timeout(remaining) {
+                    Ok(chunk) => output.extend(chunk),
+                    Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+                        break None;
+                    }
+                }
+            };
+            let Some(prompt_end) = prompt_end else {
+                let failure = InteractionFailure {
+                    step: i + 1,
+                    expected_prompt: step.wait_for.clone(),
+                    obtained: String::from_utf8_lossy(&output).into_owned(),
+                    termination: None,
+                };
+                return (output, Err(failure));
+            };
+            searched_from = prompt_end;
+
+            debug!("Sending: '{}'", step.send.replace('\n', "\\n"));
+            // a program that exits without reading its input is not an interaction
+            // failure by itself, its output will tell
+            if let Err(err) = stdin.write_all(step.send.as_bytes()) {
+                debug!("error while writing to stdin: {err:?}");
+            }
+        }
+        drop(stdin);
+
+        output.extend(chunks.iter().flatten());
+        (output, Ok(()))
+    }
+}
+
+/// Reads `stdout` in another thread, so it is possible to wait for it with a timeout.
+fn spawn_reader(mut stdout: ChildStdout) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        loop {
+            match stdout.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if sender.send(buffer[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    receiver
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    /// Asks for two numbers and prints their sum.
+    const ADDER: &str = r#"printf 'a: '; read a; printf 'b: '; read b; echo "sum: $((a + b))""#;
+
+    fn run_script(script: &InteractionScript, program: &str) -> (String, bool) {
+        let mut child = Command::new("sh")
+            .args(["-c", program])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (output, result) = script.run(stdin, stdout);
+        if result.is_err() {
+            child.kill().unwrap();
+        }
+        child.wait().unwrap();
+        (String::from_utf8(output).unwrap(), result.is_ok())
+    }
+
+    #[test]
+    fn should_not_build_without_steps() {
+        assert!(InteractionScript::build(vec![], Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn should_answer_each_prompt() {
+        let script = InteractionScript::build(
+            vec![
+                InteractionStep::new("a: ".to_string(), "2\n".to_string()),
+                InteractionStep::new("b: ".to_string(), "3\n".to_string()),
+            ],
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        assert_eq!(
+            run_script(&script, ADDER),
+            ("a: b: sum: 5\n".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn should_fail_when_prompt_does_not_appear() {
+        let script = InteractionScript::build(
+            vec![
+                InteractionStep::new("a: ".to_string(), "2\n".to_string()),
+                InteractionStep::new("c: ".to_string(), "3\n".to_string()),
+            ],
+            Duration::from_millis(200),
+        )
+        .unwrap();
+        let mut child = Command::new("sh")
+            .args(["-c", ADDER])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (_, result) = script.run(stdin, stdout);
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(
+            result,
+            Err(InteractionFailure {
+                step: 2,
+                expected_prompt: "c: ".to_string(),
+                obtained: "a: b: ".to_string(),
+                termination: None,
+            })
+        );
+    }
+
+    #[test]
+    fn should_not_reuse_previous_prompt() {
+        let script = InteractionScript::build(
+            vec![
+                InteractionStep::new("a: ".to_string(), "2\n".to_string()),
+                InteractionStep::new("a: ".to_string(), "3\n".to_string()),
+            ],
+            Duration::from_millis(200),
+        )
+        .unwrap();
+
+        assert!(!run_script(&script, ADDER).1);
+    }
+}
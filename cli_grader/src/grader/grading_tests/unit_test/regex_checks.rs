@@ -0,0 +1,330 @@
+//! Relations between the groups captured from the stdout, e.g. two printed totals that
+//! must be equal, which a literal comparison can not express.
+
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt};
+
+/// How the values of two groups are compared, serialized in lowercase, e.g. `"le"`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+/// Shown as the operator, e.g. `<=`.
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        };
+        write!(f, "{operator}")
+    }
+}
+
+/// Constraint between two groups, each one referred to by its name or by its index, e.g.
+/// `{ "left": "count", "op": "eq", "right": "2" }`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GroupCheck {
+    left: String,
+    op: Comparison,
+    right: String,
+}
+
+impl GroupCheck {
+    pub fn new(left: impl Into<String>, op: Comparison, right: impl Into<String>) -> Self {
+        Self {
+            left: left.into(),
+            op,
+            right: right.into(),
+        }
+    }
+
+    pub fn left(&self) -> &str {
+        &self.left
+    }
+
+    pub fn op(&self) -> Comparison {
+        self.op
+    }
+
+    pub fn right(&self) -> &str {
+        &self.right
+    }
+}
+
+/// Shown as `<left> <operator> <right>`, e.g. `count == 2`.
+impl fmt::Display for GroupCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.op, self.right)
+    }
+}
+
+/// Matches `pattern` against the stdout and checks its groups. Every check must hold.
+#[derive(Debug, Clone)]
+pub struct StdoutRegexChecks {
+    pattern: Regex,
+    checks: Vec<GroupCheck>,
+}
+
+/// The patterns are compared by their source, as a `Regex` can not be compared.
+impl PartialEq for StdoutRegexChecks {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.checks == other.checks
+    }
+}
+
+impl Eq for StdoutRegexChecks {}
+
+/// Check that did not hold, with the values of its groups. A value is `None` when its
+/// group did not take part in the match.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct FailedGroupCheck {
+    check: GroupCheck,
+    left: Option<String>,
+    right: Option<String>,
+}
+
+impl FailedGroupCheck {
+    pub fn check(&self) -> &GroupCheck {
+        &self.check
+    }
+
+    pub fn left(&self) -> Option<&str> {
+        self.left.as_deref()
+    }
+
+    pub fn right(&self) -> Option<&str> {
+        self.right.as_deref()
+    }
+}
+
+/// Why the stdout did not pass the checks.
+///
+/// It is serialized with its kind in snake case, e.g. `{"kind": "no_match"}` or
+/// `{"kind": "failed_checks", "value": [...]}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum RegexChecksOutcome {
+    NoMatch,
+    FailedChecks(Vec<FailedGroupCheck>),
+}
+
+/// Checks that did not pass.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct RegexChecksResult {
+    pattern: String,
+    obtained: Option<RegexChecksOutcome>,
+}
+
+impl RegexChecksResult {
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// It is `None` when nothing could be obtained from the program (e.g. it could not be
+    /// executed).
+    pub fn obtained(&self) -> Option<&RegexChecksOutcome> {
+        self.obtained.as_ref()
+    }
+}
+
+impl StdoutRegexChecks {
+    pub fn build(pattern: &str, checks: Vec<GroupCheck>) -> Result<Self, &'static str> {
+        let pattern =
+            Regex::new(pattern).map_err(|_| "stdout_regex_checks pattern is not valid")?;
+        if checks.is_empty() {
+            return Err("stdout_regex_checks must have at least one check");
+        }
+        if checks
+            .iter()
+            .flat_map(|check| [&check.left, &check.right])
+            .any(|group| !has_group(&pattern, group))
+        {
+            return Err("stdout_regex_checks refers to a group that is not in the pattern");
+        }
+        Ok(Self { pattern, checks })
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    pub fn checks(&self) -> &[GroupCheck] {
+        &self.checks
+    }
+
+    /// Checks the groups of the first match in `stdout`, returning `None` when every
+    /// check holds.
+    pub(crate) fn evaluate(&self, stdout: &str) -> Option<RegexChecksOutcome> {
+        let Some(captures) = self.pattern.captures(stdout) else {
+            return Some(RegexChecksOutcome::NoMatch);
+        };
+        let failed_checks: Vec<_> = self
+            .checks
+            .iter()
+            .filter_map(|check| {
+                let left = group_value(&captures, &check.left);
+                let right = group_value(&captures, &check.right);
+                let holds = match (left, right) {
+                    (Some(left), Some(right)) => check.op.holds(compare(left, right)),
+                    _ => false,
+                };
+                (!holds).then(|| FailedGroupCheck {
+                    check: check.clone(),
+                    left: left.map(str::to_string),
+                    right: right.map(str::to_string),
+                })
+            })
+            .collect();
+        (!failed_checks.is_empty()).then_some(RegexChecksOutcome::FailedChecks(failed_checks))
+    }
+
+    pub(crate) fn result(&self, obtained: Option<RegexChecksOutcome>) -> RegexChecksResult {
+        RegexChecksResult {
+            pattern: self.pattern.as_str().to_string(),
+            obtained,
+        }
+    }
+}
+
+/// Whether `group`, a name or an index, is a group of `pattern`.
+fn has_group(pattern: &Regex, group: &str) -> bool {
+    match group.parse::<usize>() {
+        Ok(index) => index < pattern.captures_len(),
+        Err(_) => pattern.capture_names().any(|name| name == Some(group)),
+    }
+}
+
+fn group_value<'h>(captures: &Captures<'h>, group: &str) -> Option<&'h str> {
+    match group.parse::<usize>() {
+        Ok(index) => captures.get(index),
+        Err(_) => captures.name(group),
+    }
+    .map(|captured| captured.as_str())
+}
+
+/// Compares the values as numbers when both are numbers, ignoring the whitespace around
+/// them, e.g. `"10" > "9"`, and as text otherwise.
+fn compare(left: &str, right: &str) -> Ordering {
+    if let (Ok(left), Ok(right)) = (left.trim().parse::<f64>(), right.trim().parse::<f64>())
+        && let Some(ordering) = left.partial_cmp(&right)
+    {
+        return ordering;
+    }
+    left.cmp(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_not_build_with_invalid_pattern_or_checks() {
+        let check = || vec![GroupCheck::new("1", Comparison::Eq, "2")];
+        assert_eq!(
+            StdoutRegexChecks::build(r"(\d+) (\d+", check()),
+            Err("stdout_regex_checks pattern is not valid")
+        );
+        assert_eq!(
+            StdoutRegexChecks::build(r"(\d+) (\d+)", vec![]),
+            Err("stdout_regex_checks must have at least one check")
+        );
+        assert_eq!(
+            StdoutRegexChecks::build(r"(\d+)", check()),
+            Err("stdout_regex_checks refers to a group that is not in the pattern")
+        );
+        assert_eq!(
+            StdoutRegexChecks::build(
+                r"(?<a>\d+)",
+                vec![GroupCheck::new("a", Comparison::Eq, "b")]
+            ),
+            Err("stdout_regex_checks refers to a group that is not in the pattern")
+        );
+    }
+
+    #[test]
+    fn should_check_the_groups_of_the_first_match() {
+        let checks = StdoutRegexChecks::build(
+            r"sum: (?<sum>\S+), total: (?<total>\S+)",
+            vec![GroupCheck::new("sum", Comparison::Eq, "total")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            checks.evaluate("sum: 10, total: 10.0\nsum: 1, total: 2\n"),
+            None
+        );
+        assert_eq!(
+            checks.evaluate("sum: 9, total: 10\n"),
+            Some(RegexChecksOutcome::FailedChecks(vec![FailedGroupCheck {
+                check: GroupCheck::new("sum", Comparison::Eq, "total"),
+                left: Some("9".to_string()),
+                right: Some("10".to_string()),
+            }]))
+        );
+        assert_eq!(
+            checks.evaluate("nothing\n"),
+            Some(RegexChecksOutcome::NoMatch)
+        );
+    }
+
+    #[test]
+    fn should_compare_numbers_by_value_and_the_rest_as_text() {
+        let checks = StdoutRegexChecks::build(
+            r"(\S+) (\S+)",
+            vec![
+                GroupCheck::new("1", Comparison::Lt, "2"),
+                GroupCheck::new("1", Comparison::Ne, "2"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(checks.evaluate("9 10"), None);
+        assert_eq!(checks.evaluate("abc abd"), None);
+        assert!(checks.evaluate("b a").is_some());
+    }
+
+    #[test]
+    fn should_fail_the_checks_of_groups_that_did_not_match() {
+        let checks = StdoutRegexChecks::build(
+            r"(\d+)(?: of (\d+))?",
+            vec![GroupCheck::new("1", Comparison::Le, "2")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            checks.evaluate("3"),
+            Some(RegexChecksOutcome::FailedChecks(vec![FailedGroupCheck {
+                check: GroupCheck::new("1", Comparison::Le, "2"),
+                left: Some("3".to_string()),
+                right: None,
+            }]))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Names of what is being graded by the current thread, e.g. to tell which assertion the
+//! log records belong to when several of them run at the same time.
+
+use log::kv::{self, Key, Source, Value, VisitSource};
+use std::cell::RefCell;
+
+thread_local! {
+    static CURRENT: RefCell<LogContext> = RefCell::new(LogContext::default());
+}
+
+/// Section, unit test and assertion that the current thread is running. The fields that
+/// do not apply, e.g. the assertion while a global setup runs, are `None`.
+///
+/// It is a `log::kv::Source`, so a logger can attach it to every record with
+/// `LogContext::current()`, as the log records of the grader only carry their message.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct LogContext {
+    section: Option<String>,
+    unit_test: Option<String>,
+    assertion: Option<String>,
+}
+
+impl LogContext {
+    /// Context of the current thread. The threads that run unit tests in parallel inherit
+    /// the one of the section.
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    pub fn unit_test(&self) -> Option<&str> {
+        self.unit_test.as_deref()
+    }
+
+    pub fn assertion(&self) -> Option<&str> {
+        self.assertion.as_deref()
+    }
+
+    /// Makes it the context of the current thread until the guard is dropped, which
+    /// restores the previous one.
+    pub(crate) fn enter(self) -> LogContextGuard {
+        LogContextGuard {
+            previous: CURRENT.with(|current| current.replace(self)),
+        }
+    }
+
+    /// Enters the section, leaving the unit test and the assertion of the current context.
+    pub(crate) fn enter_section(name: &str) -> LogContextGuard {
+        LogContext {
+            section: Some(name.to_string()),
+            unit_test: None,
+            assertion: None,
+        }
+        .enter()
+    }
+
+    /// Enters the unit test within the section of the current context.
+    pub(crate) fn enter_unit_test(name: &str) -> LogContextGuard {
+        LogContext {
+            unit_test: Some(name.to_string()),
+            assertion: None,
+            ..Self::current()
+        }
+        .enter()
+    }
+
+    /// Enters the assertion within the unit test of the current context.
+    pub(crate) fn enter_assertion(name: &str) -> LogContextGuard {
+        LogContext {
+            assertion: Some(name.to_string()),
+            ..Self::current()
+        }
+        .enter()
+    }
+}
+
+impl Source for LogContext {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        for (key, value) in [
+            ("section", &self.section),
+            ("unit_test", &self.unit_test),
+            ("assertion", &self.assertion),
+        ] {
+            if let Some(value) = value {
+                visitor.visit_pair(Key::from_str(key), Value::from(value.as_str()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Restores the previous context when dropped, even if the code that entered it panics.
+#[must_use]
+pub(crate) struct LogContextGuard {
+    previous: LogContext,
+}
+
+impl Drop for LogContextGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.replace(std::mem::take(&mut self.previous)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Pairs visited by `Source`, as strings.
+    fn pairs(context: &LogContext) -> BTreeMap<String, String> {
+        struct Collect(BTreeMap<String, String>);
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+                self.0.insert(key.to_string(), value.to_string());
+                Ok(())
+            }
+        }
+        let mut collect = Collect(BTreeMap::new());
+        context.visit(&mut collect).unwrap();
+        collect.0
+    }
+
+    #[test]
+    fn should_nest_the_contexts_and_restore_the_previous_ones() {
+        let section = LogContext::enter_section("section 1");
+        {
+            let _unit_test = LogContext::enter_unit_test("unit test");
+            let _assertion = LogContext::enter_assertion("assertion");
+            assert_eq!(
+                LogContext::current(),
+                LogContext {
+                    section: Some("section 1".to_string()),
+                    unit_test: Some("unit test".to_string()),
+                    assertion: Some("assertion".to_string()),
+                }
+            );
+        }
+        assert_eq!(LogContext::current().section(), Some("section 1"));
+        assert_eq!(LogContext::current().unit_test(), None);
+        drop(section);
+        assert_eq!(LogContext::current(), LogContext::default());
+    }
+
+    #[test]
+    fn should_be_local_to_the_thread() {
+        let _section = LogContext::enter_section("section 1");
+        std::thread::spawn(|| assert_eq!(LogContext::current(), LogContext::default()))
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn should_visit_only_the_defined_fields() {
+        let _section = LogContext::enter_section("section 1");
+        let _assertion = LogContext::enter_assertion("assertion");
+
+        assert_eq!(
+            pairs(&LogContext::current()),
+            BTreeMap::from([
+                ("assertion".to_string(), "assertion".to_string()),
+                ("section".to_string(), "section 1".to_string()),
+            ])
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, Mul};
+use std::{
+    fmt,
+    ops::{AddAssign, Mul},
+};
 
 /// The way that the score will be calculated.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum GradingMode {
     /// Score will be binary (0 or 1, true or false).
@@ -13,22 +17,105 @@ pub enum GradingMode {
 }
 
 /// The actual score. It mirrors the structure of `Mode`.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+///
+/// It is serialized as `{"absolute": true}` or `{"weighted": {"current": 2, "max": 5}}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
 pub enum Score {
     Absolute(bool),
     Weighted { current: u32, max: u32 },
 }
 
 impl Score {
-    /// Creates a default version for `Score` which represents the 0 in the chosen mode.
+    /// Creates a default version for `Score` which is the neutral element of the addition
+    /// in the chosen mode: nothing has failed yet in `Absolute` and nothing was scored in
+    /// `Weighted`.
     pub fn default(grading_mode: GradingMode) -> Self {
         match grading_mode {
-            GradingMode::Absolute => Self::Absolute(false),
+            GradingMode::Absolute => Self::Absolute(true),
             GradingMode::Weighted => Self::Weighted { current: 0, max: 0 },
         }
     }
 }
 
+impl Score {
+    /// Whether the score reaches `threshold`, a percentage of the maximum score.
+    ///
+    /// `Absolute` scores only meet it when they pass, unless the threshold is 0. A
+    /// `Weighted` score without any weight always meets it.
+    pub fn meets_threshold(&self, threshold: u32) -> bool {
+        match *self {
+            Score::Absolute(passed) => passed || threshold == 0,
+            Score::Weighted { current, max } => {
+                u64::from(current) * 100 >= u64::from(threshold) * u64::from(max)
+            }
+        }
+    }
+}
+
+impl Score {
+    /// Percentage of the maximum score reached, or `None` for `Absolute` scores and
+    /// `Weighted` scores without any weight.
+    pub fn percentage(&self) -> Option<f64> {
+        match *self {
+            Score::Absolute(_) | Score::Weighted { max: 0, .. } => None,
+            Score::Weighted { current, max } => Some(f64::from(current) * 100.0 / f64::from(max)),
+        }
+    }
+}
+
+impl Score {
+    /// Points reached. An `Absolute` score counts as `1` out of `1` when it passes and as
+    /// `0` out of `1` when it fails.
+    pub fn current(&self) -> u32 {
+        match *self {
+            Score::Absolute(passed) => u32::from(passed),
+            Score::Weighted { current, .. } => current,
+        }
+    }
+
+    /// Points that could be reached, which is always `1` for an `Absolute` score.
+    pub fn max(&self) -> u32 {
+        match *self {
+            Score::Absolute(_) => 1,
+            Score::Weighted { max, .. } => max,
+        }
+    }
+
+    /// Whether nothing was lost: the `Absolute` score passes, or the `Weighted` score
+    /// reaches its maximum, which a score without any weight always does.
+    pub fn is_pass(&self) -> bool {
+        self.current() == self.max()
+    }
+}
+
+impl Score {
+    /// Score as shown at the end of a report: `PASS`/`FAIL` for an `Absolute` score and
+    /// `<current> / <max> (<percentage>%)` for a `Weighted` one, without the percentage
+    /// when there is no weight, e.g. `0 / 0`.
+    pub fn summary(&self) -> String {
+        match (*self, self.percentage()) {
+            (Score::Absolute(_), _) => self.to_string(),
+            (Score::Weighted { current, max }, Some(percentage)) => {
+                format!("{current} / {max} ({percentage:.1}%)")
+            }
+            (Score::Weighted { current, max }, None) => format!("{current} / {max}"),
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    /// Absolute scores are shown as `PASS`/`FAIL` and weighted ones as `<current>/<max>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Absolute(true) => write!(f, "PASS"),
+            Score::Absolute(false) => write!(f, "FAIL"),
+            Score::Weighted { current, max } => write!(f, "{current}/{max}"),
+        }
+    }
+}
+
+/// `Weighted` scores saturate at `u32::MAX` instead of wrapping.
 impl AddAssign for Score {
     fn add_assign(&mut self, rhs: Self) {
         match (self, rhs) {
@@ -43,22 +130,23 @@ impl AddAssign for Score {
                     max: m2,
                 },
             ) => {
-                *c1 = *c1 + c2;
-                *m1 = *m1 + m2;
+                *c1 = c1.saturating_add(c2);
+                *m1 = m1.saturating_add(m2);
             }
             _ => panic!("unexpected addition between different scoring modes"),
         };
     }
 }
 
+/// `Weighted` scores saturate at `u32::MAX` instead of wrapping.
 impl Mul<u32> for Score {
     type Output = Score;
 
     fn mul(self, rhs: u32) -> Self::Output {
         match self {
             Score::Weighted { current: c, max: m } => Score::Weighted {
-                current: c * rhs,
-                max: m * rhs,
+                current: c.saturating_mul(rhs),
+                max: m.saturating_mul(rhs),
             },
             Score::Absolute(b) => Score::Absolute(b),
         }
@@ -73,6 +161,7 @@ mod tests {
         use super::*;
 
         #[test]
+        #[allow(clippy::erasing_op)]
         fn should_multiply_score_correctly() {
             // Absolute mode
             assert_eq!(Score::Absolute(false) * 23, Score::Absolute(false));
@@ -98,9 +187,148 @@ mod tests {
             );
         }
     }
+    mod percentage_tests {
+        use super::*;
+
+        #[test]
+        fn should_compute_the_percentage_of_weighted_scores() {
+            assert_eq!(
+                Score::Weighted { current: 2, max: 5 }.percentage(),
+                Some(40.0)
+            );
+            assert_eq!(
+                Score::Weighted { current: 7, max: 7 }.percentage(),
+                Some(100.0)
+            );
+        }
+
+        #[test]
+        fn should_not_compute_a_percentage_without_a_maximum() {
+            assert_eq!(Score::Weighted { current: 0, max: 0 }.percentage(), None);
+            assert_eq!(Score::Absolute(true).percentage(), None);
+        }
+    }
+    mod accessors_tests {
+        use super::*;
+
+        #[test]
+        fn should_expose_the_points_of_weighted_scores() {
+            let score = Score::Weighted { current: 2, max: 5 };
+            assert_eq!((score.current(), score.max()), (2, 5));
+            assert!(!score.is_pass());
+            assert!(Score::Weighted { current: 5, max: 5 }.is_pass());
+            assert!(Score::Weighted { current: 0, max: 0 }.is_pass());
+        }
+
+        #[test]
+        fn should_count_absolute_scores_as_one_point() {
+            let passed = Score::Absolute(true);
+            assert_eq!((passed.current(), passed.max()), (1, 1));
+            assert!(passed.is_pass());
+            let failed = Score::Absolute(false);
+            assert_eq!((failed.current(), failed.max()), (0, 1));
+            assert!(!failed.is_pass());
+        }
+    }
+    mod meets_threshold_tests {
+        use super::*;
+
+        #[test]
+        fn should_compare_weighted_score_with_percentage() {
+            let score = Score::Weighted { current: 1, max: 3 };
+            assert!(score.meets_threshold(0));
+            assert!(score.meets_threshold(33));
+            assert!(!score.meets_threshold(34));
+            assert!(!score.meets_threshold(100));
+            assert!(Score::Weighted { current: 3, max: 3 }.meets_threshold(100));
+            assert!(Score::Weighted { current: 0, max: 0 }.meets_threshold(100));
+        }
+
+        #[test]
+        fn should_meet_threshold_only_when_absolute_score_passes() {
+            assert!(Score::Absolute(true).meets_threshold(100));
+            assert!(Score::Absolute(true).meets_threshold(1));
+            assert!(!Score::Absolute(false).meets_threshold(1));
+            assert!(Score::Absolute(false).meets_threshold(0));
+        }
+    }
+    mod overflow_tests {
+        use super::*;
+
+        #[test]
+        fn should_saturate_instead_of_wrapping() {
+            let mut score = Score::Weighted {
+                current: u32::MAX - 1,
+                max: u32::MAX - 1,
+            };
+            score += Score::Weighted { current: 0, max: 2 };
+            assert_eq!(
+                score,
+                Score::Weighted {
+                    current: u32::MAX - 1,
+                    max: u32::MAX
+                }
+            );
+            assert_eq!(
+                Score::Weighted {
+                    current: 2,
+                    max: u32::MAX / 2 + 1
+                } * 2,
+                Score::Weighted {
+                    current: 4,
+                    max: u32::MAX
+                }
+            );
+        }
+    }
+    mod display_tests {
+        use super::*;
+
+        #[test]
+        fn should_display_score_correctly() {
+            assert_eq!(Score::Absolute(true).to_string(), "PASS");
+            assert_eq!(Score::Absolute(false).to_string(), "FAIL");
+            assert_eq!(
+                Score::Weighted {
+                    current: 3,
+                    max: 10
+                }
+                .to_string(),
+                "3/10"
+            );
+        }
+
+        #[test]
+        fn should_summarize_the_score_with_its_maximum() {
+            assert_eq!(Score::Absolute(true).summary(), "PASS");
+            assert_eq!(Score::Absolute(false).summary(), "FAIL");
+            assert_eq!(
+                Score::Weighted {
+                    current: 37,
+                    max: 50
+                }
+                .summary(),
+                "37 / 50 (74.0%)"
+            );
+            assert_eq!(Score::Weighted { current: 0, max: 0 }.summary(), "0 / 0");
+        }
+    }
     mod add_assign_tests {
         use super::*;
 
+        #[test]
+        fn should_start_from_the_neutral_element() {
+            let mut score = Score::default(GradingMode::Absolute);
+            score += Score::Absolute(true);
+            assert_eq!(score, Score::Absolute(true));
+            score += Score::Absolute(false);
+            assert_eq!(score, Score::Absolute(false));
+
+            let mut score = Score::default(GradingMode::Weighted);
+            score += Score::Weighted { current: 1, max: 2 };
+            assert_eq!(score, Score::Weighted { current: 1, max: 2 });
+        }
+
         #[test]
         #[should_panic]
         fn should_panic_when_adding_incompatible_modes() {
@@ -143,4 +371,32 @@ mod tests {
             );
         }
     }
+
+    mod serialization_tests {
+        use super::*;
+        use crate::config::test_macros::test_serialize_and_deserialize;
+
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_absolute,
+            Score::Absolute(false),
+            Score
+        );
+        test_serialize_and_deserialize!(
+            should_serialize_deserialize_weighted,
+            Score::Weighted { current: 2, max: 5 },
+            Score
+        );
+
+        #[test]
+        fn should_have_a_stable_representation() {
+            assert_eq!(
+                serde_json::to_string(&Score::Absolute(true)).unwrap(),
+                r#"{"absolute":true}"#
+            );
+            assert_eq!(
+                serde_json::to_string(&Score::Weighted { current: 2, max: 5 }).unwrap(),
+                r#"{"weighted":{"current":2,"max":5}}"#
+            );
+        }
+    }
 }
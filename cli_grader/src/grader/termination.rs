@@ -0,0 +1,118 @@
+//! Stopping programs that went past a time limit, e.g. the maximum duration of a
+//! performance test or the step timeout of an interaction.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, io, process::Child, time::Duration};
+
+/// How a program that went past its time limit was stopped.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Termination {
+    /// It exited by itself during the grace period, after being sent SIGTERM.
+    Terminated,
+    /// It was killed, with SIGKILL on Unix, either right away or after the grace period.
+    Killed,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Termination::Terminated => write!(f, "exited after SIGTERM"),
+            Termination::Killed => write!(f, "killed"),
+        }
+    }
+}
+
+/// Stops `child` and waits for it. It is `None` when the program had already exited.
+///
+/// On Unix, the program is first sent SIGTERM, so it can flush its output and clean up,
+/// and it is only killed with SIGKILL if it is still running after `grace`. Without a
+/// grace period, or on other platforms, it is killed right away.
+pub(crate) fn terminate(child: &mut Child, grace: Duration) -> io::Result<Option<Termination>> {
+    if child.try_wait()?.is_some() {
+        return Ok(None);
+    }
+    #[cfg(unix)]
+    if !grace.is_zero() && send_sigterm(child) && exits_within(child, grace)? {
+        return Ok(Some(Termination::Terminated));
+    }
+    child.kill()?;
+    child.wait()?;
+    Ok(Some(Termination::Killed))
+}
+
+/// Whether SIGTERM could be sent to `child`.
+#[cfg(unix)]
+fn send_sigterm(child: &Child) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(child.id()) else {
+        return false;
+    };
+    // SAFETY: `kill` does not touch any memory, and the child was not waited for yet, so
+    // its pid was not reused by another process
+    unsafe { libc::kill(pid, libc::SIGTERM) == 0 }
+}
+
+/// Whether `child` exits before `grace` is over, checking it every millisecond.
+#[cfg(unix)]
+fn exits_within(child: &mut Child, grace: Duration) -> io::Result<bool> {
+    let deadline = std::time::Instant::now() + grace;
+    while std::time::Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(true);
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{process::Command, thread, time::Instant};
+
+    fn spawn_sh(script: &str) -> Child {
+        Command::new("sh").args(["-c", script]).spawn().unwrap()
+    }
+
+    #[test]
+    fn should_kill_right_away_without_grace_period() {
+        let mut child = spawn_sh("trap 'exit 0' TERM; sleep 30 & wait");
+
+        let start = Instant::now();
+        let termination = terminate(&mut child, Duration::ZERO).unwrap();
+
+        assert_eq!(termination, Some(Termination::Killed));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn should_let_the_program_exit_during_the_grace_period() {
+        let mut child = spawn_sh("trap 'exit 0' TERM; sleep 30 & wait");
+        // give the shell the time to install the trap
+        thread::sleep(Duration::from_millis(100));
+
+        let termination = terminate(&mut child, Duration::from_secs(5)).unwrap();
+
+        assert_eq!(termination, Some(Termination::Terminated));
+    }
+
+    #[test]
+    fn should_kill_the_program_that_ignores_sigterm_after_the_grace_period() {
+        let mut child = spawn_sh("trap '' TERM; sleep 30 & wait");
+        thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        let termination = terminate(&mut child, Duration::from_millis(200)).unwrap();
+
+        assert_eq!(termination, Some(Termination::Killed));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn should_not_stop_a_program_that_already_exited() {
+        let mut child = spawn_sh("exit 0");
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(terminate(&mut child, Duration::ZERO).unwrap(), None);
+    }
+}
@@ -5,7 +5,12 @@
 //! will be implemented here.
 
 use is_executable::is_executable;
-use std::{fmt::Debug, path::PathBuf, process::Command};
+use std::{
+    env,
+    fmt::Debug,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 /// This is the common interface to represent anything that may is executable and thus
 /// testable by this framework. The executable is able to generate a
@@ -19,7 +24,15 @@ use std::{fmt::Debug, path::PathBuf, process::Command};
 ///   other programming languages' source code.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum ExecutableArtifact {
-    CompiledProgram { name: String, path: PathBuf },
+    CompiledProgram {
+        name: String,
+        path: PathBuf,
+        /// Arguments passed before the ones of each test, e.g. interpreter flags.
+        base_args: Vec<String>,
+    },
+    /// Program declared in the configuration whose path was not provided, e.g. a student
+    /// that did not submit it. It can not be executed, so every test using it fails.
+    NotSubmitted { name: String },
     // PythonProgram
     // JavascriptProgram
 }
@@ -28,33 +41,93 @@ pub enum ProgramType {
     Compiled,
 }
 
+/// How the path given for a program is turned into the one that is executed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PathResolution<'a> {
+    /// A bare name, i.e. without a path separator, is looked up on `PATH`, as `Command`
+    /// does, and any other path is relative to the current directory.
+    Auto,
+    /// The path must be a bare name, which is looked up on `PATH`.
+    Path,
+    /// The path is relative to this directory, usually the one of the configuration file.
+    RelativeTo(&'a Path),
+    /// The path is relative to the current directory, even if it is a bare name.
+    Cwd,
+}
+
+impl PathResolution<'_> {
+    /// Path to execute for `path`, which is only checked to be a bare name with `Path`.
+    ///
+    /// The paths that are not looked up on `PATH` are made absolute, as the programs are
+    /// run from the temporary directory of each test, not from the current one.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf, &'static str> {
+        match self {
+            PathResolution::Auto | PathResolution::Path if is_bare_name(path) => {
+                Ok(path.to_path_buf())
+            }
+            PathResolution::Auto | PathResolution::Cwd => absolute(path),
+            PathResolution::Path => Err("program looked up on PATH must be a bare name"),
+            PathResolution::RelativeTo(directory) => absolute(&directory.join(path)),
+        }
+    }
+}
+
 impl ExecutableArtifact {
     pub fn build(
         name: String,
         path: PathBuf,
         program_type: ProgramType,
+        resolution: PathResolution,
     ) -> Result<Self, &'static str> {
+        let path = resolution.resolve(&path)?;
         match program_type {
             ProgramType::Compiled => {
-                // try to read it as file
-                if !is_executable(&path) {
-                    return Err("path does not point to an executable");
+                // a bare name left by `resolve` is looked up on `PATH`, as `Command` does
+                let is_found = if is_bare_name(&path) {
+                    is_executable_on_path(&path)
+                } else {
+                    is_executable(&path)
+                };
+                if !is_found {
+                    return Err("program not executable");
                 }
 
-                Ok(ExecutableArtifact::CompiledProgram { name, path })
+                Ok(ExecutableArtifact::CompiledProgram {
+                    name,
+                    path,
+                    base_args: vec![],
+                })
             }
         }
     }
 
-    pub fn new_cmd(&self) -> Command {
+    pub fn with_base_args(mut self, args: Vec<String>) -> Self {
+        match &mut self {
+            ExecutableArtifact::CompiledProgram { base_args, .. } => *base_args = args,
+            ExecutableArtifact::NotSubmitted { .. } => {}
+        }
+        self
+    }
+
+    /// Creates the command with the `base_args` already applied, so the arguments of each
+    /// test can be appended to it. It is `None` when the program was not submitted.
+    pub fn new_cmd(&self) -> Option<Command> {
         match self {
-            ExecutableArtifact::CompiledProgram { path, .. } => Command::new(path),
+            ExecutableArtifact::CompiledProgram {
+                path, base_args, ..
+            } => {
+                let mut cmd = Command::new(path);
+                cmd.args(base_args);
+                Some(cmd)
+            }
+            ExecutableArtifact::NotSubmitted { .. } => None,
         }
     }
 
     pub fn name(&self) -> String {
         match self {
-            ExecutableArtifact::CompiledProgram { name, .. } => name.to_string(),
+            ExecutableArtifact::CompiledProgram { name, .. }
+            | ExecutableArtifact::NotSubmitted { name } => name.to_string(),
         }
     }
 
@@ -67,10 +140,37 @@ impl ExecutableArtifact {
         Self::CompiledProgram {
             name: format!("program{n}"),
             path: PathBuf::from(path),
+            base_args: vec![],
         }
     }
 }
 
+/// Whether `path` has no path separator, e.g. `python3`, so `Command` looks it up on
+/// `PATH`.
+fn is_bare_name(path: &Path) -> bool {
+    path.parent() == Some(Path::new(""))
+}
+
+/// Makes `path` absolute against the current directory, without resolving the symbolic
+/// links, e.g. `./prog` becomes `/home/user/prog`.
+fn absolute(path: &Path) -> Result<PathBuf, &'static str> {
+    std::path::absolute(path).map_err(|err| {
+        log::debug!("error: {err:?}");
+        "could not resolve the path of the program"
+    })
+}
+
+/// Whether `path` is the bare name of an executable in one of the directories of the
+/// `PATH` environment variable, e.g. `python3`, which is how `Command` resolves it.
+fn is_executable_on_path(path: &Path) -> bool {
+    if !is_bare_name(path) {
+        return false;
+    }
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|directory| is_executable(directory.join(path)))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,7 +180,197 @@ mod tests {
     #[test]
     fn should_build_a_valid_executable() {
         let path = utils::create_dummy_executable();
-        ExecutableArtifact::build("some name".to_string(), path, ProgramType::Compiled).unwrap();
+        ExecutableArtifact::build(
+            "some name".to_string(),
+            path,
+            ProgramType::Compiled,
+            PathResolution::Auto,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_keep_non_utf8_paths_untouched() {
+        use std::{ffi::OsStr, fs, os::unix::ffi::OsStrExt, os::unix::fs::PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir
+            .path()
+            .join(OsStr::from_bytes(b"my program \xff\xfe (v2) #1.sh"));
+        fs::write(&path, "#!/bin/sh\necho ok\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let executable = ExecutableArtifact::build(
+            "program1".to_string(),
+            path.clone(),
+            ProgramType::Compiled,
+            PathResolution::Auto,
+        )
+        .unwrap();
+
+        assert_eq!(
+            executable.new_cmd().unwrap().get_program(),
+            path.as_os_str()
+        );
+        assert_eq!(
+            executable.new_cmd().unwrap().output().unwrap().stdout,
+            b"ok\n"
+        );
+    }
+
+    #[test]
+    fn should_apply_base_args_before_test_args() {
+        let executable = ExecutableArtifact::build(
+            "program1".to_string(),
+            "/bin/echo".into(),
+            ProgramType::Compiled,
+            PathResolution::Auto,
+        )
+        .unwrap()
+        .with_base_args(vec!["-n".to_string(), "base".to_string()]);
+
+        let mut cmd = executable.new_cmd().unwrap();
+        cmd.arg("test");
+
+        assert_eq!(cmd.output().unwrap().stdout, b"base test");
+    }
+
+    #[test]
+    fn should_build_an_executable_found_on_path() {
+        ExecutableArtifact::build(
+            "program1".to_string(),
+            "sh".into(),
+            ProgramType::Compiled,
+            PathResolution::Auto,
+        )
+        .unwrap();
+        assert_eq!(
+            ExecutableArtifact::build(
+                "program1".to_string(),
+                "surely-not-a-program-on-path".into(),
+                ProgramType::Compiled,
+                PathResolution::Auto
+            ),
+            Err("program not executable")
+        );
+    }
+    #[test]
+    fn should_only_look_up_programs_on_path_when_asked() {
+        let build = |path: &str| {
+            ExecutableArtifact::build(
+                "program1".to_string(),
+                path.into(),
+                ProgramType::Compiled,
+                PathResolution::Path,
+            )
+        };
+        let executable = build("sh").unwrap();
+        assert_eq!(executable.new_cmd().unwrap().get_program(), "sh");
+        assert_eq!(
+            build("/bin/sh"),
+            Err("program looked up on PATH must be a bare name")
+        );
+        assert_eq!(
+            build("surely-not-a-program-on-path"),
+            Err("program not executable")
+        );
+    }
+
+    #[test]
+    fn should_resolve_programs_relative_to_a_directory_when_asked() {
+        let path = utils::create_dummy_executable();
+        let (directory, file_name) = (path.parent().unwrap(), path.file_name().unwrap());
+
+        let executable = ExecutableArtifact::build(
+            "program1".to_string(),
+            file_name.into(),
+            ProgramType::Compiled,
+            PathResolution::RelativeTo(directory),
+        )
+        .unwrap();
+        assert_eq!(
+            executable.new_cmd().unwrap().get_program(),
+            path.as_os_str()
+        );
+
+        // `sh` is on PATH, but not in the directory
+        assert_eq!(
+            ExecutableArtifact::build(
+                "program1".to_string(),
+                "sh".into(),
+                ProgramType::Compiled,
+                PathResolution::RelativeTo(directory),
+            ),
+            Err("program not executable")
+        );
+    }
+
+    #[test]
+    fn should_resolve_bare_names_relative_to_the_cwd_when_asked() {
+        // `sh` is on PATH, but it is not a file of the current directory
+        assert_eq!(
+            ExecutableArtifact::build(
+                "program1".to_string(),
+                "sh".into(),
+                ProgramType::Compiled,
+                PathResolution::Cwd,
+            ),
+            Err("program not executable")
+        );
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(
+            PathResolution::Cwd.resolve(Path::new("prog")),
+            Ok(cwd.join("prog"))
+        );
+        assert_eq!(
+            PathResolution::Cwd.resolve(Path::new("bin/prog")),
+            Ok(cwd.join("bin/prog"))
+        );
+        assert_eq!(
+            PathResolution::RelativeTo(Path::new("")).resolve(Path::new("prog")),
+            Ok(cwd.join("prog"))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_run_a_relative_program_from_another_directory_in_every_mode() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prog.sh");
+        fs::write(&path, "#!/bin/sh\necho ok\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        // the same file, but relative to the current directory
+        let relative_path = env::current_dir()
+            .unwrap()
+            .components()
+            .skip(1)
+            .fold(PathBuf::new(), |relative_path, _| relative_path.join(".."))
+            .join(path.strip_prefix("/").unwrap());
+        let other_dir = tempfile::tempdir().unwrap();
+
+        for (path, resolution) in [
+            (relative_path.as_path(), PathResolution::Auto),
+            (relative_path.as_path(), PathResolution::Cwd),
+            (Path::new("prog.sh"), PathResolution::RelativeTo(dir.path())),
+        ] {
+            let executable = ExecutableArtifact::build(
+                "program1".to_string(),
+                path.to_path_buf(),
+                ProgramType::Compiled,
+                resolution,
+            )
+            .unwrap();
+            let output = executable
+                .new_cmd()
+                .unwrap()
+                .current_dir(other_dir.path())
+                .output()
+                .unwrap();
+            assert_eq!(output.stdout, b"ok\n", "{resolution:?}");
+        }
     }
 
     #[test]
@@ -90,7 +380,19 @@ mod tests {
             "some name".to_string(),
             PathBuf::from_str("invalid_path").unwrap(),
             ProgramType::Compiled,
+            PathResolution::Auto,
         )
         .unwrap();
     }
+
+    #[test]
+    fn should_not_create_a_command_for_a_program_not_submitted() {
+        let executable = ExecutableArtifact::NotSubmitted {
+            name: "program2".to_string(),
+        }
+        .with_base_args(vec!["-n".to_string()]);
+
+        assert!(executable.new_cmd().is_none());
+        assert_eq!(executable.name(), "program2");
+    }
 }
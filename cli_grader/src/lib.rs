@@ -8,16 +8,53 @@ mod input;
 mod report;
 pub(crate) mod utils;
 
+pub use config::manifest::{Manifest, ManifestEntry};
+pub use config::report_section::{ReportSection, ScoreBand, ScoreColors};
+pub use config::{GlobalConfig, Initialized, NotInitialized};
+pub use grader::BatchResult;
+pub use grader::CombinedGradingResult;
 pub use grader::Grader;
 pub use grader::GradingConfig;
 pub use grader::GradingResult;
+pub use grader::GradingTestSectionResult;
+pub use grader::builder::{GradingConfigBuilder, SectionBuilder, UnitTestBuilder};
+pub use grader::diff::{AssertionPath, ResultDiff};
+pub use grader::error::GraderError;
+pub use grader::grading_tests::GradindTestsResult;
+pub use grader::grading_tests::performance_test::{
+    DurationStatistics, PerformanceTestResult, PerformanceTestsResult,
+};
+pub use grader::grading_tests::unit_test::assertion::{
+    AnyOfResult, Assertion, AssertionResult, EmptyOutputResult, ExecutionStatus,
+    ExpectedObtainedResult, ForbiddenOutputResult, UnequalStreamsResult,
+};
+pub use grader::grading_tests::unit_test::extraction::{
+    Extracted, ExtractionResult, StdoutExtract,
+};
+pub use grader::grading_tests::unit_test::interaction::InteractionFailure;
+pub use grader::grading_tests::unit_test::regex_checks::{
+    Comparison, FailedGroupCheck, GroupCheck, RegexChecksOutcome, RegexChecksResult,
+    StdoutRegexChecks,
+};
+pub use grader::grading_tests::unit_test::{UnitTestResult, UnitTestsResult};
+pub use grader::log_context::LogContext;
 pub use grader::score::GradingMode;
+pub use grader::score::Score;
+pub use report::ReportOutput;
+pub use report::Reporter;
+pub use report::diff::write_diff;
+pub use report::explain::write_explanation;
+pub use report::score::ScoreReporter;
+pub use report::text::TextReporter;
+pub use report::tree::write_test_tree;
+pub use report::truncate_output;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
-enum LoggingMode {
+pub enum LoggingMode {
     Silent,
     #[default]
     Normal,
@@ -33,7 +70,7 @@ pub fn add(left: u64, right: u64) -> u64 {
         GradingMode::Weighted,
     );
     let grader = Grader::new(&conf);
-    grader.run();
+    grader.run().unwrap();
     left + right
 }
 
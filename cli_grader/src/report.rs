@@ -1,9 +1,103 @@
+use crate::grader::{GradingResult, GradingTestSectionResult};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::io;
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub mod diff;
+pub mod explain;
+pub mod score;
+pub mod text;
+pub mod tree;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ReportOutput {
     Txt,
     #[default]
     Stdout,
 }
+
+/// Lines kept from the start of a captured output by default, see `truncate_output`.
+pub const DEFAULT_OUTPUT_HEAD_LINES: usize = 10;
+/// Lines kept from the end of a captured output by default, see `truncate_output`.
+pub const DEFAULT_OUTPUT_TAIL_LINES: usize = 10;
+
+/// Keeps only the first `head` and the last `tail` lines of `output`, replacing the ones in
+/// between with a `... (M lines omitted) ...` line. It is used for the output of setup and
+/// teardown commands, which may flood the report, while the relevant error is usually at
+/// the start or at the end.
+pub fn truncate_output(output: &str, head: usize, tail: usize) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() <= head.saturating_add(tail) {
+        return output.to_string();
+    }
+    let omitted = lines.len() - head - tail;
+    let marker = format!("... ({omitted} lines omitted) ...");
+    let mut truncated = lines[..head]
+        .iter()
+        .copied()
+        .chain([marker.as_str()])
+        .chain(lines[lines.len() - tail..].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if output.ends_with('\n') {
+        truncated.push('\n');
+    }
+    truncated
+}
+
+/// Receives the results of a grading run as soon as they are produced, allowing the
+/// report to be written incrementally instead of waiting for the whole `GradingResult`.
+pub trait Reporter {
+    /// Called once, before the first section, with a result that only has the details of
+    /// the run, such as its title and when it started.
+    fn on_grading_start(&mut self, _result: &GradingResult) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called every time a section finishes running.
+    fn on_section_complete(&mut self, section_result: &GradingTestSectionResult) -> io::Result<()>;
+
+    /// Called once, after the last section, with the aggregated result.
+    fn on_grading_complete(&mut self, result: &GradingResult) -> io::Result<()>;
+}
+
+/// Reporter that ignores every notification.
+pub(crate) struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn on_section_complete(&mut self, _: &GradingTestSectionResult) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn on_grading_complete(&mut self, _: &GradingResult) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_not_truncate_short_outputs() {
+        assert_eq!(truncate_output("a\nb\nc\n", 1, 2), "a\nb\nc\n");
+        assert_eq!(truncate_output("", 0, 0), "");
+    }
+
+    #[test]
+    fn should_keep_first_and_last_lines() {
+        assert_eq!(
+            truncate_output("1\n2\n3\n4\n5\n6\n", 2, 1),
+            "1\n2\n... (3 lines omitted) ...\n6\n"
+        );
+        assert_eq!(
+            truncate_output("1\n2\n3", 0, 1),
+            "... (2 lines omitted) ...\n3"
+        );
+        assert_eq!(
+            truncate_output("1\n2\n3", 1, 0),
+            "1\n... (2 lines omitted) ..."
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Plain text description of how the assertions changed between two runs.
+
+use crate::grader::diff::{AssertionPath, ResultDiff};
+use std::io::{self, Write};
+
+/// Writes the change of the score followed by the assertions of each kind of change,
+/// omitting the kinds without any assertion.
+pub fn write_diff<W: Write>(writer: &mut W, diff: &ResultDiff) -> io::Result<()> {
+    writeln!(writer, "Score: {} → {}", diff.old_score(), diff.new_score())?;
+    if diff.is_empty() {
+        return writeln!(writer, "No assertion changed");
+    }
+    write_paths(writer, "Newly passed", '+', diff.newly_passed())?;
+    write_paths(writer, "Newly failed", '-', diff.newly_failed())?;
+    write_paths(
+        writer,
+        "Changed diagnostics",
+        '~',
+        diff.changed_diagnostics(),
+    )?;
+    write_paths(writer, "Added", '+', diff.added())?;
+    write_paths(writer, "Removed", '-', diff.removed())?;
+    Ok(())
+}
+
+fn write_paths<W: Write>(
+    writer: &mut W,
+    title: &str,
+    marker: char,
+    paths: &[AssertionPath],
+) -> io::Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    writeln!(writer, "{title} ({}):", paths.len())?;
+    for path in paths {
+        writeln!(writer, "  {marker} {path}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grader::{
+        Grader, GradingConfig, GradingResult,
+        builder::{SectionBuilder, UnitTestBuilder},
+        grading_tests::unit_test::assertion::Assertion,
+    };
+
+    fn echo_result(expected_stdouts: &[&str]) -> GradingResult {
+        let unit_test = expected_stdouts.iter().enumerate().fold(
+            UnitTestBuilder::new("echo args", "echo"),
+            |unit_test, (i, stdout)| {
+                unit_test.assertion(
+                    Assertion::new(format!("test {i}"), vec!["hello".to_string()], None, 1)
+                        .with_stdout(stdout.to_string()),
+                )
+            },
+        );
+        let config = GradingConfig::builder("echo")
+            .section(SectionBuilder::new("section 1").unit_test(unit_test))
+            .build()
+            .unwrap();
+        Grader::new(&config).run().unwrap()
+    }
+
+    fn diff_report(old: &GradingResult, new: &GradingResult) -> String {
+        let mut report = vec![];
+        write_diff(&mut report, &old.diff(new)).unwrap();
+        String::from_utf8(report).unwrap()
+    }
+
+    #[test]
+    fn should_report_only_the_kinds_of_changes_that_happened() {
+        let old = echo_result(&["hello\n", "bye\n", "hello\n"]);
+        let new = echo_result(&["bye\n", "hello\n"]);
+
+        assert_eq!(
+            diff_report(&old, &new),
+            "Score: 2/3 → 1/2\n\
+             Newly passed (1):\n\
+             \x20 + section 1 / echo args / test 1\n\
+             Newly failed (1):\n\
+             \x20 - section 1 / echo args / test 0\n\
+             Removed (1):\n\
+             \x20 - section 1 / echo args / test 2\n"
+        );
+    }
+
+    #[test]
+    fn should_report_when_nothing_changed() {
+        let result = echo_result(&["hello\n"]);
+
+        assert_eq!(
+            diff_report(&result, &result),
+            "Score: 1/1 → 1/1\nNo assertion changed\n"
+        );
+    }
+}
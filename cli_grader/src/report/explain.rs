@@ -0,0 +1,353 @@
+//! Plain text description of what each test checks, written without running anything.
+
+use crate::grader::{
+    GradingConfig, GradingTestSection,
+    grading_tests::{
+        GradingTests,
+        performance_test::PerformanceTest,
+        unit_test::{
+            UnitTest,
+            assertion::{Assertion, StdoutMatch},
+        },
+    },
+};
+use std::io::{self, Write};
+
+/// Describes every section, unit test and assertion of `config`, including the expected
+/// values and weights, so students and authors know exactly how the programs are graded.
+pub fn write_explanation<W: Write>(writer: &mut W, config: &GradingConfig) -> io::Result<()> {
+    match config.author() {
+        Some(author) => writeln!(writer, "{} (by {author})", config.name())?,
+        None => writeln!(writer, "{}", config.name())?,
+    }
+    writeln!(writer, "Mode: {:?}", config.grading_mode())?;
+    for section in config.grading_sections() {
+        write_section(writer, section)?;
+    }
+    Ok(())
+}
+
+fn write_section<W: Write>(writer: &mut W, section: &GradingTestSection) -> io::Result<()> {
+    write!(writer, "{} (weight: {})", section.name(), section.weight())?;
+    if section.is_extra_credit() {
+        write!(writer, " (extra credit)")?;
+    }
+    writeln!(writer)?;
+    match section.tests() {
+        GradingTests::UnitTests(unit_tests) => {
+            for unit_test in unit_tests.unit_tests() {
+                write_unit_test(writer, unit_test)?;
+            }
+        }
+        GradingTests::PerformanceTests(performance_tests) => {
+            for performance_test in performance_tests.performance_tests() {
+                write_performance_test(writer, performance_test)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_unit_test<W: Write>(writer: &mut W, unit_test: &UnitTest) -> io::Result<()> {
+    write!(
+        writer,
+        "  {} ({})",
+        unit_test.name(),
+        unit_test.executable_name()
+    )?;
+    if let Some(weight) = unit_test.weight() {
+        write!(writer, " (weight: {weight}, split between the assertions)")?;
+    }
+    writeln!(writer)?;
+    for assertion in unit_test.assertions() {
+        write_assertion(writer, assertion)?;
+    }
+    Ok(())
+}
+
+fn write_assertion<W: Write>(writer: &mut W, assertion: &Assertion) -> io::Result<()> {
+    writeln!(
+        writer,
+        "    {} (weight: {})",
+        assertion.name(),
+        assertion.weight()
+    )?;
+    writeln!(writer, "      args: {:?}", assertion.args())?;
+    if let Some(cwd) = assertion.cwd() {
+        writeln!(writer, "      cwd: {}", cwd.display())?;
+    }
+    if let Some(stdin) = assertion.stdin() {
+        match str::from_utf8(stdin) {
+            Ok(stdin) => writeln!(writer, "      stdin: {stdin:?}")?,
+            Err(_) => writeln!(writer, "      stdin: {} bytes", stdin.len())?,
+        }
+    }
+    if let Some(interaction) = assertion.interaction() {
+        writeln!(writer, "      interaction:")?;
+        for step in interaction.steps() {
+            writeln!(
+                writer,
+                "        wait for {:?} and send {:?}",
+                step.wait_for(),
+                step.send()
+            )?;
+        }
+    }
+    if let Some(stdout) = assertion.stdout() {
+        writeln!(writer, "      stdout must be: {stdout:?}")?;
+    }
+    if !assertion.stdout_any_of().is_empty() {
+        writeln!(writer, "      stdout must be one of:")?;
+        for acceptable in assertion.stdout_any_of() {
+            writeln!(writer, "        {acceptable:?}")?;
+        }
+    }
+    if let Some(stderr) = assertion.stderr() {
+        writeln!(writer, "      stderr must be: {stderr:?}")?;
+    }
+    if let Some(status) = assertion.status() {
+        writeln!(writer, "      status must be: {status}")?;
+    }
+    if let Some(forbidden) = assertion.stdout_not_contains() {
+        writeln!(writer, "      stdout must not contain: {forbidden:?}")?;
+    }
+    if let Some(forbidden) = assertion.stderr_not_contains() {
+        writeln!(writer, "      stderr must not contain: {forbidden:?}")?;
+    }
+    if let Some(line_count) = assertion.stdout_line_count() {
+        writeln!(writer, "      stdout must have {line_count} line(s)")?;
+    }
+    if let Some(bytes) = assertion.stdout_exact_bytes() {
+        writeln!(writer, "      stdout must have {bytes} byte(s)")?;
+    }
+    if let Some(max_bytes) = assertion.stdout_max_bytes() {
+        writeln!(writer, "      stdout must have at most {max_bytes} byte(s)")?;
+    }
+    match assertion.stdout_non_empty() {
+        Some(true) => writeln!(writer, "      stdout must not be empty")?,
+        Some(false) => writeln!(writer, "      stdout must be empty")?,
+        None => {}
+    }
+    match assertion.stderr_empty() {
+        Some(true) => writeln!(writer, "      stderr must be empty")?,
+        Some(false) => writeln!(writer, "      stderr must not be empty")?,
+        None => {}
+    }
+    if assertion.stdout_equals_stderr() {
+        writeln!(writer, "      stdout must be equal to stderr")?;
+    }
+    if let Some(extract) = assertion.stdout_extract() {
+        writeln!(
+            writer,
+            "      stdout must have a number >= {} captured by {:?}",
+            extract.min(),
+            extract.pattern()
+        )?;
+    }
+    if let Some(regex_checks) = assertion.stdout_regex_checks() {
+        let checks: Vec<String> = regex_checks
+            .checks()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        writeln!(
+            writer,
+            "      stdout must match {:?} with: {}",
+            regex_checks.pattern(),
+            checks.join(", ")
+        )?;
+    }
+    if assertion.strips_ansi() {
+        writeln!(writer, "      ANSI escape codes are ignored")?;
+    }
+    match assertion.stdout_match() {
+        StdoutMatch::Exact => {}
+        StdoutMatch::TrimLineEnds => writeln!(
+            writer,
+            "      whitespace at the end of the stdout lines is ignored"
+        )?,
+        StdoutMatch::TrimEnd => writeln!(
+            writer,
+            "      whitespace at the end of the stdout is ignored"
+        )?,
+        StdoutMatch::CollapseWhitespace => writeln!(
+            writer,
+            "      stdout is compared word by word, ignoring the whitespace"
+        )?,
+    }
+    if let Some((program, args)) = assertion.normalize_with() {
+        writeln!(
+            writer,
+            "      stdout is normalized with: {program:?} {args:?}"
+        )?;
+    }
+    if !assertion.tags().is_empty() {
+        writeln!(writer, "      tags: {}", assertion.tags().join(", "))?;
+    }
+    Ok(())
+}
+
+fn write_performance_test<W: Write>(
+    writer: &mut W,
+    performance_test: &PerformanceTest,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "  {} ({}) (weight: {})",
+        performance_test.name(),
+        performance_test.executable_name(),
+        performance_test.weight()
+    )?;
+    writeln!(writer, "      args: {:?}", performance_test.args())?;
+    if let Some(stdin) = performance_test.stdin() {
+        writeln!(writer, "      stdin: {stdin:?}")?;
+    }
+    writeln!(
+        writer,
+        "      must finish successfully in less than {:?}",
+        performance_test.max_duration()
+    )?;
+    if let Some(min_duration) = performance_test.min_duration() {
+        writeln!(writer, "      must take at least {min_duration:?}")?;
+    }
+    if performance_test.warmup() > 0 {
+        writeln!(
+            writer,
+            "      runs {} time(s) before measuring",
+            performance_test.warmup()
+        )?;
+    }
+    if performance_test.repeat() > 1 {
+        writeln!(
+            writer,
+            "      runs {} times, graded by the mean duration",
+            performance_test.repeat()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grader::{
+            GradingConfig, GradingTestSection,
+            grading_tests::{
+                GradingTests,
+                performance_test::{PerformanceTest, PerformanceTests},
+                unit_test::{UnitTest, UnitTests, assertion::Assertion, extraction::StdoutExtract},
+            },
+            score::GradingMode,
+        },
+        input::ExecutableArtifact,
+    };
+    use std::time::Duration;
+
+    fn executable() -> ExecutableArtifact {
+        ExecutableArtifact::CompiledProgram {
+            name: "program1".to_string(),
+            path: "____not_executed".into(),
+            base_args: vec![],
+        }
+    }
+
+    #[test]
+    fn should_describe_every_test_without_running_it() {
+        let mut config = GradingConfig::new(
+            "echo".to_string(),
+            Some("author".to_string()),
+            GradingMode::Weighted,
+        );
+        let unit_test = UnitTest::new("echo args".to_string(), executable()).with_assertion(
+            Assertion::build(
+                "should echo".to_string(),
+                vec!["two words".to_string()],
+                Some("in".to_string()),
+                Some("two words\n".to_string()),
+                None,
+                Some(0),
+                2,
+            )
+            .unwrap()
+            .with_cwd("src".into())
+            .with_stdout_not_contains("DEBUG".to_string())
+            .with_stdout_non_empty(true)
+            .with_stderr_empty(true)
+            .with_stdout_equals_stderr(true)
+            .with_stdout_any_of(vec!["two words\n".to_string(), "two  words\n".to_string()])
+            .with_stdout_extract(StdoutExtract::build(r"words: (\d+)", 2.0).unwrap())
+            .with_strip_ansi(true)
+            .with_stdout_match(StdoutMatch::TrimLineEnds)
+            .with_normalize_with(("tr".to_string(), vec!["-s".to_string(), " ".to_string()]))
+            .with_tags(vec!["basic".to_string(), "io".to_string()]),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            3,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.add_grading_section(GradingTestSection::new(
+            "section 2".to_string(),
+            1,
+            GradingTests::PerformanceTests(PerformanceTests::new(vec![
+                PerformanceTest::build(
+                    "fast".to_string(),
+                    executable(),
+                    vec![],
+                    None,
+                    Duration::from_secs(1),
+                    Some(Duration::from_millis(10)),
+                    4,
+                )
+                .unwrap()
+                .with_repeat(5)
+                .unwrap()
+                .with_warmup(2),
+            ])),
+        ));
+        let mut writer = vec![];
+
+        write_explanation(&mut writer, &config).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "echo (by author)\n\
+             Mode: Weighted\n\
+             section 1 (weight: 3)\n\
+             \x20 echo args (program1)\n\
+             \x20   should echo (weight: 2)\n\
+             \x20     args: [\"two words\"]\n\
+             \x20     cwd: src\n\
+             \x20     stdin: \"in\"\n\
+             \x20     stdout must be: \"two words\\n\"\n\
+             \x20     stdout must be one of:\n\
+             \x20       \"two words\\n\"\n\
+             \x20       \"two  words\\n\"\n\
+             \x20     status must be: 0\n\
+             \x20     stdout must not contain: \"DEBUG\"\n\
+             \x20     stdout must not be empty\n\
+             \x20     stderr must be empty\n\
+             \x20     stdout must be equal to stderr\n\
+             \x20     stdout must have a number >= 2 captured by \"words: (\\\\d+)\"\n\
+             \x20     ANSI escape codes are ignored\n\
+             \x20     whitespace at the end of the stdout lines is ignored\n\
+             \x20     stdout is normalized with: \"tr\" [\"-s\", \" \"]\n\
+             \x20     tags: basic, io\n\
+             section 2 (weight: 1)\n\
+             \x20 fast (program1) (weight: 4)\n\
+             \x20     args: []\n\
+             \x20     must finish successfully in less than 1s\n\
+             \x20     must take at least 10ms\n\
+             \x20     runs 2 time(s) before measuring\n\
+             \x20     runs 5 times, graded by the mean duration\n"
+        );
+    }
+}
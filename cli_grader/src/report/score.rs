@@ -0,0 +1,70 @@
+//! Single line report with only the final score, e.g. to capture it in a shell script.
+
+use crate::{
+    grader::{GradingResult, GradingTestSectionResult},
+    report::Reporter,
+};
+use std::io::{self, Write};
+
+/// Writes only the final score, as the `Final score` line of the text report, e.g.
+/// `7 / 10 (70.0%)` or `PASS`, once the grading completes.
+pub struct ScoreReporter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> ScoreReporter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> Reporter for ScoreReporter<W> {
+    fn on_section_complete(
+        &mut self,
+        _section_result: &GradingTestSectionResult,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn on_grading_complete(&mut self, result: &GradingResult) -> io::Result<()> {
+        writeln!(self.writer, "{}", result.score_line())?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grader::{
+        Grader, GradingConfig,
+        builder::{SectionBuilder, UnitTestBuilder},
+        grading_tests::unit_test::assertion::Assertion,
+    };
+
+    #[test]
+    fn should_write_only_the_final_score() {
+        let config = GradingConfig::builder("echo")
+            .section(
+                SectionBuilder::new("section 1").unit_test(
+                    UnitTestBuilder::new("echo args", "echo")
+                        .assertion(
+                            Assertion::new("a".to_string(), vec!["hi".to_string()], None, 2)
+                                .with_stdout("hi\n".to_string()),
+                        )
+                        .assertion(
+                            Assertion::new("b".to_string(), vec!["hi".to_string()], None, 1)
+                                .with_stdout("bye\n".to_string()),
+                        ),
+                ),
+            )
+            .build()
+            .unwrap();
+        let mut report = vec![];
+
+        Grader::new(&config)
+            .run_with_reporter(&mut ScoreReporter::new(&mut report))
+            .unwrap();
+
+        assert_eq!(String::from_utf8(report).unwrap(), "2 / 3 (66.7%)\n");
+    }
+}
@@ -0,0 +1,1791 @@
+//! Plain text report, written section by section while the grading is running.
+
+use crate::{
+    config::report_section::{ScoreBand, ScoreColors},
+    grader::{
+        GradingResult, GradingTestSectionResult,
+        grading_tests::{
+            GradindTestsResult,
+            performance_test::PerformanceTestResult,
+            unit_test::{
+                UnitTestResult,
+                assertion::{
+                    AnyOfResult, AssertionResult, EmptyOutputResult, ExecutionStatus,
+                    ExpectedObtainedResult, ExpectedStatus, ForbiddenOutputResult, StatusResult,
+                    UnequalStreamsResult,
+                },
+                extraction::{Extracted, ExtractionResult},
+                interaction::InteractionFailure,
+                regex_checks::{RegexChecksOutcome, RegexChecksResult},
+            },
+        },
+        score::Score,
+    },
+    report::Reporter,
+};
+use std::{collections::BTreeMap, fmt::Debug, io::Write, time::Duration};
+
+pub struct TextReporter<W: Write> {
+    writer: W,
+    /// Whether the diagnostics of the failed assertions will be shown.
+    is_verbose: bool,
+    /// Whether the measured durations of sections and assertions will be shown.
+    shows_durations: bool,
+    /// Whether only the final summary will be shown, omitting every section.
+    shows_only_summary: bool,
+    /// Whether the final summary will have the score of each assertion tag.
+    groups_by_tag: bool,
+    /// Whether the report starts with the title, the author and when the run started.
+    shows_header: bool,
+    /// Whether the failed assertions of a unit test whose failures read the same are
+    /// shown together, see `write_unit_test_result`.
+    groups_failures: bool,
+    /// Bands used to color the final score line. It is not colored when `None`.
+    score_colors: Option<ScoreColors>,
+}
+
+impl<W: Write> TextReporter<W> {
+    pub fn new(writer: W, is_verbose: bool) -> Self {
+        Self {
+            writer,
+            is_verbose,
+            shows_durations: false,
+            shows_only_summary: false,
+            groups_by_tag: false,
+            shows_header: false,
+            groups_failures: false,
+            score_colors: None,
+        }
+    }
+
+    pub fn with_durations(mut self) -> Self {
+        self.shows_durations = true;
+        self
+    }
+
+    pub fn with_summary_only(mut self) -> Self {
+        self.shows_only_summary = true;
+        self
+    }
+
+    pub fn with_tag_summary(mut self) -> Self {
+        self.groups_by_tag = true;
+        self
+    }
+
+    pub fn with_header(mut self) -> Self {
+        self.shows_header = true;
+        self
+    }
+
+    pub fn with_grouped_failures(mut self) -> Self {
+        self.groups_failures = true;
+        self
+    }
+
+    /// Colors the final score line with ANSI escape codes according to `score_colors`. It
+    /// should only be used when the writer is a terminal.
+    pub fn with_score_colors(mut self, score_colors: ScoreColors) -> Self {
+        self.score_colors = Some(score_colors);
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_unit_test_result(&mut self, result: &UnitTestResult) -> std::io::Result<()> {
+        writeln!(
+            self.writer,
+            "  {} ({}): {}",
+            result.name(),
+            result.executable_name(),
+            result.score()
+        )?;
+        if self.groups_failures {
+            self.write_grouped_assertion_results(result.assertion_results())?;
+        } else {
+            for assertion_result in result.assertion_results() {
+                self.write_assertion_result(assertion_result)?;
+            }
+        }
+        for name in result.skipped_assertions() {
+            writeln!(self.writer, "    ⏭️ {name} (skipped)")?;
+        }
+        Ok(())
+    }
+
+    /// The failed assertions with the same execution status and the same details, except
+    /// for their argv, are written once, where the first of them would be, along with the
+    /// names of all of them. The others are written as usual.
+    fn write_grouped_assertion_results(
+        &mut self,
+        results: &[AssertionResult],
+    ) -> std::io::Result<()> {
+        let mut groups: Vec<FailureGroup> = vec![];
+        // index in `groups` of each failed assertion
+        let mut group_indices = vec![None; results.len()];
+        for (i, result) in results.iter().enumerate() {
+            if result.passed() {
+                continue;
+            }
+            let mut details = TextReporter::new(vec![], self.is_verbose);
+            details.write_failure_details(result, false)?;
+            let details = details.into_inner();
+            let existing_group = groups.iter().position(|group| {
+                group.execution_status == result.execution_status() && group.details == details
+            });
+            let group_index = existing_group.unwrap_or_else(|| {
+                groups.push(FailureGroup {
+                    execution_status: result.execution_status(),
+                    details,
+                    members: vec![],
+                });
+                groups.len() - 1
+            });
+            groups[group_index].members.push(i);
+            group_indices[i] = Some(group_index);
+        }
+        for (i, result) in results.iter().enumerate() {
+            let group = match group_indices[i] {
+                Some(group_index) if groups[group_index].members.len() > 1 => &groups[group_index],
+                _ => {
+                    self.write_assertion_result(result)?;
+                    continue;
+                }
+            };
+            if group.members[0] != i {
+                continue;
+            }
+            let names: Vec<&str> = group.members.iter().map(|&j| results[j].name()).collect();
+            writeln!(
+                self.writer,
+                "    ❌ this failure occurred in {} assertions: {}",
+                names.len(),
+                names.join(", ")
+            )?;
+            self.writer.write_all(&group.details)?;
+        }
+        Ok(())
+    }
+
+    fn write_assertion_result(&mut self, result: &AssertionResult) -> std::io::Result<()> {
+        let mark = if result.passed() { "✅" } else { "❌" };
+        write!(self.writer, "    {mark} {}", result.name())?;
+        if let Some(duration) = result.duration() {
+            self.write_duration(duration)?;
+        }
+        writeln!(self.writer)?;
+        if result.passed() {
+            return Ok(());
+        }
+        self.write_failure_details(result, true)
+    }
+
+    /// Everything shown below the name of a failed assertion. The diagnostics are only
+    /// shown in verbose mode, unlike what happened to the program when it did not exit by
+    /// itself, e.g. it was terminated by a signal.
+    fn write_failure_details(
+        &mut self,
+        result: &AssertionResult,
+        shows_argv: bool,
+    ) -> std::io::Result<()> {
+        if result.execution_status().is_abnormal() {
+            writeln!(self.writer, "      program {}", result.execution_status())?;
+        }
+        if let Some(hint) = result.hint() {
+            writeln!(self.writer, "      hint: {hint}")?;
+        }
+        if !self.is_verbose {
+            return Ok(());
+        }
+        if shows_argv {
+            writeln!(self.writer, "      argv: {:?}", result.argv())?;
+            if let Some(command) = result.reproduction_command() {
+                writeln!(self.writer, "      reproduce: {command}")?;
+            }
+        }
+        if let Some(d) = result.stdout_diagnostics() {
+            self.write_diagnostics("stdout", d)?;
+        }
+        if let Some(d) = result.stdout_any_of_diagnostics() {
+            self.write_any_of_diagnostics("stdout", d)?;
+        }
+        if let Some(d) = result.stderr_diagnostics() {
+            self.write_diagnostics("stderr", d)?;
+        }
+        if let Some(d) = result.status_diagnostics() {
+            self.write_status_diagnostics(d)?;
+        }
+        if let Some(d) = result.stdout_not_contains_diagnostics() {
+            self.write_forbidden_output_diagnostics("stdout", d)?;
+        }
+        if let Some(d) = result.stderr_not_contains_diagnostics() {
+            self.write_forbidden_output_diagnostics("stderr", d)?;
+        }
+        if let Some(d) = result.stdout_line_count_diagnostics() {
+            self.write_diagnostics("stdout line count", d)?;
+        }
+        if let Some(d) = result.stdout_exact_bytes_diagnostics() {
+            self.write_diagnostics("stdout bytes", d)?;
+        }
+        if let Some(d) = result.stdout_max_bytes_diagnostics() {
+            self.write_diagnostics("stdout max bytes", d)?;
+        }
+        if let Some(d) = result.stdout_non_empty_diagnostics() {
+            self.write_diagnostics("stdout non-empty", d)?;
+        }
+        if let Some(d) = result.stderr_empty_diagnostics() {
+            self.write_empty_output_diagnostics("stderr", d)?;
+        }
+        if let Some(d) = result.stdout_equals_stderr_diagnostics() {
+            self.write_unequal_streams_diagnostics(d)?;
+        }
+        if let Some(d) = result.stdout_extract_diagnostics() {
+            self.write_extraction_diagnostics("stdout", d)?;
+        }
+        if let Some(d) = result.stdout_regex_checks_diagnostics() {
+            self.write_regex_checks_diagnostics("stdout", d)?;
+        }
+        if let Some(d) = result.interaction_diagnostics() {
+            self.write_interaction_diagnostics(d)?;
+        }
+        if let Some(stderr) = result.informational_stderr()
+            && !stderr.is_empty()
+        {
+            writeln!(self.writer, "      stderr (informational): {stderr:?}")?;
+        }
+        Ok(())
+    }
+
+    fn write_interaction_diagnostics(
+        &mut self,
+        diagnostics: &InteractionFailure,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      interaction:")?;
+        writeln!(
+            self.writer,
+            "        step {}: prompt {:?} not seen",
+            diagnostics.step(),
+            diagnostics.expected_prompt()
+        )?;
+        writeln!(
+            self.writer,
+            "        obtained: {:?}",
+            diagnostics.obtained()
+        )?;
+        if let Some(termination) = diagnostics.termination() {
+            writeln!(self.writer, "        program {termination}")?;
+        }
+        Ok(())
+    }
+
+    /// Sums the weights of the assertions of each tag, regardless of the section weights.
+    /// Untagged assertions are not shown.
+    fn write_tag_summary(&mut self, result: &GradingResult) -> std::io::Result<()> {
+        let mut score_by_tag: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+        for section_result in result.section_results() {
+            let Some(GradindTestsResult::UnitTests(unit_tests_result)) =
+                section_result.test_results()
+            else {
+                continue;
+            };
+            for unit_test_result in unit_tests_result.unit_test_results() {
+                for assertion_result in unit_test_result.assertion_results() {
+                    for tag in assertion_result.tags() {
+                        let (current, max) = score_by_tag.entry(tag).or_default();
+                        *current = current.saturating_add(assertion_result.score());
+                        *max = max.saturating_add(assertion_result.max_score());
+                    }
+                }
+            }
+        }
+        if score_by_tag.is_empty() {
+            return Ok(());
+        }
+        writeln!(self.writer, "Tags:")?;
+        for (tag, (current, max)) in score_by_tag {
+            writeln!(self.writer, "  {tag}: {current}/{max}")?;
+        }
+        Ok(())
+    }
+
+    fn write_forbidden_output_diagnostics(
+        &mut self,
+        label: &str,
+        diagnostics: &ForbiddenOutputResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        writeln!(
+            self.writer,
+            "        forbidden: {:?} found at line {}, column {}",
+            diagnostics.forbidden(),
+            diagnostics.line(),
+            diagnostics.column()
+        )
+    }
+
+    fn write_status_diagnostics(&mut self, diagnostics: &StatusResult) -> std::io::Result<()> {
+        writeln!(self.writer, "      status:")?;
+        match diagnostics.expected() {
+            ExpectedStatus::NonZero => {
+                writeln!(self.writer, "        expected: any nonzero (a failure)")?
+            }
+            expected => writeln!(self.writer, "        expected: {expected}")?,
+        }
+        match diagnostics.obtained() {
+            Some(obtained) => writeln!(self.writer, "        obtained: {obtained}"),
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+
+    fn write_any_of_diagnostics(
+        &mut self,
+        label: &str,
+        diagnostics: &AnyOfResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        writeln!(
+            self.writer,
+            "        expected: none of the {} acceptable outputs matched",
+            diagnostics.acceptable().len()
+        )?;
+        match diagnostics.obtained() {
+            Some(obtained) => writeln!(self.writer, "        obtained: {obtained:?}"),
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+
+    fn write_empty_output_diagnostics(
+        &mut self,
+        label: &str,
+        diagnostics: &EmptyOutputResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        let expected = if diagnostics.expected_empty() {
+            "empty"
+        } else {
+            "not empty"
+        };
+        writeln!(self.writer, "        expected: {expected}")?;
+        match diagnostics.obtained() {
+            Some(obtained) => writeln!(self.writer, "        obtained: {obtained:?}"),
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+
+    fn write_unequal_streams_diagnostics(
+        &mut self,
+        diagnostics: &UnequalStreamsResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      stdout equals stderr:")?;
+        for (label, obtained) in [
+            ("stdout", diagnostics.stdout()),
+            ("stderr", diagnostics.stderr()),
+        ] {
+            match obtained {
+                Some(obtained) => writeln!(self.writer, "        {label}: {obtained:?}")?,
+                None => writeln!(self.writer, "        {label}: <nothing>")?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_extraction_diagnostics(
+        &mut self,
+        label: &str,
+        diagnostics: &ExtractionResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        writeln!(
+            self.writer,
+            "        expected: a number >= {} captured by {:?}",
+            diagnostics.min(),
+            diagnostics.pattern()
+        )?;
+        match diagnostics.obtained() {
+            Some(Extracted::NoMatch) => writeln!(self.writer, "        obtained: no match"),
+            Some(Extracted::NotNumeric(captured)) => {
+                writeln!(self.writer, "        obtained: {captured:?}, not a number")
+            }
+            Some(Extracted::Number(number)) => {
+                writeln!(self.writer, "        obtained: {number}")
+            }
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+
+    /// Each failed check is shown with the values of its groups.
+    fn write_regex_checks_diagnostics(
+        &mut self,
+        label: &str,
+        diagnostics: &RegexChecksResult,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        writeln!(
+            self.writer,
+            "        expected: a match of {:?} that passes its checks",
+            diagnostics.pattern()
+        )?;
+        match diagnostics.obtained() {
+            Some(RegexChecksOutcome::NoMatch) => {
+                writeln!(self.writer, "        obtained: no match")
+            }
+            Some(RegexChecksOutcome::FailedChecks(failed_checks)) => {
+                for failed in failed_checks {
+                    let check = failed.check();
+                    let value = |value: Option<&str>| {
+                        value.map_or("<not captured>".to_string(), |value| format!("{value:?}"))
+                    };
+                    writeln!(
+                        self.writer,
+                        "        failed: {check} ({}: {}, {}: {})",
+                        check.left(),
+                        value(failed.left()),
+                        check.right(),
+                        value(failed.right())
+                    )?;
+                }
+                Ok(())
+            }
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+
+    /// The measured time is always shown, as it is what is being graded.
+    fn write_performance_test_result(
+        &mut self,
+        result: &PerformanceTestResult,
+    ) -> std::io::Result<()> {
+        let mark = if result.passed() { "✅" } else { "❌" };
+        write!(
+            self.writer,
+            "  {mark} {} ({}): ",
+            result.name(),
+            result.executable_name()
+        )?;
+        match result.measured() {
+            Some(measured) => write!(self.writer, "{measured:.2?}")?,
+            None if result.is_deadline_exceeded() => {
+                write!(self.writer, "<not run: deadline exceeded>")?
+            }
+            None => write!(self.writer, "<not executed>")?,
+        }
+        if let Some(statistics) = result.statistics() {
+            write!(
+                self.writer,
+                " (mean of {} runs, median: {:.2?}, std dev: {:.2?})",
+                statistics.runs(),
+                statistics.median(),
+                statistics.std_dev()
+            )?;
+        }
+        write!(self.writer, " (max: {:?}", result.max_duration())?;
+        if let Some(min) = result.min_duration() {
+            write!(self.writer, ", min: {min:?}")?;
+        }
+        if let Some(termination) = result.termination() {
+            write!(self.writer, ", {termination}")?;
+        }
+        writeln!(self.writer, ")")
+    }
+
+    fn write_duration(&mut self, duration: Duration) -> std::io::Result<()> {
+        if self.shows_durations {
+            write!(self.writer, " ({duration:.2?})")?;
+        }
+        Ok(())
+    }
+
+    fn write_diagnostics<T: Debug>(
+        &mut self,
+        label: &str,
+        diagnostics: &ExpectedObtainedResult<T>,
+    ) -> std::io::Result<()> {
+        writeln!(self.writer, "      {label}:")?;
+        writeln!(
+            self.writer,
+            "        expected: {:?}",
+            diagnostics.expected()
+        )?;
+        match diagnostics.obtained() {
+            Some(obtained) => writeln!(self.writer, "        obtained: {obtained:?}"),
+            None => writeln!(self.writer, "        obtained: <nothing>"),
+        }
+    }
+}
+
+/// Failed assertions of a unit test that read the same, see
+/// `TextReporter::write_grouped_assertion_results`.
+struct FailureGroup {
+    execution_status: ExecutionStatus,
+    /// What is written below the name of each of them, except for their argv.
+    details: Vec<u8>,
+    /// Indices of the assertions, in the order in which they ran.
+    members: Vec<usize>,
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g.
+/// `2024-03-01 13:05:00 UTC`, without depending on an external crate.
+fn format_utc_timestamp(timestamp: u64) -> String {
+    let (days, seconds_of_day) = (timestamp / 86_400, timestamp % 86_400);
+    // civil calendar from the days since 1970-01-01, counting eras of 400 years that
+    // start on March 1st, so the leap day is the last day of each year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Resets the color set by `ansi_color_code`.
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn ansi_color_code(band: ScoreBand) -> &'static str {
+    match band {
+        ScoreBand::Green => "\x1b[32m",
+        ScoreBand::Yellow => "\x1b[33m",
+        ScoreBand::Red => "\x1b[31m",
+    }
+}
+
+impl<W: Write> Reporter for TextReporter<W> {
+    fn on_grading_start(&mut self, result: &GradingResult) -> std::io::Result<()> {
+        if !self.shows_header {
+            return Ok(());
+        }
+        writeln!(self.writer, "Title: {}", result.title())?;
+        if let Some(author) = result.author() {
+            writeln!(self.writer, "Author: {author}")?;
+        }
+        writeln!(
+            self.writer,
+            "Date: {}",
+            format_utc_timestamp(result.started_at())
+        )
+    }
+
+    fn on_section_complete(
+        &mut self,
+        section_result: &GradingTestSectionResult,
+    ) -> std::io::Result<()> {
+        if self.shows_only_summary {
+            return Ok(());
+        }
+        write!(self.writer, "{}: ", section_result.name())?;
+        match section_result.raw_score() {
+            // the weight only changes `Weighted` scores
+            raw_score @ Score::Weighted { .. } if section_result.weight() != 1 => write!(
+                self.writer,
+                "{raw_score} (weight {}) → {}",
+                section_result.weight(),
+                section_result.score()
+            )?,
+            _ => write!(self.writer, "{}", section_result.score())?,
+        }
+        if section_result.is_extra_credit() {
+            write!(self.writer, " (extra credit)")?;
+        }
+        self.write_duration(section_result.duration())?;
+        writeln!(self.writer)?;
+        match section_result.test_results() {
+            Some(GradindTestsResult::UnitTests(unit_tests_result)) => {
+                for unit_test_result in unit_tests_result.unit_test_results() {
+                    self.write_unit_test_result(unit_test_result)?;
+                }
+                for name in unit_tests_result.skipped_unit_tests() {
+                    writeln!(self.writer, "  {name}: skipped")?;
+                }
+            }
+            Some(GradindTestsResult::PerformanceTests(performance_tests_result)) => {
+                for performance_test_result in performance_tests_result.performance_test_results() {
+                    self.write_performance_test_result(performance_test_result)?;
+                }
+            }
+            None => {}
+        }
+        // flush so the section shows up right away
+        self.writer.flush()
+    }
+
+    fn on_grading_complete(&mut self, result: &GradingResult) -> std::io::Result<()> {
+        if !self.shows_only_summary {
+            for name in result.skipped_sections() {
+                writeln!(self.writer, "{name}: skipped")?;
+            }
+        }
+        if self.groups_by_tag {
+            self.write_tag_summary(result)?;
+        }
+        if result.is_interrupted() {
+            writeln!(self.writer, "Interrupted: the results are incomplete")?;
+        }
+        if result.is_deadline_exceeded() {
+            writeln!(self.writer, "Deadline exceeded: some tests were not run")?;
+        }
+        if let Some(err) = result.global_teardown_error() {
+            writeln!(self.writer, "Global teardown failed: {err}")?;
+        }
+        // a single failure fails the whole grading, so it is pointed out
+        if result.score() == Score::Absolute(false) {
+            let failed_assertions = result.failed_assertions();
+            writeln!(
+                self.writer,
+                "Failed assertions ({}):",
+                failed_assertions.len()
+            )?;
+            for path in failed_assertions {
+                writeln!(self.writer, "  ❌ {path}")?;
+            }
+        }
+        let final_score = format!("Final score: {}", result.score_line());
+        match self
+            .score_colors
+            .and_then(|colors| colors.band_of(&result.score()))
+        {
+            Some(band) => writeln!(
+                self.writer,
+                "{}{final_score}{ANSI_RESET}",
+                ansi_color_code(band)
+            )?,
+            None => writeln!(self.writer, "{final_score}")?,
+        }
+        // shown so the run can be reproduced
+        writeln!(self.writer, "Seed: {}", result.seed())?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Grader, GradingConfig, GradingMode,
+        grader::{
+            GradingTestSection,
+            builder::{SectionBuilder, UnitTestBuilder},
+            grading_tests::{
+                GradingTests,
+                performance_test::{PerformanceTest, PerformanceTests},
+                unit_test::{
+                    UnitTest, UnitTests,
+                    assertion::Assertion,
+                    extraction::StdoutExtract,
+                    regex_checks::{Comparison, GroupCheck, StdoutRegexChecks},
+                },
+            },
+        },
+        input::ExecutableArtifact,
+    };
+
+    fn echo_config() -> GradingConfig {
+        echo_config_with(GradingMode::Weighted)
+    }
+
+    fn echo_config_with(grading_mode: GradingMode) -> GradingConfig {
+        let mut config = GradingConfig::new("echo".to_string(), None, grading_mode);
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::build(
+                "should echo".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("hello\n".to_string()),
+                None,
+                Some(0),
+                2,
+            )
+            .unwrap(),
+        )
+        .with_assertion(
+            Assertion::build(
+                "should not echo".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("bye\n".to_string()),
+                None,
+                None,
+                3,
+            )
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        config
+    }
+
+    #[test]
+    fn should_report_without_diagnostics() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 2/5\n\
+             \x20 echo args (program1): 2/5\n\
+             \x20   ✅ should echo\n\
+             \x20   ❌ should not echo\n\
+             Final score: 2 / 5 (40.0%)\n\
+             Seed: 3\n"
+        );
+    }
+    #[test]
+    fn should_color_the_final_score_by_its_band() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], false)
+            .with_summary_only()
+            .with_score_colors(ScoreColors::default());
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "\x1b[31mFinal score: 2 / 5 (40.0%)\x1b[0m\n\
+             Seed: 3\n"
+        );
+
+        let mut reporter = TextReporter::new(vec![], false)
+            .with_summary_only()
+            .with_score_colors(ScoreColors::build(50.0, 40.0).unwrap());
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "\x1b[33mFinal score: 2 / 5 (40.0%)\x1b[0m\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_that_the_results_are_incomplete_when_interrupted() {
+        let config = echo_config();
+        let interrupted = std::sync::atomic::AtomicBool::new(true);
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .with_interruption_flag(&interrupted)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "Interrupted: the results are incomplete\n\
+             Final score: 0 / 0\n\
+             Seed: 3\n"
+        );
+    }
+    #[test]
+    fn should_report_the_tests_not_run_past_the_deadline() {
+        let mut config = echo_config();
+        config.set_deadline(Duration::ZERO);
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/5\n\
+             \x20 echo args (program1): 0/5\n\
+             \x20   ❌ should echo\n\
+             \x20     program not run: deadline exceeded\n\
+             \x20   ❌ should not echo\n\
+             \x20     program not run: deadline exceeded\n\
+             Deadline exceeded: some tests were not run\n\
+             Final score: 0 / 5 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_header_when_requested() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], false).with_header();
+        let result = Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        let report = String::from_utf8(reporter.into_inner()).unwrap();
+        let header = format!(
+            "Title: echo\nDate: {}\nsection 1: 2/5\n",
+            format_utc_timestamp(result.started_at())
+        );
+        assert!(report.starts_with(&header), "{report}");
+    }
+
+    #[test]
+    fn should_format_utc_timestamps() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc_timestamp(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(
+            format_utc_timestamp(1_700_000_000),
+            "2023-11-14 22:13:20 UTC"
+        );
+    }
+
+    #[test]
+    fn should_report_diagnostics_when_verbose() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 2/5\n\
+             \x20 echo args (program1): 2/5\n\
+             \x20   ✅ should echo\n\
+             \x20   ❌ should not echo\n\
+             \x20     argv: [\"hello\"]\n\
+             \x20     reproduce: echo hello\n\
+             \x20     stdout:\n\
+             \x20       expected: \"bye\\n\"\n\
+             \x20       obtained: \"hello\\n\"\n\
+             Final score: 2 / 5 (40.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_hint_only_when_assertion_fails() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let assertion = |name: &str, stdout: &str| {
+            Assertion::build(
+                name.to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some(stdout.to_string()),
+                None,
+                None,
+                1,
+            )
+            .unwrap()
+            .with_hint(format!("hint of {name}"))
+        };
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(assertion("should echo", "hello\n"))
+        .with_assertion(assertion("should not echo", "bye\n"));
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 1/2\n\
+             \x20 echo args (program1): 1/2\n\
+             \x20   ✅ should echo\n\
+             \x20   ❌ should not echo\n\
+             \x20     hint: hint of should not echo\n\
+             Final score: 1 / 2 (50.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_only_summary_when_requested() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], true).with_summary_only();
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "Final score: 2 / 5 (40.0%)\nSeed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_mark_skipped_items() {
+        let mut unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::build(
+                "should echo".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("hello\n".to_string()),
+                None,
+                None,
+                1,
+            )
+            .unwrap(),
+        );
+        unit_test.add_skipped_assertion("should not echo".to_string());
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(
+                UnitTests::new(vec![], true, vec![], vec![], vec![], vec![unit_test])
+                    .with_skipped_unit_tests(vec!["unit test 2".to_string()]),
+            ),
+        ));
+        config.add_skipped_section("section 2".to_string());
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 1/1\n\
+             \x20 echo args (program1): 1/1\n\
+             \x20   ✅ should echo\n\
+             \x20   ⏭️ should not echo (skipped)\n\
+             \x20 unit test 2: skipped\n\
+             section 2: skipped\n\
+             Final score: 1 / 1 (100.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_score_by_tag_when_requested() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let assertion = |name: &str, stdout: &str, weight, tags: &[&str]| {
+            Assertion::build(
+                name.to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some(stdout.to_string()),
+                None,
+                None,
+                weight,
+            )
+            .unwrap()
+            .with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+        };
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(assertion("a", "hello\n", 1, &["basic"]))
+        .with_assertion(assertion("b", "bye\n", 2, &["basic", "edge-cases"]))
+        .with_assertion(assertion("c", "hello\n", 4, &[]));
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], false)
+            .with_summary_only()
+            .with_tag_summary();
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "Tags:\n\
+             \x20 basic: 1/3\n\
+             \x20 edge-cases: 0/2\n\
+             Final score: 5 / 7 (71.4%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_saturate_the_score_of_a_tag() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let assertion = |name: &str| {
+            Assertion::new(name.to_string(), vec!["hello".to_string()], None, u32::MAX)
+                .with_stdout("hello\n".to_string())
+                .with_tags(vec!["heavy".to_string()])
+        };
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(assertion("a"))
+        .with_assertion(assertion("b"));
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        let mut reporter = TextReporter::new(vec![], false)
+            .with_summary_only()
+            .with_tag_summary();
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        let report = String::from_utf8(reporter.into_inner()).unwrap();
+        assert!(report.starts_with("Tags:\n  heavy: 4294967295/4294967295\n"));
+    }
+
+    #[test]
+    fn should_report_durations_when_requested() {
+        let config = echo_config();
+        let mut reporter = TextReporter::new(vec![], false).with_durations();
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        let report = String::from_utf8(reporter.into_inner()).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 6);
+        for line in [lines[0], lines[2], lines[3]] {
+            assert!(line.ends_with("s)"), "missing duration in '{line}'");
+        }
+        assert!(lines[0].starts_with("section 1: 2/5 ("));
+        assert!(lines[2].starts_with("    ✅ should echo ("));
+        assert_eq!(lines[1], "  echo args (program1): 2/5");
+    }
+
+    #[test]
+    fn should_report_performance_tests() {
+        let mut config = GradingConfig::new("sleep".to_string(), None, GradingMode::Weighted);
+        let performance_test = |path: &str, min_duration| {
+            PerformanceTest::build(
+                format!("run {path}"),
+                ExecutableArtifact::CompiledProgram {
+                    name: "program1".to_string(),
+                    path: path.into(),
+                    base_args: vec![],
+                },
+                vec![],
+                None,
+                Duration::from_secs(5),
+                min_duration,
+                1,
+            )
+            .unwrap()
+        };
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::PerformanceTests(PerformanceTests::new(vec![
+                performance_test("true", None),
+                performance_test("____invalid_command", Some(Duration::from_millis(1))),
+                performance_test("true", None).with_repeat(3).unwrap(),
+            ])),
+        ));
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        let report = String::from_utf8(reporter.into_inner()).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "section 1: 2/3");
+        assert!(lines[1].starts_with("  ✅ run true (program1): "));
+        assert!(lines[1].ends_with(" (max: 5s)"));
+        assert_eq!(
+            lines[2],
+            "  ❌ run ____invalid_command (program1): <not executed> (max: 5s, min: 1ms)"
+        );
+        assert!(lines[3].contains(" (mean of 3 runs, median: "));
+        assert!(lines[3].ends_with(" (max: 5s)"));
+        assert_eq!(lines[4], "Final score: 2 / 3 (66.7%)");
+    }
+
+    #[test]
+    fn should_report_how_a_performance_test_was_stopped() {
+        let mut config = GradingConfig::new("sleep".to_string(), None, GradingMode::Weighted);
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::PerformanceTests(PerformanceTests::new(vec![
+                PerformanceTest::build(
+                    "sleep".to_string(),
+                    ExecutableArtifact::CompiledProgram {
+                        name: "program1".to_string(),
+                        path: "sleep".into(),
+                        base_args: vec![],
+                    },
+                    vec!["30".to_string()],
+                    None,
+                    Duration::from_millis(100),
+                    None,
+                    1,
+                )
+                .unwrap(),
+            ])),
+        ));
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        let report = String::from_utf8(reporter.into_inner()).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[1].starts_with("  ❌ sleep (program1): "));
+        assert!(lines[1].ends_with(" (max: 100ms, killed)"));
+    }
+
+    #[test]
+    fn should_report_forbidden_output_when_verbose() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new(
+                "should not debug".to_string(),
+                vec!["DEBUG: 1".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_not_contains("DEBUG".to_string())
+            .check()
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/1\n\
+             \x20 echo args (program1): 0/1\n\
+             \x20   ❌ should not debug\n\
+             \x20     argv: [\"DEBUG: 1\"]\n\
+             \x20     reproduce: echo 'DEBUG: 1'\n\
+             \x20     stdout:\n\
+             \x20       forbidden: \"DEBUG\" found at line 1, column 1\n\
+             Final score: 0 / 1 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_unmatched_acceptable_outputs_when_verbose() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new("should greet".to_string(), vec!["bye".to_string()], None, 1)
+                .with_stdout_any_of(vec!["hi\n".to_string(), "hello\n".to_string()])
+                .check()
+                .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/1\n\
+             \x20 echo args (program1): 0/1\n\
+             \x20   ❌ should greet\n\
+             \x20     argv: [\"bye\"]\n\
+             \x20     reproduce: echo bye\n\
+             \x20     stdout:\n\
+             \x20       expected: none of the 2 acceptable outputs matched\n\
+             \x20       obtained: \"bye\\n\"\n\
+             Final score: 0 / 1 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_unexpected_stderr_when_verbose() {
+        let mut config = GradingConfig::new("sh".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "sh script".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "sh".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new(
+                "should be silent".to_string(),
+                vec!["-c".to_string(), "echo oops >&2".to_string()],
+                None,
+                1,
+            )
+            .with_stderr_empty(true)
+            .check()
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/1\n\
+             \x20 sh script (program1): 0/1\n\
+             \x20   ❌ should be silent\n\
+             \x20     argv: [\"-c\", \"echo oops >&2\"]\n\
+             \x20     reproduce: sh -c 'echo oops >&2'\n\
+             \x20     stderr:\n\
+             \x20       expected: empty\n\
+             \x20       obtained: \"oops\\n\"\n\
+             Final score: 0 / 1 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+    #[test]
+    fn should_report_both_streams_when_they_differ_when_verbose() {
+        let mut config = GradingConfig::new("sh".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "sh script".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "sh".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new(
+                "should echo".to_string(),
+                vec!["-c".to_string(), "echo ping; echo pong >&2".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_equals_stderr(true)
+            .check()
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/1\n\
+             \x20 sh script (program1): 0/1\n\
+             \x20   ❌ should echo\n\
+             \x20     argv: [\"-c\", \"echo ping; echo pong >&2\"]\n\
+             \x20     reproduce: sh -c 'echo ping; echo pong >&2'\n\
+             \x20     stdout equals stderr:\n\
+             \x20       stdout: \"ping\\n\"\n\
+             \x20       stderr: \"pong\\n\"\n\
+             Final score: 0 / 1 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_extracted_number_when_verbose() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new(
+                "should be accurate".to_string(),
+                vec!["accuracy: 0.85".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_extract(StdoutExtract::build(r"accuracy: (\S+)", 0.9).unwrap())
+            .check()
+            .unwrap(),
+        )
+        .with_assertion(
+            Assertion::new(
+                "should report accuracy".to_string(),
+                vec!["accuracy: high".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_extract(StdoutExtract::build(r"accuracy: (\S+)", 0.9).unwrap())
+            .check()
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/2\n\
+             \x20 echo args (program1): 0/2\n\
+             \x20   ❌ should be accurate\n\
+             \x20     argv: [\"accuracy: 0.85\"]\n\
+             \x20     reproduce: echo 'accuracy: 0.85'\n\
+             \x20     stdout:\n\
+             \x20       expected: a number >= 0.9 captured by \"accuracy: (\\\\S+)\"\n\
+             \x20       obtained: 0.85\n\
+             \x20   ❌ should report accuracy\n\
+             \x20     argv: [\"accuracy: high\"]\n\
+             \x20     reproduce: echo 'accuracy: high'\n\
+             \x20     stdout:\n\
+             \x20       expected: a number >= 0.9 captured by \"accuracy: (\\\\S+)\"\n\
+             \x20       obtained: \"high\", not a number\n\
+             Final score: 0 / 2 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_failed_regex_checks_when_verbose() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let regex_checks = || {
+            StdoutRegexChecks::build(
+                r"(?<done>\d+) of (?<total>\d+)",
+                vec![GroupCheck::new("done", Comparison::Eq, "total")],
+            )
+            .unwrap()
+        };
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::CompiledProgram {
+                name: "program1".to_string(),
+                path: "echo".into(),
+                base_args: vec![],
+            },
+        )
+        .with_assertion(
+            Assertion::new(
+                "should finish".to_string(),
+                vec!["9 of 10".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_regex_checks(regex_checks())
+            .check()
+            .unwrap(),
+        )
+        .with_assertion(
+            Assertion::new(
+                "should report progress".to_string(),
+                vec!["done".to_string()],
+                None,
+                1,
+            )
+            .with_stdout_regex_checks(regex_checks())
+            .check()
+            .unwrap(),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/2\n\
+             \x20 echo args (program1): 0/2\n\
+             \x20   ❌ should finish\n\
+             \x20     argv: [\"9 of 10\"]\n\
+             \x20     reproduce: echo '9 of 10'\n\
+             \x20     stdout:\n\
+             \x20       expected: a match of \"(?<done>\\\\d+) of (?<total>\\\\d+)\" that passes its checks\n\
+             \x20       failed: done == total (done: \"9\", total: \"10\")\n\
+             \x20   ❌ should report progress\n\
+             \x20     argv: [\"done\"]\n\
+             \x20     reproduce: echo done\n\
+             \x20     stdout:\n\
+             \x20       expected: a match of \"(?<done>\\\\d+) of (?<total>\\\\d+)\" that passes its checks\n\
+             \x20       obtained: no match\n\
+             Final score: 0 / 2 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_programs_not_submitted() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        let unit_test = UnitTest::new(
+            "echo args".to_string(),
+            ExecutableArtifact::NotSubmitted {
+                name: "program2".to_string(),
+            },
+        )
+        .with_assertion(
+            Assertion::build(
+                "should echo".to_string(),
+                vec!["hello".to_string()],
+                None,
+                Some("hello\n".to_string()),
+                None,
+                None,
+                2,
+            )
+            .unwrap()
+            .with_hint("submit program2".to_string()),
+        );
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            1,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![unit_test],
+            )),
+        ));
+        config.set_seed(3);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/2\n\
+             \x20 echo args (program2): 0/2\n\
+             \x20   ❌ should echo\n\
+             \x20     program not submitted\n\
+             \x20     hint: submit program2\n\
+             \x20     argv: [\"hello\"]\n\
+             \x20     stdout:\n\
+             \x20       expected: \"hello\\n\"\n\
+             \x20       obtained: <nothing>\n\
+             Final score: 0 / 2 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_report_what_happened_to_a_program_that_did_not_exit_by_itself() {
+        let config = GradingConfig::builder("sh")
+            .with_seed(3)
+            .section(
+                SectionBuilder::new("section 1").unit_test(
+                    UnitTestBuilder::new("run", "sh").assertion(
+                        Assertion::new(
+                            "should say hello".to_string(),
+                            vec!["-c".to_string(), "kill -9 $$".to_string()],
+                            None,
+                            1,
+                        )
+                        .with_stdout("hello\n".to_string()),
+                    ),
+                ),
+            )
+            .build()
+            .unwrap();
+        let mut reporter = TextReporter::new(vec![], false);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/1\n\
+             \x20 run (sh): 0/1\n\
+             \x20   ❌ should say hello\n\
+             \x20     program terminated by a signal\n\
+             Final score: 0 / 1 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_show_the_informational_stderr_of_the_failed_assertions() {
+        let config = GradingConfig::builder("sh")
+            .with_seed(3)
+            .section(
+                SectionBuilder::new("section 1")
+                    .with_informational_stderr(true)
+                    .unit_test(
+                        UnitTestBuilder::new("run", "sh").assertion(
+                            Assertion::new(
+                                "should say hello".to_string(),
+                                vec!["-c".to_string(), "echo warning >&2".to_string()],
+                                None,
+                                1,
+                            )
+                            .with_stdout("hello\n".to_string()),
+                        ),
+                    ),
+            )
+            .build()
+            .unwrap();
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert!(
+            String::from_utf8(reporter.into_inner())
+                .unwrap()
+                .contains("      stderr (informational): \"warning\\n\"\n")
+        );
+    }
+
+    #[test]
+    fn should_report_that_any_nonzero_status_was_expected() {
+        let config = GradingConfig::builder("true")
+            .with_seed(3)
+            .section(
+                SectionBuilder::new("section 1").unit_test(
+                    UnitTestBuilder::new("run", "true").assertion(
+                        Assertion::new("should fail".to_string(), vec![], None, 1)
+                            .with_expected_status(ExpectedStatus::NonZero),
+                    ),
+                ),
+            )
+            .build()
+            .unwrap();
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert!(String::from_utf8(reporter.into_inner()).unwrap().contains(
+            "\x20     status:\n\
+                     \x20       expected: any nonzero (a failure)\n\
+                     \x20       obtained: 0\n"
+        ));
+    }
+
+    #[test]
+    fn should_group_the_assertions_that_failed_the_same_way_when_requested() {
+        let assertion = |name: &str, status: i32| {
+            Assertion::new(name.to_string(), vec![], None, 1).with_status(status)
+        };
+        let config = GradingConfig::builder("missing")
+            .with_seed(3)
+            .section(
+                SectionBuilder::new("section 1").unit_test(
+                    UnitTestBuilder::new("run", "/nonexistent/program")
+                        .assertion(assertion("a", 0))
+                        .assertion(assertion("b", 1))
+                        .assertion(assertion("c", 0))
+                        .assertion(assertion("d", 0)),
+                ),
+            )
+            .build()
+            .unwrap();
+        let mut reporter = TextReporter::new(vec![], true).with_grouped_failures();
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: 0/4\n\
+             \x20 run (/nonexistent/program): 0/4\n\
+             \x20   ❌ this failure occurred in 3 assertions: a, c, d\n\
+             \x20     program could not be executed\n\
+             \x20     status:\n\
+             \x20       expected: 0\n\
+             \x20       obtained: <nothing>\n\
+             \x20   ❌ b\n\
+             \x20     program could not be executed\n\
+             \x20     argv: []\n\
+             \x20     reproduce: /nonexistent/program\n\
+             \x20     status:\n\
+             \x20       expected: 1\n\
+             \x20       obtained: <nothing>\n\
+             Final score: 0 / 4 (0.0%)\n\
+             Seed: 3\n"
+        );
+    }
+
+    #[test]
+    fn should_list_the_failed_assertions_in_absolute_mode() {
+        let config = echo_config_with(GradingMode::Absolute);
+        let mut reporter = TextReporter::new(vec![], true);
+        Grader::new(&config)
+            .run_with_reporter(&mut reporter)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "section 1: FAIL\n\
+             \x20 echo args (program1): FAIL\n\
+             \x20   ✅ should echo\n\
+             \x20   ❌ should not echo\n\
+             \x20     argv: [\"hello\"]\n\
+             \x20     reproduce: echo hello\n\
+             \x20     stdout:\n\
+             \x20       expected: \"bye\\n\"\n\
+             \x20       obtained: \"hello\\n\"\n\
+             Failed assertions (1):\n\
+             \x20 ❌ section 1 / echo args / should not echo\n\
+             Final score: FAIL\n\
+             Seed: 3\n"
+        );
+    }
+}
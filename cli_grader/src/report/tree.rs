@@ -0,0 +1,139 @@
+//! Compact overview of the sections and tests, written without running anything.
+
+use crate::grader::{
+    GradingConfig, GradingTestSection, grading_tests::GradingTests, score::GradingMode,
+};
+use std::io::{self, Write};
+
+/// Lists every section with its unit tests, their programs and number of assertions, and
+/// its performance tests, followed by the maximum score. Unlike `write_explanation`, the
+/// expected values are left out, so it fits in a screen.
+pub fn write_test_tree<W: Write>(writer: &mut W, config: &GradingConfig) -> io::Result<()> {
+    writeln!(writer, "{}", config.name())?;
+    for section in config.grading_sections() {
+        write_section(writer, section)?;
+    }
+    match (config.grading_mode(), config.max_score()) {
+        (GradingMode::Absolute, _) => writeln!(writer, "Maximum score: PASS"),
+        (GradingMode::Weighted, Some(max_score)) => {
+            writeln!(writer, "Maximum score: {max_score}")
+        }
+        (GradingMode::Weighted, None) => writeln!(writer, "Maximum score: too large"),
+    }
+}
+
+fn write_section<W: Write>(writer: &mut W, section: &GradingTestSection) -> io::Result<()> {
+    write!(writer, "{} (weight {})", section.name(), section.weight())?;
+    if section.is_extra_credit() {
+        write!(writer, " (extra credit)")?;
+    }
+    writeln!(writer)?;
+    match section.tests() {
+        GradingTests::UnitTests(unit_tests) => {
+            for unit_test in unit_tests.unit_tests() {
+                write!(
+                    writer,
+                    "  UnitTest {:?} [{}] — {} assertion(s)",
+                    unit_test.name(),
+                    unit_test.executable_name(),
+                    unit_test.size()
+                )?;
+                if let Some(weight) = unit_test.weight() {
+                    write!(writer, ", weight {weight}")?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        GradingTests::PerformanceTests(performance_tests) => {
+            for performance_test in performance_tests.performance_tests() {
+                writeln!(
+                    writer,
+                    "  PerformanceTest {:?} [{}] — weight {}",
+                    performance_test.name(),
+                    performance_test.executable_name(),
+                    performance_test.weight()
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grader::grading_tests::{
+            performance_test::{PerformanceTest, PerformanceTests},
+            unit_test::{UnitTest, UnitTests, assertion::Assertion},
+        },
+        input::ExecutableArtifact,
+    };
+    use std::time::Duration;
+
+    fn executable(name: &str) -> ExecutableArtifact {
+        ExecutableArtifact::CompiledProgram {
+            name: name.to_string(),
+            path: "____not_executed".into(),
+            base_args: vec![],
+        }
+    }
+
+    fn assertion(name: &str, weight: u32) -> Assertion {
+        Assertion::new(name.to_string(), vec![], None, weight).with_stdout(String::new())
+    }
+
+    #[test]
+    fn should_list_the_tests_of_every_section() {
+        let mut config = GradingConfig::new("echo".to_string(), None, GradingMode::Weighted);
+        config.add_grading_section(GradingTestSection::new(
+            "section 1".to_string(),
+            2,
+            GradingTests::UnitTests(UnitTests::new(
+                vec![],
+                true,
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    UnitTest::new("echo args".to_string(), executable("program1"))
+                        .with_assertion(assertion("a", 1))
+                        .with_assertion(assertion("b", 2)),
+                    UnitTest::new("cat stdin".to_string(), executable("program2"))
+                        .with_weight(5)
+                        .with_assertion(assertion("c", 1)),
+                ],
+            )),
+        ));
+        config.add_grading_section(GradingTestSection::new(
+            "section 2".to_string(),
+            1,
+            GradingTests::PerformanceTests(PerformanceTests::new(vec![
+                PerformanceTest::build(
+                    "fast".to_string(),
+                    executable("program1"),
+                    vec![],
+                    None,
+                    Duration::from_secs(1),
+                    None,
+                    4,
+                )
+                .unwrap(),
+            ])),
+        ));
+        let mut writer = vec![];
+
+        write_test_tree(&mut writer, &config).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "echo\n\
+             section 1 (weight 2)\n\
+             \x20 UnitTest \"echo args\" [program1] — 2 assertion(s)\n\
+             \x20 UnitTest \"cat stdin\" [program2] — 1 assertion(s), weight 5\n\
+             section 2 (weight 1)\n\
+             \x20 PerformanceTest \"fast\" [program1] — weight 4\n\
+             Maximum score: 20\n"
+        );
+    }
+}
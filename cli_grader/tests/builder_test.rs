@@ -0,0 +1,79 @@
+use cli_grader::{
+    Assertion, Grader, GradingConfig, GradingMode, Score, SectionBuilder, TextReporter,
+    UnitTestBuilder,
+};
+
+#[test]
+fn should_grade_a_config_built_in_code() {
+    let config = GradingConfig::builder("echo and cat")
+        .with_author("author")
+        .with_seed(3)
+        .section(
+            SectionBuilder::new("echo").with_weight(2).unit_test(
+                UnitTestBuilder::new("echo args", "echo")
+                    .assertion(
+                        Assertion::new("should echo".to_string(), vec!["hi".to_string()], None, 1)
+                            .with_stdout("hi\n".to_string())
+                            .with_status(0),
+                    )
+                    .assertion(
+                        Assertion::new(
+                            "should not echo".to_string(),
+                            vec!["hi".to_string()],
+                            None,
+                            1,
+                        )
+                        .with_stdout("bye\n".to_string()),
+                    ),
+            ),
+        )
+        .section(
+            SectionBuilder::new("cat")
+                .file("input.txt", "file content")
+                .unit_test(
+                    UnitTestBuilder::new("cat", "cat")
+                        .assertion(
+                            Assertion::new(
+                                "should read stdin".to_string(),
+                                vec![],
+                                Some("abc".to_string()),
+                                1,
+                            )
+                            .with_stdout("abc".to_string()),
+                        )
+                        .assertion(
+                            Assertion::new(
+                                "should read file".to_string(),
+                                vec!["input.txt".to_string()],
+                                None,
+                                1,
+                            )
+                            .with_stdout("file content".to_string()),
+                        ),
+                ),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(config.grading_mode(), GradingMode::Weighted);
+    assert_eq!(config.max_score(), Some(6));
+
+    let mut reporter = TextReporter::new(vec![], false);
+    let result = Grader::new(&config)
+        .run_with_reporter(&mut reporter)
+        .unwrap();
+
+    assert_eq!(result.score(), Score::Weighted { current: 4, max: 6 });
+    assert_eq!(
+        String::from_utf8(reporter.into_inner()).unwrap(),
+        "echo: 1/2 (weight 2) → 2/4\n\
+         \x20 echo args (echo): 1/2\n\
+         \x20   ✅ should echo\n\
+         \x20   ❌ should not echo\n\
+         cat: 2/2\n\
+         \x20 cat (cat): 2/2\n\
+         \x20   ✅ should read stdin\n\
+         \x20   ✅ should read file\n\
+         Final score: 4 / 6 (66.7%)\n\
+         Seed: 3\n"
+    );
+}
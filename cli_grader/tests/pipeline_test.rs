@@ -0,0 +1,132 @@
+use cli_grader::{GlobalConfig, Grader, GradingResult, Score, TextReporter};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const ECHO: &str = "/bin/echo";
+const CAT: &str = "/bin/cat";
+
+/// Loads `tests/configs/<config_name>`, initializes it with `programs` (name and path of
+/// each one) and grades them, returning the result and the verbose text report.
+fn grade(config_name: &str, programs: &[(&str, &str)]) -> (GradingResult, String) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("configs")
+        .join(config_name);
+    let config = GlobalConfig::from_json(&fs::read_to_string(&path).unwrap())
+        .unwrap()
+        .with_base_dir(path.parent().unwrap());
+    let programs: Vec<(&str, PathBuf)> = programs
+        .iter()
+        .map(|(name, path)| (*name, PathBuf::from(path)))
+        .collect();
+    let mut grading_config = config
+        .initialize(&programs)
+        .unwrap()
+        .build_grading_config()
+        .unwrap();
+    grading_config.set_seed(3);
+
+    let mut reporter = TextReporter::new(vec![], true);
+    let result = Grader::new(&grading_config)
+        .run_with_reporter(&mut reporter)
+        .unwrap();
+    (result, String::from_utf8(reporter.into_inner()).unwrap())
+}
+
+#[test]
+fn should_grade_a_weighted_config() {
+    let (result, report) = grade("weighted.json", &[("program1", ECHO)]);
+
+    assert_eq!(result.score(), Score::Weighted { current: 7, max: 9 });
+    assert_eq!(
+        report,
+        "greetings: 3/4 (weight 2) → 6/8\n\
+         \x20 echo args (program1): 3/4\n\
+         \x20   ✅ hello\n\
+         \x20   ✅ two spaces\n\
+         \x20   ❌ bye\n\
+         \x20     argv: [\"hello\"]\n\
+         \x20     reproduce: /bin/echo hello\n\
+         \x20     stdout:\n\
+         \x20       expected: \"bye\\n\"\n\
+         \x20       obtained: \"hello\\n\"\n\
+         status: 1/1\n\
+         \x20 exit (program1): 1/1\n\
+         \x20   ✅ succeeds\n\
+         Final score: 7 / 9 (77.8%)\n\
+         Seed: 3\n"
+    );
+}
+
+#[test]
+fn should_grade_an_absolute_config() {
+    let (result, report) = grade("absolute.json", &[("program1", CAT)]);
+
+    assert_eq!(result.score(), Score::Absolute(false));
+    assert_eq!(
+        report,
+        "stdin: PASS\n\
+         \x20 cat stdin (program1): PASS\n\
+         \x20   ✅ copies\n\
+         \x20   ✅ copies nothing\n\
+         errors: FAIL\n\
+         \x20 missing file (program1): FAIL\n\
+         \x20   ✅ complains\n\
+         \x20   ❌ succeeds\n\
+         \x20     argv: [\"missing.txt\"]\n\
+         \x20     reproduce: /bin/cat missing.txt\n\
+         \x20     status:\n\
+         \x20       expected: 0\n\
+         \x20       obtained: 1\n\
+         Failed assertions (1):\n\
+         \x20 ❌ errors / missing file / succeeds\n\
+         Final score: FAIL\n\
+         Seed: 3\n"
+    );
+}
+
+#[test]
+fn should_grade_a_config_with_several_programs() {
+    let (result, report) = grade("multi_program.json", &[("program1", ECHO), ("cat", CAT)]);
+
+    assert_eq!(result.score(), Score::Weighted { current: 2, max: 2 });
+    assert_eq!(
+        report,
+        "echo: 1/1\n\
+         \x20 echo args (program1): 1/1\n\
+         \x20   ✅ echoes\n\
+         cat: 1/1\n\
+         \x20 cat stdin (cat): 1/1\n\
+         \x20   ✅ copies\n\
+         Final score: 2 / 2 (100.0%)\n\
+         Seed: 3\n"
+    );
+}
+
+#[test]
+fn should_run_each_test_with_its_own_program() {
+    let (result, _) = grade("multi_program.json", &[("p1", CAT), ("program2", ECHO)]);
+
+    assert_eq!(result.score(), Score::Weighted { current: 0, max: 2 });
+}
+
+#[test]
+fn should_grade_a_config_with_files_and_setup() {
+    let (result, report) = grade("setup_files.json", &[("program1", CAT)]);
+
+    assert_eq!(result.score(), Score::Weighted { current: 4, max: 4 });
+    assert_eq!(
+        report,
+        "files: 4/4\n\
+         \x20 cat files (program1): 3/3\n\
+         \x20   ✅ reads a file\n\
+         \x20   ✅ reads the sorted file\n\
+         \x20   ✅ reads the greeting\n\
+         \x20 cat table (program1): 1/1\n\
+         \x20   ✅ reads a file from a table\n\
+         Final score: 4 / 4 (100.0%)\n\
+         Seed: 3\n"
+    );
+}